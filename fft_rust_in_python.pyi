@@ -1,46 +1,415 @@
-def read_csv_py(file_path: str) -> tuple[list[float], list[float]]:
+from typing import Any, Callable, Literal
+
+# Float samples accepted by the data arguments: lists, numpy arrays, pandas/polars Series or any float64 buffer.
+ArrayLike = Any
+
+# Complex samples: a complex128/complex64 buffer such as a numpy array, or a sequence of complex numbers.
+ComplexLike = Any
+
+# Precision of interleaved complex results.
+ComplexDtype = Literal["complex64", "complex128"]
+
+# Result layout: a tuple of lists, a {name: column} dict that pandas.DataFrame accepts, or a polars.DataFrame.
+# The polars frame imports the columns through the Arrow PyCapsule interface without copying (polars 1.3 or later).
+Output = Literal["tuple", "dict", "polars"]
+
+# Called with the percentage completed (0 to 100). Returning False cancels the operation with InterruptedError.
+ProgressCallback = Callable[[float], bool | None]
+
+# Multi-channel data: a list of equally long channels, or interleaved samples (one sample per channel in turn) split
+# by the channels argument of the function. Flat samples without a channels count are a single channel.
+ChannelsLike = Any
+
+# Down-mix of multi-channel data: the average or sum of all channels, or half the difference of a stereo pair.
+MonoMode = Literal["mean", "sum", "side"]
+
+# Color scales of heatmap plots, see colormaps_py. "grey", "grayscale" and "coolwarm" are accepted as aliases.
+Colormap = Literal["viridis", "magma", "plasma", "jet", "gray", "diverging"]
+
+# Tick labels: decimals, scientific (1.5e-4), engineering with exponents in steps of three (150e-6) or SI prefixes (150µ).
+TickFormat = Literal["auto", "scientific", "engineering", "si"]
+
+# Window functions, all in their periodic form.
+WindowName = Literal["rectangular", "hann", "hamming", "blackman", "blackmanharris", "flattop"]
+
+
+def read_csv_py(
+    file_path: str,
+    output: Output = "tuple",
+    progress: ProgressCallback | None = None,
+) -> Any:
     """Reads a CSV file and extracts the first two columns (time, measured_data) as separate vectors.
     This is a very specific function to these examples and is not intended to be a robust data loader.
 
     Parameters:
         file_path (str): Path to the file that should be loaded.
+        output (Output): "tuple", a {"time", "measured_data"} dict of columns for pandas, or a "polars" DataFrame.
+        progress (ProgressCallback | None): Called periodically with the percentage of the file read.
+
+    Returns:
+        Any: The time and measured data columns in the requested output layout.
+    """
+
+def read_dataframe_py(frame: Any, time_column: str, data_column: str) -> tuple[list[float], list[float]]:
+    """Extracts a time and a data column from a pandas or polars DataFrame (or any mapping of columns).
+
+    Parameters:
+        frame (Any): The DataFrame holding the columns.
+        time_column (str): Name of the time column.
+        data_column (str): Name of the measured data column.
+
+    Returns:
+        tuple[list[float], list[float]]: The time and measured data columns.
+    """
+
+def read_segy_py(
+    file_path: str,
+    trace_range: tuple[int, int] | None = None,
+) -> tuple[float, list[list[float]]]:
+    """Reads traces from a SEG-Y seismic file.
+    Only fixed length traces are supported, the trace length is taken from the binary file header.
+
+    Parameters:
+        file_path (str): Path to the SEG-Y file that should be loaded.
+        trace_range (tuple[int, int] | None): Half open (start, end) range of trace indices to read. All traces are read when None.
+
+    Returns:
+        tuple[float, list[list[float]]]: The sample interval in seconds and a list of samples for each trace.
+    """
+
+def read_mseed_py(file_path: str) -> list[tuple[str, float, list[float]]]:
+    """Reads a MiniSEED (v2) file and returns the samples of each channel.
+    Records are concatenated per channel in file order, gaps and overlaps between records are not handled.
+
+    Parameters:
+        file_path (str): Path to the MiniSEED file that should be loaded.
+
+    Returns:
+        list[tuple[str, float, list[float]]]: A (NET.STA.LOC.CHA id, sample rate in Hz, samples) tuple for each channel.
+    """
+
+def read_edf_py(
+    file_path: str,
+    channels: list[str] | None = None,
+) -> list[tuple[str, float, list[float]]]:
+    """Reads an EDF or BDF biosignal recording (e.g. EEG/ECG) and returns the physical samples of each signal.
+
+    Parameters:
+        file_path (str): Path to the EDF/BDF file that should be loaded.
+        channels (list[str] | None): Signal labels to read. All signals except EDF+ annotations are read when None.
+
+    Returns:
+        list[tuple[str, float, list[float]]]: A (label, sample rate in Hz, samples) tuple for each selected signal.
+    """
+
+def read_wav_py(file_path: str) -> tuple[float, list[list[float]]]:
+    """Reads a WAV file. PCM (8/16/24/32 bit) and IEEE float (32/64 bit) data is supported.
+
+    Parameters:
+        file_path (str): Path to the WAV file that should be loaded.
+
+    Returns:
+        tuple[float, list[list[float]]]: The sample rate in Hz and the samples of each channel, integer formats scaled to [-1, 1).
+    """
+
+def read_flac_py(file_path: str) -> tuple[float, list[list[float]]]:
+    """Reads a FLAC file, checking every frame against its CRC.
+
+    Parameters:
+        file_path (str): Path to the FLAC file that should be loaded.
+
+    Returns:
+        tuple[float, list[list[float]]]: The sample rate in Hz and the samples of each channel scaled to [-1, 1).
+    """
+
+def read_audio_py(file_path: str) -> tuple[float, list[list[float]]]:
+    """Reads a WAV or FLAC file, recognising the format from the file content. MP3 and Ogg (Vorbis or Opus) files
+    are decoded through libsndfile when the module is built with the "lossy" feature and rejected otherwise.
+
+    Parameters:
+        file_path (str): Path to the audio file that should be loaded.
+
+    Returns:
+        tuple[float, list[list[float]]]: The sample rate in Hz and the samples of each channel scaled to [-1, 1).
+    """
+
+def deinterleave_py(data: ArrayLike, channels: int) -> list[list[float]]:
+    """Splits interleaved samples into separate channels.
+
+    Parameters:
+        data (ArrayLike): The samples, one of every channel per frame.
+        channels (int): The number of channels, which must divide the number of samples.
+
+    Returns:
+        list[list[float]]: The samples of each channel.
+    """
+
+def interleave_py(channels: list[list[float]]) -> list[float]:
+    """Interleaves equally long channels into one buffer, the inverse of deinterleave_py.
+
+    Parameters:
+        channels (list[list[float]]): The samples of each channel.
+
+    Returns:
+        list[float]: The samples frame by frame, one of every channel per frame.
+    """
+
+def select_channels_py(data: ChannelsLike, indices: list[int], channels: int | None = None) -> list[list[float]]:
+    """Picks channels by index, in the given order. An index may repeat, e.g. [0, 0] turns mono into stereo.
+
+    Parameters:
+        data (ChannelsLike): The multi-channel data.
+        indices (list[int]): The indices of the channels to keep.
+        channels (int | None): The number of channels of interleaved data, checked against the list of channels.
+
+    Returns:
+        list[list[float]]: The selected channels.
+    """
+
+def to_mono_py(data: ChannelsLike, mode: MonoMode = "mean", channels: int | None = None) -> list[float]:
+    """Mixes multi-channel data down to a single channel.
+
+    Parameters:
+        data (ChannelsLike): The multi-channel data.
+        mode (MonoMode): "mean" or "sum" of all channels, or "side", half the left minus right channel of a stereo
+            pair.
+        channels (int | None): The number of channels of interleaved data, checked against the list of channels.
+
+    Returns:
+        list[float]: The mixed samples.
+    """
+
+def open_wav_stream_py(file_path: str, chunk_frames: int = 65536) -> WavStream:
+    """Opens a WAV file for reading chunk by chunk, keeping memory bounded for files of any length.
+
+    Parameters:
+        file_path (str): Path to the WAV file that should be streamed.
+        chunk_frames (int): Samples per channel in each chunk, the last chunk may be shorter.
+
+    Returns:
+        WavStream: An iterator yielding the samples of each channel for one chunk at a time.
+    """
+
+def open_audio_capture_py(
+    fs: float = 48000.0,
+    channels: int = 1,
+    chunk_frames: int = 4096,
+    device: str = "default",
+) -> AudioCapture:
+    """Opens a live audio input through ALSA. Only available on Linux when the module is built with the "audio" feature.
+
+    Each chunk can go straight into compute_psd_welch_py and a PeakHoldSpectrum or AverageSpectrum for live
+    spectrum monitoring. Chunks must be consumed in real time, samples arriving while the consumer is busy are
+    lost to overruns.
+
+    Parameters:
+        fs (float): The sample rate in Hz, converted by ALSA if the hardware runs at another rate.
+        channels (int): The number of channels to capture.
+        chunk_frames (int): Samples per channel in each chunk yielded by iteration.
+        device (str): The ALSA capture device, e.g. "default" or "hw:1,0".
 
     Returns:
-        tuple[list[float], list[float]]: Returns a list of floats for both the time and measured data columns.
+        AudioCapture: An endless iterator yielding the samples of each channel for one chunk at a time, full
+            scale at ±1.
+    """
+
+def play_py(
+    data: ChannelsLike,
+    fs: float,
+    channels: int | None = None,
+    device: str = "default",
+    progress: ProgressCallback | None = None,
+) -> None:
+    """Plays samples through ALSA and returns when playback has finished, e.g. to audition a filtered signal
+    without writing a WAV file. Ctrl-C stops playback. Only available on Linux when the module is built with the
+    "audio" feature.
+
+    Parameters:
+        data (ChannelsLike): The samples of each channel, full scale at ±1. Louder samples are clipped.
+        fs (float): The sample rate in Hz, converted by ALSA if the hardware runs at another rate.
+        channels (int | None): The number of channels of interleaved data, checked against the list of channels.
+        device (str): The ALSA playback device, e.g. "default" or "hw:0,0".
+        progress (ProgressCallback | None): Called periodically with the percentage of samples queued.
     """
 
-def compute_fft_py(data: list[float]) -> tuple[list[float], list[float]]:
+def compute_fft_py(
+    data: ArrayLike,
+    output: Output = "tuple",
+    precision: Literal["double", "extended"] = "double",
+) -> Any:
     """Computes the FFT of the provided data and returns separate vectors for the real and imaginary parts.
 
     Parameters:
-        data (list[float]): The input data for which the FFT should be computed.
+        data (ArrayLike): The input data for which the FFT should be computed.
+        output (Output): "tuple", a {"real", "imag"} dict of columns for pandas, or a "polars" DataFrame.
+        precision (str): "double", or "extended" to compute in double-double arithmetic (about 106 bits) and round
+            the result, much slower but useful for measuring the round-off of the double precision transform.
+
+    Returns:
+        Any: The real and imaginary parts of the FFT result in the requested output layout.
+    """
+
+def compute_fft_complex_py(real: ArrayLike, imag: ArrayLike, output: Output = "tuple") -> Any:
+    """Computes the FFT of complex data given as separate real and imaginary parts, e.g. I/Q samples.
+
+    Parameters:
+        real (ArrayLike): The real (in-phase) part of the input.
+        imag (ArrayLike): The imaginary (quadrature) part of the input, of the same length.
+        output (Output): "tuple", a {"real", "imag"} dict of columns for pandas, or a "polars" DataFrame.
+
+    Returns:
+        Any: The real and imaginary parts of the FFT result in the requested output layout.
+    """
+
+def compute_ifft_complex_py(real: ArrayLike, imag: ArrayLike, output: Output = "tuple") -> Any:
+    """Computes the inverse FFT of complex data, scaled by 1/N so it undoes compute_fft_complex_py.
+
+    Parameters:
+        real (ArrayLike): The real part of the spectrum.
+        imag (ArrayLike): The imaginary part of the spectrum, of the same length.
+        output (Output): "tuple", a {"real", "imag"} dict of columns for pandas, or a "polars" DataFrame.
+
+    Returns:
+        Any: The real and imaginary parts of the time domain signal in the requested output layout.
+    """
+
+def compute_fft_fixed_py(
+    real: list[int],
+    imag: list[int] | None = None,
+    word_bits: Literal[16, 32] = 16,
+    frac_bits: int = 15,
+    scale: bool = True,
+) -> tuple[list[int], list[int]]:
+    """Computes a bit-exact fixed-point radix-2 FFT, as a reference model for integer DSP firmware.
+
+    Parameters:
+        real (list[int]): The real part as integers in the chosen Q format. The length must be a power of two.
+        imag (list[int] | None): The imaginary part, zero when omitted.
+        word_bits (int): The word size, 16 or 32. Products use 64 bit intermediates.
+        frac_bits (int): The number of fractional bits of the data and twiddle factors, e.g. 15 for Q15.
+        scale (bool): Halve the outputs of every stage so the result is the DFT divided by the length and cannot
+            overflow. Without scaling large results saturate.
+
+    Returns:
+        tuple[list[int], list[int]]: The real and imaginary parts in the same Q format. Products are rounded half
+            up and results saturated to the word range.
+    """
+
+def compute_fft_interleaved_py(
+    data: ComplexLike,
+    inverse: bool = False,
+    dtype: ComplexDtype = "complex128",
+) -> bytes:
+    """Computes the FFT of complex data, reading and returning interleaved (re, im) pairs.
+    The inverse transform is scaled by 1/N so a forward and inverse pair returns the original data.
+
+    Parameters:
+        data (ComplexLike): A complex128/complex64 buffer (e.g. a numpy array) or a sequence of complex numbers.
+        inverse (bool): Compute the inverse transform instead of the forward one.
+        dtype (ComplexDtype): The precision of the returned values.
+
+    Returns:
+        bytes: Native endian interleaved values, e.g. numpy.frombuffer(result, dtype=numpy.complex128).
+    """
+
+def to_interleaved_py(real: ArrayLike, imag: ArrayLike, dtype: ComplexDtype = "complex128") -> bytes:
+    """Packs separate real and imaginary parts into an interleaved complex buffer.
+
+    Parameters:
+        real (ArrayLike): The real part of the data.
+        imag (ArrayLike): The imaginary part of the data.
+        dtype (ComplexDtype): The precision of the returned values.
+
+    Returns:
+        bytes: Native endian interleaved values, e.g. numpy.frombuffer(result, dtype=numpy.complex128).
+    """
+
+def from_interleaved_py(data: ComplexLike) -> tuple[list[float], list[float]]:
+    """Splits interleaved complex data into separate real and imaginary parts for the other functions.
+
+    Parameters:
+        data (ComplexLike): A complex128/complex64 buffer (e.g. a numpy array) or a sequence of complex numbers.
+
+    Returns:
+        tuple[list[float], list[float]]: The real and imaginary parts.
+    """
+
+def compute_fft_axis_py(
+    data: list[list[float]],
+    axis: int = 1,
+    progress: ProgressCallback | None = None,
+) -> tuple[list[list[float]], list[list[float]]]:
+    """Computes the FFT of each row or column of a rectangular matrix (e.g. one row per channel) in parallel.
+
+    Parameters:
+        data (list[list[float]]): The input matrix.
+        axis (int): 1 transforms each row, 0 transforms each column.
+        progress (ProgressCallback | None): Called periodically with the percentage of rows or columns transformed.
 
     Returns:
-        tuple[list[float], list[float]]: A tuple containing the real and imaginary parts of the FFT result.
+        tuple[list[list[float]], list[list[float]]]: The real and imaginary parts, shaped like the input.
     """
 
-def fft_shift_py(real: list[float], imag: list[float]) -> tuple[list[float], list[float]]:
+def fft_shift_py(real: ArrayLike, imag: ArrayLike) -> tuple[list[float], list[float]]:
     """Performs FFT shift on the real and imaginary parts, moving the zero-frequency component to the center.
 
     Parameters:
-        real (list[float]): The real part of the FFT data.
-        imag (list[float]): The imaginary part of the FFT data.
+        real (ArrayLike): The real part of the FFT data.
+        imag (ArrayLike): The imaginary part of the FFT data.
 
     Returns:
         tuple[list[float], list[float]]: The FFT-shifted real and imaginary parts.
     """
 
-def compute_magnitude_py(real: list[float], imag: list[float]) -> list[float]:
+def compute_magnitude_py(real: ArrayLike, imag: ArrayLike) -> list[float]:
     """Computes the magnitude of complex data (real and imaginary parts).
 
     Parameters:
-        real (list[float]): The real part of the data.
-        imag (list[float]): The imaginary part of the data.
+        real (ArrayLike): The real part of the data.
+        imag (ArrayLike): The imaginary part of the data.
 
     Returns:
         list[float]: The magnitudes computed from the real and imaginary parts.
     """
 
+def compute_phase_py(real: ArrayLike, imag: ArrayLike) -> list[float]:
+    """Computes the phase angle in radians of complex data (real and imaginary parts).
+
+    Parameters:
+        real (ArrayLike): The real part of the data.
+        imag (ArrayLike): The imaginary part of the data.
+
+    Returns:
+        list[float]: The phase angles, in the range [-pi, pi].
+    """
+
+DbReference = float | Literal["dB", "dBFS", "dBV", "dBu", "dB SPL"]
+"""A dB reference given as a value or by name: "dBFS" is digital full scale (1.0), "dBV" 1 V, "dBu" 0.7746 V
+and "dB SPL" 20 µPa for sound pressure in Pa. Named references apply to amplitudes and their square to powers."""
+
+def amplitude_to_db_py(values: ArrayLike, reference: DbReference = 1.0) -> list[float]:
+    """Converts amplitudes (e.g. FFT magnitudes) to dB as 20 * log10(value / reference).
+
+    Parameters:
+        values (ArrayLike): The amplitudes to convert. Zeros map to -inf.
+        reference (DbReference): The amplitude corresponding to 0 dB, or a named reference such as "dBV".
+
+    Returns:
+        list[float]: The values in dB.
+    """
+
+def power_to_db_py(values: ArrayLike, reference: DbReference = 1.0) -> list[float]:
+    """Converts powers (e.g. PSD values) to dB as 10 * log10(value / reference).
+
+    Parameters:
+        values (ArrayLike): The powers to convert. Zeros map to -inf.
+        reference (DbReference): The power corresponding to 0 dB, or a named reference such as "dB SPL" whose
+            amplitude is squared.
+
+    Returns:
+        list[float]: The values in dB.
+    """
+
 def generate_frequencies_py(len: int, sampling_interval: float) -> list[float]:
     """Generates frequency bins for FFT data based on the data length and sampling interval.
 
@@ -52,32 +421,2307 @@ def generate_frequencies_py(len: int, sampling_interval: float) -> list[float]:
         list[float]: A list of frequency bins.
     """
 
-def fft_shift_frequencies_py(data: list[float]) -> list[float]:
+def fft_shift_frequencies_py(data: ArrayLike) -> list[float]:
     """Shifts the zero-frequency component of the frequency bins to the center.
 
     Parameters:
-        data (list[float]): The frequency data to be shifted.
+        data (ArrayLike): The frequency data to be shifted.
 
     Returns:
         list[float]: The FFT-shifted frequency data.
     """
 
-def generate_plot_py(
-    x: list[float],
-    y: list[float],
-    x_label: str,
-    y_label: str,
-    title: str,
-) -> bytes:
-    """Generates a plot from the provided data and returns the plot as a byte array.
+def estimate_noise_floor_py(
+    magnitude: ArrayLike,
+    method: Literal["median", "percentile"] = "median",
+    window: int = 31,
+    percentile: float | None = None,
+) -> list[float]:
+    """Estimates the broadband noise floor of a magnitude spectrum with a running median or percentile.
 
     Parameters:
-        x (list[float]): The data for the x-axis.
-        y (list[float]): The data for the y-axis.
-        x_label (str): The label for the x-axis.
-        y_label (str): The label for the y-axis.
-        title (str): The title of the plot.
+        magnitude (ArrayLike): The magnitude spectrum.
+        method (str): "median" or "percentile".
+        window (int): The number of bins in the running window.
+        percentile (float | None): The percentile (0-100) used by the "percentile" method. Defaults to 25.
 
     Returns:
-        bytes: The plot rendered as a PNG image in byte array format.
+        list[float]: The noise floor at each bin.
+    """
+
+def whiten_py(
+    real: ArrayLike,
+    imag: ArrayLike,
+    floor: ArrayLike | None = None,
+) -> tuple[list[float], list[float]]:
+    """Flattens a complex spectrum by dividing it by a noise floor, before correlation or peak picking.
+
+    Parameters:
+        real (ArrayLike): The real part of the spectrum.
+        imag (ArrayLike): The imaginary part of the spectrum.
+        floor (ArrayLike | None): The floor to divide by, for example from estimate_noise_floor_py. Defaults to
+            the magnitude of the spectrum itself, which keeps only the phase.
+
+    Returns:
+        tuple[list[float], list[float]]: The real and imaginary parts of the whitened spectrum.
+    """
+
+def crop_spectrum_py(
+    real: ArrayLike,
+    imag: ArrayLike,
+    freqs: ArrayLike,
+    f_lo: float,
+    f_hi: float,
+) -> tuple[list[float], list[float], list[float]]:
+    """Extracts the bins of a spectrum within a frequency band.
+
+    Parameters:
+        real (ArrayLike): The real part of the spectrum.
+        imag (ArrayLike): The imaginary part of the spectrum.
+        freqs (ArrayLike): The frequency of each bin in Hz, e.g. from generate_frequencies_py.
+        f_lo (float): The lower band edge in Hz.
+        f_hi (float): The upper band edge in Hz.
+
+    Returns:
+        tuple[list[float], list[float], list[float]]: The real parts, imaginary parts and frequencies of the bins whose
+            absolute frequency lies within the band, negative frequencies included.
+    """
+
+def band_pass_fd_py(
+    real: ArrayLike,
+    imag: ArrayLike,
+    freqs: ArrayLike,
+    f_lo: float,
+    f_hi: float,
+    taper: float | None = None,
+) -> tuple[list[float], list[float]]:
+    """Band-pass filters a spectrum with raised cosine transitions instead of brick-wall edges.
+
+    Parameters:
+        real (ArrayLike): The real part of the spectrum.
+        imag (ArrayLike): The imaginary part of the spectrum.
+        freqs (ArrayLike): The frequency of each bin in Hz, negative frequencies are treated by their absolute value.
+        f_lo (float): The lower band edge in Hz.
+        f_hi (float): The upper band edge in Hz.
+        taper (float | None): The width in Hz of the transitions outside the band, 0 for a brick-wall filter.
+            Defaults to a tenth of the band width.
+
+    Returns:
+        tuple[list[float], list[float]]: The real and imaginary parts of the filtered spectrum.
+    """
+
+def band_snr_py(
+    magnitude: ArrayLike,
+    freqs: ArrayLike,
+    signal_band: tuple[float, float],
+    noise_band: tuple[float, float],
+) -> float:
+    """Computes the signal-to-noise ratio of a magnitude spectrum from the power in a signal and a noise band.
+
+    Parameters:
+        magnitude (ArrayLike): The magnitude spectrum.
+        freqs (ArrayLike): The frequency of each bin in Hz.
+        signal_band (tuple[float, float]): The inclusive (low, high) band in Hz holding the signal.
+        noise_band (tuple[float, float]): The inclusive (low, high) band in Hz used to measure the noise. Its
+            power is scaled to the width of the signal band.
+
+    Returns:
+        float: The SNR in dB.
+    """
+
+def compute_psd_welch_py(
+    data: ArrayLike,
+    fs: float,
+    nperseg: int,
+    output: Output = "tuple",
+    progress: ProgressCallback | None = None,
+) -> Any:
+    """Estimates the one sided power spectral density of the data using Welch's method.
+    Segments are Hann windowed with 50% overlap and mean detrended before averaging.
+
+    Parameters:
+        data (ArrayLike): The input signal.
+        fs (float): The sampling frequency in Hz.
+        nperseg (int): The number of samples per segment, clamped to the data length.
+        output (Output): "tuple", a {"frequency", "psd"} dict of columns for pandas, or a "polars" DataFrame.
+        progress (ProgressCallback | None): Called periodically with the percentage of segments averaged.
+
+    Returns:
+        Any: The frequency bins in Hz and the PSD in units of data²/Hz in the requested output layout.
+    """
+
+def ar_psd_py(
+    data: ArrayLike,
+    fs: float,
+    order: int | None = None,
+    method: Literal["burg", "yule-walker"] = "burg",
+    n_freqs: int = 512,
+) -> dict[str, Any]:
+    """Estimates the PSD from an autoregressive model, an alternative to Welch for short records.
+
+    Parameters:
+        data (ArrayLike): The input signal. The mean is removed first.
+        fs (float): The sampling frequency in Hz.
+        order (int | None): The model order. Defaults to the order up to len / 4 (at most 100) minimizing the
+            Akaike information criterion.
+        method (str): "burg" or "yule-walker".
+        n_freqs (int): The number of frequencies between 0 and the Nyquist frequency to evaluate.
+
+    Returns:
+        dict[str, Any]: The frequencies "freqs", the one sided "psd", the prediction error filter
+            "coefficients" [1, a1, ..., ap] and the driving "noise_variance".
+    """
+
+def dpss_py(n: int, nw: float, k: int) -> list[list[float]]:
+    """Computes discrete prolate spheroidal (Slepian) sequences for multitaper estimation.
+
+    Parameters:
+        n (int): The taper length.
+        nw (float): The time half bandwidth product.
+        k (int): The number of tapers.
+
+    Returns:
+        list[list[float]]: The k unit energy tapers, from the most concentrated down.
+    """
+
+def multitaper_psd_py(
+    data: ArrayLike,
+    fs: float,
+    nw: float = 4.0,
+    k: int | None = None,
+) -> tuple[list[float], list[float]]:
+    """Estimates the PSD with Thomson's multitaper method for a low variance, low bias spectrum.
+
+    Parameters:
+        data (ArrayLike): The input signal. The mean is removed first.
+        fs (float): The sampling frequency in Hz.
+        nw (float): The time half bandwidth product. The resolution bandwidth is 2 * nw * fs / len(data).
+        k (int | None): The number of DPSS tapers. Defaults to 2 * nw - 1.
+
+    Returns:
+        tuple[list[float], list[float]]: The frequencies and the one sided PSD in data²/Hz.
+    """
+
+def psd_confidence_py(
+    psd: ArrayLike, n_averages: float, confidence: float = 0.95
+) -> tuple[list[float], list[float]]:
+    """Computes the chi-squared confidence interval of an averaged PSD estimate.
+
+    Parameters:
+        psd (ArrayLike): The PSD, e.g. from compute_psd_welch_py or multitaper_psd_py.
+        n_averages (float): The number of independent periodograms averaged, i.e. Welch segments or multitaper
+            tapers, giving 2 * n_averages degrees of freedom. Overlapping Welch segments are not fully
+            independent, pass a smaller, possibly fractional, equivalent count for a conservative band.
+        confidence (float): The confidence level between 0 and 1.
+
+    Returns:
+        tuple[list[float], list[float]]: The lower and upper bound of each bin, see generate_psd_plot_py to draw
+            them as a band.
+    """
+
+def bootstrap_psd_py(
+    data: ArrayLike,
+    fs: float,
+    n_boot: int = 1000,
+    nperseg: int = 256,
+    confidence: float = 0.95,
+    seed: int | None = None,
+) -> dict[str, list[float]]:
+    """Computes percentile bands of a Welch PSD by block bootstrap, for noise too far from Gaussian for
+    psd_confidence_py.
+
+    Each replicate averages blocks of consecutive segment periodograms drawn with replacement. Blocks of about the
+    cube root of the segment count keep the correlation of the 50% overlapping segments.
+
+    Parameters:
+        data (ArrayLike): The input signal.
+        fs (float): The sampling frequency in Hz.
+        n_boot (int): The number of bootstrap replicates, at least 2.
+        nperseg (int): The Welch segment length.
+        confidence (float): The confidence level between 0 and 1.
+        seed (int | None): Seed for reproducible bands. Without one the generator is seeded from the clock.
+
+    Returns:
+        dict[str, list[float]]: "freqs", the Welch PSD "psd" and the percentile bounds "lower" and "upper" of
+            each bin.
+    """
+
+def stationarity_test_py(data: ArrayLike, nperseg: int) -> dict[str, Any]:
+    """Tests whether the power of a signal stays constant, to tell when a single spectrum is a misleading summary.
+
+    Runs the run test of Bendat and Piersol on the mean squares of consecutive segments: too few or too many runs
+    above and below their median mean the power drifts or alternates. Use compute_spectrogram_py for signals that
+    fail the test.
+
+    Parameters:
+        data (ArrayLike): The input signal.
+        nperseg (int): The segment length. At least 4 segments are required and 20 or more give a reliable result.
+
+    Returns:
+        dict[str, Any]: "stationary" (the test passes at the 5% level), "p_value", "runs", "expected_runs",
+            "z_score" and the "segment_mean_square" sequence tested.
+    """
+
+def spectral_kurtosis_py(data: ArrayLike, fs: float, nperseg: int) -> tuple[list[float], list[float]]:
+    """Computes the spectral kurtosis of a signal from its STFT, to locate impulsive frequency bands.
+
+    Parameters:
+        data (ArrayLike): The input signal.
+        fs (float): The sampling frequency in Hz.
+        nperseg (int): The STFT segment length. Segments overlap by 75%.
+
+    Returns:
+        tuple[list[float], list[float]]: The frequencies and the spectral kurtosis at each. Stationary Gaussian
+            noise gives values near zero.
+    """
+
+def kurtogram_py(
+    data: ArrayLike,
+    fs: float,
+    max_level: int = 6,
+) -> tuple[list[list[float]], float, float, float]:
+    """Computes a fast kurtogram for selecting the demodulation band in bearing diagnostics.
+
+    Parameters:
+        data (ArrayLike): The input signal.
+        fs (float): The sampling frequency in Hz.
+        max_level (int): The deepest level. Level l splits 0 to fs / 2 into 2^l equal bands.
+
+    Returns:
+        tuple[list[list[float]], float, float, float]: The envelope kurtosis of each band per level, and the
+            centre frequency, bandwidth and kurtosis of the most impulsive band.
+    """
+
+def band_power_py(
+    data: ArrayLike,
+    fs: float,
+    bands: list[tuple[str, float, float]] | None = None,
+    nperseg: int | None = None,
+) -> list[tuple[str, float, float]]:
+    """Computes the absolute and relative power of the data in frequency bands using a Welch PSD.
+
+    Parameters:
+        data (ArrayLike): The input signal.
+        fs (float): The sampling frequency in Hz.
+        bands (list[tuple[str, float, float]] | None): (name, low, high) ranges in Hz. Defaults to the EEG bands
+            delta (0.5-4), theta (4-8), alpha (8-13), beta (13-30) and gamma (30-45).
+        nperseg (int | None): The Welch segment length. Defaults to four seconds of data.
+
+    Returns:
+        list[tuple[str, float, float]]: The (name, absolute power, relative power) of each band.
+    """
+
+class Frames:
+    """Lazy iterator over overlapping frames, yielding one list of samples per frame."""
+
+    def __iter__(self) -> Frames: ...
+
+    def __next__(self) -> list[float]: ...
+
+    def __len__(self) -> int:
+        """The number of frames left."""
+
+def frames_py(data: ArrayLike, nperseg: int, hop: int | None = None) -> Frames:
+    """Splits the data lazily into frames for custom per-frame processing.
+
+    Parameters:
+        data (ArrayLike): The input signal.
+        nperseg (int): The number of samples per frame.
+        hop (int | None): The number of samples between frame starts. Defaults to nperseg // 2.
+
+    Returns:
+        Frames: An iterator yielding each frame as a copy. Trailing samples that do not fill a frame are dropped.
+    """
+
+def find_triggers_py(
+    data: ArrayLike,
+    threshold: float,
+    hysteresis: float,
+    pre: int,
+    post: int,
+    slope: Literal["rising", "falling"] = "rising",
+) -> tuple[list[int], list[list[float]]]:
+    """Cuts fixed-length segments around threshold crossings, like an oscilloscope trigger.
+
+    Parameters:
+        data (ArrayLike): The input signal.
+        threshold (float): The level whose crossing fires the trigger.
+        hysteresis (float): How far the signal must move back past the threshold before the trigger re-arms, so
+            noise on a slow edge does not fire it repeatedly.
+        pre (int): The number of samples kept before each trigger.
+        post (int): The number of samples kept from each trigger on.
+        slope (str): "rising" or "falling" crossings.
+
+    Returns:
+        tuple[list[int], list[list[float]]]: The index of each trigger and its segment of pre + post samples.
+            Triggers too close to either end for a whole segment are dropped.
+    """
+
+def synchronous_average_py(segments: list[list[float]]) -> list[float]:
+    """Averages repetitions of an event sample by sample, suppressing everything not synchronous with it.
+
+    Parameters:
+        segments (list[list[float]]): Equally long segments aligned on the event, e.g. from find_triggers_py.
+
+    Returns:
+        list[float]: The averaged segment.
+    """
+
+def tacho_synchronous_average_py(
+    signal: ArrayLike,
+    tacho_pulses: ArrayLike,
+    fs: float,
+    samples_per_rev: int | None = None,
+) -> tuple[list[float], int]:
+    """Averages the revolutions of a rotating machine delimited by tachometer pulses.
+
+    Each revolution is resampled by cubic spline onto equally spaced angles, so speed changes between revolutions
+    do not smear the average.
+
+    Parameters:
+        signal (ArrayLike): The vibration signal.
+        tacho_pulses (ArrayLike): The pulse times in seconds, one per revolution and strictly increasing.
+        fs (float): The sampling frequency in Hz.
+        samples_per_rev (int | None): The number of angles per revolution. Defaults to the median revolution
+            length in samples.
+
+    Returns:
+        tuple[list[float], int]: One averaged revolution starting at a pulse, and the number of revolutions
+            averaged.
+    """
+
+def fill_gaps_py(
+    time: ArrayLike, data: ArrayLike, method: Literal["nan", "zero", "linear", "ar"] = "linear"
+) -> dict[str, Any]:
+    """Detects dropouts in the time vector of a recording and inserts the missing samples.
+
+    A step longer than 1.5 times the median step is a gap, filled with as many samples as the median step fits
+    into it.
+
+    Parameters:
+        time (ArrayLike): The strictly increasing sample times.
+        data (ArrayLike): The sample values.
+        method (str): "nan" to mark missing samples, "zero", "linear" for a straight line across the gap, or "ar"
+            for autoregressive predictions from both sides, cross-faded, which keep the spectral content of the
+            surrounding signal across short gaps.
+
+    Returns:
+        dict[str, Any]: The continuous "time" and "data", and "gaps" with one dict per gap holding "start_time"
+            and "end_time" (the recorded samples either side), "index" (of the first inserted sample) and
+            "missing" (the number inserted).
+    """
+
+def check_cola_py(window: WindowName, nperseg: int, hop: int) -> tuple[bool, float, list[int]]:
+    """Checks the constant overlap-add condition that STFT reconstruction relies on.
+
+    Parameters:
+        window (str): "rectangular", "hann", "hamming", "blackman", "blackmanharris" or "flattop".
+        nperseg (int): The number of samples per segment.
+        hop (int): The number of samples between segment starts, at most nperseg.
+
+    Returns:
+        tuple[bool, float, list[int]]: Whether the overlap-added windows sum to a constant, the largest relative
+            deviation from that constant and every hop from 1 to nperseg that satisfies the condition.
+    """
+
+def stft_py(
+    data: ArrayLike,
+    nperseg: int,
+    hop: int | None = None,
+    window: WindowName = "hann",
+) -> list[list[complex]]:
+    """Computes the short-time Fourier transform, with frames centred on multiples of hop.
+
+    Parameters:
+        data (ArrayLike): The input signal, zero padded by half a frame at both ends.
+        nperseg (int): The number of samples per frame.
+        hop (int | None): The number of samples between frame centres. Defaults to nperseg // 4.
+        window (str): The analysis window.
+
+    Returns:
+        list[list[complex]]: The nperseg // 2 + 1 one sided bins of each frame, one row per frame.
+    """
+
+def istft_py(
+    frames: list[list[complex]],
+    nperseg: int,
+    hop: int | None = None,
+    window: WindowName = "hann",
+    length: int | None = None,
+) -> list[float]:
+    """Inverts stft_py by weighted overlap-add, exactly for unmodified frames with enough overlap.
+
+    Parameters:
+        frames (list[list[complex]]): The one sided spectrum of each frame.
+        nperseg (int): The number of samples per frame used by stft_py.
+        hop (int | None): The number of samples between frame centres. Defaults to nperseg // 4.
+        window (str): The analysis window used by stft_py.
+        length (int | None): The output length, e.g. the original signal length. Defaults to (frames - 1) * hop.
+
+    Returns:
+        list[float]: The reconstructed signal.
+    """
+
+def griffin_lim_py(
+    magnitude: list[list[float]],
+    n_iter: int = 32,
+    nperseg: int = 1024,
+    hop: int | None = None,
+    seed: int | None = None,
+) -> list[float]:
+    """Reconstructs a signal from a magnitude-only spectrogram with the fast Griffin-Lim algorithm.
+
+    Parameters:
+        magnitude (list[list[float]]): The magnitudes of a Hann windowed stft_py, nperseg // 2 + 1 bins per frame.
+        n_iter (int): The number of iterations.
+        nperseg (int): The number of samples per frame.
+        hop (int | None): The number of samples between frame centres. Defaults to nperseg // 4.
+        seed (int | None): The seed of the random initial phases, for repeatable results.
+
+    Returns:
+        list[float]: The reconstructed signal of (frames - 1) * hop samples, possibly inverted in sign.
+    """
+
+def mel_filterbank_py(fs: float, n_fft: int, n_mels: int = 128) -> list[list[float]]:
+    """Builds triangular filters evenly spaced on the HTK mel scale over the bins of stft_py.
+
+    Parameters:
+        fs (float): The sampling frequency in Hz.
+        n_fft (int): The frame length nperseg of the STFT.
+        n_mels (int): The number of mel bands.
+
+    Returns:
+        list[list[float]]: The weight of each of the n_fft // 2 + 1 bins, one row per mel band. A mel spectrogram
+            frame is the dot product of every row with an STFT magnitude frame.
+    """
+
+def mel_to_linear_py(mel_spec: list[list[float]], fs: float, n_fft: int, n_mels: int = 128) -> list[list[float]]:
+    """Estimates a linear frequency spectrogram from a mel spectrogram with the pseudo-inverse of mel_filterbank_py.
+
+    The result is smooth within each mel band and clipped to non-negative values. Pass it to griffin_lim_py to
+    reconstruct audio.
+
+    Parameters:
+        mel_spec (list[list[float]]): The mel spectrogram, n_mels values per frame.
+        fs (float): The sampling frequency in Hz.
+        n_fft (int): The frame length nperseg of the STFT.
+        n_mels (int): The number of mel bands.
+
+    Returns:
+        list[list[float]]: The n_fft // 2 + 1 bins of each frame.
+    """
+
+def time_stretch_py(data: ArrayLike, fs: float, rate: float) -> list[float]:
+    """Changes the duration of a signal without changing its pitch, using a phase vocoder with 46 ms frames.
+
+    Parameters:
+        data (ArrayLike): The input signal.
+        fs (float): The sampling frequency in Hz.
+        rate (float): The speed factor, 2 plays twice as fast and halves the duration.
+
+    Returns:
+        list[float]: The stretched signal of len(data) / rate samples.
+    """
+
+def pitch_shift_py(data: ArrayLike, fs: float, semitones: float) -> list[float]:
+    """Shifts the pitch of a signal keeping its duration, by time stretching and resampling.
+
+    Parameters:
+        data (ArrayLike): The input signal.
+        fs (float): The sampling frequency in Hz.
+        semitones (float): The shift in semitones, 12 for an octave up and negative to lower.
+
+    Returns:
+        list[float]: The shifted signal, as long as the input.
+    """
+
+def window_report_py(window: WindowName, nperseg: int = 1024) -> dict[str, Any]:
+    """Describes the spectral leakage of a window to help choose one.
+
+    Parameters:
+        window (str): "rectangular", "hann", "hamming", "blackman", "blackmanharris" or "flattop".
+        nperseg (int): The window length in samples.
+
+    Returns:
+        dict[str, Any]: "enbw" (equivalent noise bandwidth in bins), "coherent_gain", "scalloping_loss_db" (loss for a
+            tone halfway between bins), "sidelobe_level_db" (highest sidelobe relative to the peak) and "plot", a PNG
+            comparing the responses of all windows with the chosen one highlighted.
+    """
+
+def suggest_fft_params_py(n_samples: int, fs: float, desired_resolution_hz: float) -> dict[str, Any]:
+    """Recommends Welch analysis parameters for a frequency resolution and reports the trade-offs.
+
+    Parameters:
+        n_samples (int): The length of the recording.
+        fs (float): The sampling frequency in Hz.
+        desired_resolution_hz (float): The frequency resolution needed.
+
+    Returns:
+        dict[str, Any]: "window" (hann, or rectangular when even one Hann segment is too coarse), "nperseg" (a power
+            of two), "nfft" (padded length), "resolution_hz" (window noise bandwidth), "bin_spacing_hz",
+            "segments" (with 50% overlap), "relative_std" (of the PSD estimate) and "achievable".
+    """
+
+def analyze_file_py(
+    path: str,
+    fs: float | None = None,
+    window: WindowName = "hann",
+    nperseg: int = 1024,
+    detrend: Literal["none", "mean", "linear"] = "linear",
+    plots: bool = False,
+) -> dict[str, Any]:
+    """Loads a recording and computes its statistics, amplitude spectrum and Welch PSD in one call.
+
+    Parameters:
+        path (str): A CSV (time and data columns), WAV, MiniSEED or EDF/BDF file, recognised by extension. Only the
+            first channel is analyzed.
+        fs (float | None): The sampling frequency in Hz. Defaults to the rate in the file, or for CSV files the
+            inverse of the median time step.
+        window (str): The window applied to the whole record for the amplitude spectrum.
+        nperseg (int): The Welch segment length, shortened to the record length if needed.
+        detrend (str): The trend removed before the spectra, "none", "mean" or "linear".
+        plots (bool): Whether to render PNG plots of the signal, spectrum and PSD.
+
+    Returns:
+        dict[str, Any]: "path", "fs", "n_samples", "options", "freqs" and "amplitude" (one sided amplitude spectrum
+            reading the amplitude of on-bin sinusoids), "psd_freqs" and "psd" (data²/Hz), "stats" ("mean", "std",
+            "rms", "min", "max", "crest_factor", "dominant_frequency", computed before detrending) and "plots"
+            (PNG bytes keyed "signal", "spectrum" and "psd" when requested).
+    """
+
+def generate_report_py(results: list[dict[str, Any]], path: str, format: Literal["html", "pdf"] = "html") -> None:
+    """Writes the plots, parameters and metrics of analysis results to a single self-contained file.
+
+    Parameters:
+        results (list[dict[str, Any]]): Results of analyze_file_py. The spectrum and PSD are plotted for results
+            analyzed without plots.
+        path (str): The file to write.
+        format (str): "html" for one page with embedded images or "pdf" for one A4 page per result.
+    """
+
+def analyze_directory_py(
+    pattern: str,
+    output_dir: str,
+    fs: float | None = None,
+    window: WindowName = "hann",
+    nperseg: int = 1024,
+    detrend: Literal["none", "mean", "linear"] = "linear",
+    plots: bool = False,
+    progress: ProgressCallback | None = None,
+) -> list[dict[str, Any]]:
+    """Analyzes every file matching a glob pattern in parallel, as analyze_file_py does for one file.
+
+    For each file "<name>_spectrum.csv", "<name>_psd.csv" and the requested "<name>_<plot>.png" images are written
+    to output_dir, with <name> the file path below the fixed leading directories of the pattern with separators and
+    dots replaced by underscores. "summary.csv" lists the statistics of every file. A file that fails to load is
+    reported in the summary without stopping the batch.
+
+    Parameters:
+        pattern (str): The glob pattern, e.g. "data/**/*.wav". "*" and "?" match within one path component and
+            "**" matches any number of directories. Wildcards skip hidden entries.
+        output_dir (str): The directory to write to, created if missing.
+        fs (float | None): The sampling frequency in Hz, overriding the rate found in each file.
+        window (str): The window applied to each record for the amplitude spectrum.
+        nperseg (int): The Welch segment length.
+        detrend (str): The trend removed before the spectra, "none", "mean" or "linear".
+        plots (bool): Whether to write PNG plots of the signal, spectrum and PSD.
+        progress (ProgressCallback | None): Called periodically with the percentage of files analyzed.
+
+    Returns:
+        list[dict[str, Any]]: One entry per file in path order with "path", "fs", "n_samples", "stats" (as in
+            analyze_file_py, None on failure) and "error" (None on success).
+    """
+
+def set_cache_dir_py(path: str | None = None) -> None:
+    """Caches the results of analyze_file_py and compute_psd_welch_py on disk.
+
+    A repeated call with an unchanged file or identical samples and parameters then returns the stored result.
+    Entries are keyed by a hash of the input and every parameter, so stale entries are never returned.
+
+    Parameters:
+        path (str | None): The cache directory, created if missing. None disables the cache, the default.
+    """
+
+def get_cache_dir_py() -> str | None:
+    """Returns the cache directory, None when caching is disabled."""
+
+def clear_cache_py() -> int:
+    """Deletes every entry in the cache directory and returns how many were removed."""
+
+def compare_spectra_py(a: Spectrum, b: Spectrum, align: bool = True, plot: bool = False) -> dict[str, Any]:
+    """Compares a measured amplitude spectrum against a reference, e.g. a device against a golden measurement.
+
+    Parameters:
+        a (Spectrum): The measured spectrum.
+        b (Spectrum): The reference spectrum.
+        align (bool): Whether to interpolate the reference linearly onto the bins of a within its frequency range.
+            Without it both spectra must have the same bins.
+        plot (bool): Whether to render an overlay of both spectra above a panel with their difference.
+
+    Returns:
+        dict[str, Any]: "freqs", "ratio" (a / b per bin), "difference_db" (20 log10 of the ratio, halve it for
+            power spectra), "mean_deviation_db", "rms_deviation_db", "max_deviation_db" (largest absolute
+            difference), "max_deviation_freq" and, with plot, "plot" as PNG bytes.
+    """
+
+def check_mask_py(
+    freqs: ArrayLike,
+    magnitude_db: ArrayLike,
+    mask_segments: list[tuple[float, float, float, float] | tuple[float, float, float, float, Literal["upper", "lower"]]],
+    plot: bool = False,
+) -> dict[str, Any]:
+    """Checks a spectrum against EMC-style limit lines.
+
+    Parameters:
+        freqs (ArrayLike): The frequency of each bin in Hz.
+        magnitude_db (ArrayLike): The spectrum in dB.
+        mask_segments (list[tuple]): Limit lines as (start_freq, end_freq, start_db, end_db) with an optional fifth
+            element "upper" (the default, the spectrum must stay below) or "lower" (it must stay above). Sloped
+            limits are interpolated linearly over log frequency when start_freq is positive, as EMC standards
+            specify, and linearly otherwise.
+        plot (bool): Whether to render the spectrum with the limit lines.
+
+    Returns:
+        dict[str, Any]: "passed" (whether every segment passed), "segments" with one dict per segment holding
+            "passed", "margin_db" (smallest distance from the limit, negative where violated, NaN without bins),
+            "worst_freq" and "n_bins", and with plot "plot" as PNG bytes.
+    """
+
+def average_spectra_py(
+    spectra: list[Spectrum], mode: Literal["linear", "power", "db", "peak_hold"] = "linear"
+) -> Spectrum:
+    """Combines repeated measurements of an amplitude spectrum into one representative curve.
+
+    Spectra with other bins than the first are linearly interpolated onto its bins, keeping only the bins every
+    spectrum covers.
+
+    Parameters:
+        spectra (list[Spectrum]): The measurements.
+        mode (str): "linear" for the mean, "power" for the root mean square, "db" for the mean level in dB
+            converted back to amplitude, or "peak_hold" for the largest value per bin.
+
+    Returns:
+        Spectrum: The combined spectrum.
+    """
+
+def compute_spectrogram_py(
+    data: ArrayLike,
+    fs: float,
+    nperseg: int,
+    hop: int | None = None,
+    progress: ProgressCallback | None = None,
+) -> tuple[list[float], list[float], list[list[float]]]:
+    """Computes a spectrogram from mean detrended, Hann windowed segments.
+
+    Parameters:
+        data (ArrayLike): The input signal.
+        fs (float): The sampling frequency in Hz.
+        nperseg (int): The number of samples per segment.
+        hop (int | None): The number of samples between segment starts. Defaults to nperseg // 2.
+        progress (ProgressCallback | None): Called periodically with the percentage of segments transformed.
+
+    Returns:
+        tuple[list[float], list[float], list[list[float]]]: The segment centre times in seconds, the frequency bins in Hz
+            and the PSD (data²/Hz) of each segment.
+    """
+
+def bark_spectrum_py(
+    data: ArrayLike,
+    fs: float,
+    n_bands: int = 24,
+    nperseg: int = 1024,
+    hop: int | None = None,
+) -> tuple[list[float], list[float], list[list[float]]]:
+    """Computes band powers over time in triangular bands evenly spaced on the Bark scale from 0 Hz to
+    Nyquist, after Traunmüller. 24 bands cover the critical bands of hearing.
+
+    Parameters:
+        data (ArrayLike): The input signal.
+        fs (float): The sampling frequency in Hz.
+        n_bands (int): The number of bands.
+        nperseg (int): The number of samples per segment. Bands too narrow to contain a frequency bin stay empty.
+        hop (int | None): The number of samples between segment starts. Defaults to nperseg // 2.
+
+    Returns:
+        tuple[list[float], list[float], list[list[float]]]: The segment centre times in seconds, the band centre
+            frequencies in Hz and the power (data²) of each band in each segment.
+    """
+
+def erb_spectrum_py(
+    data: ArrayLike,
+    fs: float,
+    n_bands: int = 40,
+    nperseg: int = 1024,
+    hop: int | None = None,
+) -> tuple[list[float], list[float], list[list[float]]]:
+    """Computes band powers over time in triangular bands evenly spaced on the ERB-rate scale from 0 Hz to
+    Nyquist, after Glasberg and Moore. 40 bands give about one band per ERB at 44.1 kHz.
+
+    Parameters:
+        data (ArrayLike): The input signal.
+        fs (float): The sampling frequency in Hz.
+        n_bands (int): The number of bands.
+        nperseg (int): The number of samples per segment. Bands too narrow to contain a frequency bin stay empty.
+        hop (int | None): The number of samples between segment starts. Defaults to nperseg // 2.
+
+    Returns:
+        tuple[list[float], list[float], list[list[float]]]: The segment centre times in seconds, the band centre
+            frequencies in Hz and the power (data²) of each band in each segment.
+    """
+
+def cross_spectral_matrix_py(
+    channels: list[list[float]],
+    fs: float,
+    nperseg: int = 256,
+    progress: ProgressCallback | None = None,
+) -> tuple[list[float], list[list[list[complex]]]]:
+    """Estimates the one sided cross-spectral density matrix of a set of channels with Welch's method.
+
+    Parameters:
+        channels (list[list[float]]): One row of samples per channel, all of the same length.
+        fs (float): The sampling frequency in Hz.
+        nperseg (int): The segment length. Segments use a Hann window and 50% overlap.
+        progress (ProgressCallback | None): Called periodically with the percentage of segments averaged.
+
+    Returns:
+        tuple[list[float], list[list[list[complex]]]]: The frequencies and one matrix per frequency whose entry
+            [i][j] is the cross-spectral density of channel i with channel j.
+    """
+
+def frequency_domain_decomposition_py(
+    channels: list[list[float]],
+    fs: float,
+    nperseg: int = 256,
+) -> tuple[list[float], list[list[float]], list[list[complex]]]:
+    """Performs a frequency domain decomposition (FDD) for operational modal analysis.
+
+    Parameters:
+        channels (list[list[float]]): One row of response samples per sensor.
+        fs (float): The sampling frequency in Hz.
+        nperseg (int): The segment length of the cross-spectral matrix estimate.
+
+    Returns:
+        tuple[list[float], list[list[float]], list[list[complex]]]: The frequencies, the singular values at each
+            frequency in descending order and the first singular vector (mode shape) at each frequency.
+    """
+
+def delay_and_sum_py(
+    channels: list[list[float]],
+    fs: float,
+    geometry: list[tuple[float, float]],
+    steering_angles: list[float],
+    method: Literal["das", "mvdr"] = "das",
+    nperseg: int = 256,
+    speed_of_sound: float = 343.0,
+    band: tuple[float, float] | None = None,
+    diagonal_loading: float = 1e-3,
+) -> list[float]:
+    """Scans a sensor array over far-field arrival directions and returns the spatial power spectrum.
+
+    Parameters:
+        channels (list[list[float]]): One row of samples per sensor.
+        fs (float): The sampling frequency in Hz.
+        geometry (list[tuple[float, float]]): The (x, y) position of each sensor in metres.
+        steering_angles (list[float]): Arrival directions to scan, in degrees counter-clockwise from the x axis.
+        method (str): "das" for delay-and-sum or "mvdr" for the minimum variance distortionless response beamformer.
+        nperseg (int): The segment length of the cross-spectral matrix estimate.
+        speed_of_sound (float): The propagation speed in metres per second.
+        band (tuple[float, float] | None): The (low, high) band in Hz to integrate over. Defaults to all bins above DC.
+        diagonal_loading (float): MVDR regularisation relative to the mean sensor power.
+
+    Returns:
+        list[float]: The beamformer output power at each steering angle.
+    """
+
+def gcc_phat_py(
+    a: ArrayLike,
+    b: ArrayLike,
+    fs: float,
+    max_delay: float | None = None,
+) -> tuple[float, list[float], list[float]]:
+    """Estimates the delay of one signal relative to another with the GCC-PHAT cross-correlation.
+
+    Parameters:
+        a (ArrayLike): The first signal.
+        b (ArrayLike): The reference signal.
+        fs (float): The sampling frequency in Hz.
+        max_delay (float | None): The largest delay in seconds to search. Defaults to the full signal overlap.
+
+    Returns:
+        tuple[float, list[float], list[float]]: The delay in seconds (positive when a lags behind b, refined to a
+            fraction of a sample), the lags in seconds and the whitened cross-correlation at each lag.
+    """
+
+def resample_py(data: ArrayLike, num: int) -> list[float]:
+    """Resamples the data to a new number of samples with the Fourier method.
+
+    Parameters:
+        data (ArrayLike): The input signal, treated as periodic.
+        num (int): The number of samples in the output.
+
+    Returns:
+        list[float]: The resampled signal.
+    """
+
+def align_signals_py(
+    a: ArrayLike,
+    b: ArrayLike,
+    max_lag: int | None = None,
+    fs_a: float | None = None,
+    fs_b: float | None = None,
+) -> tuple[int, list[float], list[float]]:
+    """Aligns two signals by the peak of their cross-correlation and crops them to their common span.
+
+    Parameters:
+        a (ArrayLike): The reference signal.
+        b (ArrayLike): The signal to align with a.
+        max_lag (int | None): The largest lag in samples to search. Defaults to the full signal overlap.
+        fs_a (float | None): The sampling frequency of a. Must be given together with fs_b.
+        fs_b (float | None): The sampling frequency of b. When it differs from fs_a, b is first resampled to fs_a.
+
+    Returns:
+        tuple[int, list[float], list[float]]: The lag of a relative to b in samples and the aligned, equal length
+            segments of a and b.
+    """
+
+def fractional_delay_py(data: ArrayLike, fs: float, delay_seconds: float) -> list[float]:
+    """Delays the data by any fraction of a sample with a linear phase ramp in the frequency domain.
+
+    Parameters:
+        data (ArrayLike): The input signal.
+        fs (float): The sampling frequency in Hz.
+        delay_seconds (float): The delay in seconds, negative values advance the data.
+
+    Returns:
+        list[float]: The delayed signal. The shift is circular, so pad the data to keep samples from wrapping around.
+    """
+
+def interpolate_py(
+    x: ArrayLike,
+    y: ArrayLike,
+    new_x: ArrayLike,
+    method: Literal["linear", "cubic", "sinc"] = "linear",
+) -> list[float]:
+    """Interpolates samples onto new positions, for example to regrid an irregular time vector before an FFT.
+
+    Parameters:
+        x (ArrayLike): The strictly increasing sample positions.
+        y (ArrayLike): The sample values.
+        new_x (ArrayLike): The positions to interpolate at.
+        method (str): "linear", "cubic" (natural cubic spline) or "sinc" (band-limited, requires uniform x).
+            Linear and cubic interpolation hold the end values outside the range of x.
+
+    Returns:
+        list[float]: The interpolated values at new_x.
+    """
+
+def despike_py(
+    data: ArrayLike,
+    threshold: float = 3.0,
+    method: Literal["median", "hampel"] = "hampel",
+    half_window: int = 5,
+) -> tuple[list[float], list[int]]:
+    """Removes spikes by replacing outlying samples with the running median.
+
+    Parameters:
+        data (ArrayLike): The input signal.
+        threshold (float): The deviation from the running median, in robust standard deviations, that marks a spike.
+        method (str): "median" scales the threshold by the standard deviation of the whole residual, "hampel" by the local
+            median absolute deviation.
+        half_window (int): The number of samples on each side of the running median window.
+
+    Returns:
+        tuple[list[float], list[int]]: The cleaned signal and the indices of the replaced samples.
+    """
+
+def detect_clipping_py(data: ArrayLike, threshold: float | None = None) -> list[tuple[int, int]]:
+    """Finds runs of at least three consecutive samples that hold the same value at or beyond the clipping level.
+    The crests of unclipped signals are not flat and are not reported.
+
+    Parameters:
+        data (ArrayLike): The input signal.
+        threshold (float | None): The clipping magnitude. Defaults to 99.9% of the peak magnitude.
+
+    Returns:
+        list[tuple[int, int]]: The (start, end) index range of each clipped run, with end exclusive.
+    """
+
+def detect_voice_activity_py(data: ArrayLike, fs: float, frame_ms: float = 20.0) -> dict:
+    """Flags the frames that hold speech from their energy and spectral flatness.
+
+    A frame is active when it is at least 10 dB above the noise floor (the 10th percentile of the frame energies)
+    and either harmonic (spectral flatness below 0.3) or 20 dB above the floor.
+
+    Parameters:
+        data (ArrayLike): The input signal.
+        fs (float): The sampling frequency in Hz.
+        frame_ms (float): The frame length in milliseconds, 10 to 30 ms suit speech.
+
+    Returns:
+        dict: The "frame_len" in samples, the per-frame "flags", "energy_db" and "flatness", and the "segments"
+            as (start, end) sample ranges with end exclusive, bridging pauses shorter than 200 ms.
+    """
+
+def trim_silence_py(data: ArrayLike, fs: float, threshold_db: float = 60.0) -> tuple[list[float], tuple[int, int]]:
+    """Removes the leading and trailing silence of a recording.
+
+    Parameters:
+        data (ArrayLike): The input signal.
+        fs (float): The sampling frequency in Hz.
+        threshold_db (float): How far below the loudest 10 ms frame a frame is considered silent, in dB.
+
+    Returns:
+        tuple[list[float], tuple[int, int]]: The trimmed signal and its (start, end) sample range in the input, with
+            end exclusive. Both are empty if the input is silent throughout.
+    """
+
+def split_on_silence_py(
+    data: ArrayLike,
+    fs: float,
+    threshold_db: float = 60.0,
+    min_silence_ms: float = 300.0,
+) -> list[tuple[int, int]]:
+    """Splits a long recording into events separated by silence, e.g. before batch analysis of each event.
+
+    Parameters:
+        data (ArrayLike): The input signal.
+        fs (float): The sampling frequency in Hz.
+        threshold_db (float): How far below the loudest 10 ms frame a frame is considered silent, in dB.
+        min_silence_ms (float): Silences shorter than this are bridged, in milliseconds.
+
+    Returns:
+        list[tuple[int, int]]: The (start, end) sample range of each event, with end exclusive.
+    """
+
+def declip_py(data: ArrayLike, threshold: float | None = None) -> list[float]:
+    """Reconstructs clipped runs with a cubic spline through the surrounding unclipped samples.
+
+    Parameters:
+        data (ArrayLike): The input signal.
+        threshold (float | None): The clipping magnitude. Defaults to 99.9% of the peak magnitude.
+
+    Returns:
+        list[float]: The signal with clipped runs replaced by their reconstruction.
+    """
+
+def normalize_py(
+    data: ArrayLike,
+    mode: Literal["peak", "rms", "zscore"] = "peak",
+    target: float | None = None,
+) -> list[float]:
+    """Normalizes a signal so recordings can be compared on a consistent scale.
+
+    Parameters:
+        data (ArrayLike): The input signal.
+        mode (str): "peak" scales the peak magnitude to the target, "rms" scales the root mean square to the
+            target and "zscore" removes the mean and scales to unit standard deviation.
+        target (float | None): The peak or RMS level to reach. Defaults to 1. Ignored by "zscore".
+
+    Returns:
+        list[float]: The normalized signal.
+    """
+
+def apply_gain_py(data: ArrayLike, gain_db: float) -> list[float]:
+    """Multiplies a signal by a gain in decibels.
+
+    Parameters:
+        data (ArrayLike): The input signal.
+        gain_db (float): The amplitude gain in dB (20 log10).
+
+    Returns:
+        list[float]: The scaled signal.
+    """
+
+def apply_calibration_py(
+    signal: Signal,
+    sensitivity: float | tuple[ArrayLike, ArrayLike],
+    units: str,
+) -> Signal:
+    """Converts raw readings, e.g. ADC counts or volts, to engineering units.
+
+    Parameters:
+        signal (Signal): The raw signal.
+        sensitivity (float | tuple): The sensitivity in readings per unit, e.g. 0.1 for a 100 mV/(m/s²)
+            accelerometer read in volts, or a (freqs, values) calibration curve. A curve is linearly interpolated,
+            held constant beyond its ends and applied in the frequency domain.
+        units (str): The engineering unit, e.g. "m/s²".
+
+    Returns:
+        Signal: The calibrated signal with its unit, and a constant sensitivity, recorded in the metadata.
+    """
+
+def kalman_trend_py(
+    data: ArrayLike,
+    process_noise: float,
+    measurement_noise: float,
+    smooth: bool = True,
+) -> tuple[list[float], list[float], list[float]]:
+    """Extracts the trend of a noisy stream with a local linear trend Kalman filter and RTS smoother.
+
+    Parameters:
+        data (ArrayLike): The noisy samples.
+        process_noise (float): The variance of the random slope changes per sample. Smaller values give a
+            smoother trend.
+        measurement_noise (float): The variance of the observation noise.
+        smooth (bool): Whether to run the Rauch-Tung-Striebel smoother after the forward filter.
+
+    Returns:
+        tuple[list[float], list[float], list[float]]: The trend level, its slope per sample and the variance of
+            the level at each sample.
+    """
+
+def analytic_signal_py(data: ArrayLike) -> tuple[list[float], list[float]]:
+    """Computes the analytic signal of real data with the FFT based Hilbert transform.
+
+    Parameters:
+        data (ArrayLike): The input signal.
+
+    Returns:
+        tuple[list[float], list[float]]: The real part (the data) and the imaginary part (its Hilbert transform).
+    """
+
+def emd_py(data: ArrayLike, max_imfs: int | None = None) -> list[list[float]]:
+    """Decomposes a signal into intrinsic mode functions with empirical mode decomposition.
+
+    Parameters:
+        data (ArrayLike): The input signal.
+        max_imfs (int | None): The largest number of IMFs to extract. Defaults to sifting until the residual has
+            too few extrema.
+
+    Returns:
+        list[list[float]]: The IMFs from the highest frequency down, followed by the residual trend. The rows
+            sum to the input.
+    """
+
+def instantaneous_attributes_py(imfs: list[list[float]], fs: float) -> tuple[list[list[float]], list[list[float]]]:
+    """Computes the instantaneous amplitude and frequency of each IMF from its analytic signal.
+
+    Parameters:
+        imfs (list[list[float]]): The IMFs, one per row.
+        fs (float): The sampling frequency in Hz.
+
+    Returns:
+        tuple[list[list[float]], list[list[float]]]: The instantaneous amplitude and frequency in Hz of each IMF.
+    """
+
+def hilbert_huang_spectrum_py(
+    imfs: list[list[float]],
+    fs: float,
+    freq_bins: int = 256,
+) -> tuple[list[float], list[float], list[list[float]]]:
+    """Builds the Hilbert-Huang spectrum from IMFs, for example those of emd_py without the residual.
+
+    Parameters:
+        imfs (list[list[float]]): The IMFs, one per row.
+        fs (float): The sampling frequency in Hz.
+        freq_bins (int): The number of frequency bins between 0 and the Nyquist frequency.
+
+    Returns:
+        tuple[list[float], list[float], list[list[float]]]: The sample times, the bin centre frequencies and the
+            squared instantaneous amplitude per sample and bin, in the layout of compute_spectrogram_py.
+    """
+
+def matrix_pencil_py(data: ArrayLike, fs: float, n_components: int) -> list[tuple[float, float, float, float]]:
+    """Estimates damped sinusoids in a short record with the matrix pencil method.
+
+    Resolves components much closer than the FFT bin spacing of the record.
+
+    Parameters:
+        data (ArrayLike): The real input signal, at least 6 * n_components samples long.
+        fs (float): The sampling frequency in Hz.
+        n_components (int): The number of real damped sinusoids to estimate.
+
+    Returns:
+        list[tuple[float, float, float, float]]: The (frequency in Hz, decay rate in 1/s, amplitude, phase in
+            radians) of each component, by decreasing amplitude.
+    """
+
+def music_py(
+    data: ArrayLike,
+    n_sinusoids: int,
+    fs: float,
+    n_freqs: int = 1024,
+) -> tuple[list[float], list[float], list[float]]:
+    """Estimates the frequencies of sinusoids in noise with the MUSIC subspace method.
+
+    Resolves tones closer than the FFT resolution limit of the record.
+
+    Parameters:
+        data (ArrayLike): The real input signal.
+        n_sinusoids (int): The number of real sinusoids in the signal.
+        fs (float): The sampling frequency in Hz.
+        n_freqs (int): The number of frequencies between 0 and the Nyquist frequency to evaluate.
+
+    Returns:
+        tuple[list[float], list[float], list[float]]: The estimated frequencies in ascending order, the evaluated
+            frequencies and the pseudospectrum at each.
+    """
+
+def esprit_py(data: ArrayLike, n_sinusoids: int, fs: float) -> list[float]:
+    """Estimates the frequencies of sinusoids in noise with the ESPRIT subspace method.
+
+    Parameters:
+        data (ArrayLike): The real input signal.
+        n_sinusoids (int): The number of real sinusoids in the signal.
+        fs (float): The sampling frequency in Hz.
+
+    Returns:
+        list[float]: The estimated frequencies in Hz in ascending order.
+    """
+
+def white_noise_py(n: int, std: float = 1.0, seed: int | None = None) -> list[float]:
+    """Generates Gaussian white noise.
+
+    Parameters:
+        n (int): The number of samples.
+        std (float): The standard deviation.
+        seed (int | None): Makes the output reproducible across runs and platforms. Without it the generator is
+            seeded from the clock.
+
+    Returns:
+        list[float]: The noise samples.
+    """
+
+def colored_noise_py(
+    n: int,
+    color: Literal["white", "pink", "brown"] = "pink",
+    std: float = 1.0,
+    seed: int | None = None,
+) -> list[float]:
+    """Generates white, pink (1/f) or brown (1/f²) Gaussian noise by spectral shaping.
+
+    Parameters:
+        n (int): The number of samples.
+        color (str): "white", "pink" or "brown" (also "red").
+        std (float): The standard deviation of the result.
+        seed (int | None): Makes the output reproducible across runs and platforms.
+
+    Returns:
+        list[float]: The noise samples. Colored noise has no DC component.
+    """
+
+def generate_ess_py(f1: float, f2: float, duration: float, fs: float) -> list[float]:
+    """Generates an exponential sine sweep for impulse response measurements.
+
+    Parameters:
+        f1 (float): The start frequency in Hz.
+        f2 (float): The end frequency in Hz, at most fs / 2.
+        duration (float): The sweep length in seconds.
+        fs (float): The sampling frequency in Hz.
+
+    Returns:
+        list[float]: The unit amplitude sweep.
+    """
+
+def extract_impulse_response_py(recorded: ArrayLike, sweep: ArrayLike, fs: float) -> tuple[list[float], list[float]]:
+    """Recovers an impulse response from the recorded response to a sweep by regularized spectral division.
+
+    Parameters:
+        recorded (ArrayLike): The recorded system response, long enough to hold the sweep and the decay.
+        sweep (ArrayLike): The excitation that was played, e.g. from generate_ess_py.
+        fs (float): The sampling frequency in Hz.
+
+    Returns:
+        tuple[list[float], list[float]]: The time of each sample in seconds and the impulse response, as long as the
+            recording. Harmonic distortion products of an exponential sweep are excluded.
+    """
+
+def generate_mls_py(order: int) -> list[float]:
+    """Generates one period of a maximum length sequence, a flat spectrum excitation robust to noise.
+
+    Parameters:
+        order (int): The register length, 2 to 24. The period is 2**order - 1 samples.
+
+    Returns:
+        list[float]: The sequence of +1 and -1 values. Play it repeated, at least twice in a row.
+    """
+
+def mls_impulse_response_py(recorded: ArrayLike, order: int) -> list[float]:
+    """Measures an impulse response from the recorded response to a repeated MLS.
+
+    Parameters:
+        recorded (ArrayLike): The recording, starting with the first period. The first period is skipped to reach
+            steady state and all further complete periods are averaged.
+        order (int): The order of the sequence that was played.
+
+    Returns:
+        list[float]: One period (2**order - 1 samples) of the impulse response, which must be longer than the
+            response itself to avoid time aliasing.
+    """
+
+def ir_to_fr_py(ir: ArrayLike, fs: float, smoothing_octaves: float) -> tuple[list[float], list[float], list[float]]:
+    """Converts an impulse response to a fractional-octave smoothed frequency response.
+
+    Parameters:
+        ir (ArrayLike): The impulse response, with any leading delay removed.
+        fs (float): The sampling frequency in Hz.
+        smoothing_octaves (float): The width of the complex smoothing window in octaves, e.g. 1/3 or 1/6, 0 for none.
+
+    Returns:
+        tuple[list[float], list[float], list[float]]: The frequency bins in Hz, the magnitude in dB and the wrapped
+            phase in radians.
+    """
+
+def rt60_py(ir: ArrayLike, fs: float, method: Literal["edt", "t20", "t30"] = "t30") -> float:
+    """Estimates the reverberation time from an impulse response by Schroeder backward integration.
+
+    Parameters:
+        ir (ArrayLike): The impulse response, ideally band filtered for per-band values.
+        fs (float): The sampling frequency in Hz.
+        method (str): The fitted decay range extrapolated to 60 dB: "edt" (0 to -10 dB), "t20" (-5 to -25 dB) or
+            "t30" (-5 to -35 dB).
+
+    Returns:
+        float: The reverberation time in seconds.
+    """
+
+def clarity_py(ir: ArrayLike, fs: float) -> dict[str, float]:
+    """Computes the clarity and definition of an impulse response, counting time from the direct sound.
+
+    Parameters:
+        ir (ArrayLike): The impulse response.
+        fs (float): The sampling frequency in Hz.
+
+    Returns:
+        dict[str, float]: "c50" and "c80" (early to late energy ratios in dB with 50 and 80 ms boundaries) and
+            "d50" (fraction of the energy in the first 50 ms).
+    """
+
+def fft_bandpass_py(data: ArrayLike, fs: float, low: float, high: float) -> list[float]:
+    """Keeps only the frequency content between low and high by zeroing all other FFT bins (an ideal filter).
+
+    Parameters:
+        data (ArrayLike): The input signal.
+        fs (float): The sampling frequency in Hz.
+        low (float): The lower band edge in Hz.
+        high (float): The upper band edge in Hz.
+
+    Returns:
+        list[float]: The filtered signal.
+    """
+
+def differentiate_fd_py(data: ArrayLike, fs: float, order: int = 1) -> list[float]:
+    """Differentiates a signal in the frequency domain by multiplying its spectrum by (j 2 pi f)^order.
+
+    Parameters:
+        data (ArrayLike): The input signal, treated as periodic.
+        fs (float): The sampling frequency in Hz.
+        order (int): The number of times to differentiate.
+
+    Returns:
+        list[float]: The differentiated signal.
+    """
+
+def integrate_fd_py(
+    data: ArrayLike,
+    fs: float,
+    order: int = 1,
+    highpass_cutoff: float | None = None,
+) -> list[float]:
+    """Integrates a signal in the frequency domain by dividing its spectrum by (j 2 pi f)^order.
+
+    Use order 1 to turn acceleration into velocity and order 2 to turn it into displacement.
+
+    Parameters:
+        data (ArrayLike): The input signal, treated as periodic.
+        fs (float): The sampling frequency in Hz.
+        order (int): The number of times to integrate.
+        highpass_cutoff (float | None): Bins below this frequency in Hz are zeroed to suppress drift. DC is
+            always removed.
+
+    Returns:
+        list[float]: The integrated signal.
+    """
+
+def minimum_phase_py(magnitude: ArrayLike) -> tuple[list[float], list[float]]:
+    """Builds the minimum-phase spectrum with a given magnitude response using the real cepstrum.
+
+    Parameters:
+        magnitude (ArrayLike): The magnitude of all N FFT bins, symmetric about the Nyquist bin.
+
+    Returns:
+        tuple[list[float], list[float]]: The real and imaginary parts of the minimum-phase spectrum. Its inverse
+            FFT is the minimum-phase impulse response.
+    """
+
+def linear_phase_fir_py(magnitude: ArrayLike, taps: int) -> list[float]:
+    """Designs a linear-phase FIR filter matching a magnitude response by frequency sampling.
+
+    Parameters:
+        magnitude (ArrayLike): The desired magnitude of all N FFT bins, symmetric about the Nyquist bin.
+        taps (int): The number of filter coefficients, at most N.
+
+    Returns:
+        list[float]: The Hann windowed, symmetric filter coefficients.
+    """
+
+def design_eq_py(
+    freqs: ArrayLike,
+    measured_db: ArrayLike,
+    target_db: ArrayLike,
+    fs: float,
+    n: int,
+    kind: Literal["fir", "biquad"] = "fir",
+) -> list[float] | list[list[float]]:
+    """Designs an equalizer correcting a measured magnitude response toward a target.
+
+    The correction is limited to ±18 dB. Biquad cascades are fitted greedily with peaking sections and may use
+    fewer than `n` sections when the remaining error is below 0.1 dB.
+
+    Parameters:
+        freqs (ArrayLike): The frequencies of the responses in Hz, strictly increasing.
+        measured_db (ArrayLike): The measured magnitude in dB, e.g. the "magnitude_db" of ir_to_fr_py.
+        target_db (ArrayLike): The target magnitude in dB.
+        fs (float): The sampling frequency of the filter in Hz.
+        n (int): The number of FIR taps or the maximum number of biquads.
+        kind (str): "fir" for a linear-phase FIR filter or "biquad" for a peaking biquad cascade.
+
+    Returns:
+        list[float] | list[list[float]]: The FIR taps, or one [b0, b1, b2, a0, a1, a2] row per biquad.
+    """
+
+def freqz_py(b: ArrayLike, a: ArrayLike, fs: float, n_points: int = 512) -> tuple[list[float], list[float], list[float]]:
+    """Evaluates the frequency response of the digital filter B(z) / A(z), like scipy.signal.freqz.
+
+    Parameters:
+        b (ArrayLike): The numerator coefficients of increasing powers of z^-1, e.g. FIR taps.
+        a (ArrayLike): The denominator coefficients, [1] for an FIR filter. a[0] must not be zero.
+        fs (float): The sampling frequency in Hz.
+        n_points (int): The number of frequencies spaced evenly from 0 to fs / 2, at least 2.
+
+    Returns:
+        tuple[list[float], list[float], list[float]]: The frequencies, magnitude in dB and wrapped phase in radians.
+    """
+
+class BiquadCascade:
+    """Cascade of second order sections keeping its state between chunks, for streaming filtering."""
+
+    def __init__(self, sos: list[list[float]]) -> None:
+        """Creates the cascade with a zero state.
+
+        Parameters:
+            sos (list[list[float]]): One [b0, b1, b2, a0, a1, a2] row per section, e.g. from design_eq_py.
+        """
+
+    def process(self, chunk: ArrayLike) -> list[float]:
+        """Filters the next chunk of the stream.
+
+        Parameters:
+            chunk (ArrayLike): The input samples.
+
+        Returns:
+            list[float]: The filtered samples.
+        """
+
+    def frequency_response(self, fs: float, n_points: int = 512) -> tuple[list[float], list[float], list[float]]:
+        """Evaluates the response at frequencies spaced evenly from 0 to fs / 2.
+
+        Parameters:
+            fs (float): The sampling frequency in Hz.
+            n_points (int): The number of frequencies, at least 2.
+
+        Returns:
+            tuple[list[float], list[float], list[float]]: The frequencies, magnitude in dB and wrapped phase in
+                radians.
+        """
+
+    @property
+    def sos(self) -> list[list[float]]:
+        """The normalized sections, one [b0, b1, b2, 1, a1, a2] row each."""
+
+    def reset(self) -> None:
+        """Clears the filter state."""
+
+class ArrowTable:
+    """Float64 columns exported through the Arrow PyCapsule interface. polars.DataFrame, pyarrow.table and other Arrow
+    consumers import it without copying the data.
+    """
+
+    def __arrow_c_stream__(self, requested_schema: object | None = None) -> object:
+        """An "arrow_array_stream" capsule yielding one struct array with a float64 field per column."""
+
+    def __len__(self) -> int: ...
+
+class WavStream:
+    """Chunks of a WAV file opened with open_wav_stream_py, read as they are iterated."""
+
+    def __iter__(self) -> WavStream: ...
+
+    def __next__(self) -> list[list[float]]: ...
+
+    @property
+    def sample_rate(self) -> float:
+        """The sample rate in Hz."""
+
+    @property
+    def channels(self) -> int:
+        """The number of channels."""
+
+    @property
+    def frames(self) -> int:
+        """The total number of samples per channel in the file."""
+
+class AudioCapture:
+    """Live audio input opened with open_audio_capture_py, captured as it is iterated."""
+
+    def __iter__(self) -> AudioCapture: ...
+
+    def __next__(self) -> list[list[float]]: ...
+
+    def read(self, frames: int) -> list[list[float]]:
+        """Captures the next frames samples per channel, blocking until they have arrived."""
+
+    @property
+    def sample_rate(self) -> float:
+        """The sample rate in Hz."""
+
+    @property
+    def channels(self) -> int:
+        """The number of channels."""
+
+class Decimator:
+    """Streaming decimation by an integer factor, evaluating the anti-alias filter only at the kept samples."""
+
+    def __init__(self, factor: int, taps_per_phase: int = 16) -> None:
+        """Creates the decimator with a zero state.
+
+        Parameters:
+            factor (int): The decimation factor.
+            taps_per_phase (int): The anti-alias filter length divided by the factor. Longer filters have sharper
+                transitions and more delay.
+        """
+
+    def process(self, chunk: ArrayLike) -> list[float]:
+        """Decimates the next chunk of the stream. Chunks of any length give the same output as one long chunk.
+
+        Parameters:
+            chunk (ArrayLike): The input samples.
+
+        Returns:
+            list[float]: One sample per factor inputs, the first aligned with the first input of the stream.
+        """
+
+    @property
+    def factor(self) -> int:
+        """The decimation factor."""
+
+    @property
+    def delay(self) -> float:
+        """The group delay of the filter in output samples."""
+
+    def reset(self) -> None:
+        """Clears the filter state."""
+
+class Interpolator:
+    """Streaming interpolation by an integer factor with a polyphase filter bank."""
+
+    def __init__(self, factor: int, taps_per_phase: int = 16) -> None:
+        """Creates the interpolator with a zero state.
+
+        Parameters:
+            factor (int): The interpolation factor.
+            taps_per_phase (int): The anti-imaging filter length divided by the factor.
+        """
+
+    def process(self, chunk: ArrayLike) -> list[float]:
+        """Interpolates the next chunk of the stream.
+
+        Parameters:
+            chunk (ArrayLike): The input samples.
+
+        Returns:
+            list[float]: factor samples per input sample.
+        """
+
+    @property
+    def factor(self) -> int:
+        """The interpolation factor."""
+
+    @property
+    def delay(self) -> float:
+        """The group delay of the filter in output samples."""
+
+    def reset(self) -> None:
+        """Clears the filter state."""
+
+class FarrowResampler:
+    """Streaming resampler with an arbitrary, adjustable ratio using cubic interpolation in Farrow form.
+
+    Suited to ratios near 1 such as clock drift correction. There is no anti-alias filter, so low-pass the stream
+    (e.g. with Decimator) before reducing the rate by much.
+    """
+
+    def __init__(self, ratio: float) -> None:
+        """Creates the resampler with a zero state.
+
+        Parameters:
+            ratio (float): The number of output samples per input sample, fs_out / fs_in.
+        """
+
+    def process(self, chunk: ArrayLike) -> list[float]:
+        """Resamples the next chunk of the stream. Outputs lag the inputs by two samples.
+
+        Parameters:
+            chunk (ArrayLike): The input samples.
+
+        Returns:
+            list[float]: The output samples, the first aligned with the first input of the stream.
+        """
+
+    @property
+    def ratio(self) -> float:
+        """The number of output samples per input sample. Can be changed between chunks."""
+
+    @ratio.setter
+    def ratio(self, ratio: float) -> None: ...
+
+    def reset(self) -> None:
+        """Clears the state."""
+
+class DcBlocker:
+    """Streaming one-pole high-pass filter removing DC offset and slow drift from live data."""
+
+    def __init__(self, fs: float, cutoff: float = 1.0) -> None:
+        """Creates the blocker with a zero state.
+
+        Parameters:
+            fs (float): The sampling frequency in Hz.
+            cutoff (float): The approximate -3 dB frequency in Hz, between 0 and fs / 2.
+        """
+
+    def process(self, chunk: ArrayLike) -> list[float]:
+        """Filters the next chunk of the stream.
+
+        Parameters:
+            chunk (ArrayLike): The input samples.
+
+        Returns:
+            list[float]: The filtered samples.
+        """
+
+    def reset(self) -> None:
+        """Clears the filter state."""
+
+class Agc:
+    """Streaming automatic gain control leveling a stream toward a target peak level."""
+
+    def __init__(
+        self,
+        fs: float,
+        target: float = 1.0,
+        attack: float = 0.01,
+        release: float = 0.5,
+        max_gain: float = 100.0,
+    ) -> None:
+        """Creates the control loop with unity starting gain.
+
+        Parameters:
+            fs (float): The sampling frequency in Hz.
+            target (float): The peak level to reach.
+            attack (float): The time constant in seconds with which the gain drops when the level rises.
+            release (float): The time constant in seconds with which the gain recovers when the level falls.
+            max_gain (float): The largest gain applied, so silence is not amplified to full scale.
+        """
+
+    def process(self, chunk: ArrayLike) -> list[float]:
+        """Levels the next chunk of the stream.
+
+        Parameters:
+            chunk (ArrayLike): The input samples.
+
+        Returns:
+            list[float]: The leveled samples.
+        """
+
+    @property
+    def gain(self) -> float:
+        """The gain applied to the most recent sample."""
+
+    def reset(self) -> None:
+        """Returns the gain to one."""
+
+class Signal:
+    """Uniformly sampled data with its sampling rate and channel metadata. Supports pickle, JSON and npz
+    serialization."""
+
+    def __init__(
+        self,
+        data: ArrayLike,
+        fs: float,
+        quantity: str | None = None,
+        unit: str | None = None,
+        name: str | None = None,
+        calibration: float | None = None,
+    ) -> None:
+        """Creates the signal.
+
+        Parameters:
+            data (ArrayLike): The samples.
+            fs (float): The sampling frequency in Hz.
+            quantity (str | None): The measured quantity, e.g. "Acceleration".
+            unit (str | None): The unit of the values, e.g. "m/s²".
+            name (str | None): The channel name, used as the default plot title.
+            calibration (float | None): The sensitivity the values were scaled with, recorded for reference.
+        """
+
+    @property
+    def data(self) -> list[float]:
+        """A copy of the samples."""
+
+    @property
+    def fs(self) -> float:
+        """The sampling frequency in Hz."""
+
+    @property
+    def duration(self) -> float:
+        """The length of the record in seconds."""
+
+    quantity: str | None
+    """The measured quantity, e.g. "Acceleration"."""
+
+    unit: str | None
+    """The unit of the values, e.g. "m/s²"."""
+
+    name: str | None
+    """The channel name."""
+
+    calibration: float | None
+    """The sensitivity the values were scaled with."""
+
+    @property
+    def label(self) -> str:
+        """The axis label of the values, e.g. "Acceleration [m/s²]"."""
+
+    def plot(
+        self,
+        title: str | None = None,
+        xlim: tuple[float, float] | None = None,
+        ylim: tuple[float, float] | None = None,
+        x_ticks: int | None = None,
+        y_ticks: int | None = None,
+        x_format: TickFormat = "auto",
+        y_format: TickFormat = "auto",
+        y_percentile: float | None = None,
+    ) -> bytes:
+        """Renders the signal over time as a PNG, labelled from the metadata. The title defaults to the channel
+        name. The axis arguments are those of generate_plot_py."""
+
+    def __len__(self) -> int: ...
+
+    def to_json(self) -> str:
+        """Serializes to a JSON document with "type", "fs", "data" and the metadata fields, null where unset.
+        Non-finite values are written as NaN, Infinity and -Infinity, as Python's json module does."""
+
+    @staticmethod
+    def from_json(text: str) -> Signal:
+        """Restores a signal written by to_json. Missing metadata fields are left unset."""
+
+    def to_npz(self, path: str) -> None:
+        """Writes an npz archive with the arrays "data" and "fs" plus a scalar array for each metadata field that
+        is set, readable with numpy.load."""
+
+class Spectrum:
+    """Values over frequency, e.g. an amplitude spectrum or a PSD, with channel metadata. Supports pickle, JSON
+    and npz serialization."""
+
+    def __init__(
+        self,
+        freqs: ArrayLike,
+        values: ArrayLike,
+        kind: Literal["amplitude", "power", "psd"] = "amplitude",
+        quantity: str | None = None,
+        unit: str | None = None,
+        name: str | None = None,
+        calibration: float | None = None,
+    ) -> None:
+        """Creates the spectrum.
+
+        Parameters:
+            freqs (ArrayLike): The frequency of each bin in Hz.
+            values (ArrayLike): The value of each bin, as many as there are frequencies.
+            kind (str): "amplitude" for values in the unit, "power" for unit² or "psd" for unit²/Hz.
+            quantity (str | None): The measured quantity, e.g. "Acceleration".
+            unit (str | None): The unit of the values, e.g. "m/s²".
+            name (str | None): The channel name, used as the default plot title.
+            calibration (float | None): The sensitivity the values were scaled with, recorded for reference.
+        """
+
+    @property
+    def freqs(self) -> list[float]:
+        """A copy of the bin frequencies."""
+
+    @property
+    def values(self) -> list[float]:
+        """A copy of the bin values."""
+
+    @property
+    def kind(self) -> str:
+        """"amplitude", "power" or "psd"."""
+
+    quantity: str | None
+    """The measured quantity, e.g. "Acceleration"."""
+
+    unit: str | None
+    """The unit of the values, e.g. "m/s²"."""
+
+    name: str | None
+    """The channel name."""
+
+    calibration: float | None
+    """The sensitivity the values were scaled with."""
+
+    @property
+    def label(self) -> str:
+        """The axis label of the values derived from the kind and unit, e.g. "PSD [(m/s²)²/Hz]"."""
+
+    def plot(
+        self,
+        title: str | None = None,
+        reference: DbReference | None = None,
+        xlim: tuple[float, float] | None = None,
+        ylim: tuple[float, float] | None = None,
+        x_ticks: int | None = None,
+        y_ticks: int | None = None,
+        x_format: TickFormat = "auto",
+        y_format: TickFormat = "auto",
+        y_percentile: float | None = None,
+    ) -> bytes:
+        """Renders the spectrum over frequency as a PNG, labelled from the kind and metadata. With a reference
+        the values are drawn as levels in dB against it, e.g. "PSD [dB SPL/Hz]". A value is taken as an
+        amplitude for amplitude spectra and as a power otherwise. The title defaults to the channel name. The
+        axis arguments are those of generate_plot_py, ylim in dB when a reference is given."""
+
+    def __len__(self) -> int: ...
+
+    def to_json(self) -> str:
+        """Serializes to a JSON document with "type", "kind", "freqs", "values" and the metadata fields, see
+        Signal.to_json."""
+
+    @staticmethod
+    def from_json(text: str) -> Spectrum:
+        """Restores a spectrum written by to_json."""
+
+    def to_npz(self, path: str) -> None:
+        """Writes an npz archive with the arrays "freqs", "values" and "kind" plus the metadata fields, see
+        Signal.to_npz."""
+
+def vibration_weighting_py(
+    freqs: ArrayLike,
+    weighting: Literal["Wk", "Wd", "Wc", "We", "Wf"] = "Wk",
+) -> list[float]:
+    """Evaluates the magnitude of an ISO 2631-1 frequency weighting curve.
+
+    Parameters:
+        freqs (ArrayLike): The frequencies in Hz.
+        weighting (str): "Wk" (vertical), "Wd" (horizontal), "Wc" (seat-back), "We" (rotational) or "Wf"
+            (motion sickness).
+
+    Returns:
+        list[float]: The weighting factor at each frequency.
+    """
+
+def frequency_weight_py(
+    data: ArrayLike,
+    fs: float,
+    weighting: Literal["Wk", "Wd", "Wc", "We", "Wf"] = "Wk",
+) -> list[float]:
+    """Applies an ISO 2631-1 frequency weighting to an acceleration signal.
+
+    Parameters:
+        data (ArrayLike): The acceleration signal.
+        fs (float): The sampling frequency in Hz.
+        weighting (str): "Wk", "Wd", "Wc", "We" or "Wf".
+
+    Returns:
+        list[float]: The frequency weighted acceleration.
+    """
+
+def vibration_metrics_py(
+    data: ArrayLike,
+    fs: float,
+    weighting: Literal["Wk", "Wd", "Wc", "We", "Wf"] = "Wk",
+) -> dict[str, float]:
+    """Computes ISO 2631-1 whole-body vibration exposure metrics from an acceleration signal.
+
+    Parameters:
+        data (ArrayLike): The acceleration signal in m/s^2.
+        fs (float): The sampling frequency in Hz.
+        weighting (str): "Wk", "Wd", "Wc", "We" or "Wf".
+
+    Returns:
+        dict[str, float]: The weighted RMS acceleration "rms", the vibration dose value "vdv", the maximum
+            transient vibration value "mtvv" (largest one second running RMS) and the "crest_factor".
+    """
+
+def k_weighting_py(fs: float) -> list[list[float]]:
+    """Designs the ITU-R BS.1770 K-weighting filter, a high shelf followed by a high-pass.
+
+    Parameters:
+        fs (float): The sampling frequency in Hz, above 3.4 kHz.
+
+    Returns:
+        list[list[float]]: Two second order sections [b0, b1, b2, a0, a1, a2], e.g. for BiquadCascade.
+    """
+
+def loudness_py(
+    channels: list[ArrayLike],
+    fs: float,
+    weights: ArrayLike | None = None,
+) -> dict[str, float | list[float]]:
+    """Measures programme loudness and true peak following ITU-R BS.1770-4 and EBU R 128.
+
+    Parameters:
+        channels (list[ArrayLike]): Equally long channels with samples normalized to ±1 full scale, e.g. [mono]
+            or [left, right].
+        fs (float): The sampling frequency in Hz.
+        weights (ArrayLike | None): The weight of each channel. Defaults to 1 for each channel, except 1.41 for
+            the surround channels of 5.0 (L, R, C, Ls, Rs) and 0 for the LFE of 5.1 (L, R, C, LFE, Ls, Rs).
+
+    Returns:
+        dict: The gated "integrated" loudness in LUFS (-inf if all blocks are below -70 LUFS), the "momentary"
+            (400 ms) and "short_term" (3 s) loudness every 100 ms in LUFS, and the "true_peak" in dBTP after 4x
+            oversampling.
+    """
+
+def allan_deviation_py(
+    data: ArrayLike,
+    fs: float,
+    taus: list[float] | None = None,
+) -> tuple[list[float], list[float]]:
+    """Computes the overlapping Allan deviation for clock, oscillator or sensor drift characterization.
+
+    Parameters:
+        data (ArrayLike): Rate or fractional frequency samples, for example gyroscope output.
+        fs (float): The sampling frequency in Hz.
+        taus (list[float] | None): The averaging times in seconds, rounded to whole samples. Defaults to octave
+            spaced averaging times up to half the record length.
+
+    Returns:
+        tuple[list[float], list[float]]: The averaging times used and the Allan deviation at each.
+    """
+
+def frequency_shift_py(data: ArrayLike, fs: float, shift_hz: float, dtype: ComplexDtype = "complex128") -> bytes:
+    """Translates the spectrum of the data by multiplying it with a complex exponential (heterodyning).
+
+    Parameters:
+        data (ArrayLike): The input signal.
+        fs (float): The sampling frequency in Hz.
+        shift_hz (float): The frequency shift in Hz, e.g. minus a carrier frequency to move that band to baseband.
+        dtype (ComplexDtype): The precision of the returned values.
+
+    Returns:
+        bytes: The complex shifted signal as native endian interleaved values, e.g.
+            numpy.frombuffer(result, dtype=numpy.complex128). No filtering is applied.
+    """
+
+def mix_to_baseband_py(
+    data: ArrayLike,
+    fs: float,
+    carrier: float,
+    decimation: int = 1,
+    bandwidth: float | None = None,
+) -> tuple[list[float], list[float]]:
+    """Downconverts a signal to complex baseband (I/Q) around a carrier and decimates it.
+
+    Parameters:
+        data (ArrayLike): The real passband signal.
+        fs (float): The sampling frequency in Hz.
+        carrier (float): The carrier frequency in Hz, below the Nyquist frequency.
+        decimation (int): The decimation factor. The output rate is fs / decimation.
+        bandwidth (float | None): The one sided baseband bandwidth in Hz. Defaults to the output Nyquist frequency.
+
+    Returns:
+        tuple[list[float], list[float]]: The in-phase and quadrature components.
+    """
+
+def demodulate_am_py(data: ArrayLike, fs: float, carrier: float, bandwidth: float | None = None) -> list[float]:
+    """Demodulates an amplitude modulated signal by its envelope around the carrier.
+
+    Parameters:
+        data (ArrayLike): The real AM signal.
+        fs (float): The sampling frequency in Hz.
+        carrier (float): The carrier frequency in Hz.
+        bandwidth (float | None): The message bandwidth in Hz. Defaults to the carrier frequency or the distance
+            to the Nyquist frequency, whichever is smaller.
+
+    Returns:
+        list[float]: The envelope, including the carrier amplitude as its mean.
+    """
+
+def demodulate_fm_py(data: ArrayLike, fs: float, carrier: float, bandwidth: float | None = None) -> list[float]:
+    """Demodulates a frequency modulated signal into its instantaneous frequency deviation.
+
+    Parameters:
+        data (ArrayLike): The real FM signal.
+        fs (float): The sampling frequency in Hz.
+        carrier (float): The carrier frequency in Hz.
+        bandwidth (float | None): The one sided signal bandwidth in Hz (e.g. Carson's rule). Defaults to the
+            carrier frequency or the distance to the Nyquist frequency, whichever is smaller.
+
+    Returns:
+        list[float]: The frequency deviation from the carrier in Hz at each sample.
+    """
+
+class AdaptiveFilter:
+    """LMS/NLMS adaptive FIR filter for noise cancellation and echo removal, keeping its state between blocks."""
+
+    def __init__(self, taps: int, step_size: float = 0.1, algorithm: Literal["lms", "nlms"] = "nlms") -> None:
+        """Creates a filter with all taps at zero.
+
+        Parameters:
+            taps (int): The number of filter coefficients.
+            step_size (float): The adaptation step. For "nlms" it is relative to the reference power and must be
+                below 2.
+            algorithm (str): "lms" or "nlms" (normalized LMS).
+        """
+
+    def process(self, primary: ArrayLike, reference: ArrayLike) -> tuple[list[float], list[float]]:
+        """Adapts to the next block, filtering the reference to match the primary input.
+
+        Parameters:
+            primary (ArrayLike): The primary input, for example signal plus noise or the microphone signal.
+            reference (ArrayLike): The reference input correlated with the part to remove, of the same length.
+
+        Returns:
+            tuple[list[float], list[float]]: The filter output (estimate of the correlated part) and the error
+                primary - output, which is the cleaned signal.
+        """
+
+    @property
+    def weights(self) -> list[float]:
+        """The current filter coefficients."""
+
+    def reset(self) -> None:
+        """Clears the coefficients and the reference history."""
+
+class PeakHoldSpectrum:
+    """Max-hold accumulator keeping the largest value seen in each bin of successive spectra."""
+
+    def __init__(self) -> None: ...
+
+    def update(self, spectrum: ArrayLike) -> None:
+        """Adds the next spectrum.
+
+        Parameters:
+            spectrum (ArrayLike): The spectrum, with as many bins as the first one.
+        """
+
+    @property
+    def values(self) -> list[float]:
+        """The held spectrum, empty before the first update."""
+
+    @property
+    def count(self) -> int:
+        """The number of spectra accumulated since the last reset."""
+
+    def reset(self) -> None:
+        """Discards the held spectrum."""
+
+class AverageSpectrum:
+    """Averaging accumulator for successive spectra. Feed power spectra for power averages."""
+
+    def __init__(self, mode: Literal["linear", "exponential"] = "linear", alpha: float = 0.1) -> None:
+        """Creates an empty accumulator.
+
+        Parameters:
+            mode (str): "linear" for the mean of all spectra or "exponential" for a moving average.
+            alpha (float): The weight of each new spectrum in exponential mode, in (0, 1].
+        """
+
+    def update(self, spectrum: ArrayLike) -> None:
+        """Adds the next spectrum.
+
+        Parameters:
+            spectrum (ArrayLike): The spectrum, with as many bins as the first one.
+        """
+
+    @property
+    def values(self) -> list[float]:
+        """The averaged spectrum, empty before the first update."""
+
+    @property
+    def count(self) -> int:
+        """The number of spectra accumulated since the last reset."""
+
+    def reset(self) -> None:
+        """Discards the average."""
+
+def benchmark_py(sizes: list[int], repeats: int = 10) -> list[tuple[int, float, float, float]]:
+    """Measures forward FFT throughput for each size on the current machine, excluding planning time.
+    Each size is also round-tripped through the inverse transform, raising RuntimeError if the result is wrong,
+    so this doubles as a smoke test of the installation.
+
+    Parameters:
+        sizes (list[int]): The FFT lengths to time.
+        repeats (int): The number of timed transforms per size.
+
+    Returns:
+        list[tuple[int, float, float, float]]: (size, mean seconds, best seconds, MFLOPS of the best run) for each size,
+            using the conventional 5 N log2(N) flop count.
+    """
+
+def warm_plans_py(sizes: list[int]) -> None:
+    """Plans forward and inverse FFTs of each size ahead of time, so the first transform of those sizes
+    does not pay the planning cost. Plans are kept in a process wide cache shared by all functions.
+
+    Parameters:
+        sizes (list[int]): The FFT lengths to plan.
+    """
+
+def plan_cache_stats_py() -> dict[str, int]:
+    """Returns the plan cache counters for debugging.
+
+    Returns:
+        dict[str, int]: "forward_plans" and "inverse_plans" (number of cached lengths), "hits" (requests answered
+            from the cache) and "misses" (requests that ran the planner).
+    """
+
+def plan_info_py(n: int) -> dict[str, Any]:
+    """Describes how a transform of length n is computed, to spot pathological lengths.
+
+    Parameters:
+        n (int): The transform length.
+
+    Returns:
+        dict[str, Any]: "algorithm" (top level strategy: trivial, butterfly, radix4, radix3, mixed_radix, raders or
+            bluesteins), "factors" (prime factors), "uses_raders", "uses_bluesteins", "estimated_flops" and
+            "relative_cost" (relative to a power of two transform of the same length).
+    """
+
+def fast_len_py(n: int) -> int:
+    """Returns the smallest length of at least n whose only prime factors are 2, 3 and 5.
+
+    Parameters:
+        n (int): The minimum length, e.g. the number of samples to zero pad.
+
+    Returns:
+        int: The padded length.
+    """
+
+def clear_plan_cache_py() -> None:
+    """Drops all cached FFT plans and resets the cache counters."""
+
+def set_num_threads_py(n: int) -> None:
+    """Limits the parallel routines (batch and 2-D transforms, multitaper, kurtogram, ...) to n worker threads.
+
+    Parameters:
+        n (int): The number of threads, or 0 to go back to the default pool sized by RAYON_NUM_THREADS or the number
+            of CPUs.
+    """
+
+def get_num_threads_py() -> int:
+    """Returns the number of worker threads the parallel routines run on."""
+
+def gpu_device_py() -> str | None:
+    """Returns the name of the OpenCL GPU that large batched FFTs run on, or None when they run on the CPU. Only
+    available when the module is built with the "gpu" feature.
+
+    compute_fft_axis_py offloads batches of at least 2**20 samples whose transform length is a power of two to the
+    first GPU with double precision support, and falls back to the CPU otherwise or if the GPU fails.
+    """
+
+def generate_plot_py(
+    x: ArrayLike,
+    y: ArrayLike,
+    x_label: str,
+    y_label: str,
+    title: str,
+    xlim: tuple[float, float] | None = None,
+    ylim: tuple[float, float] | None = None,
+    x_ticks: int | None = None,
+    y_ticks: int | None = None,
+    x_format: TickFormat = "auto",
+    y_format: TickFormat = "auto",
+    y_percentile: float | None = None,
+) -> bytes:
+    """Generates a plot from the provided data and returns the plot as a byte array. The line is broken at
+    NaN or infinite values.
+
+    Parameters:
+        x (ArrayLike): The data for the x-axis.
+        y (ArrayLike): The data for the y-axis.
+        x_label (str): The label for the x-axis.
+        y_label (str): The label for the y-axis.
+        title (str): The title of the plot.
+        xlim (tuple[float, float] | None): The (min, max) of the x axis, the range of the data when None.
+        ylim (tuple[float, float] | None): The (min, max) of the y axis, the range of the data when None.
+        x_ticks (int | None): The most tick labels on the x axis, about ten when None.
+        y_ticks (int | None): The most tick labels on the y axis, about ten when None.
+        x_format (TickFormat): How the x tick labels are written.
+        y_format (TickFormat): How the y tick labels are written.
+        y_percentile (float | None): Without ylim, fits the y axis from this percentile to 100 minus it with a
+            5% margin, e.g. 1 to keep a DC spike or a few bad samples from flattening the rest. From 0 to below
+            50, the full range when None. NaN and infinite values are never plotted.
+
+    Returns:
+        bytes: The plot rendered as a PNG image in byte array format.
+    """
+
+def generate_channels_plot_py(
+    data: ChannelsLike,
+    fs: float,
+    names: list[str] | None = None,
+    title: str = "Channels",
+    channels: int | None = None,
+    xlim: tuple[float, float] | None = None,
+    ylim: tuple[float, float] | None = None,
+    x_ticks: int | None = None,
+    y_ticks: int | None = None,
+    x_format: TickFormat = "auto",
+    y_format: TickFormat = "auto",
+    y_percentile: float | None = None,
+) -> bytes:
+    """Overlays the channels over time in distinct colors with a legend.
+
+    Parameters:
+        data (ChannelsLike): The multi-channel data.
+        fs (float): The sampling frequency in Hz.
+        names (list[str] | None): The legend entry of each channel, "Channel 0" and on when None.
+        title (str): The title of the plot.
+        channels (int | None): The number of channels of interleaved data, checked against the list of channels.
+        xlim (tuple[float, float] | None): The (min, max) of the x axis, the range of the data when None.
+        ylim (tuple[float, float] | None): The (min, max) of the y axis, the range of the data when None.
+        x_ticks (int | None): The most tick labels on the x axis, about ten when None.
+        y_ticks (int | None): The most tick labels on the y axis, about ten when None.
+        x_format (TickFormat): How the x tick labels are written.
+        y_format (TickFormat): How the y tick labels are written.
+        y_percentile (float | None): Without ylim, fits the y axis from this percentile to 100 minus it with a
+            5% margin, e.g. 1 to keep a DC spike or a few bad samples from flattening the rest. From 0 to below
+            50, the full range when None. NaN and infinite values are never plotted.
+
+    Returns:
+        bytes: The plot rendered as a PNG image.
+    """
+
+def colormaps_py() -> list[str]:
+    """Returns the names of the colormaps accepted by the heatmap plots.
+
+    Returns:
+        list[str]: The colormap names, perceptually uniform maps first.
+    """
+
+def generate_spectrogram_plot_py(
+    times: list[float],
+    freqs: list[float],
+    power: list[list[float]],
+    title: str,
+    colormap: Colormap = "viridis",
+    db_min: float | None = None,
+    db_max: float | None = None,
+    log_frequency: bool = False,
+    colorbar: bool = False,
+    xlim: tuple[float, float] | None = None,
+    ylim: tuple[float, float] | None = None,
+    x_ticks: int | None = None,
+    y_ticks: int | None = None,
+    x_format: TickFormat = "auto",
+    y_format: TickFormat = "auto",
+) -> bytes:
+    """Generates a spectrogram heatmap with the power in dB mapped onto a colormap.
+    Cells outside the dB range are clamped to the end colors.
+
+    Parameters:
+        times (list[float]): The segment centre times in seconds.
+        freqs (list[float]): The frequency bins in Hz.
+        power (list[list[float]]): The PSD of each segment, as returned by compute_spectrogram_py.
+        title (str): The title of the plot.
+        colormap (Colormap): The color scale, one of colormaps_py().
+        db_min (float | None): The power in dB drawn with the bottom color, 80 dB below db_max when None.
+        db_max (float | None): The power in dB drawn with the top color, the strongest cell when None.
+        log_frequency (bool): Draws the frequency axis on a log scale from the first bin above 0 Hz.
+        colorbar (bool): Adds a bar relating the colors to dB to the right of the plot.
+        xlim (tuple[float, float] | None): The (min, max) of the time axis in seconds, all segments when None.
+        ylim (tuple[float, float] | None): The (min, max) of the frequency axis in Hz, all bins when None. Must be
+            above 0 Hz with log_frequency.
+        x_ticks (int | None): The most tick labels on the time axis, about ten when None.
+        y_ticks (int | None): The most tick labels on the frequency axis, about ten when None.
+        x_format (TickFormat): How the time tick labels are written.
+        y_format (TickFormat): How the frequency tick labels are written.
+
+    Returns:
+        bytes: The plot rendered as a PNG image in byte array format.
+    """
+
+def generate_psd_plot_py(
+    freqs: ArrayLike,
+    psd: ArrayLike,
+    lower: ArrayLike | None = None,
+    upper: ArrayLike | None = None,
+    title: str = "Power spectral density",
+    reference: DbReference = 1.0,
+    xlim: tuple[float, float] | None = None,
+    ylim: tuple[float, float] | None = None,
+    x_ticks: int | None = None,
+    y_ticks: int | None = None,
+    x_format: TickFormat = "auto",
+    y_format: TickFormat = "auto",
+    y_percentile: float | None = None,
+) -> bytes:
+    """Plots a PSD in dB over frequency, optionally with a shaded confidence band.
+
+    Parameters:
+        freqs (ArrayLike): The frequency bins in Hz.
+        psd (ArrayLike): The PSD of each bin.
+        lower (ArrayLike | None): The lower confidence bound of each bin, e.g. from psd_confidence_py.
+        upper (ArrayLike | None): The upper confidence bound of each bin, passed together with lower.
+        title (str): The title of the plot.
+        reference (DbReference): The power corresponding to 0 dB, or a named reference labelling the axis, e.g.
+            "dBFS/Hz".
+        xlim (tuple[float, float] | None): The (min, max) of the frequency axis, the range of the bins when None.
+        ylim (tuple[float, float] | None): The (min, max) of the dB axis, the range of the PSD and band when None.
+        x_ticks (int | None): The most tick labels on the frequency axis, about ten when None.
+        y_ticks (int | None): The most tick labels on the dB axis, about ten when None.
+        x_format (TickFormat): How the frequency tick labels are written.
+        y_format (TickFormat): How the dB tick labels are written.
+        y_percentile (float | None): Without ylim, fits the dB axis from this percentile to 100 minus it, e.g. 1 to
+            keep a DC spike from flattening the rest, the full range when None.
+
+    Returns:
+        bytes: The plot rendered as a PNG image in byte array format.
+    """
+
+def generate_bode_plot_py(
+    freqs: ArrayLike,
+    magnitude_db: ArrayLike,
+    phase: ArrayLike,
+    title: str = "Frequency response",
+    xlim: tuple[float, float] | None = None,
+    ylim: tuple[float, float] | None = None,
+    x_ticks: int | None = None,
+    y_ticks: int | None = None,
+    x_format: TickFormat = "auto",
+    y_format: TickFormat = "auto",
+    y_percentile: float | None = None,
+) -> bytes:
+    """Draws a Bode plot, the magnitude in dB above the phase in degrees. Takes the output of freqz_py directly,
+    e.g. generate_bode_plot_py(*freqz_py(b, a, fs)).
+
+    Parameters:
+        freqs (ArrayLike): The frequencies in Hz.
+        magnitude_db (ArrayLike): The magnitude in dB at each frequency.
+        phase (ArrayLike): The phase in radians at each frequency, unwrapped for the plot so it runs on across
+            ±180°.
+        title (str): The title of the plot.
+        xlim (tuple[float, float] | None): The (min, max) of the frequency axis of both panels, the range of the
+            data when None.
+        ylim (tuple[float, float] | None): The (min, max) of the magnitude axis, the range of the data when None.
+            The phase axis always fits the data.
+        x_ticks (int | None): The most tick labels on the frequency axis, about ten when None.
+        y_ticks (int | None): The most tick labels on the magnitude axis, about ten when None.
+        x_format (TickFormat): How the frequency tick labels are written.
+        y_format (TickFormat): How the magnitude tick labels are written.
+        y_percentile (float | None): Without ylim, fits the magnitude axis from this percentile to 100 minus it,
+            e.g. 1 to keep deep notches from flattening the passband. NaN and infinite values are never plotted.
+
+    Returns:
+        bytes: The plot rendered as a PNG image.
+    """
+
+def set_log_level_py(level: Literal["off", "error", "warn", "info", "debug", "trace"]) -> None:
+    """Sets the most verbose level of log records emitted by the Rust code. Defaults to "warn".
+    Records are forwarded to the Python logging module under the "fft_rust_in_python" logger hierarchy
+    (e.g. "fft_rust_in_python.io"), so handlers and levels configured there still apply.
+
+    Parameters:
+        level (str): One of "off", "error", "warn", "info", "debug" or "trace".
     """
@@ -0,0 +1,135 @@
+//! Accumulators combining successive spectra, as spectrum analyzers do.
+
+use std::error::Error;
+
+use crate::{interpolate, Interpolation, Spectrum};
+
+/// How an [`SpectrumAccumulator`] combines each new spectrum with the previous ones.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Accumulation {
+    /// Keeps the largest value seen in each bin.
+    PeakHold,
+    /// Arithmetic mean of every spectrum so far.
+    Linear,
+    /// Exponential moving average, each new spectrum weighted by `alpha` between 0 and 1.
+    Exponential(f64),
+}
+
+/// Running combination of equally sized spectra. Feed power spectra for power averages.
+#[derive(Clone, Debug)]
+pub struct SpectrumAccumulator {
+    mode: Accumulation,
+    values: Vec<f64>,
+    count: usize,
+}
+
+impl SpectrumAccumulator {
+    pub fn new(mode: Accumulation) -> Result<Self, Box<dyn Error>> {
+        if let Accumulation::Exponential(alpha) = mode {
+            if !(alpha > 0.0 && alpha <= 1.0) {
+                return Err("The exponential weight must be in (0, 1].".into());
+            }
+        }
+        Ok(SpectrumAccumulator { mode, values: Vec::new(), count: 0 })
+    }
+
+    /// Adds the next spectrum. All spectra must have the length of the first one.
+    pub fn update(&mut self, spectrum: &[f64]) -> Result<(), Box<dyn Error>> {
+        if self.count == 0 {
+            self.values = spectrum.to_vec();
+            self.count = 1;
+            return Ok(());
+        }
+        if spectrum.len() != self.values.len() {
+            return Err(format!("Expected a spectrum of {} bins, got {}.", self.values.len(), spectrum.len()).into());
+        }
+
+        self.count += 1;
+        let weight = match self.mode {
+            Accumulation::PeakHold => {
+                self.values.iter_mut().zip(spectrum).for_each(|(v, s)| *v = v.max(*s));
+                return Ok(());
+            }
+            Accumulation::Linear => 1.0 / self.count as f64,
+            Accumulation::Exponential(alpha) => alpha,
+        };
+        self.values.iter_mut().zip(spectrum).for_each(|(v, s)| *v += weight * (s - *v));
+        Ok(())
+    }
+
+    /// Current accumulated spectrum, empty before the first update.
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    /// Number of spectra accumulated since the last reset.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn reset(&mut self) {
+        self.values.clear();
+        self.count = 0;
+    }
+}
+
+/// How [`average_spectra`] combines repeated measurements of an amplitude spectrum.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpectrumAverage {
+    /// Arithmetic mean of the values.
+    Linear,
+    /// Root mean square, the amplitude of the averaged power.
+    Power,
+    /// Mean of the levels in dB, converted back to amplitude. Less sensitive to occasional outliers.
+    Db,
+    /// Largest value in each bin.
+    PeakHold,
+}
+
+/// Combines repeated measurements into one representative spectrum on the bins of the first one, whose kind and
+///     metadata it keeps. Spectra with other bins are linearly interpolated onto them, and only the bins covered by
+///     every spectrum are kept.
+pub fn average_spectra(spectra: &[Spectrum], mode: SpectrumAverage) -> Result<Spectrum, Box<dyn Error>> {
+    let first = spectra.first().ok_or("At least one spectrum is needed.")?;
+    if spectra.iter().any(|s| s.freqs.is_empty()) {
+        return Err("Every spectrum must have at least one bin.".into());
+    }
+    let low = spectra.iter().map(|s| s.freqs[0]).fold(f64::MIN, f64::max);
+    let high = spectra.iter().map(|s| s.freqs[s.freqs.len() - 1]).fold(f64::MAX, f64::min);
+    let freqs: Vec<f64> = first.freqs.iter().copied().filter(|f| (low..=high).contains(f)).collect();
+    if freqs.is_empty() {
+        return Err("The spectra do not share a frequency range.".into());
+    }
+
+    let mut total = vec![if mode == SpectrumAverage::PeakHold { f64::NEG_INFINITY } else { 0.0 }; freqs.len()];
+    for spectrum in spectra {
+        let values = if spectrum.freqs == freqs {
+            spectrum.values.clone()
+        } else {
+            interpolate(spectrum.freqs.clone(), spectrum.values.clone(), freqs.clone(), Interpolation::Linear)?
+        };
+        for (t, v) in total.iter_mut().zip(values) {
+            match mode {
+                SpectrumAverage::Linear => *t += v,
+                SpectrumAverage::Power => *t += v * v,
+                SpectrumAverage::Db => *t += 20.0 * v.abs().max(1e-300).log10(),
+                SpectrumAverage::PeakHold => *t = t.max(v),
+            }
+        }
+    }
+
+    let count = spectra.len() as f64;
+    let values = total
+        .into_iter()
+        .map(|t| match mode {
+            SpectrumAverage::Linear => t / count,
+            SpectrumAverage::Power => (t / count).sqrt(),
+            SpectrumAverage::Db => 10f64.powf(t / count / 20.0),
+            SpectrumAverage::PeakHold => t,
+        })
+        .collect();
+    let mut average = Spectrum::new(freqs, values)?;
+    average.kind = first.kind;
+    average.metadata = first.metadata.clone();
+    Ok(average)
+}
@@ -0,0 +1,276 @@
+//! Acoustic measurement: excitation signals, impulse response extraction and room metrics.
+
+use rustfft::{FftDirection, num_complex::Complex};
+use std::error::Error;
+use std::f64::consts::PI;
+
+use crate::plan_fft;
+
+/// Generates an exponential sine sweep (Farina) from `f1` to `f2` Hz lasting `duration` seconds.
+///     The instantaneous frequency rises exponentially, spending equal time per octave.
+pub fn generate_ess(f1: f64, f2: f64, duration: f64, fs: f64) -> Result<Vec<f64>, Box<dyn Error>> {
+    if fs <= 0.0 || duration <= 0.0 {
+        return Err("fs and duration must be greater than zero.".into());
+    }
+    if f1 <= 0.0 || f1 >= f2 || f2 > fs / 2.0 {
+        return Err("Frequencies must satisfy 0 < f1 < f2 <= fs / 2.".into());
+    }
+
+    let rate = duration / (f2 / f1).ln();
+    let len = (duration * fs).round() as usize;
+    Ok((0..len)
+        .map(|n| {
+            let t = n as f64 / fs;
+            (2.0 * PI * f1 * rate * ((t / rate).exp() - 1.0)).sin()
+        })
+        .collect())
+}
+
+/// Recovers the impulse response of a system from its response `recorded` to the excitation `sweep` by regularized
+///     spectral division, `H = Y X* / (|X|² + eps)`, with `eps` a millionth of the peak sweep power so frequencies
+///     the sweep does not cover are suppressed instead of amplified.
+///     Returns `recorded.len()` samples. With an exponential sweep the harmonic distortion products land before
+///     time zero and wrap to the discarded end of the transform.
+pub fn extract_impulse_response(recorded: Vec<f64>, sweep: Vec<f64>) -> Result<Vec<f64>, Box<dyn Error>> {
+    if recorded.is_empty() || sweep.is_empty() {
+        return Err("Recording and sweep must not be empty.".into());
+    }
+
+    let out_len = recorded.len();
+    let len = (recorded.len() + sweep.len()).next_power_of_two();
+    let spectrum = |data: Vec<f64>| {
+        let mut buffer = vec![Complex::new(0.0, 0.0); len];
+        for (b, x) in buffer.iter_mut().zip(data) {
+            b.re = x;
+        }
+        plan_fft(len, FftDirection::Forward).process(&mut buffer);
+        buffer
+    };
+    let recorded = spectrum(recorded);
+    let sweep = spectrum(sweep);
+
+    let eps = 1e-6 * sweep.iter().map(|x| x.norm_sqr()).fold(0.0, f64::max);
+    let mut response: Vec<Complex<f64>> = recorded.iter().zip(&sweep).map(|(y, x)| y * x.conj() / (x.norm_sqr() + eps)).collect();
+    plan_fft(len, FftDirection::Inverse).process(&mut response);
+    log::debug!("Deconvolved {} recorded samples with a {} point transform", out_len, len);
+
+    Ok(response.into_iter().take(out_len).map(|c| c.re / len as f64).collect())
+}
+
+/// Feedback taps (1-based bit positions) of a primitive polynomial for each MLS order from 2 to 24.
+const MLS_TAPS: [&[u32]; 23] = [
+    &[2, 1],
+    &[3, 2],
+    &[4, 3],
+    &[5, 3],
+    &[6, 5],
+    &[7, 6],
+    &[8, 6, 5, 4],
+    &[9, 5],
+    &[10, 7],
+    &[11, 9],
+    &[12, 6, 4, 1],
+    &[13, 4, 3, 1],
+    &[14, 5, 3, 1],
+    &[15, 14],
+    &[16, 15, 13, 4],
+    &[17, 14],
+    &[18, 11],
+    &[19, 6, 2, 1],
+    &[20, 17],
+    &[21, 19],
+    &[22, 21],
+    &[23, 18],
+    &[24, 23, 22, 17],
+];
+
+/// Generates one period of the maximum length sequence of the given order (2 to 24), `2^order - 1` values of ±1
+///     from a linear feedback shift register. Its circular autocorrelation is `2^order - 1` at lag zero and -1
+///     elsewhere, which makes it a flat spectrum excitation.
+pub fn generate_mls(order: u32) -> Result<Vec<f64>, Box<dyn Error>> {
+    if !(2..=24).contains(&order) {
+        return Err("MLS order must be between 2 and 24.".into());
+    }
+    let taps = MLS_TAPS[order as usize - 2];
+    let len = (1usize << order) - 1;
+    let mut register: u32 = 1;
+    Ok((0..len)
+        .map(|_| {
+            let output = register & 1;
+            let feedback = taps.iter().fold(0, |acc, &t| acc ^ (register >> (order - t)) & 1);
+            register = (register >> 1) | (feedback << (order - 1));
+            if output == 1 { -1.0 } else { 1.0 }
+        })
+        .collect())
+}
+
+/// Measures an impulse response with a periodically repeated MLS of the given order.
+///     The first period of `recorded` is skipped to let the system reach steady state, every further complete
+///     period is averaged to improve the signal-to-noise ratio, then circularly cross-correlated with the sequence
+///     through the FFT. Returns one period, `2^order - 1` samples, which must be longer than the response.
+pub fn mls_impulse_response(recorded: Vec<f64>, order: u32) -> Result<Vec<f64>, Box<dyn Error>> {
+    let mls = generate_mls(order)?;
+    let len = mls.len();
+    let periods = (recorded.len() / len).saturating_sub(1);
+    if periods == 0 {
+        return Err(format!("Recording must contain at least two periods of {} samples.", len).into());
+    }
+
+    let mut average = vec![Complex::new(0.0, 0.0); len];
+    for period in recorded[len..].chunks_exact(len) {
+        for (a, y) in average.iter_mut().zip(period) {
+            a.re += y / periods as f64;
+        }
+    }
+    let mut sequence: Vec<Complex<f64>> = mls.into_iter().map(|x| Complex::new(x, 0.0)).collect();
+    plan_fft(len, FftDirection::Forward).process(&mut average);
+    plan_fft(len, FftDirection::Forward).process(&mut sequence);
+
+    let mut correlation: Vec<Complex<f64>> = average.iter().zip(&sequence).map(|(y, s)| y * s.conj()).collect();
+    plan_fft(len, FftDirection::Inverse).process(&mut correlation);
+    log::debug!("MLS impulse response of order {} averaged over {} periods", order, periods);
+
+    // The inverse transform scales by len, the correlation peak by len + 1
+    Ok(correlation.into_iter().map(|c| c.re / (len as f64 * (len + 1) as f64)).collect())
+}
+
+/// One sided frequency response, see [`ir_to_fr`] and [`BiquadCascade::frequency_response`](crate::BiquadCascade::frequency_response).
+pub struct FrequencyResponse {
+    pub freqs: Vec<f64>,
+    pub magnitude_db: Vec<f64>,
+    /// Wrapped phase in radians.
+    pub phase: Vec<f64>,
+}
+
+/// Converts an impulse response to its frequency response, smoothing the complex spectrum over a window of
+///     `smoothing_octaves` octaves centred on each bin (e.g. 1/3 or 1/6), zero for no smoothing.
+///     Complex smoothing averages out late reflections along with the noise, so remove any leading delay from the
+///     impulse response first to keep the phase from cancelling.
+pub fn ir_to_fr(ir: Vec<f64>, fs: f64, smoothing_octaves: f64) -> Result<FrequencyResponse, Box<dyn Error>> {
+    if ir.is_empty() || fs <= 0.0 || smoothing_octaves < 0.0 {
+        return Err("Impulse response must not be empty, fs must be greater than zero and smoothing must not be negative.".into());
+    }
+
+    let len = ir.len();
+    let mut spectrum: Vec<Complex<f64>> = ir.into_iter().map(|x| Complex::new(x, 0.0)).collect();
+    plan_fft(len, FftDirection::Forward).process(&mut spectrum);
+    let bins = len / 2 + 1;
+    spectrum.truncate(bins);
+
+    let smoothed = if smoothing_octaves > 0.0 {
+        // Prefix sums make each window average O(1) however wide it gets at high frequencies
+        let mut prefix = vec![Complex::new(0.0, 0.0); bins + 1];
+        for (k, c) in spectrum.iter().enumerate() {
+            prefix[k + 1] = prefix[k] + c;
+        }
+        let half_width = 2f64.powf(smoothing_octaves / 2.0);
+        (0..bins)
+            .map(|k| {
+                let low = ((k as f64 / half_width).ceil() as usize).min(k);
+                let high = ((k as f64 * half_width).floor() as usize).clamp(k, bins - 1);
+                (prefix[high + 1] - prefix[low]) / (high + 1 - low) as f64
+            })
+            .collect()
+    } else {
+        spectrum
+    };
+
+    Ok(FrequencyResponse {
+        freqs: (0..bins).map(|k| k as f64 * fs / len as f64).collect(),
+        magnitude_db: smoothed.iter().map(|c| 20.0 * c.norm().max(1e-300).log10()).collect(),
+        phase: smoothed.iter().map(|c| c.arg()).collect(),
+    })
+}
+
+/// Index of the direct sound, the first sample within 20 dB of the peak energy as in ISO 3382-1.
+fn onset(ir: &[f64]) -> usize {
+    let peak = ir.iter().map(|x| x * x).fold(0.0, f64::max);
+    ir.iter().position(|x| x * x >= 0.01 * peak).unwrap_or(0)
+}
+
+/// Evaluation range of [`rt60`], each extrapolated to a 60 dB decay.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecayMethod {
+    /// Early decay time, fitted from 0 to -10 dB.
+    Edt,
+    /// Fitted from -5 to -25 dB.
+    T20,
+    /// Fitted from -5 to -35 dB.
+    T30,
+}
+
+/// Estimates the reverberation time in seconds from an impulse response.
+///     The Schroeder backward integrated energy decay curve, starting at the direct sound, is fitted with a least
+///     squares line over the range of `method` and extrapolated to -60 dB.
+pub fn rt60(ir: Vec<f64>, fs: f64, method: DecayMethod) -> Result<f64, Box<dyn Error>> {
+    if ir.is_empty() || fs <= 0.0 {
+        return Err("Impulse response must not be empty and fs must be greater than zero.".into());
+    }
+
+    let ir = &ir[onset(&ir)..];
+    let mut decay = vec![0.0; ir.len()];
+    let mut energy = 0.0;
+    for (d, x) in decay.iter_mut().zip(ir).rev() {
+        energy += x * x;
+        *d = energy;
+    }
+    if energy == 0.0 {
+        return Err("Impulse response holds no energy.".into());
+    }
+
+    let (top, bottom) = match method {
+        DecayMethod::Edt => (0.0, -10.0),
+        DecayMethod::T20 => (-5.0, -25.0),
+        DecayMethod::T30 => (-5.0, -35.0),
+    };
+    let points: Vec<(f64, f64)> = decay
+        .iter()
+        .enumerate()
+        .map(|(n, d)| (n as f64 / fs, 10.0 * (d / energy).log10()))
+        .filter(|(_, db)| (bottom..=top).contains(db))
+        .collect();
+    if points.len() < 2 || decay.last().is_some_and(|d| 10.0 * (d / energy).log10() > bottom) {
+        return Err(format!("The energy decay does not reach {} dB, the impulse response is too short or noisy.", bottom).into());
+    }
+
+    let count = points.len() as f64;
+    let (mean_t, mean_db) = points.iter().fold((0.0, 0.0), |(t, d), (pt, pd)| (t + pt / count, d + pd / count));
+    let covariance: f64 = points.iter().map(|(t, d)| (t - mean_t) * (d - mean_db)).sum();
+    let variance: f64 = points.iter().map(|(t, _)| (t - mean_t).powi(2)).sum();
+    let slope = covariance / variance;
+    if slope >= 0.0 {
+        return Err("The energy decay curve does not decay.".into());
+    }
+    Ok(-60.0 / slope)
+}
+
+/// Early to late energy ratios of an impulse response, see [`clarity`].
+pub struct ClarityMetrics {
+    /// Clarity for speech in dB, energy before 50 ms over energy after it.
+    pub c50: f64,
+    /// Clarity for music in dB, with the boundary at 80 ms.
+    pub c80: f64,
+    /// Definition, the fraction of the energy arriving in the first 50 ms.
+    pub d50: f64,
+}
+
+/// Computes the clarity and definition metrics of an impulse response, with times counted from the direct sound.
+pub fn clarity(ir: Vec<f64>, fs: f64) -> Result<ClarityMetrics, Box<dyn Error>> {
+    if ir.is_empty() || fs <= 0.0 {
+        return Err("Impulse response must not be empty and fs must be greater than zero.".into());
+    }
+
+    let ir = &ir[onset(&ir)..];
+    let early_energy = |ms: f64| {
+        let split = ((ms / 1000.0 * fs).round() as usize).min(ir.len());
+        ir[..split].iter().map(|x| x * x).sum::<f64>()
+    };
+    let total: f64 = ir.iter().map(|x| x * x).sum();
+    if total == 0.0 {
+        return Err("Impulse response holds no energy.".into());
+    }
+    let ratio_db = |early: f64| 10.0 * (early / (total - early)).log10();
+
+    let (e50, e80) = (early_energy(50.0), early_energy(80.0));
+    Ok(ClarityMetrics { c50: ratio_db(e50), c80: ratio_db(e80), d50: e50 / total })
+}
@@ -0,0 +1,124 @@
+//! Detection of active and silent regions in recordings.
+
+use rustfft::{num_complex::Complex, FftDirection};
+use std::error::Error;
+
+use crate::{plan_fft, Window};
+
+/// Energy above the noise floor a frame needs to count as active, in dB.
+const ENERGY_MARGIN_DB: f64 = 10.0;
+/// Spectral flatness below which a frame counts as tonal or harmonic rather than noise. White noise reads about
+///     0.56.
+const FLATNESS_THRESHOLD: f64 = 0.3;
+/// Shortest pause in seconds that separates two segments of activity.
+const MIN_PAUSE: f64 = 0.2;
+/// Length of the frames whose level decides what is silent, in seconds.
+const SILENCE_FRAME: f64 = 0.01;
+
+/// Per-frame decisions and merged segments from [`detect_voice_activity`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct VoiceActivity {
+    /// Samples per frame.
+    pub frame_len: usize,
+    /// Whether each frame holds voice.
+    pub flags: Vec<bool>,
+    /// Mean square of each frame in dB.
+    pub energy_db: Vec<f64>,
+    /// Spectral flatness of each frame, the geometric over the arithmetic mean of its power spectrum.
+    pub flatness: Vec<f64>,
+    /// `(start, end)` sample ranges of speech with `end` exclusive, pauses shorter than 200 ms bridged.
+    pub segments: Vec<(usize, usize)>,
+}
+
+/// Mean square in dB of consecutive frames of `frame_len` samples, the last one possibly shorter.
+fn frame_energy_db(data: &[f64], frame_len: usize) -> Vec<f64> {
+    data.chunks(frame_len)
+        .map(|frame| 10.0 * (frame.iter().map(|x| x * x).sum::<f64>() / frame.len() as f64).max(1e-20).log10())
+        .collect()
+}
+
+/// Joins the runs of set flags into sample ranges, bridging gaps of fewer than `min_gap` frames.
+fn merge_frames(flags: &[bool], frame_len: usize, min_gap: usize, len: usize) -> Vec<(usize, usize)> {
+    let mut segments: Vec<(usize, usize)> = Vec::new();
+    for (i, _) in flags.iter().enumerate().filter(|(_, &active)| active) {
+        let (start, end) = (i * frame_len, ((i + 1) * frame_len).min(len));
+        match segments.last_mut() {
+            Some(last) if start - last.1 < min_gap * frame_len => last.1 = end,
+            _ => segments.push((start, end)),
+        }
+    }
+    segments
+}
+
+/// Flags the frames of `frame_ms` milliseconds that hold speech from their energy and spectral flatness.
+///     A frame is active when it is at least 10 dB above the noise floor, taken as the 10th percentile of the frame
+///     energies, and either harmonic (flatness below 0.3) or 20 dB above the floor, so loud fricatives count too.
+///     Active frames closer than 200 ms are merged into segments. 10 to 30 ms frames suit speech.
+pub fn detect_voice_activity(data: &[f64], fs: f64, frame_ms: f64) -> Result<VoiceActivity, Box<dyn Error>> {
+    let frame_len = (frame_ms * fs / 1000.0).round() as usize;
+    if frame_len < 4 {
+        return Err("frame_ms must span at least 4 samples.".into());
+    }
+    if data.len() < frame_len {
+        return Err("The data must hold at least one frame.".into());
+    }
+
+    let energy_db = frame_energy_db(data, frame_len);
+    let mut sorted = energy_db.clone();
+    sorted.sort_by(f64::total_cmp);
+    let floor = sorted[sorted.len() / 10];
+
+    let window = Window::Hann.coefficients(frame_len);
+    let fft = plan_fft(frame_len, FftDirection::Forward);
+    let flatness: Vec<f64> = data
+        .chunks(frame_len)
+        .map(|frame| {
+            // The short last frame is zero padded
+            let mut buffer: Vec<Complex<f64>> =
+                window.iter().enumerate().map(|(n, w)| Complex::new(w * frame.get(n).copied().unwrap_or(0.0), 0.0)).collect();
+            fft.process(&mut buffer);
+            // DC carries the offset rather than the content
+            let power: Vec<f64> = buffer[1..=frame_len / 2].iter().map(|c| c.norm_sqr() + 1e-30).collect();
+            let geometric = (power.iter().map(|p| p.ln()).sum::<f64>() / power.len() as f64).exp();
+            geometric / (power.iter().sum::<f64>() / power.len() as f64)
+        })
+        .collect();
+
+    let flags: Vec<bool> = energy_db
+        .iter()
+        .zip(&flatness)
+        .map(|(&e, &f)| e > floor + ENERGY_MARGIN_DB && (f < FLATNESS_THRESHOLD || e > floor + 2.0 * ENERGY_MARGIN_DB))
+        .collect();
+    let min_gap = ((MIN_PAUSE * fs / frame_len as f64).ceil() as usize).max(1);
+    let segments = merge_frames(&flags, frame_len, min_gap, data.len());
+    log::debug!("{} of {} frames active in {} segments", flags.iter().filter(|&&f| f).count(), flags.len(), segments.len());
+
+    Ok(VoiceActivity { frame_len, flags, energy_db, flatness, segments })
+}
+
+/// Segments of the data louder than `threshold_db` below its loudest 10 ms frame, with `(start, end)` sample
+///     ranges and `end` exclusive. Silences of fewer than `min_silence_ms` milliseconds are bridged.
+pub fn split_on_silence(data: &[f64], fs: f64, threshold_db: f64, min_silence_ms: f64) -> Result<Vec<(usize, usize)>, Box<dyn Error>> {
+    if !(threshold_db > 0.0 && fs > 0.0 && min_silence_ms >= 0.0) {
+        return Err("fs and threshold_db must be positive and min_silence_ms not negative.".into());
+    }
+    let frame_len = ((SILENCE_FRAME * fs).round() as usize).max(1);
+    let energy_db = frame_energy_db(data, frame_len);
+    let loudest = energy_db.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    // Digital silence sits at the -200 dB floor of the frame energies and is never active
+    let flags: Vec<bool> = energy_db.iter().map(|&e| e > loudest - threshold_db && e > -200.0).collect();
+    let min_gap = ((min_silence_ms * fs / 1000.0 / frame_len as f64).ceil() as usize).max(1);
+    let segments = merge_frames(&flags, frame_len, min_gap, data.len());
+    log::debug!("Split {} samples into {} segments at {} dB below the peak", data.len(), segments.len(), threshold_db);
+    Ok(segments)
+}
+
+/// Sample range `(start, end)` left after removing the leading and trailing silence, 10 ms frames at least
+///     `threshold_db` below the loudest one. Empty, `(0, 0)`, when the data is silent throughout.
+pub fn trim_silence(data: &[f64], fs: f64, threshold_db: f64) -> Result<(usize, usize), Box<dyn Error>> {
+    let segments = split_on_silence(data, fs, threshold_db, 0.0)?;
+    Ok(match (segments.first(), segments.last()) {
+        (Some(first), Some(last)) => (first.0, last.1),
+        _ => (0, 0),
+    })
+}
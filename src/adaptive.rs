@@ -0,0 +1,78 @@
+//! Adaptive filters for noise cancellation and echo removal.
+
+use std::error::Error;
+
+/// Weight update rule of an [`AdaptiveFilter`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AdaptiveAlgorithm {
+    /// Least mean squares.
+    Lms,
+    /// Normalized least mean squares, stepping relative to the power of the reference window.
+    Nlms,
+}
+
+/// FIR filter whose taps adapt so the filtered reference matches the primary input.
+///     State persists between calls to [`AdaptiveFilter::process`], so a stream can be processed in blocks.
+#[derive(Clone, Debug)]
+pub struct AdaptiveFilter {
+    weights: Vec<f64>,
+    /// Most recent reference samples, newest first.
+    history: Vec<f64>,
+    step_size: f64,
+    algorithm: AdaptiveAlgorithm,
+    /// Regularization of the NLMS normalization for silent reference windows.
+    epsilon: f64,
+}
+
+impl AdaptiveFilter {
+    pub fn new(taps: usize, step_size: f64, algorithm: AdaptiveAlgorithm) -> Result<Self, Box<dyn Error>> {
+        if taps == 0 || step_size <= 0.0 {
+            return Err("Taps and step size must be positive.".into());
+        }
+        if algorithm == AdaptiveAlgorithm::Nlms && step_size >= 2.0 {
+            return Err("The NLMS step size must be below 2 for the filter to converge.".into());
+        }
+        Ok(AdaptiveFilter { weights: vec![0.0; taps], history: vec![0.0; taps], step_size, algorithm, epsilon: 1e-8 })
+    }
+
+    /// Filters a block of `reference` samples to estimate the part of `primary` correlated with it.
+    ///     Returns the estimate and the error `primary - estimate`, which is the cleaned signal in noise cancellation.
+    pub fn process(&mut self, primary: &[f64], reference: &[f64]) -> Result<(Vec<f64>, Vec<f64>), Box<dyn Error>> {
+        if primary.len() != reference.len() {
+            return Err("Primary and reference blocks must have the same length.".into());
+        }
+
+        let mut estimates = Vec::with_capacity(primary.len());
+        let mut errors = Vec::with_capacity(primary.len());
+        for (&desired, &input) in primary.iter().zip(reference) {
+            self.history.rotate_right(1);
+            self.history[0] = input;
+
+            let estimate: f64 = self.weights.iter().zip(&self.history).map(|(w, x)| w * x).sum();
+            let error = desired - estimate;
+            let step = match self.algorithm {
+                AdaptiveAlgorithm::Lms => self.step_size,
+                AdaptiveAlgorithm::Nlms => {
+                    self.step_size / (self.epsilon + self.history.iter().map(|x| x * x).sum::<f64>())
+                }
+            };
+            self.weights.iter_mut().zip(&self.history).for_each(|(w, x)| *w += step * error * x);
+
+            estimates.push(estimate);
+            errors.push(error);
+        }
+
+        Ok((estimates, errors))
+    }
+
+    /// Current filter taps.
+    pub fn weights(&self) -> &[f64] {
+        &self.weights
+    }
+
+    /// Clears the taps and the reference history.
+    pub fn reset(&mut self) {
+        self.weights.iter_mut().for_each(|w| *w = 0.0);
+        self.history.iter_mut().for_each(|x| *x = 0.0);
+    }
+}
@@ -0,0 +1,212 @@
+//! End-to-end analysis of recordings on disk.
+
+use rustfft::{FftDirection, num_complex::Complex};
+use std::error::Error;
+use std::path::Path;
+
+use crate::{cache, compute_psd_welch, generate_plot, plan_fft, read_csv, read_edf, read_audio, read_mseed, Window};
+
+/// Trend removed by [`detrend`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Detrend {
+    None,
+    /// Subtracts the mean.
+    Mean,
+    /// Subtracts the least squares straight line.
+    Linear,
+}
+
+impl Detrend {
+    /// Lowercase name of the mode, as accepted by the Python bindings.
+    pub fn name(self) -> &'static str {
+        match self {
+            Detrend::None => "none",
+            Detrend::Mean => "mean",
+            Detrend::Linear => "linear",
+        }
+    }
+}
+
+/// Removes the mean or the least squares straight line from the data.
+pub fn detrend(data: Vec<f64>, mode: Detrend) -> Vec<f64> {
+    let len = data.len() as f64;
+    if data.is_empty() || mode == Detrend::None {
+        return data;
+    }
+    let mean = data.iter().sum::<f64>() / len;
+    let slope = match mode {
+        Detrend::Linear if data.len() > 1 => {
+            let center = (len - 1.0) / 2.0;
+            let covariance: f64 = data.iter().enumerate().map(|(n, x)| (n as f64 - center) * (x - mean)).sum();
+            covariance / (len * (len * len - 1.0) / 12.0)
+        }
+        _ => 0.0,
+    };
+    let center = (len - 1.0) / 2.0;
+    data.into_iter().enumerate().map(|(n, x)| x - mean - slope * (n as f64 - center)).collect()
+}
+
+/// Processing choices of [`analyze_file`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnalysisOptions {
+    /// Window applied to the whole record before the amplitude spectrum.
+    pub window: Window,
+    /// Segment length of the Welch PSD, shortened to the record length if needed.
+    pub nperseg: usize,
+    pub detrend: Detrend,
+    /// Renders PNG plots of the signal, spectrum and PSD.
+    pub plots: bool,
+}
+
+impl Default for AnalysisOptions {
+    fn default() -> Self {
+        AnalysisOptions { window: Window::Hann, nperseg: 1024, detrend: Detrend::Linear, plots: false }
+    }
+}
+
+/// Summary statistics of a record, computed before detrending.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SignalStats {
+    pub mean: f64,
+    pub std: f64,
+    pub rms: f64,
+    pub min: f64,
+    pub max: f64,
+    /// Peak magnitude over RMS.
+    pub crest_factor: f64,
+    /// Frequency of the largest amplitude spectrum bin above DC.
+    pub dominant_frequency: f64,
+}
+
+/// A PNG image with its name, e.g. `"psd"`.
+pub type NamedPlot = (String, Vec<u8>);
+
+/// Everything [`analyze_file`] computes for one record.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnalysisResult {
+    pub path: String,
+    pub fs: f64,
+    pub n_samples: usize,
+    pub options: AnalysisOptions,
+    /// One sided amplitude spectrum, scaled so a sinusoid on a bin reads its amplitude.
+    pub freqs: Vec<f64>,
+    pub amplitude: Vec<f64>,
+    pub psd_freqs: Vec<f64>,
+    /// Welch PSD in units of data²/Hz.
+    pub psd: Vec<f64>,
+    pub stats: SignalStats,
+    /// Named PNG images, empty unless [`AnalysisOptions::plots`] is set.
+    pub plots: Vec<NamedPlot>,
+}
+
+/// Loads the first channel of a CSV, WAV, FLAC, MiniSEED or EDF/BDF file, chosen by extension, with its sample rate.
+///     For CSV files the rate is inferred from the median step of the time column. A given `fs` overrides the
+///     rate found in the file.
+pub fn load_signal(path: &str, fs: Option<f64>) -> Result<(f64, Vec<f64>), Box<dyn Error>> {
+    let extension = Path::new(path).extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+    let (rate, data) = match extension.as_str() {
+        "csv" => {
+            let (time, data) = read_csv(path)?;
+            let mut steps: Vec<f64> = time.windows(2).map(|w| w[1] - w[0]).collect();
+            steps.sort_by(f64::total_cmp);
+            let rate = steps.get(steps.len() / 2).map_or(0.0, |step| 1.0 / step);
+            (rate, data)
+        }
+        "wav" | "flac" => {
+            let (rate, channels) = read_audio(path)?;
+            (rate, channels.into_iter().next().unwrap_or_default())
+        }
+        "mseed" | "miniseed" => read_mseed(path)?.into_iter().next().map(|c| (c.sample_rate, c.data)).unwrap_or_default(),
+        "edf" | "bdf" => read_edf(path, None)?.into_iter().next().map(|c| (c.sample_rate, c.data)).unwrap_or_default(),
+        _ => return Err(format!("Unsupported file type '{}', expected csv, wav, flac, mseed, edf or bdf.", extension).into()),
+    };
+
+    let rate = fs.unwrap_or(rate);
+    if data.is_empty() {
+        return Err(format!("{}: no samples found.", path).into());
+    }
+    if !(rate > 0.0 && rate.is_finite()) {
+        return Err(format!("{}: could not infer the sampling rate, pass fs.", path).into());
+    }
+    Ok((rate, data))
+}
+
+/// Loads a recording and computes its statistics, windowed amplitude spectrum and Welch PSD in one call.
+///     See [`load_signal`] for the supported files and how the sampling rate is found. Results are reused from the
+///     [`cache`] when it is enabled and neither the file nor the parameters changed.
+pub fn analyze_file(path: &str, fs: Option<f64>, options: &AnalysisOptions) -> Result<AnalysisResult, Box<dyn Error>> {
+    let key = cache::analysis_key(path, fs, options);
+    if let Some(result) = key.as_ref().and_then(|key| cache::load(key, cache::decode_analysis)) {
+        return Ok(result);
+    }
+    let (fs, data) = load_signal(path, fs)?;
+    let result = analyze_signal(path, data, fs, options)?;
+    if let Some(key) = key {
+        cache::store(&key, |encoder| cache::encode_analysis(encoder, &result));
+    }
+    Ok(result)
+}
+
+/// Same as [`analyze_file`] for data already in memory, `name` is recorded as the result path.
+pub fn analyze_signal(name: &str, data: Vec<f64>, fs: f64, options: &AnalysisOptions) -> Result<AnalysisResult, Box<dyn Error>> {
+    let len = data.len();
+    if len == 0 || fs <= 0.0 {
+        return Err("Data must not be empty and fs must be greater than zero.".into());
+    }
+    log::debug!("Analyzing {}: {} samples at {} Hz", name, len, fs);
+
+    let mean = data.iter().sum::<f64>() / len as f64;
+    let rms = (data.iter().map(|x| x * x).sum::<f64>() / len as f64).sqrt();
+    let std = (data.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / len as f64).sqrt();
+    let min = data.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = data.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let raw = if options.plots { data.clone() } else { Vec::new() };
+
+    let data = detrend(data, options.detrend);
+    let window = options.window.coefficients(len);
+    let window_sum: f64 = window.iter().sum();
+    let mut buffer: Vec<Complex<f64>> = data.iter().zip(&window).map(|(x, w)| Complex::new(x * w, 0.0)).collect();
+    plan_fft(len, FftDirection::Forward).process(&mut buffer);
+    let freqs: Vec<f64> = (0..=len / 2).map(|k| k as f64 * fs / len as f64).collect();
+    let amplitude: Vec<f64> = buffer[..=len / 2]
+        .iter()
+        .enumerate()
+        .map(|(k, c)| if k == 0 || 2 * k == len { c.norm() } else { 2.0 * c.norm() } / window_sum)
+        .collect();
+    let dominant = (1..amplitude.len()).max_by(|&i, &j| amplitude[i].total_cmp(&amplitude[j])).unwrap_or(0);
+
+    let (psd_freqs, psd) = compute_psd_welch(data, fs, options.nperseg)?;
+    let stats = SignalStats {
+        mean,
+        std,
+        rms,
+        min,
+        max,
+        crest_factor: if rms > 0.0 { min.abs().max(max.abs()) / rms } else { 0.0 },
+        dominant_frequency: freqs[dominant],
+    };
+
+    let mut plots = Vec::new();
+    if options.plots {
+        let db = |values: &[f64], scale: f64| values.iter().map(|v| scale * v.max(1e-300).log10()).collect::<Vec<_>>();
+        let time = raw.iter().enumerate().map(|(n, &x)| (n as f64 / fs, x)).collect();
+        plots.push(("signal".to_string(), generate_plot(time, "Time [s]", "Amplitude", name)?));
+        let spectrum = freqs.iter().copied().zip(db(&amplitude, 20.0)).collect();
+        plots.push(("spectrum".to_string(), generate_plot(spectrum, "Frequency [Hz]", "Amplitude [dB]", name)?));
+        let density = psd_freqs.iter().copied().zip(db(&psd, 10.0)).collect();
+        plots.push(("psd".to_string(), generate_plot(density, "Frequency [Hz]", "PSD [dB/Hz]", name)?));
+    }
+
+    Ok(AnalysisResult {
+        path: name.to_string(),
+        fs,
+        n_samples: len,
+        options: options.clone(),
+        freqs,
+        amplitude,
+        psd_freqs,
+        psd,
+        stats,
+        plots,
+    })
+}
@@ -0,0 +1,119 @@
+//! Sensor array processing: far-field beamforming and spatial spectra.
+
+use rustfft::num_complex::Complex;
+use std::error::Error;
+use std::f64::consts::PI;
+
+use crate::{cross_spectral_matrix, linalg, ComplexMatrix, Matrix};
+
+/// Beamformer used to scan the steering angles.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Beamformer {
+    /// Conventional (Bartlett) delay-and-sum beamformer.
+    DelayAndSum,
+    /// Minimum variance distortionless response (Capon) beamformer.
+    Mvdr,
+}
+
+/// Options shared by the spatial spectrum estimators.
+#[derive(Clone, Debug)]
+pub struct BeamformOptions {
+    /// Segment length of the cross-spectral matrix estimate.
+    pub nperseg: usize,
+    /// Propagation speed in metres per second.
+    pub speed: f64,
+    /// Frequency band `(low, high)` in Hz to integrate over, all bins above DC when `None`.
+    pub band: Option<(f64, f64)>,
+    /// Diagonal loading of the MVDR inverse, relative to the mean sensor power.
+    pub diagonal_loading: f64,
+}
+
+impl Default for BeamformOptions {
+    fn default() -> Self {
+        BeamformOptions { nperseg: 256, speed: 343.0, band: None, diagonal_loading: 1e-3 }
+    }
+}
+
+/// Steering vector of a plane wave arriving from `angle` (radians, counter-clockwise from the x axis) at `freq`.
+fn steering_vector(geometry: &[(f64, f64)], angle: f64, freq: f64, speed: f64) -> Vec<Complex<f64>> {
+    let (sin, cos) = angle.sin_cos();
+    geometry
+        .iter()
+        .map(|&(x, y)| {
+            // Sensors closer to the source receive the wavefront earlier by (r . u) / c
+            let lead = (x * cos + y * sin) / speed;
+            Complex::from_polar(1.0, 2.0 * PI * freq * lead)
+        })
+        .collect()
+}
+
+/// Computes the spatial power spectrum of a far-field source scanned over `steering_angles` (degrees).
+///     `geometry` holds the `(x, y)` sensor positions in metres, one per channel.
+pub fn spatial_spectrum(
+    channels: Matrix,
+    fs: f64,
+    geometry: Vec<(f64, f64)>,
+    steering_angles: Vec<f64>,
+    method: Beamformer,
+    options: &BeamformOptions,
+) -> Result<Vec<f64>, Box<dyn Error>> {
+    if geometry.len() != channels.len() {
+        return Err("Geometry must have one position per channel.".into());
+    }
+    if options.speed <= 0.0 {
+        return Err("Propagation speed must be greater than zero.".into());
+    }
+
+    let count = channels.len() as f64;
+    let spectra = cross_spectral_matrix(channels, fs, options.nperseg)?;
+    let (low, high) = options.band.unwrap_or((f64::MIN_POSITIVE, f64::INFINITY));
+    let bins: Vec<(f64, ComplexMatrix)> = spectra
+        .freqs
+        .into_iter()
+        .zip(spectra.matrices)
+        .filter(|(f, _)| *f > 0.0 && *f >= low && *f <= high)
+        .map(|(f, matrix)| match method {
+            Beamformer::DelayAndSum => Ok((f, matrix)),
+            Beamformer::Mvdr => {
+                let count = matrix.len();
+                let loading = options.diagonal_loading * (0..count).map(|i| matrix[i][i].re).sum::<f64>() / count as f64;
+                let mut loaded = matrix;
+                (0..count).for_each(|i| loaded[i][i] += loading.max(f64::MIN_POSITIVE));
+                linalg::invert(&loaded)
+                    .map(|inverse| (f, inverse))
+                    .ok_or_else(|| format!("Cross-spectral matrix at {} Hz is singular.", f))
+            }
+        })
+        .collect::<Result<_, _>>()?;
+    if bins.is_empty() {
+        return Err("No frequency bins fall inside the requested band.".into());
+    }
+
+    let power = steering_angles
+        .iter()
+        .map(|angle| {
+            bins.iter()
+                .map(|(f, matrix)| {
+                    let steering = steering_vector(&geometry, angle.to_radians(), *f, options.speed);
+                    let form = linalg::quadratic_form(&steering, matrix, &steering).re;
+                    match method {
+                        Beamformer::DelayAndSum => form / (count * count),
+                        Beamformer::Mvdr => 1.0 / form.max(f64::MIN_POSITIVE),
+                    }
+                })
+                .sum()
+        })
+        .collect();
+
+    Ok(power)
+}
+
+/// Computes the delay-and-sum spatial spectrum with the default options (sound in air, 256 sample segments).
+pub fn delay_and_sum(
+    channels: Matrix,
+    fs: f64,
+    geometry: Vec<(f64, f64)>,
+    steering_angles: Vec<f64>,
+) -> Result<Vec<f64>, Box<dyn Error>> {
+    spatial_spectrum(channels, fs, geometry, steering_angles, Beamformer::DelayAndSum, &BeamformOptions::default())
+}
@@ -0,0 +1,271 @@
+//! Zero-copy export of float columns through the Arrow C data and stream interfaces.
+//!
+//! An [`ArrowTable`] owns its columns and hands them to Arrow consumers (polars, pyarrow, DuckDB, or Rust through
+//! `polars_arrow::ffi` / `arrow::ffi_stream`) as an [`ArrowArrayStream`] of one struct array with a float64 child per
+//! column. The child buffers point into the columns themselves, which stay alive until the consumer releases the
+//! last array, so nothing is copied. The structs are `#[repr(C)]` and match the layout in the Arrow specification.
+
+use std::error::Error;
+use std::ffi::{c_char, c_int, c_void, CStr, CString};
+use std::ptr;
+use std::sync::Arc;
+
+/// Schema of one field in the Arrow C data interface.
+#[repr(C)]
+pub struct ArrowSchema {
+    pub format: *const c_char,
+    pub name: *const c_char,
+    pub metadata: *const c_char,
+    pub flags: i64,
+    pub n_children: i64,
+    pub children: *mut *mut ArrowSchema,
+    pub dictionary: *mut ArrowSchema,
+    pub release: Option<unsafe extern "C" fn(*mut ArrowSchema)>,
+    pub private_data: *mut c_void,
+}
+
+/// Array data in the Arrow C data interface.
+#[repr(C)]
+pub struct ArrowArray {
+    pub length: i64,
+    pub null_count: i64,
+    pub offset: i64,
+    pub n_buffers: i64,
+    pub n_children: i64,
+    pub buffers: *mut *const c_void,
+    pub children: *mut *mut ArrowArray,
+    pub dictionary: *mut ArrowArray,
+    pub release: Option<unsafe extern "C" fn(*mut ArrowArray)>,
+    pub private_data: *mut c_void,
+}
+
+/// Stream of arrays sharing one schema in the Arrow C stream interface.
+#[repr(C)]
+pub struct ArrowArrayStream {
+    pub get_schema: Option<unsafe extern "C" fn(*mut ArrowArrayStream, *mut ArrowSchema) -> c_int>,
+    pub get_next: Option<unsafe extern "C" fn(*mut ArrowArrayStream, *mut ArrowArray) -> c_int>,
+    pub get_last_error: Option<unsafe extern "C" fn(*mut ArrowArrayStream) -> *const c_char>,
+    pub release: Option<unsafe extern "C" fn(*mut ArrowArrayStream)>,
+    pub private_data: *mut c_void,
+}
+
+// The private data only holds owned allocations and an `Arc` of the columns, so the structs can move between threads
+unsafe impl Send for ArrowSchema {}
+unsafe impl Send for ArrowArray {}
+unsafe impl Send for ArrowArrayStream {}
+
+// A struct that was never handed to a consumer still owns its data. Consumers taking it over clear `release`.
+impl Drop for ArrowSchema {
+    fn drop(&mut self) {
+        if let Some(release) = self.release {
+            unsafe { release(self) };
+        }
+    }
+}
+
+impl Drop for ArrowArray {
+    fn drop(&mut self) {
+        if let Some(release) = self.release {
+            unsafe { release(self) };
+        }
+    }
+}
+
+impl Drop for ArrowArrayStream {
+    fn drop(&mut self) {
+        if let Some(release) = self.release {
+            unsafe { release(self) };
+        }
+    }
+}
+
+/// Named float64 columns of equal length, shared by every schema, array and stream exported from them.
+#[derive(Clone, Debug)]
+pub struct ArrowTable {
+    columns: Arc<Vec<(CString, Vec<f64>)>>,
+}
+
+impl ArrowTable {
+    /// Takes ownership of the columns. Fails if they differ in length or a name contains a NUL byte.
+    pub fn new(columns: Vec<(String, Vec<f64>)>) -> Result<Self, Box<dyn Error>> {
+        if let Some((name, column)) = columns.iter().find(|(_, column)| column.len() != columns[0].1.len()) {
+            return Err(format!(
+                "Column '{}' has {} rows, expected {}.",
+                name,
+                column.len(),
+                columns[0].1.len()
+            )
+            .into());
+        }
+        let columns = columns
+            .into_iter()
+            .map(|(name, column)| Ok((CString::new(name)?, column)))
+            .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+        Ok(ArrowTable { columns: Arc::new(columns) })
+    }
+
+    /// The number of rows, zero for a table without columns.
+    pub fn num_rows(&self) -> usize {
+        self.columns.first().map_or(0, |(_, column)| column.len())
+    }
+
+    /// The column names in order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.columns.iter().map(|(name, _)| name.to_str().unwrap_or_default())
+    }
+
+    /// The column with the given name.
+    pub fn column(&self, name: &str) -> Option<&[f64]> {
+        self.columns.iter().find(|(n, _)| n.as_bytes() == name.as_bytes()).map(|(_, column)| column.as_slice())
+    }
+
+    /// Exports the schema, a struct with a float64 field per column.
+    pub fn to_schema(&self) -> ArrowSchema {
+        let children: Vec<*mut ArrowSchema> = self
+            .columns
+            .iter()
+            .map(|(name, _)| Box::into_raw(Box::new(new_schema(c"g", name.clone(), Vec::new()))))
+            .collect();
+        new_schema(c"+s", CString::default(), children)
+    }
+
+    /// Exports all columns as one struct array without copying them.
+    pub fn to_array(&self) -> ArrowArray {
+        let children: Vec<*mut ArrowArray> = self
+            .columns
+            .iter()
+            .map(|(_, column)| {
+                let buffers = vec![ptr::null(), column.as_ptr() as *const c_void];
+                Box::into_raw(Box::new(new_array(self.clone(), buffers, Vec::new())))
+            })
+            .collect();
+        new_array(self.clone(), vec![ptr::null()], children)
+    }
+
+    /// Exports the table as a stream yielding [`to_array`](Self::to_array) once.
+    pub fn to_stream(&self) -> ArrowArrayStream {
+        let private = Box::new(StreamPrivate { table: self.clone(), done: false });
+        ArrowArrayStream {
+            get_schema: Some(stream_get_schema),
+            get_next: Some(stream_get_next),
+            get_last_error: Some(stream_get_last_error),
+            release: Some(release_stream),
+            private_data: Box::into_raw(private) as *mut c_void,
+        }
+    }
+}
+
+struct SchemaPrivate {
+    name: CString,
+    children: Vec<*mut ArrowSchema>,
+}
+
+struct ArrayPrivate {
+    // Keeps the columns the buffers point into alive
+    _table: ArrowTable,
+    _buffers: Vec<*const c_void>,
+    children: Vec<*mut ArrowArray>,
+}
+
+struct StreamPrivate {
+    table: ArrowTable,
+    done: bool,
+}
+
+fn new_schema(format: &'static CStr, name: CString, mut children: Vec<*mut ArrowSchema>) -> ArrowSchema {
+    let mut private = Box::new(SchemaPrivate { name, children: Vec::new() });
+    let children_ptr = children.as_mut_ptr();
+    let n_children = children.len() as i64;
+    private.children = children;
+    ArrowSchema {
+        format: format.as_ptr(),
+        name: private.name.as_ptr(),
+        metadata: ptr::null(),
+        flags: 0,
+        n_children,
+        children: children_ptr,
+        dictionary: ptr::null_mut(),
+        release: Some(release_schema),
+        private_data: Box::into_raw(private) as *mut c_void,
+    }
+}
+
+fn new_array(table: ArrowTable, mut buffers: Vec<*const c_void>, mut children: Vec<*mut ArrowArray>) -> ArrowArray {
+    let length = table.num_rows() as i64;
+    let (n_buffers, buffers_ptr) = (buffers.len() as i64, buffers.as_mut_ptr());
+    let (n_children, children_ptr) = (children.len() as i64, children.as_mut_ptr());
+    let private = Box::new(ArrayPrivate { _table: table, _buffers: buffers, children });
+    ArrowArray {
+        length,
+        null_count: 0,
+        offset: 0,
+        n_buffers,
+        n_children,
+        buffers: buffers_ptr,
+        children: children_ptr,
+        dictionary: ptr::null_mut(),
+        release: Some(release_array),
+        private_data: Box::into_raw(private) as *mut c_void,
+    }
+}
+
+unsafe extern "C" fn release_schema(schema: *mut ArrowSchema) {
+    let schema = &mut *schema;
+    let private = Box::from_raw(schema.private_data as *mut SchemaPrivate);
+    // Dropping a child releases it unless the consumer moved it out
+    for child in private.children {
+        drop(Box::from_raw(child));
+    }
+    schema.release = None;
+}
+
+unsafe extern "C" fn release_array(array: *mut ArrowArray) {
+    let array = &mut *array;
+    let private = Box::from_raw(array.private_data as *mut ArrayPrivate);
+    for child in private.children {
+        drop(Box::from_raw(child));
+    }
+    array.release = None;
+}
+
+unsafe extern "C" fn stream_get_schema(stream: *mut ArrowArrayStream, out: *mut ArrowSchema) -> c_int {
+    let private = &*((*stream).private_data as *const StreamPrivate);
+    ptr::write(out, private.table.to_schema());
+    0
+}
+
+unsafe extern "C" fn stream_get_next(stream: *mut ArrowArrayStream, out: *mut ArrowArray) -> c_int {
+    let private = &mut *((*stream).private_data as *mut StreamPrivate);
+    if private.done {
+        // A released array marks the end of the stream
+        ptr::write(
+            out,
+            ArrowArray {
+                length: 0,
+                null_count: 0,
+                offset: 0,
+                n_buffers: 0,
+                n_children: 0,
+                buffers: ptr::null_mut(),
+                children: ptr::null_mut(),
+                dictionary: ptr::null_mut(),
+                release: None,
+                private_data: ptr::null_mut(),
+            },
+        );
+    } else {
+        private.done = true;
+        ptr::write(out, private.table.to_array());
+    }
+    0
+}
+
+unsafe extern "C" fn stream_get_last_error(_stream: *mut ArrowArrayStream) -> *const c_char {
+    ptr::null()
+}
+
+unsafe extern "C" fn release_stream(stream: *mut ArrowArrayStream) {
+    let stream = &mut *stream;
+    drop(Box::from_raw(stream.private_data as *mut StreamPrivate));
+    stream.release = None;
+}
@@ -0,0 +1,250 @@
+//! Live audio capture and playback through ALSA, built with the `audio` feature on Linux only.
+//!
+//! libasound is loaded when the first stream is opened instead of being linked, so building needs no ALSA
+//! development files and a machine without it gets an error from [`AudioCapture::open`] or [`play`] rather than
+//! a module that fails to import. Samples are exchanged with ALSA as interleaved 32 bit floats with full scale at
+//! ±1, the `plug` layer of the default devices converting to whatever the hardware supports.
+
+use libloading::Library;
+use std::error::Error;
+use std::ffi::{c_char, c_int, c_long, c_uint, c_ulong, c_void, CStr, CString};
+use std::sync::OnceLock;
+
+use crate::{channel_len, Matrix, Progress};
+
+const SND_PCM_STREAM_PLAYBACK: c_int = 0;
+const SND_PCM_STREAM_CAPTURE: c_int = 1;
+const SND_PCM_ACCESS_RW_INTERLEAVED: c_int = 3;
+#[cfg(target_endian = "little")]
+const SND_PCM_FORMAT_FLOAT: c_int = 14;
+#[cfg(target_endian = "big")]
+const SND_PCM_FORMAT_FLOAT: c_int = 15;
+
+/// Latency requested from ALSA, in microseconds.
+const LATENCY_US: c_uint = 100_000;
+
+type PcmHandle = *mut c_void;
+
+/// The libasound functions used, resolved once per process.
+struct Alsa {
+    open: unsafe extern "C" fn(*mut PcmHandle, *const c_char, c_int, c_int) -> c_int,
+    set_params: unsafe extern "C" fn(PcmHandle, c_int, c_int, c_uint, c_uint, c_int, c_uint) -> c_int,
+    readi: unsafe extern "C" fn(PcmHandle, *mut c_void, c_ulong) -> c_long,
+    writei: unsafe extern "C" fn(PcmHandle, *const c_void, c_ulong) -> c_long,
+    recover: unsafe extern "C" fn(PcmHandle, c_int, c_int) -> c_int,
+    drain: unsafe extern "C" fn(PcmHandle) -> c_int,
+    close: unsafe extern "C" fn(PcmHandle) -> c_int,
+    strerror: unsafe extern "C" fn(c_int) -> *const c_char,
+    // Keeps the function pointers above valid
+    _library: Library,
+}
+
+impl Alsa {
+    fn load() -> Result<Alsa, String> {
+        // SAFETY: libasound runs no initialisation code with requirements on the caller, and every symbol is
+        //     given the signature of its declaration in alsa/pcm.h or alsa/error.h
+        unsafe {
+            let library = Library::new("libasound.so.2").map_err(|e| format!("ALSA is not available: {}", e))?;
+            macro_rules! symbol {
+                ($name:literal) => {
+                    *library.get($name).map_err(|e| format!("ALSA is not available: {}", e))?
+                };
+            }
+            Ok(Alsa {
+                open: symbol!(b"snd_pcm_open\0"),
+                set_params: symbol!(b"snd_pcm_set_params\0"),
+                readi: symbol!(b"snd_pcm_readi\0"),
+                writei: symbol!(b"snd_pcm_writei\0"),
+                recover: symbol!(b"snd_pcm_recover\0"),
+                drain: symbol!(b"snd_pcm_drain\0"),
+                close: symbol!(b"snd_pcm_close\0"),
+                strerror: symbol!(b"snd_strerror\0"),
+                _library: library,
+            })
+        }
+    }
+
+    fn get() -> Result<&'static Alsa, Box<dyn Error>> {
+        static ALSA: OnceLock<Result<Alsa, String>> = OnceLock::new();
+        ALSA.get_or_init(Alsa::load).as_ref().map_err(|e| e.clone().into())
+    }
+
+    fn describe(&self, code: c_int) -> String {
+        // SAFETY: snd_strerror returns a static string for any code
+        unsafe { CStr::from_ptr((self.strerror)(code)) }.to_string_lossy().into_owned()
+    }
+}
+
+/// An open PCM stream, closed when dropped.
+struct Pcm {
+    alsa: &'static Alsa,
+    handle: PcmHandle,
+    channels: usize,
+}
+
+// SAFETY: the handle is only used through `&mut self` or on drop, so one thread at a time, which ALSA allows
+unsafe impl Send for Pcm {}
+
+impl Pcm {
+    fn open(device: &str, stream: c_int, fs: f64, channels: usize) -> Result<Pcm, Box<dyn Error>> {
+        if !(fs >= 1.0 && fs <= c_uint::MAX as f64) || fs.fract() != 0.0 || channels == 0 {
+            return Err("fs must be a whole number of Hz and there must be at least one channel.".into());
+        }
+        let alsa = Alsa::get()?;
+        let name = CString::new(device)?;
+        let mut handle: PcmHandle = std::ptr::null_mut();
+        // SAFETY: `handle` and `name` outlive the call, which sets the handle only on success
+        let code = unsafe { (alsa.open)(&mut handle, name.as_ptr(), stream, 0) };
+        if code < 0 {
+            return Err(format!("Cannot open audio device '{}': {}", device, alsa.describe(code)).into());
+        }
+        let pcm = Pcm { alsa, handle, channels };
+        // SAFETY: the handle is open, soft resampling (1) lets the plug layer convert to the requested rate
+        let code = unsafe {
+            (alsa.set_params)(handle, SND_PCM_FORMAT_FLOAT, SND_PCM_ACCESS_RW_INTERLEAVED, channels as c_uint, fs as c_uint, 1, LATENCY_US)
+        };
+        if code < 0 {
+            return Err(format!("Cannot configure audio device '{}': {}", device, alsa.describe(code)).into());
+        }
+        log::debug!("Opened audio device '{}' with {} channels at {} Hz", device, channels, fs);
+        Ok(pcm)
+    }
+
+    /// Recovers from an overrun, underrun or suspend, failing on any other error.
+    fn recover(&mut self, code: c_long) -> Result<(), Box<dyn Error>> {
+        log::warn!("Audio stream interrupted: {}", self.alsa.describe(code as c_int));
+        // SAFETY: the handle is open, the final 1 keeps ALSA from printing the error itself
+        let code = unsafe { (self.alsa.recover)(self.handle, code as c_int, 1) };
+        if code < 0 {
+            return Err(format!("Audio stream failed: {}", self.alsa.describe(code)).into());
+        }
+        Ok(())
+    }
+
+    /// Fills the interleaved buffer with captured frames, blocking until it is full.
+    fn read(&mut self, buffer: &mut [f32]) -> Result<(), Box<dyn Error>> {
+        let mut done = 0;
+        while done < buffer.len() {
+            let rest = &mut buffer[done..];
+            // SAFETY: the buffer holds `rest.len() / channels` whole frames of floats
+            let frames = unsafe { (self.alsa.readi)(self.handle, rest.as_mut_ptr().cast(), (rest.len() / self.channels) as c_ulong) };
+            if frames < 0 {
+                self.recover(frames)?;
+            } else {
+                done += frames as usize * self.channels;
+            }
+        }
+        Ok(())
+    }
+
+    /// Queues the interleaved frames for playback, blocking while the device buffer is full.
+    fn write(&mut self, buffer: &[f32]) -> Result<(), Box<dyn Error>> {
+        let mut done = 0;
+        while done < buffer.len() {
+            let rest = &buffer[done..];
+            // SAFETY: the buffer holds `rest.len() / channels` whole frames of floats
+            let frames = unsafe { (self.alsa.writei)(self.handle, rest.as_ptr().cast(), (rest.len() / self.channels) as c_ulong) };
+            if frames < 0 {
+                self.recover(frames)?;
+            } else {
+                done += frames as usize * self.channels;
+            }
+        }
+        Ok(())
+    }
+
+    /// Blocks until the queued frames have been played.
+    fn drain(&mut self) -> Result<(), Box<dyn Error>> {
+        // SAFETY: the handle is open
+        let code = unsafe { (self.alsa.drain)(self.handle) };
+        if code < 0 {
+            return Err(format!("Audio playback failed: {}", self.alsa.describe(code)).into());
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Pcm {
+    fn drop(&mut self) {
+        // SAFETY: the handle is open and not used again, closing drops any frames still queued
+        unsafe { (self.alsa.close)(self.handle) };
+    }
+}
+
+/// A live input stream from a microphone or line input, yielding blocks of `chunk_frames` frames with one vector
+///     per channel, like [`WavStream`](crate::WavStream) does for files. Each block can go straight into the
+///     spectral estimators and a [`SpectrumAccumulator`](crate::SpectrumAccumulator) for live spectrum monitoring.
+///     The stream never ends, and blocks are read as they arrive, so a consumer slower than real time loses
+///     samples to overruns, which are logged and recovered from.
+pub struct AudioCapture {
+    pcm: Pcm,
+    fs: f64,
+    chunk_frames: usize,
+}
+
+impl AudioCapture {
+    /// Opens the ALSA capture device, e.g. `"default"` or `"hw:1,0"`, at `fs` Hz with `channels` channels.
+    pub fn open(device: &str, fs: f64, channels: usize, chunk_frames: usize) -> Result<AudioCapture, Box<dyn Error>> {
+        if chunk_frames == 0 {
+            return Err("chunk_frames must be positive.".into());
+        }
+        let pcm = Pcm::open(device, SND_PCM_STREAM_CAPTURE, fs, channels)?;
+        Ok(AudioCapture { pcm, fs, chunk_frames })
+    }
+
+    pub fn sample_rate(&self) -> f64 {
+        self.fs
+    }
+
+    pub fn channels(&self) -> usize {
+        self.pcm.channels
+    }
+
+    /// Frames per block yielded by the iterator.
+    pub fn chunk_frames(&self) -> usize {
+        self.chunk_frames
+    }
+
+    /// Captures the next `frames` frames, blocking until they have arrived.
+    pub fn read(&mut self, frames: usize) -> Result<Matrix, Box<dyn Error>> {
+        let channels = self.pcm.channels;
+        let mut buffer = vec![0f32; frames * channels];
+        self.pcm.read(&mut buffer)?;
+        Ok((0..channels).map(|c| buffer.iter().skip(c).step_by(channels).map(|&x| x as f64).collect()).collect())
+    }
+}
+
+impl Iterator for AudioCapture {
+    type Item = Result<Matrix, Box<dyn Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.read(self.chunk_frames))
+    }
+}
+
+/// Plays the channels, at full scale for ±1, on the ALSA playback `device` at `fs` Hz and returns when playback
+///     has finished.
+pub fn play(channels: &Matrix, fs: f64, device: &str) -> Result<(), Box<dyn Error>> {
+    play_with_progress(channels, fs, device, &mut |_| true)
+}
+
+/// Same as [`play`], reporting the fraction of samples queued to `progress`. Cancelling stops playback at once.
+pub fn play_with_progress(
+    channels: &Matrix,
+    fs: f64,
+    device: &str,
+    progress: &mut dyn FnMut(f64) -> bool,
+) -> Result<(), Box<dyn Error>> {
+    let len = channel_len(channels)?;
+    let mut pcm = Pcm::open(device, SND_PCM_STREAM_PLAYBACK, fs, channels.len())?;
+    let mut progress = Progress::new(progress);
+    // Blocks of a tenth of a second keep cancellation prompt
+    let block = ((fs / 10.0) as usize).max(1);
+    for start in (0..len).step_by(block) {
+        let end = (start + block).min(len);
+        let buffer: Vec<f32> = (start..end).flat_map(|n| channels.iter().map(move |c| c[n] as f32)).collect();
+        pcm.write(&buffer)?;
+        progress.update(end as f64 / len as f64)?;
+    }
+    pcm.drain()
+}
@@ -0,0 +1,201 @@
+//! Batch analysis of many recordings selected by a glob pattern.
+
+use std::error::Error;
+use std::path::{Component, Path, PathBuf};
+
+use crate::{analyze_file, threads, AnalysisOptions, AnalysisResult, SignalStats};
+
+/// Matches a single path component against a pattern with `*` (any run of characters) and `?` (one character).
+fn matches_component(pattern: &[char], name: &[char]) -> bool {
+    match (pattern.first(), name.first()) {
+        (None, None) => true,
+        (Some('*'), _) => matches_component(&pattern[1..], name) || (!name.is_empty() && matches_component(pattern, &name[1..])),
+        (Some('?'), Some(_)) => matches_component(&pattern[1..], &name[1..]),
+        (Some(p), Some(n)) if p == n => matches_component(&pattern[1..], &name[1..]),
+        _ => false,
+    }
+}
+
+fn expand(base: &Path, components: &[String], found: &mut Vec<PathBuf>) -> Result<(), Box<dyn Error>> {
+    let Some((first, rest)) = components.split_first() else {
+        if base.is_file() {
+            found.push(base.to_path_buf());
+        }
+        return Ok(());
+    };
+    if first == "**" {
+        // Zero directories, then one more level with the `**` still pending
+        expand(base, rest, found)?;
+        for entry in std::fs::read_dir(base)? {
+            let entry = entry?;
+            let path = entry.path();
+            if path.is_dir() && !entry.file_name().to_string_lossy().starts_with('.') {
+                expand(&path, components, found)?;
+            }
+        }
+        return Ok(());
+    }
+    if !first.contains(['*', '?']) {
+        return expand(&base.join(first), rest, found);
+    }
+
+    let pattern: Vec<char> = first.chars().collect();
+    if base.is_dir() {
+        for entry in std::fs::read_dir(base)? {
+            let entry = entry?;
+            let name: Vec<char> = entry.file_name().to_string_lossy().chars().collect();
+            if name.first() != Some(&'.') && matches_component(&pattern, &name) {
+                expand(&entry.path(), rest, found)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Splits a glob pattern into the leading directory without wildcards and the components after it.
+fn split_pattern(pattern: &str) -> Result<(PathBuf, Vec<String>), Box<dyn Error>> {
+    let path = Path::new(pattern);
+    let mut base = PathBuf::new();
+    let mut components = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) if components.is_empty() && !part.to_string_lossy().contains(['*', '?']) => base.push(part),
+            Component::Normal(part) => components.push(part.to_string_lossy().into_owned()),
+            other if components.is_empty() => base.push(other),
+            _ => return Err(format!("Unsupported component in glob pattern '{}'.", pattern).into()),
+        }
+    }
+    if base.as_os_str().is_empty() {
+        base.push(".");
+    }
+    Ok((base, components))
+}
+
+/// Lists the files matching a glob pattern in sorted order.
+///     `*` and `?` match within one path component and `**` matches any number of directories. Like in shells,
+///     wildcards skip hidden entries.
+pub fn glob(pattern: &str) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let (base, components) = split_pattern(pattern)?;
+    let mut found = Vec::new();
+    expand(&base, &components, &mut found)?;
+    found.sort();
+    found.dedup();
+    Ok(found)
+}
+
+/// Outcome of one file in [`analyze_directory`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct BatchEntry {
+    pub path: String,
+    pub fs: f64,
+    pub n_samples: usize,
+    /// Statistics of the record, `None` when the analysis failed.
+    pub stats: Option<SignalStats>,
+    pub error: Option<String>,
+}
+
+/// Writes the spectrum, PSD and plots of one result next to each other in `output_dir`.
+fn write_outputs(result: &AnalysisResult, stem: &str, output_dir: &Path) -> Result<(), Box<dyn Error>> {
+    let mut writer = csv::Writer::from_path(output_dir.join(format!("{}_spectrum.csv", stem)))?;
+    writer.write_record(["frequency", "amplitude"])?;
+    for (f, a) in result.freqs.iter().zip(&result.amplitude) {
+        writer.write_record([f.to_string(), a.to_string()])?;
+    }
+    writer.flush()?;
+
+    let mut writer = csv::Writer::from_path(output_dir.join(format!("{}_psd.csv", stem)))?;
+    writer.write_record(["frequency", "psd"])?;
+    for (f, p) in result.psd_freqs.iter().zip(&result.psd) {
+        writer.write_record([f.to_string(), p.to_string()])?;
+    }
+    writer.flush()?;
+
+    for (name, png) in &result.plots {
+        std::fs::write(output_dir.join(format!("{}_{}.png", stem, name)), png)?;
+    }
+    Ok(())
+}
+
+/// Analyzes every file matching `pattern` in parallel with [`analyze_file`].
+///     For each file `<name>_spectrum.csv`, `<name>_psd.csv` and the requested plots are written to `output_dir`,
+///     where `<name>` is the file path below the fixed leading directories of the pattern with separators and dots
+///     replaced by underscores. A `summary.csv` lists the statistics of every file, or its error. A failing file
+///     does not stop the batch.
+pub fn analyze_directory(
+    pattern: &str,
+    fs: Option<f64>,
+    options: &AnalysisOptions,
+    output_dir: &str,
+) -> Result<Vec<BatchEntry>, Box<dyn Error>> {
+    analyze_directory_with_progress(pattern, fs, options, output_dir, &mut |_| true)
+}
+
+/// Same as [`analyze_directory`], reporting the fraction of files analyzed so far to `progress`. Cancelling stops
+///     before the remaining files and the summary are written.
+pub fn analyze_directory_with_progress(
+    pattern: &str,
+    fs: Option<f64>,
+    options: &AnalysisOptions,
+    output_dir: &str,
+    progress: &mut dyn FnMut(f64) -> bool,
+) -> Result<Vec<BatchEntry>, Box<dyn Error>> {
+    let files = glob(pattern)?;
+    let (base, _) = split_pattern(pattern)?;
+    let output_dir = Path::new(output_dir);
+    std::fs::create_dir_all(output_dir)?;
+    log::debug!("Batch analysis of {} files matching {}", files.len(), pattern);
+
+    let entries: Vec<BatchEntry> = threads::par_map_with_progress(files, progress, |file| {
+        let path = file.to_string_lossy().into_owned();
+        let relative = file.strip_prefix(&base).unwrap_or(&file).to_string_lossy().into_owned();
+        let stem: String = relative
+            .chars()
+            .map(|c| if c == '/' || c == '\\' || c == '.' || c == ':' { '_' } else { c })
+            .collect();
+        let outcome = analyze_file(&path, fs, options)
+            .and_then(|result| write_outputs(&result, &stem, output_dir).map(|_| result))
+            .map_err(|e| e.to_string());
+        match outcome {
+            Ok(result) => BatchEntry {
+                path,
+                fs: result.fs,
+                n_samples: result.n_samples,
+                stats: Some(result.stats),
+                error: None,
+            },
+            Err(error) => {
+                log::warn!("{}: {}", path, error);
+                BatchEntry { path, fs: 0.0, n_samples: 0, stats: None, error: Some(error) }
+            }
+        }
+    })?;
+
+    let mut writer = csv::Writer::from_path(output_dir.join("summary.csv"))?;
+    writer.write_record([
+        "path",
+        "fs",
+        "n_samples",
+        "mean",
+        "std",
+        "rms",
+        "min",
+        "max",
+        "crest_factor",
+        "dominant_frequency",
+        "error",
+    ])?;
+    for entry in &entries {
+        let mut record = vec![entry.path.clone(), entry.fs.to_string(), entry.n_samples.to_string()];
+        match &entry.stats {
+            Some(s) => record.extend(
+                [s.mean, s.std, s.rms, s.min, s.max, s.crest_factor, s.dominant_frequency].map(|v| v.to_string()),
+            ),
+            None => record.extend(std::iter::repeat_n(String::new(), 7)),
+        }
+        record.push(entry.error.clone().unwrap_or_default());
+        writer.write_record(&record)?;
+    }
+    writer.flush()?;
+
+    Ok(entries)
+}
@@ -0,0 +1,287 @@
+//! Command line access to the spectral tools for CSV, WAV and FLAC files, and MP3 and Ogg files with the `lossy`
+//! feature.
+
+use std::error::Error;
+use std::io::Write;
+
+use fft_rust_in_python::*;
+
+const USAGE: &str = "Usage: fftcli <command> <input.csv|input.wav|input.flac> [options]
+
+Commands:
+  spectrum      One sided FFT magnitude (CSV: frequency,magnitude)
+  spectrogram   Hann windowed segment PSDs over time (CSV: time,frequency,psd)
+  filter        FFT band-pass filter (CSV: time,value)
+  plot          Time domain plot of the input
+
+Options:
+  -o, --output <path>   Output file, .png renders a plot and anything else writes CSV [default: CSV to stdout]
+  --fs <hz>             Sampling frequency, overrides the rate derived from the input
+  --channel <n>         Audio channel to analyse [default: 0]
+  --nperseg <n>         Spectrogram segment length [default: 256]
+  --low <hz>            Filter lower edge [default: 0]
+  --high <hz>           Filter upper edge [default: Nyquist]
+  --time-column <col>   CSV time column, by header name or zero based index
+  --data-column <col>   CSV data column, by header name or zero based index
+
+MP3 and Ogg inputs need a build with the lossy feature and libsndfile installed.
+CSV inputs without these options take the column named \"time\" in the header, or without a header the first
+strictly increasing column, as time and the first other column as the measured data. The time has to be strictly
+increasing and uniformly sampled unless --fs is given.";
+
+struct Options {
+    command: String,
+    input: String,
+    output: Option<String>,
+    fs: Option<f64>,
+    channel: usize,
+    nperseg: usize,
+    low: f64,
+    high: Option<f64>,
+    time_column: Option<String>,
+    data_column: Option<String>,
+}
+
+fn parse_args(args: &[String]) -> Result<Options, Box<dyn Error>> {
+    let mut positional = Vec::new();
+    let mut options = Options {
+        command: String::new(),
+        input: String::new(),
+        output: None,
+        fs: None,
+        channel: 0,
+        nperseg: 256,
+        low: 0.0,
+        high: None,
+        time_column: None,
+        data_column: None,
+    };
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if !arg.starts_with('-') {
+            positional.push(arg.clone());
+            continue;
+        }
+        let value = iter.next().ok_or_else(|| format!("Missing value for {}.", arg))?;
+        match arg.as_str() {
+            "-o" | "--output" => options.output = Some(value.clone()),
+            "--fs" => options.fs = Some(value.parse()?),
+            "--channel" => options.channel = value.parse()?,
+            "--nperseg" => options.nperseg = value.parse()?,
+            "--low" => options.low = value.parse()?,
+            "--high" => options.high = Some(value.parse()?),
+            "--time-column" => options.time_column = Some(value.clone()),
+            "--data-column" => options.data_column = Some(value.clone()),
+            _ => return Err(format!("Unknown option {}.", arg).into()),
+        }
+    }
+
+    match positional.as_slice() {
+        [command, input] => {
+            options.command = command.clone();
+            options.input = input.clone();
+            Ok(options)
+        }
+        _ => Err("Expected a command and an input file.".into()),
+    }
+}
+
+/// Loads the selected signal and its sampling frequency from an audio or CSV file.
+fn load_signal(options: &Options) -> Result<(Vec<f64>, f64), Box<dyn Error>> {
+    let input = options.input.to_lowercase();
+    let (data, fs) = if [".wav", ".flac", ".mp3", ".ogg", ".opus"].iter().any(|extension| input.ends_with(extension)) {
+        let (sample_rate, mut channels) = read_audio(&options.input)?;
+        if options.channel >= channels.len() {
+            return Err(format!("Channel {} not found, the file has {} channels.", options.channel, channels.len()).into());
+        }
+        (channels.swap_remove(options.channel), Some(sample_rate))
+    } else {
+        load_csv(options)?
+    };
+
+    match options.fs.or(fs) {
+        Some(fs) if fs > 0.0 && fs.is_finite() => Ok((data, fs)),
+        _ => Err("Could not determine the sampling frequency, pass it with --fs.".into()),
+    }
+}
+
+/// Finds a CSV column by header name or zero based index.
+fn find_column(header: Option<&csv::StringRecord>, width: usize, column: &str) -> Result<usize, Box<dyn Error>> {
+    let by_name = header.and_then(|header| header.iter().position(|name| name.trim().eq_ignore_ascii_case(column.trim())));
+    match by_name.or_else(|| column.trim().parse().ok()) {
+        Some(index) if index < width => Ok(index),
+        _ => Err(format!("Column '{}' not found in the CSV file.", column).into()),
+    }
+}
+
+/// Parses a CSV column, failing on the first field that is not a number.
+fn parse_column(records: &[csv::StringRecord], index: usize) -> Result<Vec<f64>, Box<dyn Error>> {
+    records
+        .iter()
+        .map(|record| {
+            let field = record.get(index).unwrap_or("").trim();
+            field.parse().map_err(|_| {
+                let line = record.position().map_or(0, |p| p.line());
+                format!("Line {}: could not parse '{}' in column {} as a number.", line, field, index).into()
+            })
+        })
+        .collect()
+}
+
+/// Index of the first sample that is not larger than the one before it.
+fn first_non_increasing(values: &[f64]) -> Option<usize> {
+    values.windows(2).position(|w| w[1].partial_cmp(&w[0]) != Some(std::cmp::Ordering::Greater)).map(|i| i + 1)
+}
+
+/// The sampling frequency of a time column, which has to be strictly increasing with every step within 1% of the
+///     mean step.
+fn sampling_frequency(time: &[f64]) -> Result<f64, Box<dyn Error>> {
+    if time.len() < 2 {
+        return Err("The time column needs at least two rows, pass the sampling frequency with --fs.".into());
+    }
+    if let Some(i) = first_non_increasing(time) {
+        return Err(format!("The time column is not strictly increasing at row {} ({} after {}).", i + 1, time[i], time[i - 1]).into());
+    }
+    let step = (time[time.len() - 1] - time[0]) / (time.len() - 1) as f64;
+    if let Some(i) = time.windows(2).position(|w| (w[1] - w[0] - step).abs() > 0.01 * step) {
+        return Err(format!(
+            "The time column is not uniformly sampled, the step at row {} is {} s against a mean of {} s. Resample the data or pass --fs.",
+            i + 2,
+            time[i + 1] - time[i],
+            step
+        )
+        .into());
+    }
+    Ok(1.0 / step)
+}
+
+/// Loads the data column of a CSV file and, unless `--fs` is given, the sampling frequency from its time column.
+///     A first row that is not all numbers is the header, and fields that are empty on every row (such as a
+///     trailing comma) are not counted as columns.
+fn load_csv(options: &Options) -> Result<(Vec<f64>, Option<f64>), Box<dyn Error>> {
+    let mut reader = csv::ReaderBuilder::new().has_headers(false).flexible(true).from_path(&options.input)?;
+    let mut records = reader.records().collect::<Result<Vec<_>, _>>()?;
+    let is_header = |record: &csv::StringRecord| record.iter().any(|f| !f.trim().is_empty() && f.trim().parse::<f64>().is_err());
+    let header = if records.first().is_some_and(is_header) { Some(records.remove(0)) } else { None };
+    if records.is_empty() {
+        return Err(format!("{} has no data rows.", options.input).into());
+    }
+
+    let width = records.iter().map(|record| record.len()).max().unwrap_or(0);
+    let columns: Vec<usize> = (0..width)
+        .filter(|&i| records.iter().any(|record| !record.get(i).unwrap_or("").trim().is_empty()))
+        .collect();
+
+    let time_column = match (&options.time_column, &header) {
+        (Some(column), _) => Some(find_column(header.as_ref(), width, column)?),
+        (None, Some(header)) => header.iter().position(|name| name.trim().eq_ignore_ascii_case("time")),
+        (None, None) => columns.iter().copied().find(|&i| {
+            parse_column(&records, i).is_ok_and(|values| values.len() > 1 && first_non_increasing(&values).is_none())
+        }),
+    };
+    let data_column = match &options.data_column {
+        Some(column) => find_column(header.as_ref(), width, column)?,
+        None => columns
+            .iter()
+            .copied()
+            .find(|&i| Some(i) != time_column)
+            .ok_or("No data column found, name it with --data-column.")?,
+    };
+
+    let fs = match (options.fs, time_column) {
+        (Some(_), _) => None,
+        (None, Some(column)) => Some(sampling_frequency(&parse_column(&records, column)?)?),
+        (None, None) => return Err("No time column found, name it with --time-column or pass --fs.".into()),
+    };
+    Ok((parse_column(&records, data_column)?, fs))
+}
+
+/// Writes the rows as CSV to the output file or stdout.
+fn write_csv(output: Option<&str>, header: &[&str], rows: impl Iterator<Item = Vec<f64>>) -> Result<(), Box<dyn Error>> {
+    let sink: Box<dyn Write> = match output {
+        Some(path) => Box::new(std::fs::File::create(path)?),
+        None => Box::new(std::io::stdout().lock()),
+    };
+    let mut writer = csv::Writer::from_writer(sink);
+    writer.write_record(header)?;
+    for row in rows {
+        writer.write_record(row.iter().map(|v| v.to_string()))?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn run(options: &Options) -> Result<(), Box<dyn Error>> {
+    let (data, fs) = load_signal(options)?;
+    let output = options.output.as_deref();
+    let png_output = output.filter(|path| path.to_lowercase().ends_with(".png"));
+    let time = |len: usize| (0..len).map(move |i| i as f64 / fs);
+
+    match options.command.as_str() {
+        "spectrum" => {
+            let len = data.len();
+            let (real, imag) = compute_fft(data)?;
+            let mut magnitude = compute_magnitude(real, imag)?;
+            magnitude.truncate(len / 2 + 1);
+            let freqs: Vec<f64> = (0..magnitude.len()).map(|k| k as f64 * fs / len as f64).collect();
+            match png_output {
+                Some(path) => {
+                    let points = freqs.into_iter().zip(magnitude).collect();
+                    std::fs::write(path, generate_plot(points, "Frequency [Hz]", "Magnitude", "Spectrum")?)?;
+                }
+                None => write_csv(output, &["frequency", "magnitude"], freqs.into_iter().zip(magnitude).map(|(f, m)| vec![f, m]))?,
+            }
+        }
+        "spectrogram" => {
+            let spectrogram = compute_spectrogram(data, fs, options.nperseg, options.nperseg / 2)?;
+            match png_output {
+                Some(path) => std::fs::write(path, generate_spectrogram_plot(&spectrogram, "Spectrogram", &SpectrogramPlotOptions::default(), &PlotOptions::default())?)?,
+                None => {
+                    let rows = spectrogram.times.iter().zip(&spectrogram.power).flat_map(|(t, row)| {
+                        spectrogram.freqs.iter().zip(row).map(move |(f, p)| vec![*t, *f, *p])
+                    });
+                    write_csv(output, &["time", "frequency", "psd"], rows)?;
+                }
+            }
+        }
+        "filter" => {
+            let filtered = fft_bandpass(data, fs, options.low, options.high.unwrap_or(fs / 2.0))?;
+            match png_output {
+                Some(path) => {
+                    let points = time(filtered.len()).zip(filtered).collect();
+                    std::fs::write(path, generate_plot(points, "Time [s]", "Filtered", "Filtered signal")?)?;
+                }
+                None => write_csv(output, &["time", "value"], time(filtered.len()).zip(filtered).map(|(t, v)| vec![t, v]))?,
+            }
+        }
+        "plot" => {
+            let path = png_output.ok_or("The plot command needs a .png output path.")?;
+            let points = time(data.len()).zip(data).collect();
+            std::fs::write(path, generate_plot(points, "Time [s]", "Measured Data", &options.input)?)?;
+        }
+        other => return Err(format!("Unknown command {}.", other).into()),
+    }
+
+    Ok(())
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.is_empty() || args.iter().any(|a| a == "-h" || a == "--help") {
+        println!("{}", USAGE);
+        return;
+    }
+
+    let options = match parse_args(&args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            std::process::exit(2);
+        }
+    };
+    if let Err(e) = run(&options) {
+        eprintln!("error: {}", e);
+        std::process::exit(1);
+    }
+}
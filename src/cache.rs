@@ -0,0 +1,235 @@
+//! Optional on-disk cache of analysis results.
+//!
+//! Disabled until [`set_cache_dir`] names a directory. Entries are keyed by a hash of the input (the file contents
+//! for [`analyze_file`](crate::analyze_file), the samples for [`compute_psd_welch`](crate::compute_psd_welch)) and
+//! every parameter, so changed inputs or parameters simply miss. Unreadable entries are treated as misses.
+
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use crate::{AnalysisOptions, AnalysisResult, Detrend, SignalStats, Window};
+
+static CACHE_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Bumped whenever the entry layout or the cached computations change, orphaning older entries.
+const FORMAT: &[u8; 8] = b"FFTCACH1";
+
+/// Caches results under `dir` from now on, creating it if needed. `None` disables the cache.
+pub fn set_cache_dir(dir: Option<&str>) -> Result<(), Box<dyn Error>> {
+    if let Some(dir) = dir {
+        std::fs::create_dir_all(dir)?;
+    }
+    *CACHE_DIR.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = dir.map(PathBuf::from);
+    Ok(())
+}
+
+/// Returns the cache directory, `None` when caching is disabled.
+pub fn cache_dir() -> Option<PathBuf> {
+    CACHE_DIR.read().unwrap_or_else(|poisoned| poisoned.into_inner()).clone()
+}
+
+/// Deletes every cache entry and returns how many were removed.
+pub fn clear_cache() -> Result<usize, Box<dyn Error>> {
+    let Some(dir) = cache_dir() else {
+        return Ok(0);
+    };
+    let mut removed = 0;
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|e| e == "fftcache") {
+            std::fs::remove_file(path)?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+/// 128 bit cache key from two FNV-1a hashes with different offsets, identical on every platform and release.
+pub(crate) struct CacheKey([u64; 2]);
+
+impl CacheKey {
+    pub(crate) fn new(kind: &str) -> Self {
+        let mut key = CacheKey([0xcbf2_9ce4_8422_2325, 0x6c62_272e_07bb_0142]);
+        key.bytes(kind.as_bytes());
+        key
+    }
+
+    pub(crate) fn bytes(&mut self, data: &[u8]) -> &mut Self {
+        const PRIME: u64 = 0x0000_0100_0000_01b3;
+        for hash in &mut self.0 {
+            // Length prefix so consecutive fields cannot run into each other
+            for &byte in (data.len() as u64).to_le_bytes().iter().chain(data) {
+                *hash = (*hash ^ u64::from(byte)).wrapping_mul(PRIME);
+            }
+        }
+        self
+    }
+
+    pub(crate) fn floats(&mut self, data: &[f64]) -> &mut Self {
+        let bytes: Vec<u8> = data.iter().flat_map(|x| x.to_le_bytes()).collect();
+        self.bytes(&bytes)
+    }
+
+    fn path(&self, dir: &Path) -> PathBuf {
+        dir.join(format!("{:016x}{:016x}.fftcache", self.0[0], self.0[1]))
+    }
+}
+
+/// Little endian writer of cache entries.
+#[derive(Default)]
+pub(crate) struct Encoder(Vec<u8>);
+
+impl Encoder {
+    pub(crate) fn u64(&mut self, value: u64) -> &mut Self {
+        self.0.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    pub(crate) fn f64(&mut self, value: f64) -> &mut Self {
+        self.u64(value.to_bits())
+    }
+
+    pub(crate) fn bytes(&mut self, data: &[u8]) -> &mut Self {
+        self.u64(data.len() as u64);
+        self.0.extend_from_slice(data);
+        self
+    }
+
+    pub(crate) fn floats(&mut self, data: &[f64]) -> &mut Self {
+        self.u64(data.len() as u64);
+        data.iter().for_each(|x| self.0.extend_from_slice(&x.to_le_bytes()));
+        self
+    }
+}
+
+/// Reader matching [`Encoder`], returning `None` on truncated data.
+pub(crate) struct Decoder<'a>(&'a [u8]);
+
+impl Decoder<'_> {
+    fn take(&mut self, len: usize) -> Option<&[u8]> {
+        if len > self.0.len() {
+            return None;
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Some(head)
+    }
+
+    pub(crate) fn u64(&mut self) -> Option<u64> {
+        Some(u64::from_le_bytes(self.take(8)?.try_into().ok()?))
+    }
+
+    pub(crate) fn f64(&mut self) -> Option<f64> {
+        self.u64().map(f64::from_bits)
+    }
+
+    pub(crate) fn bytes(&mut self) -> Option<Vec<u8>> {
+        let len = self.u64()? as usize;
+        self.take(len).map(<[u8]>::to_vec)
+    }
+
+    pub(crate) fn floats(&mut self) -> Option<Vec<f64>> {
+        let len = self.u64()? as usize;
+        let bytes = self.take(len.checked_mul(8)?)?;
+        Some(bytes.chunks_exact(8).map(|c| f64::from_le_bytes(c.try_into().unwrap_or([0; 8]))).collect())
+    }
+}
+
+/// Returns the cached entry for `key` if caching is enabled and a readable entry exists.
+pub(crate) fn load<T>(key: &CacheKey, decode: impl FnOnce(&mut Decoder) -> Option<T>) -> Option<T> {
+    let path = key.path(&cache_dir()?);
+    let data = std::fs::read(&path).ok()?;
+    let value = data.strip_prefix(FORMAT).and_then(|body| decode(&mut Decoder(body)));
+    if value.is_some() {
+        log::debug!("Cache hit {}", path.display());
+    }
+    value
+}
+
+/// Stores an entry if caching is enabled. Failures are logged, since the cache only saves time.
+pub(crate) fn store(key: &CacheKey, encode: impl FnOnce(&mut Encoder)) {
+    let Some(dir) = cache_dir() else {
+        return;
+    };
+    let mut encoder = Encoder(FORMAT.to_vec());
+    encode(&mut encoder);
+    // Write then rename so concurrent readers never see a partial entry
+    let path = key.path(&dir);
+    let partial = path.with_extension(format!("{}.partial", std::process::id()));
+    if let Err(e) = std::fs::write(&partial, &encoder.0).and_then(|_| std::fs::rename(&partial, &path)) {
+        log::warn!("Could not write cache entry {}: {}", path.display(), e);
+        let _ = std::fs::remove_file(&partial);
+    }
+}
+
+/// Key of an [`analyze_file`](crate::analyze_file) call, `None` if caching is disabled or the file cannot be read.
+pub(crate) fn analysis_key(path: &str, fs: Option<f64>, options: &AnalysisOptions) -> Option<CacheKey> {
+    cache_dir()?;
+    let contents = std::fs::read(path).ok()?;
+    let mut key = CacheKey::new("analyze_file");
+    key.bytes(&contents)
+        .bytes(path.as_bytes())
+        .floats(&[fs.unwrap_or(f64::NAN), options.nperseg as f64, options.plots as u8 as f64])
+        .bytes(options.window.name().as_bytes())
+        .bytes(options.detrend.name().as_bytes());
+    Some(key)
+}
+
+/// Key of a Welch PSD, `None` if caching is disabled.
+pub(crate) fn psd_key(data: &[f64], fs: f64, nperseg: usize) -> Option<CacheKey> {
+    cache_dir()?;
+    let mut key = CacheKey::new("compute_psd_welch");
+    key.floats(data).floats(&[fs, nperseg as f64]);
+    Some(key)
+}
+
+pub(crate) fn encode_analysis(encoder: &mut Encoder, result: &AnalysisResult) {
+    let stats = &result.stats;
+    encoder
+        .bytes(result.path.as_bytes())
+        .f64(result.fs)
+        .u64(result.n_samples as u64)
+        .bytes(result.options.window.name().as_bytes())
+        .u64(result.options.nperseg as u64)
+        .bytes(result.options.detrend.name().as_bytes())
+        .u64(result.options.plots as u64)
+        .floats(&result.freqs)
+        .floats(&result.amplitude)
+        .floats(&result.psd_freqs)
+        .floats(&result.psd)
+        .floats(&[stats.mean, stats.std, stats.rms, stats.min, stats.max, stats.crest_factor, stats.dominant_frequency])
+        .u64(result.plots.len() as u64);
+    for (name, png) in &result.plots {
+        encoder.bytes(name.as_bytes()).bytes(png);
+    }
+}
+
+pub(crate) fn decode_analysis(decoder: &mut Decoder) -> Option<AnalysisResult> {
+    let string = |decoder: &mut Decoder| String::from_utf8(decoder.bytes()?).ok();
+    let path = string(decoder)?;
+    let fs = decoder.f64()?;
+    let n_samples = decoder.u64()? as usize;
+    let window_name = string(decoder)?;
+    let window = Window::ALL.into_iter().find(|w| w.name() == window_name)?;
+    let nperseg = decoder.u64()? as usize;
+    let detrend_name = string(decoder)?;
+    let detrend = [Detrend::None, Detrend::Mean, Detrend::Linear].into_iter().find(|d| d.name() == detrend_name)?;
+    let plots = decoder.u64()? != 0;
+    let (freqs, amplitude, psd_freqs, psd) = (decoder.floats()?, decoder.floats()?, decoder.floats()?, decoder.floats()?);
+    let [mean, std, rms, min, max, crest_factor, dominant_frequency] = decoder.floats()?.try_into().ok()?;
+    let plot_count = decoder.u64()?;
+    let images = (0..plot_count).map(|_| Some((string(decoder)?, decoder.bytes()?))).collect::<Option<_>>()?;
+    Some(AnalysisResult {
+        path,
+        fs,
+        n_samples,
+        options: AnalysisOptions { window, nperseg, detrend, plots },
+        freqs,
+        amplitude,
+        psd_freqs,
+        psd,
+        stats: SignalStats { mean, std, rms, min, max, crest_factor, dominant_frequency },
+        plots: images,
+    })
+}
@@ -0,0 +1,94 @@
+//! Multi-channel data and its reduction to single channels.
+//!
+//! Multi-channel data is a [`Matrix`] with one vector per channel, all of the same length, as returned by
+//! [`read_wav`](crate::read_wav) and taken by [`loudness`](crate::loudness) or
+//! [`cross_spectral_matrix`](crate::cross_spectral_matrix). Interleaved buffers, frame after frame with one sample
+//! per channel, are converted with [`deinterleave`] and [`interleave`].
+
+use std::error::Error;
+
+use crate::Matrix;
+
+/// How [`to_mono`] combines the channels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MonoMode {
+    /// Average of all channels, keeping the level of content common to them.
+    Mean,
+    /// Sum of all channels.
+    Sum,
+    /// Half the difference of the two channels of a stereo pair, the content panned off centre or out of phase.
+    Side,
+}
+
+impl MonoMode {
+    /// Lowercase name of the mode, as accepted by the Python bindings.
+    pub fn name(self) -> &'static str {
+        match self {
+            MonoMode::Mean => "mean",
+            MonoMode::Sum => "sum",
+            MonoMode::Side => "side",
+        }
+    }
+}
+
+/// Number of samples per channel, checking that there is at least one channel and that all have the same length.
+pub fn channel_len(channels: &Matrix) -> Result<usize, Box<dyn Error>> {
+    let len = channels.first().ok_or("At least one channel is needed.")?.len();
+    if channels.iter().any(|c| c.len() != len) {
+        return Err("All channels must have the same length.".into());
+    }
+    Ok(len)
+}
+
+/// Splits interleaved samples, one sample of every channel per frame, into `channels` channels.
+pub fn deinterleave(data: &[f64], channels: usize) -> Result<Matrix, Box<dyn Error>> {
+    if channels == 0 || !data.len().is_multiple_of(channels) {
+        return Err(format!("{} interleaved samples do not split into {} channels.", data.len(), channels).into());
+    }
+    Ok((0..channels).map(|c| data.iter().skip(c).step_by(channels).copied().collect()).collect())
+}
+
+/// Interleaves the channels, the inverse of [`deinterleave`].
+pub fn interleave(channels: &Matrix) -> Result<Vec<f64>, Box<dyn Error>> {
+    let len = channel_len(channels)?;
+    Ok((0..len).flat_map(|n| channels.iter().map(move |c| c[n])).collect())
+}
+
+/// The channels at `indices`, in that order. An index may repeat, e.g. `[0, 0]` duplicates a mono channel to
+///     stereo.
+pub fn select_channels(channels: &Matrix, indices: &[usize]) -> Result<Matrix, Box<dyn Error>> {
+    channel_len(channels)?;
+    indices
+        .iter()
+        .map(|&i| {
+            channels
+                .get(i)
+                .cloned()
+                .ok_or_else(|| format!("Channel {} not found, there are {} channels.", i, channels.len()).into())
+        })
+        .collect()
+}
+
+/// Mixes the channels down to one as chosen by `mode`. [`MonoMode::Side`] needs exactly two channels.
+pub fn to_mono(channels: &Matrix, mode: MonoMode) -> Result<Vec<f64>, Box<dyn Error>> {
+    let len = channel_len(channels)?;
+    let sum = |n: usize| channels.iter().map(|c| c[n]).sum::<f64>();
+    Ok(match mode {
+        MonoMode::Mean => (0..len).map(|n| sum(n) / channels.len() as f64).collect(),
+        MonoMode::Sum => (0..len).map(sum).collect(),
+        MonoMode::Side => match channels.as_slice() {
+            [left, right] => left.iter().zip(right).map(|(l, r)| (l - r) / 2.0).collect(),
+            _ => return Err(format!("Side needs a stereo pair, got {} channels.", channels.len()).into()),
+        },
+    })
+}
+
+/// Applies a single channel operation, a filter or transform, to every channel in turn.
+///     Stateful filters should be cloned or reset for each channel so no state leaks between them.
+pub fn map_channels<F>(channels: &Matrix, mut operation: F) -> Result<Matrix, Box<dyn Error>>
+where
+    F: FnMut(&[f64]) -> Result<Vec<f64>, Box<dyn Error>>,
+{
+    channel_len(channels)?;
+    channels.iter().map(|c| operation(c)).collect()
+}
@@ -0,0 +1,123 @@
+//! Color scales for heatmap plots such as [`generate_spectrogram_plot`](crate::generate_spectrogram_plot).
+
+use plotters::style::colors::colormaps::ViridisRGB;
+use plotters::style::RGBColor;
+
+/// Color scale of heatmap plots.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Colormap {
+    /// Perceptually uniform dark blue to yellow, readable in grayscale and by colour blind viewers.
+    #[default]
+    Viridis,
+    /// Perceptually uniform black to pale yellow through purple and orange.
+    Magma,
+    /// Perceptually uniform dark blue to yellow through magenta, brighter at the low end than magma.
+    Plasma,
+    /// The rainbow of MATLAB, dark blue to dark red. Its uneven lightness invents edges, prefer it only to match
+    ///     existing figures.
+    Jet,
+    /// Black to white.
+    Gray,
+    /// Blue to red through light gray, for values with a meaningful centre such as differences or correlations.
+    Diverging,
+}
+
+/// Lookup tables at nine evenly spaced positions, interpolated linearly in between.
+const MAGMA: [(u8, u8, u8); 9] = [
+    (0, 0, 4),
+    (28, 16, 68),
+    (79, 18, 123),
+    (129, 37, 129),
+    (181, 54, 122),
+    (229, 80, 100),
+    (251, 135, 97),
+    (254, 194, 135),
+    (252, 253, 191),
+];
+const PLASMA: [(u8, u8, u8); 9] = [
+    (13, 8, 135),
+    (75, 3, 161),
+    (125, 3, 168),
+    (168, 34, 150),
+    (203, 70, 121),
+    (229, 107, 93),
+    (248, 148, 65),
+    (253, 195, 40),
+    (240, 249, 33),
+];
+const JET: [(u8, u8, u8); 9] = [
+    (0, 0, 128),
+    (0, 0, 255),
+    (0, 128, 255),
+    (0, 255, 255),
+    (128, 255, 128),
+    (255, 255, 0),
+    (255, 128, 0),
+    (255, 0, 0),
+    (128, 0, 0),
+];
+/// The cool to warm map of Moreland (2009).
+const DIVERGING: [(u8, u8, u8); 9] = [
+    (59, 76, 192),
+    (98, 130, 234),
+    (141, 176, 254),
+    (184, 208, 249),
+    (221, 221, 221),
+    (245, 196, 173),
+    (244, 154, 123),
+    (222, 96, 77),
+    (180, 4, 38),
+];
+
+fn interpolate(table: &[(u8, u8, u8)], t: f64) -> RGBColor {
+    let position = t * (table.len() - 1) as f64;
+    let i = (position as usize).min(table.len() - 2);
+    let alpha = position - i as f64;
+    let mix = |a: u8, b: u8| (a as f64 + alpha * (b as f64 - a as f64)).round() as u8;
+    let (a, b) = (table[i], table[i + 1]);
+    RGBColor(mix(a.0, b.0), mix(a.1, b.1), mix(a.2, b.2))
+}
+
+impl Colormap {
+    /// Every colormap, in the order the Python bindings list them.
+    pub const ALL: [Colormap; 6] =
+        [Colormap::Viridis, Colormap::Magma, Colormap::Plasma, Colormap::Jet, Colormap::Gray, Colormap::Diverging];
+
+    /// Lowercase name of the colormap, as accepted by the Python bindings.
+    pub fn name(self) -> &'static str {
+        match self {
+            Colormap::Viridis => "viridis",
+            Colormap::Magma => "magma",
+            Colormap::Plasma => "plasma",
+            Colormap::Jet => "jet",
+            Colormap::Gray => "gray",
+            Colormap::Diverging => "diverging",
+        }
+    }
+
+    /// The colormap called `name`, ignoring case. "grey" and "grayscale" name [`Colormap::Gray`] and "coolwarm"
+    ///     names [`Colormap::Diverging`] too.
+    pub fn from_name(name: &str) -> Option<Colormap> {
+        match name.to_lowercase().as_str() {
+            "grey" | "grayscale" | "greyscale" => Some(Colormap::Gray),
+            "coolwarm" => Some(Colormap::Diverging),
+            name => Colormap::ALL.into_iter().find(|colormap| colormap.name() == name),
+        }
+    }
+
+    /// Color at `t` from 0 for the lowest value to 1 for the highest, values outside clamped to the ends.
+    pub fn color(self, t: f64) -> RGBColor {
+        let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+        match self {
+            Colormap::Viridis => ViridisRGB::get_color_normalized(t, 0.0, 1.0),
+            Colormap::Magma => interpolate(&MAGMA, t),
+            Colormap::Plasma => interpolate(&PLASMA, t),
+            Colormap::Jet => interpolate(&JET, t),
+            Colormap::Gray => {
+                let level = (t * 255.0).round() as u8;
+                RGBColor(level, level, level)
+            }
+            Colormap::Diverging => interpolate(&DIVERGING, t),
+        }
+    }
+}
@@ -0,0 +1,160 @@
+//! Comparison of spectra against reference measurements.
+
+use std::error::Error;
+
+use crate::{interpolate, Interpolation, Spectrum};
+
+/// Per-bin and summary deviation of one spectrum from another, see [`compare_spectra`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpectrumComparison {
+    pub freqs: Vec<f64>,
+    /// Linear ratio of the measured to the reference value in each bin.
+    pub ratio: Vec<f64>,
+    /// The ratio in dB, `20 log10(ratio)`.
+    pub difference_db: Vec<f64>,
+    /// Mean of the dB differences, the overall level offset.
+    pub mean_deviation_db: f64,
+    pub rms_deviation_db: f64,
+    /// Largest absolute dB difference and the frequency it occurs at.
+    pub max_deviation_db: f64,
+    pub max_deviation_freq: f64,
+    /// The compared values of both spectra on `freqs`, after alignment.
+    pub measured: Vec<f64>,
+    pub reference: Vec<f64>,
+}
+
+/// Compares a measured amplitude spectrum `a` against a reference `b`, e.g. a device under test against a golden
+///     unit. With `align` the reference is linearly interpolated onto the bins of `a` that lie within its frequency
+///     range, so spectra of different resolution can be compared. Without it both must have the same bins.
+///     Differences are in amplitude dB, halve them for power spectra.
+pub fn compare_spectra(a: &Spectrum, b: &Spectrum, align: bool) -> Result<SpectrumComparison, Box<dyn Error>> {
+    if a.values.is_empty() || b.values.is_empty() {
+        return Err("Both spectra must have at least one bin.".into());
+    }
+
+    let (freqs, measured, reference) = if align {
+        let (low, high) = (b.freqs[0], b.freqs[b.freqs.len() - 1]);
+        let (freqs, measured): (Vec<f64>, Vec<f64>) =
+            a.freqs.iter().zip(&a.values).filter(|(f, _)| (low..=high).contains(*f)).map(|(f, v)| (*f, *v)).unzip();
+        if freqs.is_empty() {
+            return Err("The spectra do not overlap in frequency.".into());
+        }
+        let reference = interpolate(b.freqs.clone(), b.values.clone(), freqs.clone(), Interpolation::Linear)?;
+        (freqs, measured, reference)
+    } else {
+        if a.freqs.len() != b.freqs.len() {
+            return Err(format!("Got {} and {} bins, pass align to interpolate.", a.freqs.len(), b.freqs.len()).into());
+        }
+        (a.freqs.clone(), a.values.clone(), b.values.clone())
+    };
+
+    let ratio: Vec<f64> = measured.iter().zip(&reference).map(|(m, r)| m.abs() / r.abs().max(1e-300)).collect();
+    let difference_db: Vec<f64> = ratio.iter().map(|r| 20.0 * r.max(1e-300).log10()).collect();
+    let len = difference_db.len() as f64;
+    let worst = (0..difference_db.len()).max_by(|&i, &j| difference_db[i].abs().total_cmp(&difference_db[j].abs())).unwrap_or(0);
+
+    Ok(SpectrumComparison {
+        mean_deviation_db: difference_db.iter().sum::<f64>() / len,
+        rms_deviation_db: (difference_db.iter().map(|d| d * d).sum::<f64>() / len).sqrt(),
+        max_deviation_db: difference_db[worst].abs(),
+        max_deviation_freq: freqs[worst],
+        freqs,
+        ratio,
+        difference_db,
+        measured,
+        reference,
+    })
+}
+
+/// Side of a [`MaskSegment`] the spectrum has to stay on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MaskKind {
+    /// A limit line the spectrum must not exceed, as in emission tests.
+    Upper,
+    /// A floor the spectrum must not fall below.
+    Lower,
+}
+
+/// A straight limit line from `(start_freq, start_db)` to `(end_freq, end_db)`.
+///     The limit is interpolated linearly over log frequency when `start_freq` is positive, as EMC standards
+///     specify sloped limits, and linearly otherwise.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MaskSegment {
+    pub start_freq: f64,
+    pub end_freq: f64,
+    pub start_db: f64,
+    pub end_db: f64,
+    pub kind: MaskKind,
+}
+
+impl MaskSegment {
+    /// The limit at `freq`, which must lie within the segment.
+    pub fn limit(&self, freq: f64) -> f64 {
+        if self.end_freq == self.start_freq {
+            return self.start_db;
+        }
+        let position = if self.start_freq > 0.0 {
+            (freq / self.start_freq).ln() / (self.end_freq / self.start_freq).ln()
+        } else {
+            (freq - self.start_freq) / (self.end_freq - self.start_freq)
+        };
+        self.start_db + position * (self.end_db - self.start_db)
+    }
+}
+
+/// Outcome of one segment in [`check_mask`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SegmentResult {
+    pub passed: bool,
+    /// Smallest distance of the spectrum from the limit in dB, negative where it is violated. NaN when no bin
+    ///     falls in the segment.
+    pub margin_db: f64,
+    /// Frequency of the bin with the smallest margin.
+    pub worst_freq: f64,
+    /// Number of bins within the segment.
+    pub n_bins: usize,
+}
+
+/// Pass/fail verdict of a spectrum against a limit mask.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MaskResult {
+    /// Whether every segment passed.
+    pub passed: bool,
+    pub segments: Vec<SegmentResult>,
+}
+
+/// Checks a spectrum in dB against EMC-style limit lines, reporting the margin of every segment.
+pub fn check_mask(freqs: &[f64], magnitude_db: &[f64], segments: &[MaskSegment]) -> Result<MaskResult, Box<dyn Error>> {
+    if freqs.len() != magnitude_db.len() {
+        return Err("freqs and magnitude_db must have the same length.".into());
+    }
+    if let Some(segment) = segments.iter().find(|s| !(s.end_freq >= s.start_freq && s.start_freq >= 0.0)) {
+        return Err(format!("Invalid mask segment from {} Hz to {} Hz.", segment.start_freq, segment.end_freq).into());
+    }
+
+    let segments: Vec<SegmentResult> = segments
+        .iter()
+        .map(|segment| {
+            let margins = freqs
+                .iter()
+                .zip(magnitude_db)
+                .filter(|(f, _)| (segment.start_freq..=segment.end_freq).contains(*f))
+                .map(|(&f, &value)| match segment.kind {
+                    MaskKind::Upper => (f, segment.limit(f) - value),
+                    MaskKind::Lower => (f, value - segment.limit(f)),
+                });
+            // A NaN bin is the worst possible and fails the segment
+            let (n_bins, worst) = margins.fold((0, (f64::NAN, f64::INFINITY)), |(n, worst), (f, margin)| {
+                (n + 1, if margin < worst.1 || margin.is_nan() { (f, margin) } else { worst })
+            });
+            SegmentResult {
+                passed: n_bins == 0 || worst.1 >= 0.0,
+                margin_db: if n_bins == 0 { f64::NAN } else { worst.1 },
+                worst_freq: worst.0,
+                n_bins,
+            }
+        })
+        .collect();
+
+    Ok(MaskResult { passed: segments.iter().all(|s| s.passed), segments })
+}
@@ -0,0 +1,450 @@
+//! Signal conditioning applied before spectral analysis.
+
+use rustfft::{num_complex::Complex, FftDirection};
+use std::error::Error;
+
+use crate::parametric::ar_models;
+use crate::{interpolate, plan_fft, ArMethod, Interpolation, Signal};
+
+/// Spike detector used by [`despike`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DespikeMethod {
+    /// Compares each sample with a running median, scaled by the standard deviation of the whole residual.
+    Median,
+    /// Hampel filter: compares each sample with the running median, scaled by the local median absolute deviation.
+    Hampel,
+}
+
+/// Data with spikes replaced and the indices of the replaced samples.
+pub struct Despiked {
+    pub data: Vec<f64>,
+    pub spikes: Vec<usize>,
+}
+
+/// Scale factor turning a median absolute deviation into a standard deviation estimate for Gaussian data.
+const MAD_SCALE: f64 = 1.4826;
+
+fn median(values: &mut [f64]) -> f64 {
+    values.sort_unstable_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    if values.len() % 2 == 1 { values[mid] } else { 0.5 * (values[mid - 1] + values[mid]) }
+}
+
+/// Replaces samples deviating from the running median of `2 * half_window + 1` samples by more than
+///     `threshold` robust standard deviations with that median.
+pub fn despike(data: Vec<f64>, threshold: f64, method: DespikeMethod, half_window: usize) -> Result<Despiked, Box<dyn Error>> {
+    if threshold <= 0.0 || half_window == 0 {
+        return Err("Threshold and window must be positive.".into());
+    }
+
+    let len = data.len();
+    let mut window = Vec::with_capacity(2 * half_window + 1);
+    let mut medians = Vec::with_capacity(len);
+    let mut spreads = Vec::with_capacity(len);
+    for i in 0..len {
+        window.clear();
+        window.extend_from_slice(&data[i.saturating_sub(half_window)..(i + half_window + 1).min(len)]);
+        let centre = median(&mut window);
+        if method == DespikeMethod::Hampel {
+            window.iter_mut().for_each(|v| *v = (*v - centre).abs());
+            spreads.push(MAD_SCALE * median(&mut window));
+        }
+        medians.push(centre);
+    }
+    if method == DespikeMethod::Median {
+        // Many residuals of a running median are exactly zero, so their MAD would collapse
+        let energy: f64 = data.iter().zip(&medians).map(|(x, m)| (x - m).powi(2)).sum();
+        spreads = vec![(energy / len.max(1) as f64).sqrt(); len];
+    }
+
+    let mut cleaned = data;
+    let mut spikes = Vec::new();
+    for (i, value) in cleaned.iter_mut().enumerate() {
+        if (*value - medians[i]).abs() > threshold * spreads[i] {
+            *value = medians[i];
+            spikes.push(i);
+        }
+    }
+    log::debug!("Replaced {} spikes out of {} samples", spikes.len(), len);
+
+    Ok(Despiked { data: cleaned, spikes })
+}
+
+/// Finds clipped runs, at least three consecutive samples holding the same value (within a few ulp) at a magnitude
+///     of at least `threshold`. The threshold defaults to 99.9% of the peak magnitude. The flat top keeps the crests
+///     of clean signals out, which reach the peak in one sample or at most two equal ones. Returns `(start, end)`
+///     ranges with `end` exclusive.
+pub fn detect_clipping(data: &[f64], threshold: Option<f64>) -> Vec<(usize, usize)> {
+    const MIN_RUN: usize = 3;
+    let threshold = threshold.unwrap_or_else(|| 0.999 * data.iter().fold(0.0, |m: f64, x| m.max(x.abs())));
+    let tolerance = 4.0 * f64::EPSILON * threshold;
+    let clipped = |x: f64| x.abs() >= threshold && threshold > 0.0;
+
+    let mut runs = Vec::new();
+    let mut start = 0;
+    for i in 1..=data.len() {
+        let flat = i < data.len() && clipped(data[i]) && clipped(data[i - 1]) && (data[i] - data[i - 1]).abs() <= tolerance;
+        if !flat {
+            if i - start >= MIN_RUN {
+                runs.push((start, i));
+            }
+            start = i;
+        }
+    }
+    runs
+}
+
+/// Reconstructs clipped runs with a cubic spline through the unclipped samples around each run.
+///     The reconstruction never falls below the clipping level, since the true signal exceeded it.
+pub fn declip(data: Vec<f64>, threshold: Option<f64>) -> Result<Vec<f64>, Box<dyn Error>> {
+    const CONTEXT: usize = 8;
+    let runs = detect_clipping(&data, threshold);
+    log::debug!("Repairing {} clipped runs", runs.len());
+
+    let mut repaired = data.clone();
+    for (index, &(start, end)) in runs.iter().enumerate() {
+        // Take context samples on each side, stopping at neighbouring clipped runs
+        let left = start.saturating_sub(CONTEXT).max(if index > 0 { runs[index - 1].1 } else { 0 });
+        let right = (end + CONTEXT).min(runs.get(index + 1).map_or(data.len(), |run| run.0));
+        let known: Vec<usize> = (left..start).chain(end..right).collect();
+        if known.len() < 2 {
+            continue;
+        }
+
+        let x = known.iter().map(|&i| i as f64).collect();
+        let y = known.iter().map(|&i| data[i]).collect();
+        let estimate = interpolate(x, y, (start..end).map(|i| i as f64).collect(), Interpolation::Cubic)?;
+        for (i, value) in (start..end).zip(estimate) {
+            if value.abs() > data[i].abs() && value.signum() == data[i].signum() {
+                repaired[i] = value;
+            }
+        }
+    }
+
+    Ok(repaired)
+}
+
+/// Scaling applied by [`normalize`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Normalization {
+    /// Scales the peak magnitude to the target.
+    Peak,
+    /// Scales the root mean square to the target.
+    Rms,
+    /// Removes the mean and scales to unit standard deviation, ignoring the target.
+    ZScore,
+}
+
+/// Normalizes the data so recordings can be compared on a consistent scale. The target defaults to 1.
+pub fn normalize(data: Vec<f64>, mode: Normalization, target: Option<f64>) -> Result<Vec<f64>, Box<dyn Error>> {
+    if data.is_empty() {
+        return Err("Data must not be empty.".into());
+    }
+    let target = target.unwrap_or(1.0);
+    let len = data.len() as f64;
+
+    let (offset, level) = match mode {
+        Normalization::Peak => (0.0, data.iter().fold(0.0, |m: f64, x| m.max(x.abs())) / target),
+        Normalization::Rms => (0.0, (data.iter().map(|x| x * x).sum::<f64>() / len).sqrt() / target),
+        Normalization::ZScore => {
+            let mean = data.iter().sum::<f64>() / len;
+            (mean, (data.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / len).sqrt())
+        }
+    };
+    if level == 0.0 || !level.is_finite() {
+        return Err("Cannot normalize silent or non-finite data.".into());
+    }
+
+    Ok(data.into_iter().map(|x| (x - offset) / level).collect())
+}
+
+/// Multiplies the data by a gain given in decibels (amplitude, `20 log10`).
+pub fn apply_gain(data: Vec<f64>, gain_db: f64) -> Vec<f64> {
+    let gain = 10f64.powf(gain_db / 20.0);
+    data.into_iter().map(|x| x * gain).collect()
+}
+
+/// Sensitivity of a sensor and its acquisition chain in raw readings per engineering unit, e.g. V per m/s².
+#[derive(Clone, Debug, PartialEq)]
+pub enum Sensitivity {
+    /// The same sensitivity at all frequencies, as on a calibration certificate.
+    Constant(f64),
+    /// Sensitivity measured at increasing frequencies, linearly interpolated in between and held beyond the ends.
+    Curve { freqs: Vec<f64>, values: Vec<f64> },
+}
+
+/// Converts raw readings, e.g. ADC counts or volts, to engineering units by dividing by the sensitivity.
+///     A curve is applied in the frequency domain over the whole record, correcting the roll-off of a sensor
+///     near the edges of its band. The signal metadata records the unit and, for a constant sensitivity, its
+///     value.
+pub fn apply_calibration(mut signal: Signal, sensitivity: &Sensitivity, unit: &str) -> Result<Signal, Box<dyn Error>> {
+    if signal.data.is_empty() {
+        return Err("Cannot calibrate an empty signal.".into());
+    }
+    match sensitivity {
+        Sensitivity::Constant(value) => {
+            if *value == 0.0 || !value.is_finite() {
+                return Err("The sensitivity must be finite and non-zero.".into());
+            }
+            signal.data.iter_mut().for_each(|x| *x /= value);
+            signal.metadata.calibration = Some(*value);
+        }
+        Sensitivity::Curve { freqs, values } => {
+            if values.iter().any(|v| !(*v > 0.0 && v.is_finite())) {
+                return Err("The sensitivity curve must be positive and finite.".into());
+            }
+            let len = signal.data.len();
+            // Negative frequencies mirror the positive ones so the output stays real
+            let bins = (0..len).map(|k| k.min(len - k) as f64 * signal.fs / len as f64).collect();
+            let response = interpolate(freqs.clone(), values.clone(), bins, Interpolation::Linear)?;
+            let mut buffer: Vec<Complex<f64>> = signal.data.iter().map(|&x| Complex::new(x, 0.0)).collect();
+            plan_fft(len, FftDirection::Forward).process(&mut buffer);
+            buffer.iter_mut().zip(&response).for_each(|(c, r)| *c /= r * len as f64);
+            plan_fft(len, FftDirection::Inverse).process(&mut buffer);
+            signal.data = buffer.into_iter().map(|c| c.re).collect();
+            signal.metadata.calibration = None;
+        }
+    }
+    log::debug!("Calibrated {} samples to {}", signal.data.len(), unit);
+    signal.metadata.unit = Some(unit.to_string());
+    Ok(signal)
+}
+
+type Mat2 = [[f64; 2]; 2];
+
+fn mat2_mul(a: &Mat2, b: &Mat2) -> Mat2 {
+    [
+        [a[0][0] * b[0][0] + a[0][1] * b[1][0], a[0][0] * b[0][1] + a[0][1] * b[1][1]],
+        [a[1][0] * b[0][0] + a[1][1] * b[1][0], a[1][0] * b[0][1] + a[1][1] * b[1][1]],
+    ]
+}
+
+fn mat2_transpose(a: &Mat2) -> Mat2 {
+    [[a[0][0], a[1][0]], [a[0][1], a[1][1]]]
+}
+
+fn mat2_add(a: &Mat2, b: &Mat2, sign: f64) -> Mat2 {
+    [[a[0][0] + sign * b[0][0], a[0][1] + sign * b[0][1]], [a[1][0] + sign * b[1][0], a[1][1] + sign * b[1][1]]]
+}
+
+fn mat2_inverse(a: &Mat2) -> Mat2 {
+    let det = a[0][0] * a[1][1] - a[0][1] * a[1][0];
+    [[a[1][1] / det, -a[0][1] / det], [-a[1][0] / det, a[0][0] / det]]
+}
+
+/// Level and slope of a trend estimated by [`kalman_trend`], with the variance of the level.
+pub struct TrendEstimate {
+    pub level: Vec<f64>,
+    /// Slope in units per sample.
+    pub slope: Vec<f64>,
+    pub variance: Vec<f64>,
+}
+
+/// Extracts the trend of a noisy stream with a local linear trend Kalman filter, optionally followed by a
+///     Rauch-Tung-Striebel smoother. `process_noise` is the variance of the random slope changes per sample and
+///     `measurement_noise` the variance of the observation noise, so their ratio sets the smoothness.
+pub fn kalman_trend(
+    data: Vec<f64>,
+    process_noise: f64,
+    measurement_noise: f64,
+    smooth: bool,
+) -> Result<TrendEstimate, Box<dyn Error>> {
+    if data.is_empty() {
+        return Err("Data must not be empty.".into());
+    }
+    if process_noise < 0.0 || measurement_noise <= 0.0 {
+        return Err("Process noise must not be negative and measurement noise must be positive.".into());
+    }
+
+    let transition: Mat2 = [[1.0, 1.0], [0.0, 1.0]];
+    // Integrated white noise acceleration over one sample
+    let q: Mat2 = [[process_noise / 3.0, process_noise / 2.0], [process_noise / 2.0, process_noise]];
+    let mean = data.iter().sum::<f64>() / data.len() as f64;
+    let spread = data.iter().map(|y| (y - mean).powi(2)).sum::<f64>() / data.len() as f64 + measurement_noise;
+
+    // Start from a diffuse prior so the first samples dominate the initial state
+    let mut state = [data[0], 0.0];
+    let mut covariance: Mat2 = [[1e3 * spread, 0.0], [0.0, 1e3 * spread]];
+    let mut filtered = Vec::with_capacity(data.len());
+    let mut predicted = Vec::with_capacity(data.len());
+    for (k, &y) in data.iter().enumerate() {
+        if k > 0 {
+            state = [state[0] + state[1], state[1]];
+            covariance = mat2_add(&mat2_mul(&mat2_mul(&transition, &covariance), &mat2_transpose(&transition)), &q, 1.0);
+        }
+        predicted.push((state, covariance));
+
+        let innovation = y - state[0];
+        let gain = [covariance[0][0] / (covariance[0][0] + measurement_noise), covariance[1][0] / (covariance[0][0] + measurement_noise)];
+        state = [state[0] + gain[0] * innovation, state[1] + gain[1] * innovation];
+        covariance = [
+            [(1.0 - gain[0]) * covariance[0][0], (1.0 - gain[0]) * covariance[0][1]],
+            [covariance[1][0] - gain[1] * covariance[0][0], covariance[1][1] - gain[1] * covariance[0][1]],
+        ];
+        filtered.push((state, covariance));
+    }
+
+    if smooth {
+        for k in (0..filtered.len() - 1).rev() {
+            let (state, covariance) = filtered[k];
+            let (next_state, next_covariance) = filtered[k + 1];
+            let (predicted_state, predicted_covariance) = predicted[k + 1];
+            let gain = mat2_mul(&mat2_mul(&covariance, &mat2_transpose(&transition)), &mat2_inverse(&predicted_covariance));
+            let difference = [next_state[0] - predicted_state[0], next_state[1] - predicted_state[1]];
+            let smoothed_state = [
+                state[0] + gain[0][0] * difference[0] + gain[0][1] * difference[1],
+                state[1] + gain[1][0] * difference[0] + gain[1][1] * difference[1],
+            ];
+            let correction = mat2_mul(&mat2_mul(&gain, &mat2_add(&next_covariance, &predicted_covariance, -1.0)), &mat2_transpose(&gain));
+            filtered[k] = (smoothed_state, mat2_add(&covariance, &correction, 1.0));
+        }
+    }
+
+    Ok(TrendEstimate {
+        level: filtered.iter().map(|(x, _)| x[0]).collect(),
+        slope: filtered.iter().map(|(x, _)| x[1]).collect(),
+        variance: filtered.iter().map(|(_, p)| p[0][0]).collect(),
+    })
+}
+
+/// How [`fill_gaps`] fills missing samples.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GapFill {
+    /// Marks missing samples as NaN, for downstream code that handles them.
+    Nan,
+    Zero,
+    /// Straight line between the samples either side of the gap.
+    Linear,
+    /// Autoregressive prediction forward from before and backward from after the gap, cross-faded. Keeps the
+    ///     spectral content of the surrounding signal across short gaps.
+    Ar,
+}
+
+/// A dropout found by [`fill_gaps`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Gap {
+    /// Times of the recorded samples either side of the gap.
+    pub start_time: f64,
+    pub end_time: f64,
+    /// Index of the first inserted sample in the filled record.
+    pub index: usize,
+    /// Number of samples inserted.
+    pub missing: usize,
+}
+
+/// A record on a continuous time base, see [`fill_gaps`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct FilledRecord {
+    pub time: Vec<f64>,
+    pub data: Vec<f64>,
+    pub gaps: Vec<Gap>,
+}
+
+/// Predicts `count` samples following `context` with a Burg AR model fitted to it, `None` if it is too short.
+fn ar_extrapolate(context: &[f64], count: usize) -> Option<Vec<f64>> {
+    let order = (context.len() / 4).min(32);
+    if order == 0 {
+        return None;
+    }
+    let (coefficients, _) = ar_models(context, order, ArMethod::Burg).pop()?;
+    let mut history = context.to_vec();
+    for _ in 0..count {
+        let n = history.len();
+        let next = -(1..=order).map(|i| coefficients[i] * history[n - i]).sum::<f64>();
+        history.push(next);
+    }
+    Some(history.split_off(context.len()))
+}
+
+/// Detects dropouts in a sampled record and inserts the missing samples, so spectra are not silently corrupted
+///     by a time base with holes. A step longer than 1.5 times the median step is a gap, filled with as many
+///     samples as the median step fits into it.
+pub fn fill_gaps(time: &[f64], data: &[f64], method: GapFill) -> Result<FilledRecord, Box<dyn Error>> {
+    const CONTEXT: usize = 1024;
+    if time.len() != data.len() || time.len() < 3 {
+        return Err("time and data must have the same length of at least three samples.".into());
+    }
+    if time.windows(2).any(|w| w[1] <= w[0]) {
+        return Err("time must be strictly increasing.".into());
+    }
+    let mut steps: Vec<f64> = time.windows(2).map(|w| w[1] - w[0]).collect();
+    let step = median(&mut steps);
+
+    let mut filled = FilledRecord { time: Vec::with_capacity(time.len()), data: Vec::with_capacity(data.len()), gaps: Vec::new() };
+    for i in 0..time.len() {
+        filled.time.push(time[i]);
+        filled.data.push(data[i]);
+        let Some(&next_time) = time.get(i + 1) else {
+            break;
+        };
+        let gap = next_time - time[i];
+        if gap <= 1.5 * step {
+            continue;
+        }
+
+        let missing = ((gap / step).round() as usize).max(2) - 1;
+        let spacing = gap / (missing + 1) as f64;
+        let values = match method {
+            GapFill::Nan => vec![f64::NAN; missing],
+            GapFill::Zero => vec![0.0; missing],
+            GapFill::Linear | GapFill::Ar => {
+                let linear: Vec<f64> =
+                    (1..=missing).map(|k| data[i] + (data[i + 1] - data[i]) * k as f64 / (missing + 1) as f64).collect();
+                // Backward context runs up to the next gap, reversed so it can be predicted forward
+                let after_end = (i + 1..time.len())
+                    .find(|&j| j + 1 < time.len() && time[j + 1] - time[j] > 1.5 * step)
+                    .map_or(time.len(), |j| j + 1)
+                    .min(i + 1 + CONTEXT);
+                let before = &filled.data[filled.data.len().saturating_sub(CONTEXT)..];
+                let after: Vec<f64> = data[i + 1..after_end].iter().rev().copied().collect();
+                let predictions = match method {
+                    GapFill::Ar => ar_extrapolate(before, missing).zip(ar_extrapolate(&after, missing)),
+                    _ => None,
+                };
+                match predictions {
+                    Some((forward, backward)) => (0..missing)
+                        .map(|k| {
+                            let weight = (k + 1) as f64 / (missing + 1) as f64;
+                            (1.0 - weight) * forward[k] + weight * backward[missing - 1 - k]
+                        })
+                        .collect(),
+                    None => linear,
+                }
+            }
+        };
+
+        filled.gaps.push(Gap { start_time: time[i], end_time: next_time, index: filled.data.len(), missing });
+        filled.time.extend((1..=missing).map(|k| time[i] + spacing * k as f64));
+        filled.data.extend(values);
+    }
+    if !filled.gaps.is_empty() {
+        log::warn!("Filled {} gaps with {} samples", filled.gaps.len(), filled.gaps.iter().map(|g| g.missing).sum::<usize>());
+    }
+
+    Ok(filled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(len: usize, phase: f64) -> Vec<f64> {
+        (0..len).map(|i| (2.0 * std::f64::consts::PI * (i as f64 + phase) / 1000.0).sin()).collect()
+    }
+
+    #[test]
+    fn clean_sine_is_not_clipped() {
+        // With a half sample offset the two samples around each crest are equal
+        for phase in [0.0, 0.5] {
+            let data = sine(2000, phase);
+            assert!(detect_clipping(&data, None).is_empty());
+            assert_eq!(declip(data.clone(), None).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn hard_clipped_sine_gives_the_flat_runs() {
+        let data: Vec<f64> = sine(2000, 0.0).into_iter().map(|x| x.clamp(-0.8, 0.8)).collect();
+        assert_eq!(detect_clipping(&data, None), vec![(148, 353), (648, 853), (1148, 1353), (1648, 1853)]);
+    }
+}
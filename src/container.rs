@@ -0,0 +1,550 @@
+//! Signal and spectrum containers that can be saved, restored and sent between processes.
+//!
+//! Both serialize to a small JSON document and to NumPy `.npz` archives readable with `numpy.load`.
+
+use std::error::Error;
+use std::fmt::Write as _;
+
+/// Optional description of what a [`Signal`] or [`Spectrum`] holds, used for automatic plot labels.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Metadata {
+    /// Physical quantity, e.g. `"Acceleration"`.
+    pub quantity: Option<String>,
+    /// Unit of the signal values, e.g. `"m/s²"`. Spectra give the unit of the signal they were computed from.
+    pub unit: Option<String>,
+    /// Channel name, e.g. `"Bearing housing X"`.
+    pub name: Option<String>,
+    /// Sensitivity the raw readings were divided by to obtain the unit, e.g. in mV per m/s², kept for
+    ///     traceability. The values are not rescaled by it.
+    pub calibration: Option<f64>,
+}
+
+impl Metadata {
+    /// The quantity with its unit in brackets, e.g. `"Acceleration [m/s²]"`, with `"Amplitude"` as the default
+    ///     quantity.
+    pub fn label(&self) -> String {
+        let quantity = self.quantity.as_deref().unwrap_or("Amplitude");
+        match &self.unit {
+            Some(unit) => format!("{} [{}]", quantity, unit),
+            None => quantity.to_string(),
+        }
+    }
+
+    /// The unit squared, parenthesized unless it is a single symbol, e.g. `"V²"` or `"(m/s²)²"`.
+    pub fn squared_unit(&self) -> Option<String> {
+        self.unit.as_ref().map(|unit| {
+            if unit.chars().all(char::is_alphabetic) {
+                format!("{}²", unit)
+            } else {
+                format!("({})²", unit)
+            }
+        })
+    }
+}
+
+/// Uniformly sampled data with its sampling rate.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Signal {
+    pub data: Vec<f64>,
+    pub fs: f64,
+    pub metadata: Metadata,
+}
+
+/// What the values of a [`Spectrum`] measure.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SpectrumKind {
+    /// Amplitude in the unit of the signal.
+    #[default]
+    Amplitude,
+    /// Power per bin in the squared unit.
+    Power,
+    /// Power spectral density in the squared unit per Hz.
+    Psd,
+}
+
+impl SpectrumKind {
+    /// Lowercase name of the kind, as accepted by the Python bindings.
+    pub fn name(self) -> &'static str {
+        match self {
+            SpectrumKind::Amplitude => "amplitude",
+            SpectrumKind::Power => "power",
+            SpectrumKind::Psd => "psd",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        [SpectrumKind::Amplitude, SpectrumKind::Power, SpectrumKind::Psd].into_iter().find(|k| k.name() == name)
+    }
+}
+
+/// Values over frequency, e.g. an amplitude spectrum or a PSD.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Spectrum {
+    pub freqs: Vec<f64>,
+    pub values: Vec<f64>,
+    pub kind: SpectrumKind,
+    pub metadata: Metadata,
+}
+
+impl Signal {
+    /// Creates a signal without metadata, fill in [`Signal::metadata`] to describe it.
+    pub fn new(data: Vec<f64>, fs: f64) -> Result<Self, Box<dyn Error>> {
+        if !(fs > 0.0 && fs.is_finite()) {
+            return Err("fs must be positive and finite.".into());
+        }
+        Ok(Signal { data, fs, metadata: Metadata::default() })
+    }
+
+    /// Length of the record in seconds.
+    pub fn duration(&self) -> f64 {
+        self.data.len() as f64 / self.fs
+    }
+
+    /// Axis label of the values, see [`Metadata::label`].
+    pub fn label(&self) -> String {
+        self.metadata.label()
+    }
+
+    /// Serializes to `{"type": "Signal", "fs": ..., "data": [...], ...}` followed by the metadata fields, `null`
+    ///     where unset. Non-finite values are written as `NaN`, `Infinity` and `-Infinity` like Python's `json`
+    ///     module does.
+    pub fn to_json(&self) -> String {
+        let mut json = String::from("{\"type\": \"Signal\", \"fs\": ");
+        write_number(&mut json, self.fs);
+        json.push_str(", \"data\": ");
+        write_array(&mut json, &self.data);
+        write_metadata(&mut json, &self.metadata);
+        json.push('}');
+        json
+    }
+
+    /// Parses the output of [`Signal::to_json`]. Missing metadata fields are left unset.
+    pub fn from_json(text: &str) -> Result<Self, Box<dyn Error>> {
+        let fields = parse_document(text, "Signal")?;
+        let mut signal = Signal::new(array_field(&fields, "data")?, number_field(&fields, "fs")?)?;
+        signal.metadata = metadata_fields(&fields)?;
+        Ok(signal)
+    }
+
+    /// Writes an `.npz` archive holding the arrays `data` and `fs`, plus a string or scalar array per metadata
+    ///     field that is set.
+    pub fn to_npz(&self, path: &str) -> Result<(), Box<dyn Error>> {
+        let mut arrays = vec![("data", NpyArray::Floats(&self.data)), ("fs", NpyArray::Scalar(self.fs))];
+        arrays.extend(metadata_arrays(&self.metadata));
+        write_npz(path, &arrays)
+    }
+}
+
+impl Spectrum {
+    /// Creates an amplitude spectrum without metadata, set [`Spectrum::kind`] and [`Spectrum::metadata`] to
+    ///     describe other spectra.
+    pub fn new(freqs: Vec<f64>, values: Vec<f64>) -> Result<Self, Box<dyn Error>> {
+        if freqs.len() != values.len() {
+            return Err(format!("Got {} frequencies but {} values.", freqs.len(), values.len()).into());
+        }
+        Ok(Spectrum { freqs, values, kind: SpectrumKind::Amplitude, metadata: Metadata::default() })
+    }
+
+    /// Axis label of the values derived from the kind and unit, e.g. `"Acceleration [m/s²]"`,
+    ///     `"Power [(m/s²)²]"` or `"PSD [(m/s²)²/Hz]"`.
+    pub fn label(&self) -> String {
+        let squared = self.metadata.squared_unit();
+        match (self.kind, squared) {
+            (SpectrumKind::Amplitude, _) => self.metadata.label(),
+            (SpectrumKind::Power, Some(unit)) => format!("Power [{}]", unit),
+            (SpectrumKind::Power, None) => "Power".to_string(),
+            (SpectrumKind::Psd, Some(unit)) => format!("PSD [{}/Hz]", unit),
+            (SpectrumKind::Psd, None) => "PSD [1/Hz]".to_string(),
+        }
+    }
+
+    /// Serializes to `{"type": "Spectrum", "freqs": [...], "values": [...], "kind": ..., ...}` followed by the
+    ///     metadata fields, see [`Signal::to_json`].
+    pub fn to_json(&self) -> String {
+        let mut json = String::from("{\"type\": \"Spectrum\", \"freqs\": ");
+        write_array(&mut json, &self.freqs);
+        json.push_str(", \"values\": ");
+        write_array(&mut json, &self.values);
+        json.push_str(", \"kind\": ");
+        write_string(&mut json, self.kind.name());
+        write_metadata(&mut json, &self.metadata);
+        json.push('}');
+        json
+    }
+
+    /// Parses the output of [`Spectrum::to_json`]. A missing kind reads as an amplitude spectrum.
+    pub fn from_json(text: &str) -> Result<Self, Box<dyn Error>> {
+        let fields = parse_document(text, "Spectrum")?;
+        let mut spectrum = Spectrum::new(array_field(&fields, "freqs")?, array_field(&fields, "values")?)?;
+        if let Some(kind) = optional_string_field(&fields, "kind")? {
+            spectrum.kind = SpectrumKind::from_name(&kind).ok_or_else(|| format!("Unknown spectrum kind '{}'.", kind))?;
+        }
+        spectrum.metadata = metadata_fields(&fields)?;
+        Ok(spectrum)
+    }
+
+    /// Writes an `.npz` archive holding the arrays `freqs`, `values` and `kind`, plus a string or scalar array
+    ///     per metadata field that is set.
+    pub fn to_npz(&self, path: &str) -> Result<(), Box<dyn Error>> {
+        let mut arrays = vec![
+            ("freqs", NpyArray::Floats(&self.freqs)),
+            ("values", NpyArray::Floats(&self.values)),
+            ("kind", NpyArray::Text(self.kind.name())),
+        ];
+        arrays.extend(metadata_arrays(&self.metadata));
+        write_npz(path, &arrays)
+    }
+}
+
+fn write_string(json: &mut String, text: &str) {
+    json.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(json, "\\u{:04x}", c as u32);
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+fn write_metadata(json: &mut String, metadata: &Metadata) {
+    for (key, value) in [("quantity", &metadata.quantity), ("unit", &metadata.unit), ("name", &metadata.name)] {
+        let _ = write!(json, ", \"{}\": ", key);
+        match value {
+            Some(text) => write_string(json, text),
+            None => json.push_str("null"),
+        }
+    }
+    json.push_str(", \"calibration\": ");
+    match metadata.calibration {
+        Some(value) => write_number(json, value),
+        None => json.push_str("null"),
+    }
+}
+
+fn write_number(json: &mut String, value: f64) {
+    match value {
+        v if v.is_nan() => json.push_str("NaN"),
+        v if v == f64::INFINITY => json.push_str("Infinity"),
+        v if v == f64::NEG_INFINITY => json.push_str("-Infinity"),
+        // Debug formatting is the shortest text that parses back to the same value and keeps a decimal point
+        v => {
+            let _ = write!(json, "{:?}", v);
+        }
+    }
+}
+
+fn write_array(json: &mut String, values: &[f64]) {
+    json.push('[');
+    for (i, &value) in values.iter().enumerate() {
+        if i > 0 {
+            json.push_str(", ");
+        }
+        write_number(json, value);
+    }
+    json.push(']');
+}
+
+/// The subset of JSON the containers are written in.
+#[derive(Debug)]
+enum JsonValue {
+    Null,
+    Number(f64),
+    Text(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+
+struct JsonParser<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl JsonParser<'_> {
+    fn skip_whitespace(&mut self) {
+        let rest = &self.text[self.position..];
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    fn error(&self, message: &str) -> Box<dyn Error> {
+        format!("Invalid JSON at offset {}: {}.", self.position, message).into()
+    }
+
+    fn expect(&mut self, token: char) -> Result<(), Box<dyn Error>> {
+        self.skip_whitespace();
+        if !self.text[self.position..].starts_with(token) {
+            return Err(self.error(&format!("expected '{}'", token)));
+        }
+        self.position += 1;
+        Ok(())
+    }
+
+    /// Consumes `token` if it comes next.
+    fn accept(&mut self, token: char) -> bool {
+        self.skip_whitespace();
+        let found = self.text[self.position..].starts_with(token);
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn value(&mut self) -> Result<JsonValue, Box<dyn Error>> {
+        self.skip_whitespace();
+        let rest = &self.text[self.position..];
+        for (word, value) in [("null", None), ("NaN", Some(f64::NAN)), ("Infinity", Some(f64::INFINITY)), ("-Infinity", Some(f64::NEG_INFINITY))] {
+            if rest.starts_with(word) {
+                self.position += word.len();
+                return Ok(value.map_or(JsonValue::Null, JsonValue::Number));
+            }
+        }
+        match rest.chars().next() {
+            Some('"') => self.string().map(JsonValue::Text),
+            Some('[') => {
+                self.position += 1;
+                let mut items = Vec::new();
+                if !self.accept(']') {
+                    loop {
+                        items.push(self.value()?);
+                        if self.accept(']') {
+                            break;
+                        }
+                        self.expect(',')?;
+                    }
+                }
+                Ok(JsonValue::Array(items))
+            }
+            Some('{') => {
+                self.position += 1;
+                let mut fields = Vec::new();
+                if !self.accept('}') {
+                    loop {
+                        self.skip_whitespace();
+                        let key = self.string()?;
+                        self.expect(':')?;
+                        fields.push((key, self.value()?));
+                        if self.accept('}') {
+                            break;
+                        }
+                        self.expect(',')?;
+                    }
+                }
+                Ok(JsonValue::Object(fields))
+            }
+            Some(c) if c == '-' || c.is_ascii_digit() => {
+                let length = rest.find(|c: char| !(c.is_ascii_digit() || "+-.eE".contains(c))).unwrap_or(rest.len());
+                let number = rest[..length].parse().map_err(|_| self.error("malformed number"))?;
+                self.position += length;
+                Ok(JsonValue::Number(number))
+            }
+            _ => Err(self.error("unexpected character")),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, Box<dyn Error>> {
+        if !self.text[self.position..].starts_with('"') {
+            return Err(self.error("expected a string"));
+        }
+        let mut result = String::new();
+        let mut chars = self.text[self.position + 1..].char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.position += i + 2;
+                    return Ok(result);
+                }
+                '\\' => match chars.next().map(|(_, c)| c) {
+                    Some('n') => result.push('\n'),
+                    Some('t') => result.push('\t'),
+                    Some('r') => result.push('\r'),
+                    Some('b') => result.push('\u{8}'),
+                    Some('f') => result.push('\u{c}'),
+                    Some('u') => {
+                        let code: String = chars.by_ref().take(4).map(|(_, c)| c).collect();
+                        let code = u32::from_str_radix(&code, 16).map_err(|_| self.error("malformed escape"))?;
+                        result.push(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+                    }
+                    Some(c) => result.push(c),
+                    None => break,
+                },
+                c => result.push(c),
+            }
+        }
+        Err(self.error("unterminated string"))
+    }
+}
+
+/// Parses a JSON object and checks its `type` field, returning the fields.
+fn parse_document(text: &str, kind: &str) -> Result<Vec<(String, JsonValue)>, Box<dyn Error>> {
+    let mut parser = JsonParser { text, position: 0 };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    if parser.position != text.len() {
+        return Err(parser.error("trailing characters"));
+    }
+    let JsonValue::Object(fields) = value else {
+        return Err("Expected a JSON object.".into());
+    };
+    match fields.iter().find(|(key, _)| key == "type") {
+        Some((_, JsonValue::Text(found))) if found == kind => Ok(fields),
+        Some((_, JsonValue::Text(found))) => Err(format!("Expected a {} document, got a {}.", kind, found).into()),
+        _ => Err(format!("Expected a {} document without a type field.", kind).into()),
+    }
+}
+
+fn field<'a>(fields: &'a [(String, JsonValue)], name: &str) -> Result<&'a JsonValue, Box<dyn Error>> {
+    fields.iter().find(|(key, _)| key == name).map(|(_, value)| value).ok_or_else(|| format!("Missing field '{}'.", name).into())
+}
+
+fn number_field(fields: &[(String, JsonValue)], name: &str) -> Result<f64, Box<dyn Error>> {
+    match field(fields, name)? {
+        JsonValue::Number(value) => Ok(*value),
+        _ => Err(format!("Field '{}' must be a number.", name).into()),
+    }
+}
+
+fn array_field(fields: &[(String, JsonValue)], name: &str) -> Result<Vec<f64>, Box<dyn Error>> {
+    let JsonValue::Array(items) = field(fields, name)? else {
+        return Err(format!("Field '{}' must be an array.", name).into());
+    };
+    items
+        .iter()
+        .map(|item| match item {
+            JsonValue::Number(value) => Ok(*value),
+            // Some JSON writers turn NaN into null
+            JsonValue::Null => Ok(f64::NAN),
+            _ => Err(format!("Field '{}' must hold numbers.", name).into()),
+        })
+        .collect()
+}
+
+fn optional_string_field(fields: &[(String, JsonValue)], name: &str) -> Result<Option<String>, Box<dyn Error>> {
+    match fields.iter().find(|(key, _)| key == name).map(|(_, value)| value) {
+        None | Some(JsonValue::Null) => Ok(None),
+        Some(JsonValue::Text(text)) => Ok(Some(text.clone())),
+        _ => Err(format!("Field '{}' must be a string.", name).into()),
+    }
+}
+
+fn metadata_fields(fields: &[(String, JsonValue)]) -> Result<Metadata, Box<dyn Error>> {
+    let calibration = match fields.iter().find(|(key, _)| key == "calibration").map(|(_, value)| value) {
+        None | Some(JsonValue::Null) => None,
+        Some(JsonValue::Number(value)) => Some(*value),
+        _ => return Err("Field 'calibration' must be a number.".into()),
+    };
+    Ok(Metadata {
+        quantity: optional_string_field(fields, "quantity")?,
+        unit: optional_string_field(fields, "unit")?,
+        name: optional_string_field(fields, "name")?,
+        calibration,
+    })
+}
+
+/// Contents of one `.npy` file in an archive.
+enum NpyArray<'a> {
+    Floats(&'a [f64]),
+    /// A zero dimensional float64 array.
+    Scalar(f64),
+    /// A zero dimensional unicode string array.
+    Text(&'a str),
+}
+
+/// The metadata fields that are set, as `.npz` entries.
+fn metadata_arrays(metadata: &Metadata) -> Vec<(&'static str, NpyArray<'_>)> {
+    let mut arrays = Vec::new();
+    for (key, value) in [("quantity", &metadata.quantity), ("unit", &metadata.unit), ("name", &metadata.name)] {
+        if let Some(text) = value {
+            arrays.push((key, NpyArray::Text(text)));
+        }
+    }
+    if let Some(calibration) = metadata.calibration {
+        arrays.push(("calibration", NpyArray::Scalar(calibration)));
+    }
+    arrays
+}
+
+/// CRC-32 as used by zip archives.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+/// A little endian `.npy` file, float64 or UTF-32 text as NumPy stores unicode strings.
+fn npy(array: &NpyArray) -> Vec<u8> {
+    let (descr, shape, body): (String, String, Vec<u8>) = match array {
+        NpyArray::Floats(values) => {
+            ("<f8".to_string(), format!("({},)", values.len()), values.iter().flat_map(|v| v.to_le_bytes()).collect())
+        }
+        NpyArray::Scalar(value) => ("<f8".to_string(), "()".to_string(), value.to_le_bytes().to_vec()),
+        NpyArray::Text(text) => {
+            let body: Vec<u8> = text.chars().flat_map(|c| (c as u32).to_le_bytes()).collect();
+            (format!("<U{}", text.chars().count().max(1)), "()".to_string(), if text.is_empty() { vec![0; 4] } else { body })
+        }
+    };
+    let mut header = format!("{{'descr': '{}', 'fortran_order': False, 'shape': {}, }}", descr, shape);
+    // Magic, version and header length take 10 bytes, the data starts 64 byte aligned
+    let padding = (64 - (10 + header.len() + 1) % 64) % 64;
+    header.extend(std::iter::repeat_n(' ', padding));
+    header.push('\n');
+
+    let mut file = b"\x93NUMPY\x01\x00".to_vec();
+    file.extend_from_slice(&(header.len() as u16).to_le_bytes());
+    file.extend_from_slice(header.as_bytes());
+    file.extend_from_slice(&body);
+    file
+}
+
+/// Writes named arrays as an uncompressed zip of `.npy` files, the layout of `numpy.savez`.
+fn write_npz(path: &str, arrays: &[(&str, NpyArray)]) -> Result<(), Box<dyn Error>> {
+    let mut archive = Vec::new();
+    let mut directory = Vec::new();
+    for (name, array) in arrays {
+        let name = format!("{}.npy", name);
+        let data = npy(array);
+        let size = u32::try_from(data.len()).map_err(|_| "Array too large for an npz archive.")?;
+        let offset = u32::try_from(archive.len()).map_err(|_| "Arrays too large for an npz archive.")?;
+        // Version, flags, stored method, DOS time and date, CRC and sizes shared by both headers
+        let mut common = Vec::with_capacity(26);
+        common.extend_from_slice(&[20, 0, 0, 0, 0, 0, 0, 0, 0x21, 0]);
+        common.extend_from_slice(&crc32(&data).to_le_bytes());
+        common.extend_from_slice(&size.to_le_bytes());
+        common.extend_from_slice(&size.to_le_bytes());
+        common.extend_from_slice(&(name.len() as u16).to_le_bytes());
+
+        archive.extend_from_slice(b"PK\x03\x04");
+        archive.extend_from_slice(&common);
+        archive.extend_from_slice(&[0, 0]);
+        archive.extend_from_slice(name.as_bytes());
+        archive.extend_from_slice(&data);
+
+        directory.extend_from_slice(b"PK\x01\x02\x14\x00");
+        directory.extend_from_slice(&common);
+        // Extra and comment lengths, disk, internal and external attributes
+        directory.extend_from_slice(&[0; 12]);
+        directory.extend_from_slice(&offset.to_le_bytes());
+        directory.extend_from_slice(name.as_bytes());
+    }
+
+    let directory_offset = u32::try_from(archive.len()).map_err(|_| "Arrays too large for an npz archive.")?;
+    let count = (arrays.len() as u16).to_le_bytes();
+    archive.extend_from_slice(&directory);
+    archive.extend_from_slice(b"PK\x05\x06\x00\x00\x00\x00");
+    archive.extend_from_slice(&count);
+    archive.extend_from_slice(&count);
+    archive.extend_from_slice(&(directory.len() as u32).to_le_bytes());
+    archive.extend_from_slice(&directory_offset.to_le_bytes());
+    archive.extend_from_slice(&[0, 0]);
+    std::fs::write(path, archive)?;
+    Ok(())
+}
@@ -0,0 +1,127 @@
+//! Time delay estimation and alignment of pairs of signals.
+
+use rustfft::{FftDirection, num_complex::Complex};
+use std::error::Error;
+
+use crate::filter::apply_response;
+use crate::{plan_fft, resample};
+
+/// Estimated delay between two signals along with the correlation function it was picked from.
+pub struct DelayEstimate {
+    /// Delay of the first signal relative to the second in seconds, positive when the first lags behind.
+    pub delay: f64,
+    /// Lag of each correlation value in seconds.
+    pub lags: Vec<f64>,
+    pub correlation: Vec<f64>,
+}
+
+/// Circular cross-correlation of `a` with `b`, zero padded so no wrap-around occurs.
+///     When `phat` is set the cross spectrum is whitened (phase transform) before the inverse FFT.
+fn cross_correlate(a: &[f64], b: &[f64], phat: bool) -> Vec<f64> {
+    let n = (a.len() + b.len()).next_power_of_two();
+    let transform = |x: &[f64]| {
+        let mut buffer: Vec<Complex<f64>> = x.iter().map(|&v| Complex::new(v, 0.0)).collect();
+        buffer.resize(n, Complex::new(0.0, 0.0));
+        plan_fft(n, FftDirection::Forward).process(&mut buffer);
+        buffer
+    };
+    let mut spectrum: Vec<Complex<f64>> = transform(a).into_iter().zip(transform(b)).map(|(x, y)| x * y.conj()).collect();
+    if phat {
+        spectrum.iter_mut().for_each(|c| *c /= c.norm().max(1e-300));
+    }
+    plan_fft(n, FftDirection::Inverse).process(&mut spectrum);
+    spectrum.into_iter().map(|c| c.re / n as f64).collect()
+}
+
+/// Picks the peak of a circular correlation within `max_lag` samples and refines it with a parabolic fit.
+///     Returns the fractional lag in samples along with the correlation values for lags `-max_lag..=max_lag`.
+fn peak_lag(circular: &[f64], max_lag: usize) -> (f64, Vec<f64>) {
+    let n = circular.len();
+    let max_lag = max_lag.min(n / 2 - 1);
+    let correlation: Vec<f64> = (0..=2 * max_lag).map(|i| circular[(i + n - max_lag) % n]).collect();
+    let best = (0..correlation.len()).max_by(|&i, &j| correlation[i].total_cmp(&correlation[j])).unwrap_or(max_lag);
+
+    let mut offset = 0.0;
+    if best > 0 && best + 1 < correlation.len() {
+        let (left, centre, right) = (correlation[best - 1], correlation[best], correlation[best + 1]);
+        let denominator = left - 2.0 * centre + right;
+        if denominator.abs() > 0.0 {
+            offset = (0.5 * (left - right) / denominator).clamp(-0.5, 0.5);
+        }
+    }
+    (best as f64 + offset - max_lag as f64, correlation)
+}
+
+/// Estimates the delay of `a` relative to `b` with the generalized cross-correlation phase transform (GCC-PHAT).
+///     The search is limited to `max_delay` seconds when given, otherwise to the full overlap of the signals.
+pub fn gcc_phat(a: Vec<f64>, b: Vec<f64>, fs: f64, max_delay: Option<f64>) -> Result<DelayEstimate, Box<dyn Error>> {
+    if a.is_empty() || b.is_empty() {
+        return Err("Signals must not be empty.".into());
+    }
+    if fs <= 0.0 {
+        return Err("Sampling frequency must be greater than zero.".into());
+    }
+
+    let max_lag = match max_delay {
+        Some(delay) if delay < 0.0 => return Err("Maximum delay must not be negative.".into()),
+        Some(delay) => (delay * fs).ceil() as usize,
+        None => a.len().max(b.len()),
+    };
+    let (lag, correlation) = peak_lag(&cross_correlate(&a, &b, true), max_lag);
+    let max_lag = (correlation.len() / 2) as f64;
+    let lags = (0..correlation.len()).map(|i| (i as f64 - max_lag) / fs).collect();
+
+    Ok(DelayEstimate { delay: lag / fs, lags, correlation })
+}
+
+/// Two signals cropped to their common, aligned span.
+pub struct Alignment {
+    /// Lag of the first signal relative to the second in samples (of the first signal's rate).
+    pub lag: i64,
+    pub a: Vec<f64>,
+    pub b: Vec<f64>,
+}
+
+/// Aligns `b` with `a` by the peak of their cross-correlation, then crops both to the overlapping span.
+///     When `rates` gives the sampling frequencies `(fs_a, fs_b)`, `b` is first resampled to the rate of `a`.
+///     The lag search is limited to `max_lag` samples when given.
+pub fn align_signals(
+    a: Vec<f64>,
+    b: Vec<f64>,
+    max_lag: Option<usize>,
+    rates: Option<(f64, f64)>,
+) -> Result<Alignment, Box<dyn Error>> {
+    if a.is_empty() || b.is_empty() {
+        return Err("Signals must not be empty.".into());
+    }
+    let b = match rates {
+        Some((fs_a, fs_b)) if fs_a <= 0.0 || fs_b <= 0.0 => return Err("Sampling frequencies must be greater than zero.".into()),
+        Some((fs_a, fs_b)) if fs_a != fs_b => {
+            let num = (b.len() as f64 * fs_a / fs_b).round().max(1.0) as usize;
+            log::debug!("Resampling second signal from {} to {} samples", b.len(), num);
+            resample(b, num)?
+        }
+        _ => b,
+    };
+
+    let (lag, _) = peak_lag(&cross_correlate(&a, &b, false), max_lag.unwrap_or(a.len().max(b.len())));
+    let lag = lag.round() as i64;
+    let (start_a, start_b) = if lag >= 0 { (lag as usize, 0) } else { (0, lag.unsigned_abs() as usize) };
+    let len = a.len().saturating_sub(start_a).min(b.len().saturating_sub(start_b));
+    if len == 0 {
+        return Err("The aligned signals do not overlap.".into());
+    }
+
+    Ok(Alignment { lag, a: a[start_a..start_a + len].to_vec(), b: b[start_b..start_b + len].to_vec() })
+}
+
+/// Delays the data by `delay` seconds, which need not be a whole number of samples, by applying the linear phase
+///     ramp `exp(-j 2 pi f delay)` to its spectrum. Negative delays advance the data.
+///     The shift is circular, so samples pushed past one end wrap around to the other; pad the data first to avoid it.
+pub fn fractional_delay(data: Vec<f64>, fs: f64, delay: f64) -> Result<Vec<f64>, Box<dyn Error>> {
+    if data.is_empty() || fs <= 0.0 {
+        return Err("Data must not be empty and fs must be greater than zero.".into());
+    }
+    log::debug!("Delaying {} samples by {} s", data.len(), delay);
+    Ok(apply_response(data, fs, |freq| Complex::from_polar(1.0, -2.0 * std::f64::consts::PI * freq * delay)))
+}
@@ -0,0 +1,90 @@
+//! Digital downconversion and AM/FM demodulation.
+
+use rustfft::{FftDirection, num_complex::Complex};
+use std::error::Error;
+use std::f64::consts::PI;
+
+use crate::plan_fft;
+
+/// Mixes the data down by `carrier` Hz and low-pass filters the complex result at `cutoff` Hz with an ideal
+///     FFT filter, returning the complex baseband signal at the original rate.
+fn baseband(data: &[f64], fs: f64, carrier: f64, cutoff: f64) -> Result<Vec<Complex<f64>>, Box<dyn Error>> {
+    let len = data.len();
+    if len == 0 || fs <= 0.0 {
+        return Err("Data must not be empty and fs must be greater than zero.".into());
+    }
+    if carrier < 0.0 || carrier >= fs / 2.0 || cutoff <= 0.0 {
+        return Err("Carrier must be between 0 and the Nyquist frequency and the bandwidth must be positive.".into());
+    }
+
+    let mut buffer: Vec<Complex<f64>> = data
+        .iter()
+        .enumerate()
+        .map(|(n, &x)| Complex::from_polar(x, -2.0 * PI * carrier * n as f64 / fs))
+        .collect();
+    plan_fft(len, FftDirection::Forward).process(&mut buffer);
+    for (k, c) in buffer.iter_mut().enumerate() {
+        let freq = k.min(len - k) as f64 * fs / len as f64;
+        if freq > cutoff {
+            *c = Complex::new(0.0, 0.0);
+        }
+    }
+    plan_fft(len, FftDirection::Inverse).process(&mut buffer);
+    buffer.iter_mut().for_each(|c| *c /= len as f64);
+    Ok(buffer)
+}
+
+/// Default baseband bandwidth, keeping the mixing image at twice the carrier out of the passband.
+fn default_cutoff(fs: f64, carrier: f64) -> f64 {
+    carrier.min(fs / 2.0 - carrier)
+}
+
+/// Translates the spectrum of the data by `shift` Hz by multiplying it with `exp(j 2 pi shift t)`.
+///     A negative shift equal to a carrier frequency moves that band to baseband. No filtering is applied,
+///     so the mirrored negative frequency content is translated as well.
+pub fn frequency_shift(data: Vec<f64>, fs: f64, shift: f64) -> Result<Vec<Complex<f64>>, Box<dyn Error>> {
+    if fs <= 0.0 {
+        return Err("fs must be greater than zero.".into());
+    }
+    Ok(data
+        .into_iter()
+        .enumerate()
+        .map(|(n, x)| Complex::from_polar(x, 2.0 * PI * shift * n as f64 / fs))
+        .collect())
+}
+
+/// Downconverts the data to complex baseband (I/Q) around `carrier` Hz and decimates it by `decimation`.
+///     The anti-alias filter cuts at `fs / (2 * decimation)` unless a narrower `bandwidth` is given.
+///     Returns the in-phase and quadrature components at `fs / decimation`.
+pub fn mix_to_baseband(
+    data: Vec<f64>,
+    fs: f64,
+    carrier: f64,
+    decimation: usize,
+    bandwidth: Option<f64>,
+) -> Result<(Vec<f64>, Vec<f64>), Box<dyn Error>> {
+    if decimation == 0 {
+        return Err("Decimation must be positive.".into());
+    }
+    let cutoff = bandwidth.unwrap_or(f64::INFINITY).min(fs / (2.0 * decimation as f64));
+    let signal = baseband(&data, fs, carrier, cutoff)?;
+    Ok(signal.iter().step_by(decimation).map(|c| (c.re, c.im)).unzip())
+}
+
+/// Recovers the message of an amplitude modulated signal as the envelope around `carrier` Hz.
+///     The envelope keeps the carrier amplitude, so subtract its mean for the message of standard (DSB-LC) AM.
+pub fn demodulate_am(data: Vec<f64>, fs: f64, carrier: f64, bandwidth: Option<f64>) -> Result<Vec<f64>, Box<dyn Error>> {
+    let signal = baseband(&data, fs, carrier, bandwidth.unwrap_or_else(|| default_cutoff(fs, carrier)))?;
+    // Mixing a real carrier to DC keeps half of its amplitude
+    Ok(signal.iter().map(|c| 2.0 * c.norm()).collect())
+}
+
+/// Recovers the message of a frequency modulated signal as the instantaneous frequency deviation in Hz
+///     from `carrier`, using the phase difference of successive baseband samples.
+pub fn demodulate_fm(data: Vec<f64>, fs: f64, carrier: f64, bandwidth: Option<f64>) -> Result<Vec<f64>, Box<dyn Error>> {
+    let signal = baseband(&data, fs, carrier, bandwidth.unwrap_or_else(|| default_cutoff(fs, carrier)))?;
+    let mut deviation: Vec<f64> = signal.windows(2).map(|w| (w[1] * w[0].conj()).arg() * fs / (2.0 * PI)).collect();
+    // Repeat the first estimate so the output lines up with the input samples
+    deviation.insert(0, deviation.first().copied().unwrap_or(0.0));
+    Ok(deviation)
+}
@@ -0,0 +1,150 @@
+//! Filter design from frequency responses.
+
+use rustfft::{FftDirection, num_complex::Complex};
+use std::error::Error;
+use std::f64::consts::PI;
+
+use crate::iir::Biquad;
+use crate::interpolate::{interpolate, Interpolation};
+use crate::plan_fft;
+use crate::spectral::hann_window;
+
+/// Builds the minimum-phase spectrum with the given magnitude using the real cepstrum (homomorphic) method.
+///     `magnitude` holds all `N` FFT bins (as returned by `compute_magnitude`) and should be symmetric.
+///     Returns the real and imaginary parts of the minimum-phase spectrum.
+pub fn minimum_phase(magnitude: Vec<f64>) -> Result<(Vec<f64>, Vec<f64>), Box<dyn Error>> {
+    let len = magnitude.len();
+    if len < 2 || magnitude.iter().any(|m| *m < 0.0 || !m.is_finite()) {
+        return Err("Magnitude must have at least two finite, non-negative bins.".into());
+    }
+
+    // Floor zeros so the log stays finite, 240 dB below the peak
+    let floor = magnitude.iter().copied().fold(0.0, f64::max).max(f64::MIN_POSITIVE) * 1e-12;
+    let mut cepstrum: Vec<Complex<f64>> = magnitude.iter().map(|m| Complex::new(m.max(floor).ln(), 0.0)).collect();
+    plan_fft(len, FftDirection::Inverse).process(&mut cepstrum);
+
+    // Fold the anti-causal part of the cepstrum onto the causal part
+    for (n, c) in cepstrum.iter_mut().enumerate() {
+        let fold = if n == 0 || 2 * n == len {
+            1.0
+        } else if 2 * n < len {
+            2.0
+        } else {
+            0.0
+        };
+        *c = Complex::new(c.re * fold / len as f64, 0.0);
+    }
+
+    plan_fft(len, FftDirection::Forward).process(&mut cepstrum);
+    Ok(cepstrum.into_iter().map(|c| c.exp()).map(|c| (c.re, c.im)).unzip())
+}
+
+/// Designs a linear-phase FIR filter with `taps` coefficients approximating the given magnitude response
+///     by frequency sampling and a Hann window. `magnitude` holds all `N` FFT bins and `taps` must not exceed `N`.
+pub fn linear_phase_fir(magnitude: Vec<f64>, taps: usize) -> Result<Vec<f64>, Box<dyn Error>> {
+    let len = magnitude.len();
+    if taps == 0 || taps > len {
+        return Err("The number of taps must be between 1 and the number of magnitude bins.".into());
+    }
+
+    // Delay the zero-phase response by half the filter length so it becomes causal
+    let delay = (taps - 1) as f64 / 2.0;
+    let mut buffer: Vec<Complex<f64>> = magnitude
+        .iter()
+        .enumerate()
+        .map(|(k, &m)| {
+            let bin = if 2 * k <= len { k as f64 } else { k as f64 - len as f64 };
+            Complex::from_polar(m, -2.0 * PI * bin * delay / len as f64)
+        })
+        .collect();
+    plan_fft(len, FftDirection::Inverse).process(&mut buffer);
+
+    // A symmetric Hann window without the zero end points keeps the taps symmetric and so the phase linear
+    let window = hann_window(taps + 1).into_iter().skip(1);
+    Ok(buffer.iter().zip(window).map(|(c, w)| c.re / len as f64 * w).collect())
+}
+
+/// Form of the correction filter made by [`design_eq`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EqDesign {
+    /// Linear-phase FIR filter with the given number of taps.
+    Fir(usize),
+    /// Cascade of at most the given number of peaking biquads.
+    Biquads(usize),
+}
+
+/// Correction filter returned by [`design_eq`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum EqFilter {
+    Fir(Vec<f64>),
+    Biquads(Vec<Biquad>),
+}
+
+/// Largest boost or cut applied by [`design_eq`] in dB, deep notches in a measurement are not worth filling.
+const MAX_CORRECTION_DB: f64 = 18.0;
+
+/// Designs a filter correcting the measured magnitude response toward the target, both in dB at `freqs` Hz.
+///     The correction `target - measured` is limited to ±18 dB. FIR filters sample it on a dense grid, holding the
+///     end values outside the measured range. Biquad cascades are fitted greedily: each peaking section is placed
+///     at the largest remaining error with the bandwidth where that error falls to half, stopping early once the
+///     remaining error is below 0.1 dB.
+pub fn design_eq(
+    freqs: Vec<f64>,
+    measured_db: Vec<f64>,
+    target_db: Vec<f64>,
+    fs: f64,
+    design: EqDesign,
+) -> Result<EqFilter, Box<dyn Error>> {
+    if freqs.is_empty() || freqs.len() != measured_db.len() || freqs.len() != target_db.len() {
+        return Err("Frequencies, measured and target responses must be non-empty and have the same length.".into());
+    }
+    if fs <= 0.0 {
+        return Err("fs must be greater than zero.".into());
+    }
+    let correction: Vec<f64> = measured_db
+        .iter()
+        .zip(&target_db)
+        .map(|(m, t)| (t - m).clamp(-MAX_CORRECTION_DB, MAX_CORRECTION_DB))
+        .collect();
+
+    match design {
+        EqDesign::Fir(taps) => {
+            let len = (4 * taps).next_power_of_two().max(1024);
+            let grid = (0..=len / 2).map(|k| k as f64 * fs / len as f64).collect();
+            let half = interpolate(freqs, correction, grid, Interpolation::Linear)?;
+            let magnitude = (0..len).map(|k| 10f64.powf(half[k.min(len - k)] / 20.0)).collect();
+            Ok(EqFilter::Fir(linear_phase_fir(magnitude, taps)?))
+        }
+        EqDesign::Biquads(count) => {
+            let points: Vec<(f64, f64)> = freqs
+                .into_iter()
+                .zip(correction)
+                .filter(|(f, _)| *f > 0.0 && *f < fs / 2.0)
+                .collect();
+            if points.is_empty() {
+                return Err("No frequencies between 0 and fs / 2 to fit.".into());
+            }
+
+            let mut sections: Vec<Biquad> = Vec::with_capacity(count);
+            for _ in 0..count {
+                let residual: Vec<f64> = points
+                    .iter()
+                    .map(|&(f, c)| c - sections.iter().map(|s| 20.0 * s.response(f, fs).norm().log10()).sum::<f64>())
+                    .collect();
+                let peak = (0..residual.len()).max_by(|&i, &j| residual[i].abs().total_cmp(&residual[j].abs())).unwrap_or(0);
+                let gain = residual[peak];
+                if gain.abs() < 0.1 {
+                    break;
+                }
+
+                let inside = |r: f64| r * gain > 0.0 && r.abs() > gain.abs() / 2.0;
+                let lower = residual[..peak].iter().rposition(|&r| !inside(r)).unwrap_or(0);
+                let upper = residual[peak..].iter().position(|&r| !inside(r)).map_or(residual.len() - 1, |i| peak + i);
+                let octaves = (points[upper].0 / points[lower].0).log2().max(1.0 / 12.0);
+                let q = (2f64.powf(octaves / 2.0) / (2f64.powf(octaves) - 1.0)).clamp(0.2, 20.0);
+                sections.push(Biquad::peaking(fs, points[peak].0, gain, q)?);
+            }
+            Ok(EqFilter::Biquads(sections))
+        }
+    }
+}
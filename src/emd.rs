@@ -0,0 +1,142 @@
+//! Empirical mode decomposition and the Hilbert-Huang spectrum.
+
+use std::error::Error;
+use std::f64::consts::PI;
+
+use crate::transform::analytic;
+use crate::{interpolate, Interpolation, Matrix, Spectrogram};
+
+/// Largest number of sifting iterations spent on one intrinsic mode function.
+const MAX_SIFTS: usize = 100;
+
+/// Sifting stops once an iteration changes the candidate IMF by less than this fraction of its energy.
+const SIFT_TOLERANCE: f64 = 1e-3;
+
+/// Indices of the local maxima and minima of the data.
+fn extrema(data: &[f64]) -> (Vec<usize>, Vec<usize>) {
+    let (mut maxima, mut minima) = (Vec::new(), Vec::new());
+    for i in 1..data.len().saturating_sub(1) {
+        if data[i] > data[i - 1] && data[i] >= data[i + 1] {
+            maxima.push(i);
+        } else if data[i] < data[i - 1] && data[i] <= data[i + 1] {
+            minima.push(i);
+        }
+    }
+    (maxima, minima)
+}
+
+/// Cubic spline envelope through the given extrema, extended flat to both ends of the data.
+fn envelope(data: &[f64], points: &[usize]) -> Result<Vec<f64>, Box<dyn Error>> {
+    let last = data.len() - 1;
+    let mut x = vec![0.0];
+    let mut y = vec![data[points[0]]];
+    x.extend(points.iter().filter(|&&i| i > 0 && i < last).map(|&i| i as f64));
+    y.extend(points.iter().filter(|&&i| i > 0 && i < last).map(|&i| data[i]));
+    x.push(last as f64);
+    y.push(data[points[points.len() - 1]]);
+    interpolate(x, y, (0..data.len()).map(|i| i as f64).collect(), Interpolation::Cubic)
+}
+
+/// Decomposes the data into intrinsic mode functions (IMFs) by sifting, from the highest frequency down.
+///     Stops after `max_imfs` IMFs or once the residual has too few extrema to sift. The last row of the
+///     result is the residual trend, so the rows always sum to the input.
+pub fn emd(data: Vec<f64>, max_imfs: Option<usize>) -> Result<Matrix, Box<dyn Error>> {
+    if data.len() < 4 {
+        return Err("Data must have at least four samples.".into());
+    }
+
+    let max_imfs = max_imfs.unwrap_or(usize::MAX);
+    let mut residual = data;
+    let mut imfs = Vec::new();
+    while imfs.len() < max_imfs {
+        let (maxima, minima) = extrema(&residual);
+        if maxima.len() < 2 || minima.len() < 2 {
+            break;
+        }
+
+        let mut mode = residual.clone();
+        for _ in 0..MAX_SIFTS {
+            let (maxima, minima) = extrema(&mode);
+            if maxima.len() < 2 || minima.len() < 2 {
+                break;
+            }
+            let upper = envelope(&mode, &maxima)?;
+            let lower = envelope(&mode, &minima)?;
+            let previous = mode.clone();
+            mode.iter_mut().zip(upper.iter().zip(&lower)).for_each(|(m, (u, l))| *m -= 0.5 * (u + l));
+
+            let change: f64 = previous.iter().zip(&mode).map(|(p, m)| (p - m).powi(2)).sum();
+            let energy: f64 = previous.iter().map(|p| p * p).sum();
+            if change <= SIFT_TOLERANCE * energy {
+                break;
+            }
+        }
+
+        residual.iter_mut().zip(&mode).for_each(|(r, m)| *r -= m);
+        imfs.push(mode);
+    }
+    log::debug!("EMD extracted {} intrinsic mode functions", imfs.len());
+
+    imfs.push(residual);
+    Ok(imfs)
+}
+
+/// Instantaneous amplitude and frequency of each IMF from its analytic signal.
+pub struct InstantaneousAttributes {
+    pub amplitude: Matrix,
+    /// Instantaneous frequency in Hz, from the derivative of the unwrapped phase.
+    pub frequency: Matrix,
+}
+
+/// Computes the instantaneous amplitude and frequency of each IMF.
+pub fn instantaneous_attributes(imfs: Matrix, fs: f64) -> Result<InstantaneousAttributes, Box<dyn Error>> {
+    if fs <= 0.0 || imfs.iter().any(|imf| imf.len() < 2) {
+        return Err("Each IMF needs at least two samples and fs must be greater than zero.".into());
+    }
+
+    let (amplitude, frequency) = imfs
+        .into_iter()
+        .map(|imf| {
+            let z = analytic(imf);
+            let amplitude: Vec<f64> = z.iter().map(|c| c.norm()).collect();
+            let mut frequency: Vec<f64> = z
+                .windows(2)
+                .map(|w| (w[1] * w[0].conj()).arg() * fs / (2.0 * PI))
+                .collect();
+            frequency.push(frequency[frequency.len() - 1]);
+            (amplitude, frequency)
+        })
+        .unzip();
+
+    Ok(InstantaneousAttributes { amplitude, frequency })
+}
+
+/// Builds the Hilbert-Huang spectrum: the squared instantaneous amplitude of every IMF accumulated into
+///     `freq_bins` frequency bins between 0 and the Nyquist frequency at each sample.
+///     Pass the IMFs without the residual trend returned last by [`emd`].
+pub fn hilbert_huang_spectrum(imfs: Matrix, fs: f64, freq_bins: usize) -> Result<Spectrogram, Box<dyn Error>> {
+    if freq_bins == 0 {
+        return Err("The number of frequency bins must be positive.".into());
+    }
+    let len = imfs.first().map_or(0, Vec::len);
+    if imfs.iter().any(|imf| imf.len() != len) {
+        return Err("All IMFs must have the same length.".into());
+    }
+
+    let attributes = instantaneous_attributes(imfs, fs)?;
+    let width = fs / 2.0 / freq_bins as f64;
+    let mut power = vec![vec![0.0; freq_bins]; len];
+    for (amplitude, frequency) in attributes.amplitude.iter().zip(&attributes.frequency) {
+        for (t, (a, f)) in amplitude.iter().zip(frequency).enumerate() {
+            if *f >= 0.0 && *f < fs / 2.0 {
+                power[t][(f / width) as usize] += a * a;
+            }
+        }
+    }
+
+    Ok(Spectrogram {
+        times: (0..len).map(|t| t as f64 / fs).collect(),
+        freqs: (0..freq_bins).map(|k| (k as f64 + 0.5) * width).collect(),
+        power,
+    })
+}
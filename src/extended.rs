@@ -0,0 +1,247 @@
+//! Double-double arithmetic and an extended precision FFT built on it.
+//!
+//! A [`DoubleDouble`] represents a value as the unevaluated sum of two `f64`, giving about 106 bits of mantissa.
+//! The transform is much slower than rustfft and is meant for validating the round-off of double precision results.
+
+use std::f64::consts::PI;
+use std::ops::{Add, Mul, Neg, Sub};
+
+/// An extended precision value `hi + lo` with `|lo| <= ulp(hi) / 2`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DoubleDouble {
+    pub hi: f64,
+    pub lo: f64,
+}
+
+/// Error free sum of two doubles.
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let s = a + b;
+    let v = s - a;
+    (s, (a - (s - v)) + (b - v))
+}
+
+/// Error free sum when `|a| >= |b|`.
+fn quick_two_sum(a: f64, b: f64) -> (f64, f64) {
+    let s = a + b;
+    (s, b - (s - a))
+}
+
+impl DoubleDouble {
+    pub fn new(value: f64) -> Self {
+        DoubleDouble { hi: value, lo: 0.0 }
+    }
+
+    fn normalized((hi, lo): (f64, f64)) -> Self {
+        let (hi, lo) = quick_two_sum(hi, lo);
+        DoubleDouble { hi, lo }
+    }
+
+    /// Divides by a double.
+    fn div_f64(self, divisor: f64) -> Self {
+        let q1 = self.hi / divisor;
+        let product = DoubleDouble::new(q1) * DoubleDouble::new(divisor);
+        let remainder = self - product;
+        DoubleDouble::normalized((q1, remainder.hi / divisor))
+    }
+
+    /// The double nearest to the value.
+    pub fn to_f64(self) -> f64 {
+        self.hi + self.lo
+    }
+}
+
+impl Add for DoubleDouble {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        let (s, e) = two_sum(self.hi, other.hi);
+        let (t, f) = two_sum(self.lo, other.lo);
+        let (s, e) = quick_two_sum(s, e + t);
+        DoubleDouble::normalized((s, e + f))
+    }
+}
+
+impl Neg for DoubleDouble {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        DoubleDouble { hi: -self.hi, lo: -self.lo }
+    }
+}
+
+impl Sub for DoubleDouble {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self + -other
+    }
+}
+
+impl Mul for DoubleDouble {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        let p = self.hi * other.hi;
+        let e = self.hi.mul_add(other.hi, -p);
+        DoubleDouble::normalized((p, e + (self.hi * other.lo + self.lo * other.hi)))
+    }
+}
+
+/// Complex value with double-double parts.
+#[derive(Clone, Copy, Debug, Default)]
+struct ComplexDd {
+    re: DoubleDouble,
+    im: DoubleDouble,
+}
+
+impl ComplexDd {
+    fn conj(self) -> Self {
+        ComplexDd { re: self.re, im: -self.im }
+    }
+
+    fn scale(self, factor: f64) -> Self {
+        // Only used with powers of two, for which the scaling is exact
+        let factor = DoubleDouble::new(factor);
+        ComplexDd { re: self.re * factor, im: self.im * factor }
+    }
+}
+
+impl Add for ComplexDd {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        ComplexDd { re: self.re + other.re, im: self.im + other.im }
+    }
+}
+
+impl Sub for ComplexDd {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        ComplexDd { re: self.re - other.re, im: self.im - other.im }
+    }
+}
+
+impl Mul for ComplexDd {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        ComplexDd {
+            re: self.re * other.re - self.im * other.im,
+            im: self.re * other.im + self.im * other.re,
+        }
+    }
+}
+
+/// `2 pi` to double-double precision.
+const TWO_PI: DoubleDouble = DoubleDouble { hi: 2.0 * PI, lo: 2.449_293_598_294_706_4e-16 };
+
+/// Returns `exp(-j 2 pi / n)` from the Taylor series of sine and cosine.
+fn root_of_unity(n: usize) -> ComplexDd {
+    let x = TWO_PI.div_f64(n as f64);
+    let x2 = x * x;
+    let (mut cos, mut sin) = (DoubleDouble::new(1.0), x);
+    let (mut cos_term, mut sin_term) = (DoubleDouble::new(1.0), x);
+    for k in 1..40 {
+        let k = k as f64;
+        cos_term = -(cos_term * x2).div_f64((2.0 * k - 1.0) * (2.0 * k));
+        sin_term = -(sin_term * x2).div_f64((2.0 * k) * (2.0 * k + 1.0));
+        cos = cos + cos_term;
+        sin = sin + sin_term;
+        if cos_term.hi.abs() < 1e-40 && sin_term.hi.abs() < 1e-40 {
+            break;
+        }
+    }
+    ComplexDd { re: cos, im: -sin }
+}
+
+/// Returns `exp(-j 2 pi k / n)` for `k` in `0..count`.
+///     Built by repeated multiplication, so the error grows linearly with `k` but stays far below double precision.
+fn twiddles(n: usize, count: usize) -> Vec<ComplexDd> {
+    let root = root_of_unity(n);
+    let mut table = Vec::with_capacity(count);
+    let mut current = ComplexDd { re: DoubleDouble::new(1.0), im: DoubleDouble::default() };
+    for _ in 0..count {
+        table.push(current);
+        current = current * root;
+    }
+    table
+}
+
+/// Returns `(cos, -sin)` of `2 pi k / n` for `k` in `0..count`, rounded to `f64`.
+///     Only IEEE basic operations are involved, so the table is identical on every platform, unlike the results of
+///     the platform `cos` and `sin`.
+pub(crate) fn unit_twiddles(n: usize, count: usize) -> Vec<(f64, f64)> {
+    twiddles(n, count).into_iter().map(|c| (c.re.to_f64(), c.im.to_f64())).collect()
+}
+
+/// In place iterative radix-2 FFT, `data.len()` must be a power of two.
+fn radix2(data: &mut [ComplexDd]) {
+    let n = data.len();
+    let bits = n.trailing_zeros();
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+        if i < j {
+            data.swap(i, j);
+        }
+    }
+
+    let table = twiddles(n, n / 2);
+    let mut len = 2;
+    while len <= n {
+        let stride = n / len;
+        for chunk in data.chunks_mut(len) {
+            let (even, odd) = chunk.split_at_mut(len / 2);
+            for (k, (e, o)) in even.iter_mut().zip(odd.iter_mut()).enumerate() {
+                let t = *o * table[k * stride];
+                (*e, *o) = (*e + t, *e - t);
+            }
+        }
+        len *= 2;
+    }
+}
+
+/// Computes the forward DFT of real data in double-double precision.
+///     Powers of two use a radix-2 transform, other lengths Bluestein's algorithm on a padded radix-2 transform.
+///     Returns the real and imaginary part of each bin.
+pub fn fft(data: &[f64]) -> Vec<(DoubleDouble, DoubleDouble)> {
+    let n = data.len();
+    let input = data.iter().map(|&x| ComplexDd { re: DoubleDouble::new(x), im: DoubleDouble::default() });
+    if n <= 1 || n.is_power_of_two() {
+        let mut buffer: Vec<ComplexDd> = input.collect();
+        if n > 1 {
+            radix2(&mut buffer);
+        }
+        return buffer.into_iter().map(|c| (c.re, c.im)).collect();
+    }
+
+    // Bluestein: X[k] = w[k] sum x[m] w[m] conj(w[k - m]) with the chirp w[k] = exp(-j pi k^2 / n)
+    let half_turns = twiddles(2 * n, 2 * n);
+    let chirp: Vec<ComplexDd> = (0..n).map(|k| half_turns[(k * k) % (2 * n)]).collect();
+    let m = (2 * n - 1).next_power_of_two();
+
+    let mut a = vec![ComplexDd::default(); m];
+    for ((slot, x), w) in a.iter_mut().zip(input).zip(&chirp) {
+        *slot = x * *w;
+    }
+    let mut b = vec![ComplexDd::default(); m];
+    b[0] = chirp[0].conj();
+    for k in 1..n {
+        b[k] = chirp[k].conj();
+        b[m - k] = chirp[k].conj();
+    }
+    radix2(&mut a);
+    radix2(&mut b);
+
+    // Inverse transform of the product through conjugation, the 1 / m scaling is exact
+    let mut product: Vec<ComplexDd> = a.iter().zip(&b).map(|(x, y)| (*x * *y).conj()).collect();
+    radix2(&mut product);
+    product
+        .iter()
+        .zip(&chirp)
+        .map(|(c, w)| {
+            let value = c.conj().scale(1.0 / m as f64) * *w;
+            (value.re, value.im)
+        })
+        .collect()
+}
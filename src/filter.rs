@@ -0,0 +1,82 @@
+//! Filters applied to time domain data.
+
+use rustfft::{FftDirection, num_complex::Complex};
+use std::error::Error;
+use std::f64::consts::PI;
+
+use crate::plan_fft;
+
+/// Keeps only the frequency content between `low` and `high` Hz by zeroing all other FFT bins.
+///     This is an ideal (brick-wall) filter, so expect ringing around sharp transients.
+pub fn fft_bandpass(data: Vec<f64>, fs: f64, low: f64, high: f64) -> Result<Vec<f64>, Box<dyn Error>> {
+    if data.is_empty() || fs <= 0.0 || low < 0.0 || low >= high {
+        return Err("Data must not be empty, fs must be greater than zero and 0 <= low < high.".into());
+    }
+
+    let len = data.len();
+    log::debug!("FFT band-pass of {} samples keeping {} to {} Hz", len, low, high);
+    let mut buffer: Vec<Complex<f64>> = data.into_iter().map(|x| Complex::new(x, 0.0)).collect();
+    plan_fft(len, FftDirection::Forward).process(&mut buffer);
+
+    for (k, c) in buffer.iter_mut().enumerate() {
+        // Bins above the Nyquist bin hold the matching negative frequencies
+        let freq = k.min(len - k) as f64 * fs / len as f64;
+        if freq < low || freq > high {
+            *c = Complex::new(0.0, 0.0);
+        }
+    }
+
+    plan_fft(len, FftDirection::Inverse).process(&mut buffer);
+    Ok(buffer.into_iter().map(|c| c.re / len as f64).collect())
+}
+
+/// Multiplies the spectrum of the data by `response(freq)`, where `freq` is the signed frequency of each bin in Hz.
+pub(crate) fn apply_response(data: Vec<f64>, fs: f64, response: impl Fn(f64) -> Complex<f64>) -> Vec<f64> {
+    let len = data.len();
+    let mut buffer: Vec<Complex<f64>> = data.into_iter().map(|x| Complex::new(x, 0.0)).collect();
+    plan_fft(len, FftDirection::Forward).process(&mut buffer);
+
+    for (k, c) in buffer.iter_mut().enumerate() {
+        if 2 * k == len {
+            // The Nyquist bin has no sign, keep only the real part of the response so the output stays real
+            *c *= response(fs / 2.0).re;
+        } else {
+            let freq = if 2 * k < len { k as f64 } else { k as f64 - len as f64 } * fs / len as f64;
+            *c *= response(freq);
+        }
+    }
+
+    plan_fft(len, FftDirection::Inverse).process(&mut buffer);
+    buffer.into_iter().map(|c| c.re / len as f64).collect()
+}
+
+/// Differentiates the data `order` times by multiplying its spectrum by `(j 2 pi f)^order`.
+///     The data is treated as periodic, so taper or detrend it first to avoid edge artifacts.
+pub fn differentiate_fd(data: Vec<f64>, fs: f64, order: u32) -> Result<Vec<f64>, Box<dyn Error>> {
+    if data.is_empty() || fs <= 0.0 {
+        return Err("Data must not be empty and fs must be greater than zero.".into());
+    }
+    Ok(apply_response(data, fs, |freq| Complex::new(0.0, 2.0 * PI * freq).powu(order)))
+}
+
+/// Integrates the data `order` times by dividing its spectrum by `(j 2 pi f)^order`, for example to turn
+///     acceleration into velocity (`order = 1`) or displacement (`order = 2`).
+///     DC and every bin below `highpass_cutoff` Hz are zeroed since integration amplifies low frequency drift.
+pub fn integrate_fd(data: Vec<f64>, fs: f64, order: u32, highpass_cutoff: Option<f64>) -> Result<Vec<f64>, Box<dyn Error>> {
+    if data.is_empty() || fs <= 0.0 {
+        return Err("Data must not be empty and fs must be greater than zero.".into());
+    }
+    let cutoff = highpass_cutoff.unwrap_or(0.0);
+    if cutoff < 0.0 {
+        return Err("High-pass cutoff must not be negative.".into());
+    }
+
+    log::debug!("Integrating {} samples {} times with a {} Hz high-pass", data.len(), order, cutoff);
+    Ok(apply_response(data, fs, |freq| {
+        if freq == 0.0 || freq.abs() < cutoff {
+            Complex::new(0.0, 0.0)
+        } else {
+            Complex::new(0.0, 2.0 * PI * freq).powu(order).inv()
+        }
+    }))
+}
@@ -0,0 +1,90 @@
+//! Bit-exact fixed-point FFT, a reference model for integer DSP firmware.
+
+use std::error::Error;
+
+use crate::extended;
+
+/// Word size of the fixed-point data and twiddle factors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FixedWord {
+    I16,
+    I32,
+}
+
+impl FixedWord {
+    fn bits(self) -> u32 {
+        match self {
+            FixedWord::I16 => 16,
+            FixedWord::I32 => 32,
+        }
+    }
+}
+
+/// Shifts right by `shift` bits rounding half up, then saturates to the word range.
+fn round_shift(value: i64, shift: u32, (min, max): (i64, i64)) -> i64 {
+    let rounded = if shift == 0 { value } else { (value + (1 << (shift - 1))) >> shift };
+    rounded.clamp(min, max)
+}
+
+/// Computes the FFT of fixed-point data with `frac_bits` fractional bits (e.g. Q15 is `FixedWord::I16` with 15).
+///     The length must be a power of two. Twiddle factors are quantized to the same format, every product is rounded
+///     half up and every result saturated to the word range, with 64 bit intermediates so the results are bit-exact
+///     on every platform. With `scale` each radix-2 stage halves its outputs, so the result is the DFT divided by the
+///     length and cannot overflow; without it large inputs saturate.
+pub fn fixed_point_fft(
+    real: Vec<i32>,
+    imag: Vec<i32>,
+    word: FixedWord,
+    frac_bits: u32,
+    scale: bool,
+) -> Result<(Vec<i32>, Vec<i32>), Box<dyn Error>> {
+    let n = real.len();
+    if n != imag.len() {
+        return Err("Real and imaginary parts must have the same length.".into());
+    }
+    if !n.is_power_of_two() {
+        return Err("Length must be a power of two.".into());
+    }
+    let bits = word.bits();
+    if frac_bits >= bits {
+        return Err(format!("frac_bits must be below the word size of {} bits.", bits).into());
+    }
+    let range = (-(1i64 << (bits - 1)), (1i64 << (bits - 1)) - 1);
+    if real.iter().chain(&imag).any(|&x| !(range.0..=range.1).contains(&(x as i64))) {
+        return Err(format!("Values must fit in {} bit words.", bits).into());
+    }
+
+    let one = (1i64 << frac_bits) as f64;
+    let twiddles: Vec<(i64, i64)> = extended::unit_twiddles(n, n / 2)
+        .into_iter()
+        .map(|(c, s)| (((c * one).round() as i64).clamp(range.0, range.1), ((s * one).round() as i64).clamp(range.0, range.1)))
+        .collect();
+
+    let mut data: Vec<(i64, i64)> = real.into_iter().zip(imag).map(|(re, im)| (re as i64, im as i64)).collect();
+    let index_bits = n.trailing_zeros();
+    for i in 0..n {
+        let j = i.reverse_bits().checked_shr(usize::BITS - index_bits).unwrap_or(0);
+        if i < j {
+            data.swap(i, j);
+        }
+    }
+
+    let stage_shift = u32::from(scale);
+    let mut len = 2;
+    while len <= n {
+        let stride = n / len;
+        for chunk in data.chunks_mut(len) {
+            let (even, odd) = chunk.split_at_mut(len / 2);
+            for (k, (e, o)) in even.iter_mut().zip(odd.iter_mut()).enumerate() {
+                let (wr, wi) = twiddles[k * stride];
+                let tr = round_shift(o.0 * wr - o.1 * wi, frac_bits, range);
+                let ti = round_shift(o.0 * wi + o.1 * wr, frac_bits, range);
+                *o = (round_shift(e.0 - tr, stage_shift, range), round_shift(e.1 - ti, stage_shift, range));
+                *e = (round_shift(e.0 + tr, stage_shift, range), round_shift(e.1 + ti, stage_shift, range));
+            }
+        }
+        len *= 2;
+    }
+
+    Ok(data.into_iter().map(|(re, im)| (re as i32, im as i32)).unzip())
+}
@@ -0,0 +1,341 @@
+//! Decoder for FLAC (Free Lossless Audio Codec) files.
+
+use std::error::Error;
+
+use crate::Matrix;
+
+/// Coefficients of the fixed polynomial predictors of order 0 to 4.
+const FIXED_COEFFICIENTS: [&[i64]; 5] = [&[], &[1], &[2, -1], &[3, -3, 1], &[4, -6, 4, -1]];
+
+/// Reads a byte slice most significant bit first.
+struct BitReader<'a> {
+    data: &'a [u8],
+    /// Position in bits from the start of `data`.
+    pos: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8], byte: usize) -> Self {
+        BitReader { data, pos: byte * 8 }
+    }
+
+    fn check(&self, bits: usize) -> Result<(), Box<dyn Error>> {
+        if self.pos + bits > self.data.len() * 8 {
+            return Err("FLAC stream is truncated.".into());
+        }
+        Ok(())
+    }
+
+    /// Unsigned value of the next `bits` bits, at most 64.
+    fn read(&mut self, bits: u32) -> Result<u64, Box<dyn Error>> {
+        self.check(bits as usize)?;
+        let end = self.pos + bits as usize;
+        let mut value = 0u64;
+        while self.pos < end {
+            let offset = self.pos % 8;
+            let take = (8 - offset).min(end - self.pos);
+            let byte = (self.data[self.pos / 8] as u64) >> (8 - offset - take);
+            value = (value << take) | (byte & ((1 << take) - 1));
+            self.pos += take;
+        }
+        Ok(value)
+    }
+
+    /// Two's complement value of the next `bits` bits.
+    fn read_signed(&mut self, bits: u32) -> Result<i64, Box<dyn Error>> {
+        if bits == 0 {
+            return Ok(0);
+        }
+        let value = self.read(bits)?;
+        Ok(((value << (64 - bits)) as i64) >> (64 - bits))
+    }
+
+    /// Number of zero bits before the next set bit, which is consumed too.
+    fn read_unary(&mut self) -> Result<u64, Box<dyn Error>> {
+        let mut count = 0;
+        loop {
+            self.check(1)?;
+            let offset = self.pos % 8;
+            let byte = self.data[self.pos / 8] << offset;
+            if byte == 0 {
+                count += 8 - offset as u64;
+                self.pos += 8 - offset;
+            } else {
+                let zeros = byte.leading_zeros() as usize;
+                count += zeros as u64;
+                self.pos += zeros + 1;
+                return Ok(count);
+            }
+        }
+    }
+
+    fn align(&mut self) {
+        self.pos = self.pos.div_ceil(8) * 8;
+    }
+
+    fn byte_pos(&self) -> usize {
+        self.pos / 8
+    }
+}
+
+/// CRC-16 with polynomial 0x8005 that closes every FLAC frame.
+fn crc16(bytes: &[u8]) -> u16 {
+    bytes.iter().fold(0u16, |crc, &byte| {
+        (0..8).fold(crc ^ ((byte as u16) << 8), |crc, _| if crc & 0x8000 != 0 { (crc << 1) ^ 0x8005 } else { crc << 1 })
+    })
+}
+
+/// Format of the stream from the STREAMINFO block.
+struct StreamInfo {
+    sample_rate: u32,
+    channels: usize,
+    bits: u32,
+    /// Samples per channel, zero when the encoder did not know it.
+    total_samples: u64,
+}
+
+/// Decodes the Rice coded residual of a subframe whose first `order` samples are warm-up samples.
+fn read_residual(reader: &mut BitReader, block_size: usize, order: usize, residual: &mut Vec<i64>) -> Result<(), Box<dyn Error>> {
+    let (parameter_bits, escape) = match reader.read(2)? {
+        0 => (4, 15),
+        1 => (5, 31),
+        method => return Err(format!("Reserved FLAC residual coding method {}.", method).into()),
+    };
+    let partition_order = reader.read(4)? as u32;
+    let partition_len = block_size >> partition_order;
+    if partition_len << partition_order != block_size || partition_len < order {
+        return Err("FLAC residual partitions do not fit the block.".into());
+    }
+
+    for partition in 0..1usize << partition_order {
+        let count = if partition == 0 { partition_len - order } else { partition_len };
+        let parameter = reader.read(parameter_bits)? as u32;
+        if parameter == escape {
+            let bits = reader.read(5)? as u32;
+            for _ in 0..count {
+                residual.push(reader.read_signed(bits)?);
+            }
+        } else {
+            for _ in 0..count {
+                let value = (reader.read_unary()? << parameter) | reader.read(parameter)?;
+                // Zigzag coding maps 0, -1, 1, -2, ... to 0, 1, 2, 3, ...
+                residual.push((value >> 1) as i64 ^ -((value & 1) as i64));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Decodes one subframe of `block_size` samples of `bits` bits each.
+fn read_subframe(reader: &mut BitReader, block_size: usize, bits: u32) -> Result<Vec<i64>, Box<dyn Error>> {
+    if reader.read(1)? != 0 {
+        return Err("FLAC subframe padding bit is set.".into());
+    }
+    let kind = reader.read(6)?;
+    let wasted = if reader.read(1)? == 1 { reader.read_unary()? as u32 + 1 } else { 0 };
+    if wasted >= bits {
+        return Err("FLAC subframe has more wasted bits than sample bits.".into());
+    }
+    let bits = bits - wasted;
+
+    let mut samples = Vec::with_capacity(block_size);
+    match kind {
+        0 => samples.resize(block_size, reader.read_signed(bits)?),
+        1 => {
+            for _ in 0..block_size {
+                samples.push(reader.read_signed(bits)?);
+            }
+        }
+        8..=12 | 32..=63 => {
+            let order = if kind < 32 { kind as usize - 8 } else { kind as usize - 31 };
+            if order > block_size {
+                return Err("FLAC predictor order exceeds the block size.".into());
+            }
+            for _ in 0..order {
+                samples.push(reader.read_signed(bits)?);
+            }
+            let (coefficients, shift) = if kind < 32 {
+                (FIXED_COEFFICIENTS[order].to_vec(), 0)
+            } else {
+                let precision = reader.read(4)? as u32 + 1;
+                if precision == 16 {
+                    return Err("Invalid FLAC LPC coefficient precision.".into());
+                }
+                let shift = reader.read_signed(5)?;
+                if shift < 0 {
+                    return Err("Negative FLAC LPC shifts are not supported.".into());
+                }
+                let coefficients = (0..order).map(|_| reader.read_signed(precision)).collect::<Result<Vec<_>, _>>()?;
+                (coefficients, shift as u32)
+            };
+
+            let mut residual = Vec::with_capacity(block_size - order);
+            read_residual(reader, block_size, order, &mut residual)?;
+            for r in residual {
+                let n = samples.len();
+                let prediction: i64 = coefficients.iter().enumerate().map(|(j, c)| c * samples[n - 1 - j]).sum();
+                samples.push(r + (prediction >> shift));
+            }
+        }
+        _ => return Err(format!("Reserved FLAC subframe type {}.", kind).into()),
+    }
+
+    if wasted > 0 {
+        samples.iter_mut().for_each(|s| *s <<= wasted);
+    }
+    Ok(samples)
+}
+
+/// Decodes the frame starting at byte `start`, appending its samples to `channels`, and returns the byte after it.
+fn read_frame(data: &[u8], start: usize, info: &StreamInfo, channels: &mut [Vec<i64>]) -> Result<usize, Box<dyn Error>> {
+    let mut reader = BitReader::new(data, start);
+    if reader.read(15)? != 0b111_1111_1111_1100 {
+        return Err(format!("Lost FLAC frame sync at byte {}.", start).into());
+    }
+    reader.read(1)?;
+    let size_code = reader.read(4)?;
+    let rate_code = reader.read(4)?;
+    let assignment = reader.read(4)?;
+    let bits = match reader.read(3)? {
+        0 => info.bits,
+        1 => 8,
+        2 => 12,
+        4 => 16,
+        5 => 20,
+        6 => 24,
+        7 => 32,
+        _ => return Err("Reserved FLAC sample size.".into()),
+    };
+    if bits != info.bits {
+        return Err(format!("FLAC frame has {} bit samples, the stream {}.", bits, info.bits).into());
+    }
+    reader.read(1)?;
+
+    // Frame or sample number, UTF-8 coded
+    let first = reader.read(8)?;
+    for _ in 0..(first as u8).leading_ones().saturating_sub(1) {
+        reader.read(8)?;
+    }
+    let block_size = match size_code {
+        1 => 192,
+        2..=5 => 576 << (size_code - 2),
+        6 => reader.read(8)? as usize + 1,
+        7 => reader.read(16)? as usize + 1,
+        8..=15 => 256 << (size_code - 8),
+        _ => return Err("Reserved FLAC block size.".into()),
+    };
+    match rate_code {
+        12 => {
+            reader.read(8)?;
+        }
+        13 | 14 => {
+            reader.read(16)?;
+        }
+        15 => return Err("Invalid FLAC sample rate code.".into()),
+        _ => {}
+    }
+    // The CRC-8 of the header is covered by the CRC-16 of the frame
+    reader.read(8)?;
+
+    let channel_count = match assignment {
+        0..=7 => assignment as usize + 1,
+        8..=10 => 2,
+        _ => return Err("Reserved FLAC channel assignment.".into()),
+    };
+    if channel_count != info.channels {
+        return Err(format!("FLAC frame has {} channels, the stream {}.", channel_count, info.channels).into());
+    }
+
+    // The side channel of stereo decorrelation needs one more bit
+    let mut subframes = Vec::with_capacity(channel_count);
+    for channel in 0..channel_count {
+        let side = matches!((assignment, channel), (8, 1) | (9, 0) | (10, 1));
+        subframes.push(read_subframe(&mut reader, block_size, bits + side as u32)?);
+    }
+    if assignment >= 8 {
+        let (a, b) = subframes.split_at_mut(1);
+        for (a, b) in a[0].iter_mut().zip(b[0].iter_mut()) {
+            (*a, *b) = match assignment {
+                8 => (*a, *a - *b),
+                9 => (*a + *b, *b),
+                _ => {
+                    let mid = (*a << 1) | (*b & 1);
+                    ((mid + *b) >> 1, (mid - *b) >> 1)
+                }
+            };
+        }
+    }
+
+    reader.align();
+    let end = reader.byte_pos();
+    let crc = reader.read(16)? as u16;
+    if crc16(&data[start..end]) != crc {
+        return Err(format!("FLAC frame at byte {} fails its CRC check.", start).into());
+    }
+
+    for (channel, samples) in channels.iter_mut().zip(subframes) {
+        channel.extend(samples);
+    }
+    Ok(end + 2)
+}
+
+/// Reads a FLAC file and returns the sample rate in Hz and the samples of each channel scaled to [-1, 1),
+///     like [`read_wav`](crate::read_wav). Every frame is checked against its CRC. Streams with a leading ID3v2
+///     tag, as some taggers write, are accepted.
+pub fn read_flac(file_path: &str) -> Result<(f64, Matrix), Box<dyn Error>> {
+    let data = std::fs::read(file_path)?;
+    let mut offset = 0;
+    if data.len() >= 10 && &data[0..3] == b"ID3" {
+        // The tag size is stored in four bytes of seven bits each
+        offset = 10 + data[6..10].iter().fold(0usize, |size, &b| (size << 7) | (b & 0x7f) as usize);
+    }
+    if data.get(offset..offset + 4) != Some(b"fLaC") {
+        return Err("File is not a FLAC file.".into());
+    }
+    offset += 4;
+
+    let mut info = None;
+    loop {
+        let header = data.get(offset..offset + 4).ok_or("FLAC metadata is truncated.")?;
+        let last = header[0] & 0x80 != 0;
+        let length = u32::from_be_bytes([0, header[1], header[2], header[3]]) as usize;
+        let body = offset + 4;
+        if header[0] & 0x7f == 0 {
+            // Skip the block and frame size limits
+            let mut reader = BitReader::new(data.get(..body + 18).ok_or("FLAC STREAMINFO is truncated.")?, body + 10);
+            info = Some(StreamInfo {
+                sample_rate: reader.read(20)? as u32,
+                channels: reader.read(3)? as usize + 1,
+                bits: reader.read(5)? as u32 + 1,
+                total_samples: reader.read(36)?,
+            });
+        }
+        offset = body + length;
+        if last {
+            break;
+        }
+    }
+    let info = info.ok_or("FLAC file has no STREAMINFO block.")?;
+    if info.sample_rate == 0 {
+        return Err("FLAC file has no sample rate.".into());
+    }
+
+    // The header's count is untrusted, so reserve no more than the file could hold uncompressed
+    let capacity = info.total_samples.min((data.len() as u64 * 8) / info.bits as u64) as usize;
+    let mut samples = vec![Vec::with_capacity(capacity); info.channels];
+    // Files of unknown length run to the end, trailing tags end files of known length
+    while offset + 2 <= data.len() && (info.total_samples == 0 || (samples[0].len() as u64) < info.total_samples) {
+        offset = read_frame(&data, offset, &info, &mut samples)?;
+    }
+    if info.total_samples > 0 {
+        samples.iter_mut().for_each(|channel| channel.truncate(info.total_samples as usize));
+    }
+
+    let scale = 1.0 / (1u64 << (info.bits - 1)) as f64;
+    let channels: Matrix = samples.into_iter().map(|channel| channel.into_iter().map(|s| s as f64 * scale).collect()).collect();
+    log::debug!(
+        "{}: {} channels of {} frames at {} Hz ({} bits)",
+        file_path, info.channels, channels[0].len(), info.sample_rate, info.bits
+    );
+    Ok((info.sample_rate as f64, channels))
+}
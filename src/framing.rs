@@ -0,0 +1,96 @@
+//! Splitting data into overlapping frames and segments around trigger events.
+
+use std::error::Error;
+
+use crate::Matrix;
+
+/// Iterator over frames of `nperseg` samples starting every `hop` samples.
+///     Trailing samples that do not fill a whole frame are not yielded.
+pub struct Frames {
+    data: Vec<f64>,
+    nperseg: usize,
+    hop: usize,
+    start: usize,
+}
+
+impl Iterator for Frames {
+    type Item = Vec<f64>;
+
+    fn next(&mut self) -> Option<Vec<f64>> {
+        let frame = self.data.get(self.start..self.start + self.nperseg)?.to_vec();
+        self.start += self.hop;
+        Some(frame)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = match self.data.len().checked_sub(self.start + self.nperseg) {
+            Some(rest) => rest / self.hop + 1,
+            None => 0,
+        };
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for Frames {}
+
+/// Lazily frames the data into windows of `nperseg` samples starting every `hop` samples.
+pub fn frames(data: Vec<f64>, nperseg: usize, hop: usize) -> Result<Frames, Box<dyn Error>> {
+    if nperseg == 0 || hop == 0 {
+        return Err("nperseg and hop must be positive.".into());
+    }
+    Ok(Frames { data, nperseg, hop, start: 0 })
+}
+
+/// Direction of the threshold crossing that fires [`find_triggers`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TriggerSlope {
+    Rising,
+    Falling,
+}
+
+/// Segments cut around the trigger events of a signal.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Triggered {
+    /// Sample index of each kept trigger, the first sample past the threshold.
+    pub indices: Vec<usize>,
+    /// `pre + post` samples per trigger, starting `pre` samples before it.
+    pub segments: Matrix,
+}
+
+/// Finds threshold crossings like an oscilloscope trigger and cuts fixed-length segments around them.
+///     After firing, the trigger re-arms only once the signal has moved `hysteresis` back past the threshold, so
+///     noise on a slow edge does not fire it repeatedly. Triggers whose segment would extend past either end of
+///     the data are dropped. The segments have equal lengths, ready for batch transforms or synchronous averaging.
+pub fn find_triggers(
+    data: &[f64],
+    threshold: f64,
+    hysteresis: f64,
+    pre: usize,
+    post: usize,
+    slope: TriggerSlope,
+) -> Result<Triggered, Box<dyn Error>> {
+    if !(hysteresis >= 0.0 && hysteresis.is_finite()) || pre + post == 0 {
+        return Err("Hysteresis must be non-negative and finite, and segments must hold at least one sample.".into());
+    }
+    // Flip the signal for falling edges so only rising crossings need handling
+    let sign = if slope == TriggerSlope::Rising { 1.0 } else { -1.0 };
+    let (threshold, rearm) = (sign * threshold, sign * threshold - hysteresis);
+
+    let mut armed = data.first().is_some_and(|&x| sign * x < threshold);
+    let mut indices = Vec::new();
+    let mut segments = Vec::new();
+    for (i, &x) in data.iter().enumerate() {
+        let x = sign * x;
+        if armed && x >= threshold {
+            armed = false;
+            if i >= pre && i + post <= data.len() {
+                indices.push(i);
+                segments.push(data[i - pre..i + post].to_vec());
+            }
+        } else if !armed && x < rearm {
+            armed = true;
+        }
+    }
+    log::debug!("Found {} triggers at threshold {}", indices.len(), sign * threshold);
+    Ok(Triggered { indices, segments })
+}
@@ -0,0 +1,139 @@
+//! Test signal generation.
+
+use rustfft::num_complex::Complex;
+use std::error::Error;
+
+use crate::filter::apply_response;
+
+/// Small deterministic random number generator (xoshiro256**) seeded through SplitMix64.
+///     Only integer operations and IEEE basic arithmetic are involved, so a seed produces the same samples on every
+///     platform and release.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: [u64; 4],
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        let mut x = seed;
+        let mut next = || {
+            x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = x;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            z ^ (z >> 31)
+        };
+        Rng { state: [next(), next(), next(), next()] }
+    }
+
+    /// Seeds from the clock, for callers that do not need reproducible output.
+    pub fn from_entropy() -> Self {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64);
+        Rng::new(nanos ^ u64::from(std::process::id()).rotate_left(32))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let s = &mut self.state;
+        let result = s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = s[1] << 17;
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(45);
+        result
+    }
+
+    /// Uniform sample in `[0, 1)` with 53 random bits.
+    pub fn uniform(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Standard normal sample from Marsaglia's polar method.
+    pub fn normal(&mut self) -> f64 {
+        loop {
+            let u = 2.0 * self.uniform() - 1.0;
+            let v = 2.0 * self.uniform() - 1.0;
+            let s = u * u + v * v;
+            if s > 0.0 && s < 1.0 {
+                return u * (-2.0 * portable_ln(s) / s).sqrt();
+            }
+        }
+    }
+}
+
+/// Natural logarithm of a positive normal number from IEEE basic operations only, unlike `f64::ln` whose last bit
+///     depends on the platform math library.
+fn portable_ln(x: f64) -> f64 {
+    const LN_2: f64 = std::f64::consts::LN_2;
+    // Split x into 2^exponent * mantissa with the mantissa in [sqrt(1/2), sqrt(2))
+    let bits = x.to_bits();
+    let mut exponent = ((bits >> 52) & 0x7ff) as i64 - 1023;
+    let mut mantissa = f64::from_bits((bits & 0x000f_ffff_ffff_ffff) | 0x3ff0_0000_0000_0000);
+    if mantissa > std::f64::consts::SQRT_2 {
+        mantissa /= 2.0;
+        exponent += 1;
+    }
+    // ln(m) = 2 atanh(z) with z = (m - 1) / (m + 1), |z| < 0.172
+    let z = (mantissa - 1.0) / (mantissa + 1.0);
+    let z2 = z * z;
+    let mut term = z;
+    let mut sum = 0.0;
+    for k in 0..12 {
+        sum += term / (2 * k + 1) as f64;
+        term *= z2;
+    }
+    exponent as f64 * LN_2 + 2.0 * sum
+}
+
+/// Generates `len` samples of Gaussian white noise with standard deviation `std`.
+///     The same `seed` always produces the same samples, without one the generator is seeded from the clock.
+pub fn white_noise(len: usize, std: f64, seed: Option<u64>) -> Result<Vec<f64>, Box<dyn Error>> {
+    if std < 0.0 {
+        return Err("Standard deviation must not be negative.".into());
+    }
+    let mut rng = seed.map_or_else(Rng::from_entropy, Rng::new);
+    Ok((0..len).map(|_| std * rng.normal()).collect())
+}
+
+/// Spectral shape of the noise made by [`colored_noise`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NoiseColor {
+    White,
+    /// Power falling as `1/f`, equal power per octave.
+    Pink,
+    /// Power falling as `1/f²`, the spectrum of a random walk.
+    Brown,
+}
+
+/// Generates `len` samples of white, pink or brown Gaussian noise with standard deviation `std`.
+///     Colored noise is made by scaling the spectrum of white noise by `f^(-1/2)` or `f^(-1)` and has no DC
+///     component. The same `seed` always produces the same samples.
+pub fn colored_noise(len: usize, color: NoiseColor, std: f64, seed: Option<u64>) -> Result<Vec<f64>, Box<dyn Error>> {
+    if std < 0.0 {
+        return Err("Standard deviation must not be negative.".into());
+    }
+    let white = white_noise(len, 1.0, seed)?;
+    let exponent = match color {
+        NoiseColor::White => return Ok(white.into_iter().map(|x| std * x).collect()),
+        NoiseColor::Pink => 0.5,
+        NoiseColor::Brown => 1.0,
+    };
+    if len < 2 {
+        return Err("Colored noise needs at least two samples.".into());
+    }
+
+    let shaped = apply_response(white, len as f64, |freq| {
+        if freq == 0.0 {
+            Complex::new(0.0, 0.0)
+        } else {
+            Complex::new(freq.abs().powf(-exponent), 0.0)
+        }
+    });
+    let mean = shaped.iter().sum::<f64>() / len as f64;
+    let rms = (shaped.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / len as f64).sqrt();
+    Ok(shaped.into_iter().map(|x| std * (x - mean) / rms).collect())
+}
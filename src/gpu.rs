@@ -0,0 +1,301 @@
+//! Batched FFTs on the GPU through OpenCL, built with the `gpu` feature.
+//!
+//! The OpenCL runtime is loaded on first use instead of being linked, so building needs no OpenCL SDK and a machine
+//! without a GPU driver keeps the CPU path. [`compute_fft_axis`](crate::compute_fft_axis) and the segment FFTs of
+//! [`compute_spectrogram`](crate::compute_spectrogram) offload batches of at least [`GPU_MIN_SAMPLES`] samples whose
+//! transform length is a power of two, computed in double precision on the first GPU supporting `cl_khr_fp64`.
+//! Anything else, including a GPU error, falls back to the CPU.
+
+use libloading::Library;
+use std::ffi::{c_char, c_void, CString};
+use std::sync::{Mutex, OnceLock};
+
+use crate::Matrix;
+
+/// Smallest batch, in samples over all lanes, worth the transfer to and from the GPU.
+pub const GPU_MIN_SAMPLES: usize = 1 << 20;
+
+type Handle = *mut c_void;
+
+const CL_SUCCESS: i32 = 0;
+const CL_DEVICE_TYPE_GPU: u64 = 1 << 2;
+const CL_DEVICE_NAME: u32 = 0x102B;
+const CL_DEVICE_EXTENSIONS: u32 = 0x1030;
+const CL_MEM_READ_WRITE: u64 = 1;
+
+/// Stockham autosort radix-2 pass, merging transforms of length `p` into transforms of length `2p` for every lane.
+///     Running it for `p = 1, 2, 4, ... n / 2` between two buffers leaves the transforms in natural order.
+const KERNEL: &str = r#"
+#pragma OPENCL EXTENSION cl_khr_fp64 : enable
+__kernel void fft_radix2(__global const double2* x, __global double2* y, const int p, const int n) {
+    const int i = get_global_id(0);
+    const size_t lane = get_global_id(1) * (size_t)n;
+    const int k = i & (p - 1);
+    const double2 u0 = x[lane + i];
+    const double2 v = x[lane + i + n / 2];
+    double c;
+    const double s = sincos(-M_PI * k / p, &c);
+    const double2 u1 = (double2)(v.x * c - v.y * s, v.x * s + v.y * c);
+    const int j = (i << 1) - k;
+    y[lane + j] = u0 + u1;
+    y[lane + j + p] = u0 - u1;
+}
+"#;
+
+#[cfg(target_os = "windows")]
+const LIBRARY: &str = "OpenCL.dll";
+#[cfg(target_os = "macos")]
+const LIBRARY: &str = "/System/Library/Frameworks/OpenCL.framework/OpenCL";
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const LIBRARY: &str = "libOpenCL.so.1";
+
+/// The OpenCL 1.2 functions used.
+struct OpenCl {
+    get_platform_ids: unsafe extern "C" fn(u32, *mut Handle, *mut u32) -> i32,
+    get_device_ids: unsafe extern "C" fn(Handle, u64, u32, *mut Handle, *mut u32) -> i32,
+    get_device_info: unsafe extern "C" fn(Handle, u32, usize, *mut c_void, *mut usize) -> i32,
+    create_context: unsafe extern "C" fn(*const isize, u32, *const Handle, *const c_void, *mut c_void, *mut i32) -> Handle,
+    create_command_queue: unsafe extern "C" fn(Handle, Handle, u64, *mut i32) -> Handle,
+    create_program_with_source: unsafe extern "C" fn(Handle, u32, *const *const c_char, *const usize, *mut i32) -> Handle,
+    build_program: unsafe extern "C" fn(Handle, u32, *const Handle, *const c_char, *const c_void, *mut c_void) -> i32,
+    create_kernel: unsafe extern "C" fn(Handle, *const c_char, *mut i32) -> Handle,
+    create_buffer: unsafe extern "C" fn(Handle, u64, usize, *mut c_void, *mut i32) -> Handle,
+    set_kernel_arg: unsafe extern "C" fn(Handle, u32, usize, *const c_void) -> i32,
+    enqueue_write_buffer: unsafe extern "C" fn(Handle, Handle, u32, usize, usize, *const c_void, u32, *const Handle, *mut Handle) -> i32,
+    enqueue_read_buffer: unsafe extern "C" fn(Handle, Handle, u32, usize, usize, *mut c_void, u32, *const Handle, *mut Handle) -> i32,
+    enqueue_nd_range_kernel:
+        unsafe extern "C" fn(Handle, Handle, u32, *const usize, *const usize, *const usize, u32, *const Handle, *mut Handle) -> i32,
+    finish: unsafe extern "C" fn(Handle) -> i32,
+    release_mem_object: unsafe extern "C" fn(Handle) -> i32,
+    release_kernel: unsafe extern "C" fn(Handle) -> i32,
+    release_program: unsafe extern "C" fn(Handle) -> i32,
+    release_command_queue: unsafe extern "C" fn(Handle) -> i32,
+    release_context: unsafe extern "C" fn(Handle) -> i32,
+    // Keeps the function pointers above valid
+    _library: Library,
+}
+
+fn check(code: i32, call: &str) -> Result<(), String> {
+    if code == CL_SUCCESS {
+        Ok(())
+    } else {
+        Err(format!("OpenCL {} failed with error {}", call, code))
+    }
+}
+
+impl OpenCl {
+    fn load() -> Result<OpenCl, String> {
+        // SAFETY: the OpenCL ICD loader runs no initialisation code with requirements on the caller, and every
+        //     symbol is given the signature of its declaration in CL/cl.h
+        unsafe {
+            let library = Library::new(LIBRARY).map_err(|e| format!("OpenCL is not available: {}", e))?;
+            macro_rules! symbol {
+                ($name:literal) => {
+                    *library.get($name).map_err(|e| format!("OpenCL is not available: {}", e))?
+                };
+            }
+            Ok(OpenCl {
+                get_platform_ids: symbol!(b"clGetPlatformIDs\0"),
+                get_device_ids: symbol!(b"clGetDeviceIDs\0"),
+                get_device_info: symbol!(b"clGetDeviceInfo\0"),
+                create_context: symbol!(b"clCreateContext\0"),
+                create_command_queue: symbol!(b"clCreateCommandQueue\0"),
+                create_program_with_source: symbol!(b"clCreateProgramWithSource\0"),
+                build_program: symbol!(b"clBuildProgram\0"),
+                create_kernel: symbol!(b"clCreateKernel\0"),
+                create_buffer: symbol!(b"clCreateBuffer\0"),
+                set_kernel_arg: symbol!(b"clSetKernelArg\0"),
+                enqueue_write_buffer: symbol!(b"clEnqueueWriteBuffer\0"),
+                enqueue_read_buffer: symbol!(b"clEnqueueReadBuffer\0"),
+                enqueue_nd_range_kernel: symbol!(b"clEnqueueNDRangeKernel\0"),
+                finish: symbol!(b"clFinish\0"),
+                release_mem_object: symbol!(b"clReleaseMemObject\0"),
+                release_kernel: symbol!(b"clReleaseKernel\0"),
+                release_program: symbol!(b"clReleaseProgram\0"),
+                release_command_queue: symbol!(b"clReleaseCommandQueue\0"),
+                release_context: symbol!(b"clReleaseContext\0"),
+                _library: library,
+            })
+        }
+    }
+
+    /// A text property of the device, without the terminating zero.
+    fn device_text(&self, device: Handle, property: u32) -> Result<String, String> {
+        let mut size = 0;
+        // SAFETY: the device handle came from clGetDeviceIDs, the first call only queries the size
+        check(unsafe { (self.get_device_info)(device, property, 0, std::ptr::null_mut(), &mut size) }, "clGetDeviceInfo")?;
+        let mut text = vec![0u8; size];
+        // SAFETY: `text` holds the `size` bytes the property needs
+        check(unsafe { (self.get_device_info)(device, property, size, text.as_mut_ptr().cast(), std::ptr::null_mut()) }, "clGetDeviceInfo")?;
+        Ok(String::from_utf8_lossy(&text).trim_end_matches('\0').to_string())
+    }
+
+    /// The first GPU of any platform that supports double precision.
+    fn find_device(&self) -> Result<Handle, String> {
+        let mut count = 0;
+        // SAFETY: the first call only queries the number of platforms, the second fills `platforms`
+        check(unsafe { (self.get_platform_ids)(0, std::ptr::null_mut(), &mut count) }, "clGetPlatformIDs")?;
+        let mut platforms = vec![std::ptr::null_mut(); count as usize];
+        check(unsafe { (self.get_platform_ids)(count, platforms.as_mut_ptr(), std::ptr::null_mut()) }, "clGetPlatformIDs")?;
+
+        for platform in platforms {
+            let mut count = 0;
+            // SAFETY: as above, a platform without GPUs returns an error and is skipped
+            if unsafe { (self.get_device_ids)(platform, CL_DEVICE_TYPE_GPU, 0, std::ptr::null_mut(), &mut count) } != CL_SUCCESS {
+                continue;
+            }
+            let mut devices = vec![std::ptr::null_mut(); count as usize];
+            let code = unsafe { (self.get_device_ids)(platform, CL_DEVICE_TYPE_GPU, count, devices.as_mut_ptr(), std::ptr::null_mut()) };
+            check(code, "clGetDeviceIDs")?;
+            for device in devices {
+                if self.device_text(device, CL_DEVICE_EXTENSIONS)?.split_whitespace().any(|e| e == "cl_khr_fp64") {
+                    return Ok(device);
+                }
+            }
+        }
+        Err("No OpenCL GPU with double precision support found".to_string())
+    }
+}
+
+/// An OpenCL object, released with the matching `clRelease*` function when dropped. Objects must be dropped before
+///     the [`OpenCl`] their release function came from.
+struct Object {
+    handle: Handle,
+    release: unsafe extern "C" fn(Handle) -> i32,
+}
+
+impl Object {
+    /// Takes ownership of a handle returned by a `clCreate*` call, after checking the error code it set.
+    fn new(handle: Handle, code: i32, call: &str, release: unsafe extern "C" fn(Handle) -> i32) -> Result<Object, String> {
+        check(code, call)?;
+        Ok(Object { handle, release })
+    }
+}
+
+impl Drop for Object {
+    fn drop(&mut self) {
+        // SAFETY: the handle was created by the call given to `Object::new` and is not used again
+        unsafe { (self.release)(self.handle) };
+    }
+}
+
+/// The context, queue and compiled kernel on the chosen device, kept for the life of the process.
+struct Gpu {
+    // Fields drop in order, so the objects are released kernel first and before the library is unloaded
+    kernel: Object,
+    _program: Object,
+    queue: Object,
+    context: Object,
+    cl: OpenCl,
+    device_name: String,
+}
+
+// SAFETY: OpenCL objects may be used from any thread, except that setting the arguments of a kernel must not race
+//     with another launch, which the mutex around the `Gpu` rules out
+unsafe impl Send for Gpu {}
+
+impl Gpu {
+    fn init() -> Result<Gpu, String> {
+        let cl = OpenCl::load()?;
+        let device = cl.find_device()?;
+        let device_name = cl.device_text(device, CL_DEVICE_NAME)?;
+        let source = CString::new(KERNEL).map_err(|e| e.to_string())?;
+        let mut code = CL_SUCCESS;
+        // SAFETY: the device handle is valid and every out pointer lives across its call. The objects created so
+        //     far are released when a later step fails
+        unsafe {
+            let context = (cl.create_context)(std::ptr::null(), 1, &device, std::ptr::null(), std::ptr::null_mut(), &mut code);
+            let context = Object::new(context, code, "clCreateContext", cl.release_context)?;
+            let queue = (cl.create_command_queue)(context.handle, device, 0, &mut code);
+            let queue = Object::new(queue, code, "clCreateCommandQueue", cl.release_command_queue)?;
+            let program = (cl.create_program_with_source)(context.handle, 1, &source.as_ptr(), std::ptr::null(), &mut code);
+            let program = Object::new(program, code, "clCreateProgramWithSource", cl.release_program)?;
+            let code = (cl.build_program)(program.handle, 1, &device, std::ptr::null(), std::ptr::null(), std::ptr::null_mut());
+            check(code, "clBuildProgram")?;
+            let mut code = CL_SUCCESS;
+            let kernel = (cl.create_kernel)(program.handle, c"fft_radix2".as_ptr(), &mut code);
+            let kernel = Object::new(kernel, code, "clCreateKernel", cl.release_kernel)?;
+            log::debug!("Using OpenCL device '{}' for batched FFTs", device_name);
+            Ok(Gpu { kernel, _program: program, queue, context, cl, device_name })
+        }
+    }
+
+    fn get() -> Result<&'static Mutex<Gpu>, String> {
+        static GPU: OnceLock<Result<Mutex<Gpu>, String>> = OnceLock::new();
+        GPU.get_or_init(|| Gpu::init().map(Mutex::new)).as_ref().map_err(String::clone)
+    }
+
+    fn buffer(&self, bytes: usize) -> Result<Object, String> {
+        let mut code = CL_SUCCESS;
+        // SAFETY: the context is valid and no host pointer is passed
+        let mem = unsafe { (self.cl.create_buffer)(self.context.handle, CL_MEM_READ_WRITE, bytes, std::ptr::null_mut(), &mut code) };
+        Object::new(mem, code, "clCreateBuffer", self.cl.release_mem_object)
+    }
+
+    /// Transforms `lanes` lanes of `n` interleaved complex values in place.
+    fn fft(&self, data: &mut [f64], n: usize, lanes: usize) -> Result<(), String> {
+        let bytes = std::mem::size_of_val(data);
+        let (mut source, mut target) = (self.buffer(bytes)?, self.buffer(bytes)?);
+        let cl = &self.cl;
+        // SAFETY: both buffers hold `bytes` bytes, the blocking copies finish before `data` is touched again, and
+        //     the kernel arguments match the kernel signature
+        unsafe {
+            let code = (cl.enqueue_write_buffer)(self.queue.handle, source.handle, 1, 0, bytes, data.as_ptr().cast(), 0, std::ptr::null(), std::ptr::null_mut());
+            check(code, "clEnqueueWriteBuffer")?;
+            let global = [n / 2, lanes];
+            let n = n as i32;
+            let mut p = 1i32;
+            while p < n {
+                check((cl.set_kernel_arg)(self.kernel.handle, 0, size_of::<Handle>(), (&source.handle as *const Handle).cast()), "clSetKernelArg")?;
+                check((cl.set_kernel_arg)(self.kernel.handle, 1, size_of::<Handle>(), (&target.handle as *const Handle).cast()), "clSetKernelArg")?;
+                check((cl.set_kernel_arg)(self.kernel.handle, 2, size_of::<i32>(), (&p as *const i32).cast()), "clSetKernelArg")?;
+                check((cl.set_kernel_arg)(self.kernel.handle, 3, size_of::<i32>(), (&n as *const i32).cast()), "clSetKernelArg")?;
+                let code = (cl.enqueue_nd_range_kernel)(
+                    self.queue.handle,
+                    self.kernel.handle,
+                    2,
+                    std::ptr::null(),
+                    global.as_ptr(),
+                    std::ptr::null(),
+                    0,
+                    std::ptr::null(),
+                    std::ptr::null_mut(),
+                );
+                check(code, "clEnqueueNDRangeKernel")?;
+                std::mem::swap(&mut source, &mut target);
+                p *= 2;
+            }
+            let code = (cl.enqueue_read_buffer)(self.queue.handle, source.handle, 1, 0, bytes, data.as_mut_ptr().cast(), 0, std::ptr::null(), std::ptr::null_mut());
+            check(code, "clEnqueueReadBuffer")?;
+            check((cl.finish)(self.queue.handle), "clFinish")
+        }
+    }
+}
+
+/// Name of the GPU the batched FFTs run on, or `None` when there is none and everything runs on the CPU.
+pub fn gpu_device() -> Option<String> {
+    Gpu::get().ok().map(|gpu| gpu.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).device_name.clone())
+}
+
+/// The forward FFT of every lane on the GPU as real and imaginary parts, or `None` to compute them on the CPU
+///     because the batch is too small, the length is not a power of two, there is no GPU or it failed.
+pub(crate) fn fft_lanes(lanes: &[Vec<f64>]) -> Option<(Matrix, Matrix)> {
+    let n = lanes.first().map_or(0, Vec::len);
+    if n < 2 || !n.is_power_of_two() || n > i32::MAX as usize || n * lanes.len() < GPU_MIN_SAMPLES {
+        return None;
+    }
+    let gpu = match Gpu::get() {
+        Ok(gpu) => gpu.lock().unwrap_or_else(|poisoned| poisoned.into_inner()),
+        Err(e) => {
+            log::debug!("Batched FFT on the CPU: {}", e);
+            return None;
+        }
+    };
+
+    let mut data: Vec<f64> = lanes.iter().flatten().flat_map(|&x| [x, 0.0]).collect();
+    if let Err(e) = gpu.fft(&mut data, n, lanes.len()) {
+        log::warn!("GPU FFT failed, falling back to the CPU: {}", e);
+        return None;
+    }
+    Some(data.chunks_exact(2 * n).map(|lane| lane.chunks_exact(2).map(|c| (c[0], c[1])).unzip()).unzip())
+}
@@ -0,0 +1,149 @@
+//! Recursive (IIR) filters built from second order sections.
+
+use rustfft::num_complex::Complex;
+use std::error::Error;
+use std::f64::consts::PI;
+
+use crate::acoustics::FrequencyResponse;
+
+/// A second order section `(b0 + b1 z^-1 + b2 z^-2) / (1 + a1 z^-1 + a2 z^-2)`, normalized so `a0 = 1`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Biquad {
+    pub b0: f64,
+    pub b1: f64,
+    pub b2: f64,
+    pub a1: f64,
+    pub a2: f64,
+}
+
+impl Biquad {
+    /// Builds a section from `[b0, b1, b2, a0, a1, a2]`, the row layout of an SOS matrix.
+    pub fn from_sos(row: &[f64]) -> Result<Self, Box<dyn Error>> {
+        let [b0, b1, b2, a0, a1, a2] = row else {
+            return Err("Each second order section needs six coefficients [b0, b1, b2, a0, a1, a2].".into());
+        };
+        if *a0 == 0.0 {
+            return Err("a0 of a second order section must not be zero.".into());
+        }
+        Ok(Biquad { b0: b0 / a0, b1: b1 / a0, b2: b2 / a0, a1: a1 / a0, a2: a2 / a0 })
+    }
+
+    /// Returns the coefficients as an SOS row `[b0, b1, b2, 1, a1, a2]`.
+    pub fn to_sos(&self) -> [f64; 6] {
+        [self.b0, self.b1, self.b2, 1.0, self.a1, self.a2]
+    }
+
+    /// Peaking equalizer with `gain_db` at `freq` Hz from the Audio EQ Cookbook (R. Bristow-Johnson).
+    pub fn peaking(fs: f64, freq: f64, gain_db: f64, q: f64) -> Result<Self, Box<dyn Error>> {
+        if fs <= 0.0 || freq <= 0.0 || freq >= fs / 2.0 || q <= 0.0 {
+            return Err("Peaking filter needs 0 < freq < fs / 2 and a positive Q.".into());
+        }
+        let amplitude = 10f64.powf(gain_db / 40.0);
+        let w0 = 2.0 * PI * freq / fs;
+        let alpha = w0.sin() / (2.0 * q);
+        let a0 = 1.0 + alpha / amplitude;
+        Ok(Biquad {
+            b0: (1.0 + alpha * amplitude) / a0,
+            b1: -2.0 * w0.cos() / a0,
+            b2: (1.0 - alpha * amplitude) / a0,
+            a1: -2.0 * w0.cos() / a0,
+            a2: (1.0 - alpha / amplitude) / a0,
+        })
+    }
+
+    /// Complex response of the section at `freq` Hz.
+    pub fn response(&self, freq: f64, fs: f64) -> Complex<f64> {
+        let z1 = Complex::from_polar(1.0, -2.0 * PI * freq / fs);
+        let z2 = z1 * z1;
+        (self.b0 + self.b1 * z1 + self.b2 * z2) / (1.0 + self.a1 * z1 + self.a2 * z2)
+    }
+}
+
+/// Cascade of second order sections run in transposed direct form II.
+///     The filter state persists between calls to [`BiquadCascade::process`], so a stream can be filtered in chunks.
+#[derive(Clone, Debug)]
+pub struct BiquadCascade {
+    sections: Vec<Biquad>,
+    state: Vec<[f64; 2]>,
+}
+
+impl BiquadCascade {
+    pub fn new(sections: Vec<Biquad>) -> Self {
+        let state = vec![[0.0; 2]; sections.len()];
+        BiquadCascade { sections, state }
+    }
+
+    /// Builds a cascade from SOS rows `[b0, b1, b2, a0, a1, a2]`.
+    pub fn from_sos(sos: &[Vec<f64>]) -> Result<Self, Box<dyn Error>> {
+        Ok(BiquadCascade::new(sos.iter().map(|row| Biquad::from_sos(row)).collect::<Result<_, _>>()?))
+    }
+
+    pub fn sections(&self) -> &[Biquad] {
+        &self.sections
+    }
+
+    /// Filters the next chunk of the stream.
+    pub fn process(&mut self, chunk: &[f64]) -> Vec<f64> {
+        let mut output = chunk.to_vec();
+        for (section, state) in self.sections.iter().zip(self.state.iter_mut()) {
+            for x in output.iter_mut() {
+                let y = section.b0 * *x + state[0];
+                state[0] = section.b1 * *x - section.a1 * y + state[1];
+                state[1] = section.b2 * *x - section.a2 * y;
+                *x = y;
+            }
+        }
+        output
+    }
+
+    /// Clears the filter state, as before the first chunk.
+    pub fn reset(&mut self) {
+        self.state.iter_mut().for_each(|s| *s = [0.0; 2]);
+    }
+
+    /// Evaluates the response of the cascade at `n_points` frequencies spaced evenly from 0 to `fs / 2`.
+    pub fn frequency_response(&self, fs: f64, n_points: usize) -> Result<FrequencyResponse, Box<dyn Error>> {
+        if fs <= 0.0 || n_points < 2 {
+            return Err("fs must be greater than zero and at least two points are needed.".into());
+        }
+        let freqs: Vec<f64> = (0..n_points).map(|k| k as f64 * fs / 2.0 / (n_points - 1) as f64).collect();
+        let response: Vec<Complex<f64>> = freqs
+            .iter()
+            .map(|&f| self.sections.iter().map(|s| s.response(f, fs)).product())
+            .collect();
+        Ok(FrequencyResponse {
+            freqs,
+            magnitude_db: response.iter().map(|c| 20.0 * c.norm().max(1e-300).log10()).collect(),
+            phase: response.iter().map(|c| c.arg()).collect(),
+        })
+    }
+}
+
+/// Evaluates the response of the digital filter `B(z) / A(z)` at `n_points` frequencies spaced evenly from 0 to
+///     `fs / 2`, with `b` and `a` the coefficients of increasing powers of `z^-1` as in `scipy.signal.freqz`.
+///     Pass `a = [1]` for an FIR filter. [`generate_bode_plot`](crate::generate_bode_plot) draws the result.
+pub fn freqz(b: Vec<f64>, a: Vec<f64>, fs: f64, n_points: usize) -> Result<FrequencyResponse, Box<dyn Error>> {
+    if b.is_empty() || a.first().is_none_or(|&a0| a0 == 0.0) {
+        return Err("b must not be empty and a must start with a non-zero coefficient.".into());
+    }
+    if fs <= 0.0 || n_points < 2 {
+        return Err("fs must be greater than zero and at least two points are needed.".into());
+    }
+
+    let polynomial = |coefficients: &[f64], z1: Complex<f64>| {
+        coefficients.iter().rev().fold(Complex::new(0.0, 0.0), |acc, &c| acc * z1 + c)
+    };
+    let freqs: Vec<f64> = (0..n_points).map(|k| k as f64 * fs / 2.0 / (n_points - 1) as f64).collect();
+    let response: Vec<Complex<f64>> = freqs
+        .iter()
+        .map(|&f| {
+            let z1 = Complex::from_polar(1.0, -2.0 * PI * f / fs);
+            polynomial(&b, z1) / polynomial(&a, z1)
+        })
+        .collect();
+    Ok(FrequencyResponse {
+        freqs,
+        magnitude_db: response.iter().map(|c| 20.0 * c.norm().max(1e-300).log10()).collect(),
+        phase: response.iter().map(|c| c.arg()).collect(),
+    })
+}
@@ -0,0 +1,101 @@
+//! Interpolation of sampled data onto new abscissae.
+
+use rayon::prelude::*;
+use std::error::Error;
+use std::f64::consts::PI;
+
+use crate::threads;
+
+/// Interpolation method used by [`interpolate`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Interpolation {
+    Linear,
+    /// Natural cubic spline.
+    Cubic,
+    /// Band-limited (Whittaker-Shannon) interpolation, requires uniformly spaced `x`.
+    Sinc,
+}
+
+/// Second derivatives of the natural cubic spline through the points, solved with the Thomas algorithm.
+fn spline_second_derivatives(x: &[f64], y: &[f64]) -> Vec<f64> {
+    let n = x.len();
+    let mut second = vec![0.0; n];
+    if n < 3 {
+        return second;
+    }
+
+    let mut diagonal = vec![0.0; n];
+    let mut rhs = vec![0.0; n];
+    for i in 1..n - 1 {
+        let (h0, h1) = (x[i] - x[i - 1], x[i + 1] - x[i]);
+        diagonal[i] = 2.0 * (h0 + h1);
+        rhs[i] = 6.0 * ((y[i + 1] - y[i]) / h1 - (y[i] - y[i - 1]) / h0);
+        if i > 1 {
+            let factor = h0 / diagonal[i - 1];
+            diagonal[i] -= factor * h0;
+            rhs[i] -= factor * rhs[i - 1];
+        }
+    }
+    for i in (1..n - 1).rev() {
+        let upper = if i + 1 < n - 1 { (x[i + 1] - x[i]) * second[i + 1] } else { 0.0 };
+        second[i] = (rhs[i] - upper) / diagonal[i];
+    }
+    second
+}
+
+/// Interpolates the samples `y` taken at the strictly increasing positions `x` onto `new_x`.
+///     Linear and cubic interpolation hold the end values outside the range of `x`.
+pub fn interpolate(x: Vec<f64>, y: Vec<f64>, new_x: Vec<f64>, method: Interpolation) -> Result<Vec<f64>, Box<dyn Error>> {
+    if x.len() != y.len() || x.is_empty() {
+        return Err("x and y must be non-empty and have the same length.".into());
+    }
+    if x.windows(2).any(|w| w[1] <= w[0]) {
+        return Err("x must be strictly increasing.".into());
+    }
+    let last = x.len() - 1;
+
+    let values = match method {
+        Interpolation::Linear | Interpolation::Cubic => {
+            let second = if method == Interpolation::Cubic { spline_second_derivatives(&x, &y) } else { vec![0.0; x.len()] };
+            new_x
+                .iter()
+                .map(|&t| {
+                    if t <= x[0] {
+                        return y[0];
+                    }
+                    if t >= x[last] {
+                        return y[last];
+                    }
+                    let i = x.partition_point(|&v| v <= t) - 1;
+                    let h = x[i + 1] - x[i];
+                    let (a, b) = ((x[i + 1] - t) / h, (t - x[i]) / h);
+                    let linear = a * y[i] + b * y[i + 1];
+                    linear + ((a * a * a - a) * second[i] + (b * b * b - b) * second[i + 1]) * h * h / 6.0
+                })
+                .collect()
+        }
+        Interpolation::Sinc => {
+            let step = if last > 0 { (x[last] - x[0]) / last as f64 } else { 1.0 };
+            if x.windows(2).any(|w| ((w[1] - w[0]) - step).abs() > 1e-6 * step) {
+                return Err("Sinc interpolation requires uniformly spaced x.".into());
+            }
+            threads::install(|| {
+                new_x
+                    .par_iter()
+                    .map(|&t| {
+                        let position = (t - x[0]) / step;
+                        y.iter()
+                            .enumerate()
+                            .map(|(n, &v)| {
+                                let arg = PI * (position - n as f64);
+                                if arg.abs() < 1e-12 { v } else { v * arg.sin() / arg }
+                            })
+                            .sum()
+                    })
+                    .collect()
+            })
+        }
+    };
+
+    Ok(values)
+}
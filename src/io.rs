@@ -0,0 +1,600 @@
+//! Loaders for the supported measurement file formats.
+
+use csv::ReaderBuilder;
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+
+use crate::{read_flac, Matrix, Progress};
+
+/// Reads a CSV file and extracts the first two columns (time, measured_data) as separate vectors.
+///     This is a very specific funciton to these examples and is not intended to be a robust data loader
+pub fn read_csv(file_path: &str) -> Result<(Vec<f64>, Vec<f64>), Box<dyn Error>> {
+    read_csv_with_progress(file_path, &mut |_| true)
+}
+
+/// Same as [`read_csv`], reporting the fraction of the file read so far to `progress`.
+pub fn read_csv_with_progress(
+    file_path: &str,
+    progress: &mut dyn FnMut(f64) -> bool,
+) -> Result<(Vec<f64>, Vec<f64>), Box<dyn Error>> {
+    let file_len = std::fs::metadata(file_path)?.len().max(1) as f64;
+    let mut progress = Progress::new(progress);
+    let mut reader = ReaderBuilder::new()
+        .has_headers(true)  // Assumes the first row is a header
+        .from_path(file_path)?;
+
+    let mut time = Vec::new();
+    let mut measured_data = Vec::new();
+    let mut skipped = 0;
+    let mut record = csv::StringRecord::new();
+    while reader.read_record(&mut record)? {
+        if record.len() >= 2 {
+            let line = record.position().map_or(0, |p| p.line());
+            let parse = |field: &str| {
+                field.trim().parse::<f64>().inspect_err(|e| {
+                    log::warn!("{}: line {}: could not parse '{}' as a number ({})", file_path, line, field, e)
+                })
+            };
+            time.push(parse(&record[0])?);
+            measured_data.push(parse(&record[1])?);
+        } else {
+            skipped += 1;
+        }
+        progress.update(reader.position().byte() as f64 / file_len)?;
+    }
+    progress.update(1.0)?;
+
+    log::debug!("{}: read {} rows, skipped {} rows with fewer than two columns", file_path, time.len(), skipped);
+    Ok((time, measured_data))
+}
+
+/// Converts a big-endian IBM System/360 single precision float to an f64.
+fn ibm_to_f64(bytes: [u8; 4]) -> f64 {
+    let bits = u32::from_be_bytes(bytes);
+    let sign = if bits >> 31 == 1 { -1.0 } else { 1.0 };
+    let exponent = ((bits >> 24) & 0x7f) as i32;
+    let fraction = (bits & 0x00ff_ffff) as f64 / 16_777_216.0;
+    sign * fraction * 16f64.powi(exponent - 64)
+}
+
+/// Reads traces from a SEG-Y file and returns the sample interval (in seconds) and one vector per trace.
+///     `trace_range` selects traces as a half open `(start, end)` index range, all traces are read when it is `None`.
+///     Only fixed length traces are supported, the trace length is taken from the binary file header.
+pub fn read_segy(
+    file_path: &str,
+    trace_range: Option<(usize, usize)>,
+) -> Result<(f64, Matrix), Box<dyn Error>> {
+    let mut file = File::open(file_path)?;
+    let file_len = file.metadata()?.len();
+
+    // Skip the 3200 byte textual header and read the 400 byte binary header
+    let mut binary_header = [0u8; 400];
+    file.seek(SeekFrom::Start(3200))?;
+    file.read_exact(&mut binary_header)?;
+
+    let read_u16 = |offset: usize| u16::from_be_bytes([binary_header[offset], binary_header[offset + 1]]);
+    let sample_interval_us = read_u16(16);
+    let samples_per_trace = read_u16(20) as usize;
+    let format_code = read_u16(24);
+    let extended_headers = read_u16(304) as u64;
+
+    if sample_interval_us == 0 || samples_per_trace == 0 {
+        return Err("SEG-Y binary header has no sample interval or sample count.".into());
+    }
+
+    let sample_size = match format_code {
+        1 | 2 | 5 => 4,
+        3 => 2,
+        6 => 8,
+        8 => 1,
+        _ => return Err(format!("Unsupported SEG-Y data sample format code {}.", format_code).into()),
+    };
+
+    let data_start = 3600 + extended_headers * 3200;
+    let trace_size = (240 + samples_per_trace * sample_size) as u64;
+    let trace_count = (file_len.saturating_sub(data_start) / trace_size) as usize;
+
+    let (start, end) = trace_range.unwrap_or((0, trace_count));
+    if start > end || end > trace_count {
+        return Err(format!(
+            "Trace range {}..{} is out of bounds for a file with {} traces.",
+            start, end, trace_count
+        )
+        .into());
+    }
+
+    let mut traces = Vec::with_capacity(end - start);
+    let mut raw = vec![0u8; samples_per_trace * sample_size];
+    for index in start..end {
+        // Skip the 240 byte trace header
+        file.seek(SeekFrom::Start(data_start + index as u64 * trace_size + 240))?;
+        file.read_exact(&mut raw)?;
+
+        let trace: Vec<f64> = raw
+            .chunks_exact(sample_size)
+            .map(|b| match format_code {
+                1 => ibm_to_f64([b[0], b[1], b[2], b[3]]),
+                2 => i32::from_be_bytes([b[0], b[1], b[2], b[3]]) as f64,
+                3 => i16::from_be_bytes([b[0], b[1]]) as f64,
+                5 => f32::from_be_bytes([b[0], b[1], b[2], b[3]]) as f64,
+                6 => f64::from_be_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]),
+                _ => b[0] as i8 as f64,
+            })
+            .collect();
+        traces.push(trace);
+    }
+
+    log::debug!(
+        "{}: read traces {}..{} of {} with {} samples at {} us (format code {})",
+        file_path, start, end, trace_count, samples_per_trace, sample_interval_us, format_code
+    );
+    Ok((sample_interval_us as f64 * 1e-6, traces))
+}
+
+/// A single channel of continuous samples loaded from a multi-channel file format.
+pub struct Channel {
+    /// Channel identifier, `NET.STA.LOC.CHA` for MiniSEED and the signal label for EDF/BDF.
+    pub id: String,
+    pub sample_rate: f64,
+    pub data: Vec<f64>,
+}
+
+/// Sign extends the lowest `bits` bits of `value`.
+fn sign_extend(value: u32, bits: u32) -> i32 {
+    let shift = 32 - bits;
+    ((value << shift) as i32) >> shift
+}
+
+/// Decodes Steim1 or Steim2 compressed frames into integer samples.
+fn decode_steim(data: &[u8], sample_count: usize, steim2: bool) -> Result<Vec<i32>, Box<dyn Error>> {
+    let mut differences = Vec::with_capacity(sample_count);
+    let mut first_sample = 0;
+
+    for (frame_index, frame) in data.chunks_exact(64).enumerate() {
+        let words: Vec<u32> = frame
+            .chunks_exact(4)
+            .map(|w| u32::from_be_bytes([w[0], w[1], w[2], w[3]]))
+            .collect();
+        let control = words[0];
+
+        for (i, &word) in words.iter().enumerate().skip(1) {
+            // The first frame stores the forward and reverse integration constants in words 1 and 2
+            if frame_index == 0 && i == 1 {
+                first_sample = word as i32;
+                continue;
+            }
+            if frame_index == 0 && i == 2 {
+                continue;
+            }
+
+            let nibble = (control >> (30 - 2 * i)) & 0b11;
+            let (count, bits) = match (nibble, steim2, word >> 30) {
+                (0, _, _) => continue,
+                (1, _, _) => (4, 8),
+                (2, false, _) => (2, 16),
+                (3, false, _) => (1, 32),
+                (2, true, 1) => (1, 30),
+                (2, true, 2) => (2, 15),
+                (2, true, 3) => (3, 10),
+                (3, true, 0) => (5, 6),
+                (3, true, 1) => (6, 5),
+                (3, true, 2) => (7, 4),
+                _ => return Err("Invalid Steim2 difference code.".into()),
+            };
+            for k in (0..count).rev() {
+                let value = if bits == 32 { word } else { (word >> (k * bits)) & ((1 << bits) - 1) };
+                differences.push(sign_extend(value, bits));
+            }
+        }
+    }
+
+    // The first difference is relative to the previous record, so integration starts from the forward constant
+    let mut samples = Vec::with_capacity(sample_count);
+    if sample_count > 0 {
+        samples.push(first_sample);
+    }
+    for &difference in differences.iter().skip(1).take(sample_count.saturating_sub(1)) {
+        let previous = *samples.last().unwrap_or(&first_sample);
+        samples.push(previous.wrapping_add(difference));
+    }
+    if samples.len() != sample_count {
+        return Err("Steim frames contain fewer samples than the record header reports.".into());
+    }
+    Ok(samples)
+}
+
+/// Reads a MiniSEED (v2) file and returns the samples of each channel with its sample rate.
+///     Records are concatenated per channel in file order, gaps and overlaps between records are not handled.
+///     Supported encodings are 16/32 bit integers, 32/64 bit floats, Steim1 and Steim2.
+pub fn read_mseed(file_path: &str) -> Result<Vec<Channel>, Box<dyn Error>> {
+    let bytes = std::fs::read(file_path)?;
+    let mut channels: Vec<Channel> = Vec::new();
+    let mut offset = 0;
+
+    while offset + 48 <= bytes.len() {
+        let header = &bytes[offset..offset + 48];
+
+        // The header byte order is detected from the plausibility of the start year
+        let big_endian = (1900..=2100).contains(&u16::from_be_bytes([header[20], header[21]]));
+        let read_u16 = |b: &[u8], at: usize| {
+            let pair = [b[at], b[at + 1]];
+            if big_endian { u16::from_be_bytes(pair) } else { u16::from_le_bytes(pair) }
+        };
+
+        let text = |range: std::ops::Range<usize>| String::from_utf8_lossy(&header[range]).trim().to_string();
+        let id = format!("{}.{}.{}.{}", text(18..20), text(8..13), text(13..15), text(15..18));
+
+        let sample_count = read_u16(header, 30) as usize;
+        let factor = read_u16(header, 32) as i16 as f64;
+        let multiplier = read_u16(header, 34) as i16 as f64;
+        let sample_rate = match (factor > 0.0, multiplier > 0.0) {
+            _ if factor == 0.0 || multiplier == 0.0 => 0.0,
+            (true, true) => factor * multiplier,
+            (true, false) => -factor / multiplier,
+            (false, true) => -multiplier / factor,
+            (false, false) => 1.0 / (factor * multiplier),
+        };
+        let data_offset = read_u16(header, 44) as usize;
+
+        // Walk the blockette chain looking for blockette 1000 (encoding and record length)
+        let mut encoding = None;
+        let mut data_big_endian = big_endian;
+        let mut record_length = None;
+        let mut blockette = read_u16(header, 46) as usize;
+        while blockette != 0 && offset + blockette + 8 <= bytes.len() {
+            let at = offset + blockette;
+            if read_u16(&bytes, at) == 1000 {
+                encoding = Some(bytes[at + 4]);
+                data_big_endian = bytes[at + 5] == 1;
+                // SEED allows records of 2^8 to 2^20 bytes, anything else is a corrupt exponent
+                let exponent = bytes[at + 6];
+                let length = 1usize.checked_shl(exponent as u32).filter(|_| (8..=20).contains(&exponent));
+                record_length = Some(length.ok_or_else(|| {
+                    format!("Record at byte {} has an invalid record length exponent {}.", offset, exponent)
+                })?);
+            }
+            let next = read_u16(&bytes, at + 2) as usize;
+            // Each blockette must point further into the record, or a corrupt chain would loop forever
+            if next != 0 && next <= blockette {
+                return Err(format!("Record at byte {} has a blockette chain pointing backwards.", offset).into());
+            }
+            blockette = next;
+        }
+
+        let (encoding, record_length) = match (encoding, record_length) {
+            (Some(encoding), Some(record_length)) => (encoding, record_length),
+            _ => return Err(format!("Record at byte {} has no blockette 1000.", offset).into()),
+        };
+        if offset + record_length > bytes.len() || data_offset > record_length {
+            return Err(format!("Record at byte {} is truncated.", offset).into());
+        }
+        let payload = &bytes[offset + data_offset..offset + record_length];
+
+        let word = |b: &[u8]| {
+            let quad = [b[0], b[1], b[2], b[3]];
+            if data_big_endian { u32::from_be_bytes(quad) } else { u32::from_le_bytes(quad) }
+        };
+        let samples: Vec<f64> = match encoding {
+            1 => payload
+                .chunks_exact(2)
+                .map(|b| if data_big_endian { i16::from_be_bytes([b[0], b[1]]) } else { i16::from_le_bytes([b[0], b[1]]) } as f64)
+                .take(sample_count)
+                .collect(),
+            3 => payload.chunks_exact(4).map(|b| word(b) as i32 as f64).take(sample_count).collect(),
+            4 => payload.chunks_exact(4).map(|b| f32::from_bits(word(b)) as f64).take(sample_count).collect(),
+            5 => payload
+                .chunks_exact(8)
+                .map(|b| {
+                    let (high, low) = if data_big_endian { (word(b), word(&b[4..])) } else { (word(&b[4..]), word(b)) };
+                    f64::from_bits(((high as u64) << 32) | low as u64)
+                })
+                .take(sample_count)
+                .collect(),
+            10 | 11 => decode_steim(payload, sample_count, encoding == 11)?
+                .into_iter()
+                .map(|x| x as f64)
+                .collect(),
+            _ => return Err(format!("Unsupported MiniSEED encoding {}.", encoding).into()),
+        };
+
+        log::trace!("{}: record at byte {} of {} with {} samples (encoding {})", file_path, offset, id, sample_count, encoding);
+        match channels.iter_mut().find(|c| c.id == id) {
+            Some(channel) => channel.data.extend(samples),
+            None => channels.push(Channel { id, sample_rate, data: samples }),
+        }
+        offset += record_length;
+    }
+
+    for channel in &channels {
+        log::debug!("{}: channel {} has {} samples at {} Hz", file_path, channel.id, channel.data.len(), channel.sample_rate);
+    }
+    Ok(channels)
+}
+
+/// Reads an EDF or BDF recording and returns the physical samples of each signal with its sample rate.
+///     `channels` selects signals by label, all signals except EDF+ annotations are read when it is `None`.
+pub fn read_edf(file_path: &str, channels: Option<Vec<String>>) -> Result<Vec<Channel>, Box<dyn Error>> {
+    let bytes = std::fs::read(file_path)?;
+    if bytes.len() < 256 {
+        return Err("File is too short to contain an EDF header.".into());
+    }
+
+    let field = |start: usize, len: usize| -> Result<String, Box<dyn Error>> {
+        let raw = bytes.get(start..start + len).ok_or("EDF header is truncated.")?;
+        Ok(String::from_utf8_lossy(raw).trim().to_string())
+    };
+    let number = |start: usize, len: usize| -> Result<f64, Box<dyn Error>> {
+        let text = field(start, len)?;
+        text.parse().map_err(|_| format!("Invalid number '{}' in EDF header.", text).into())
+    };
+
+    // BDF files start with 0xFF followed by "BIOSEMI" and store 24 bit samples
+    let sample_size = if bytes[0] == 0xff { 3 } else { 2 };
+    let header_bytes = number(184, 8)? as usize;
+    let record_count = number(236, 8)?;
+    let record_duration = number(244, 8)?;
+    let signal_count = number(252, 4)? as usize;
+
+    // Each per signal field is stored for all signals before the next field starts
+    let field_start = |offset: usize, width: usize, signal: usize| 256 + offset * signal_count + width * signal;
+    let mut signals = Vec::with_capacity(signal_count);
+    for i in 0..signal_count {
+        let label = field(field_start(0, 16, i), 16)?;
+        let physical_min = number(field_start(104, 8, i), 8)?;
+        let physical_max = number(field_start(112, 8, i), 8)?;
+        let digital_min = number(field_start(120, 8, i), 8)?;
+        let digital_max = number(field_start(128, 8, i), 8)?;
+        let samples_per_record = number(field_start(216, 8, i), 8)? as usize;
+        let gain = (physical_max - physical_min) / (digital_max - digital_min);
+        signals.push((label, samples_per_record, gain, physical_min - digital_min * gain));
+    }
+
+    let record_size: usize = signals.iter().map(|s| s.1 * sample_size).sum();
+    if record_size == 0 {
+        return Err("EDF file contains no samples.".into());
+    }
+    if !(record_duration.is_finite() && record_duration > 0.0) {
+        return Err(format!("EDF record duration must be greater than zero, got {}.", record_duration).into());
+    }
+    // A record count of -1 means it was not known when the header was written
+    let available = bytes.len().saturating_sub(header_bytes) / record_size;
+    let records = if record_count < 0.0 { available } else { (record_count as usize).min(available) };
+
+    let wanted = |label: &str| match &channels {
+        Some(names) => names.iter().any(|name| name == label),
+        None => label != "EDF Annotations",
+    };
+    if let Some(names) = &channels {
+        if let Some(missing) = names.iter().find(|name| !signals.iter().any(|s| &s.0 == *name)) {
+            return Err(format!("Channel '{}' not found in EDF file.", missing).into());
+        }
+    }
+
+    log::debug!("{}: {} signals in {} records of {} s", file_path, signal_count, records, record_duration);
+    let mut result = Vec::new();
+    let mut signal_offset = 0;
+    for (label, samples_per_record, gain, offset) in signals {
+        if wanted(&label) {
+            let mut data = Vec::with_capacity(records * samples_per_record);
+            for record in 0..records {
+                let start = header_bytes + record * record_size + signal_offset;
+                let raw = &bytes[start..start + samples_per_record * sample_size];
+                data.extend(raw.chunks_exact(sample_size).map(|b| {
+                    let digital = if sample_size == 3 {
+                        i32::from_le_bytes([0, b[0], b[1], b[2]]) >> 8
+                    } else {
+                        i16::from_le_bytes([b[0], b[1]]) as i32
+                    };
+                    digital as f64 * gain + offset
+                }));
+            }
+            result.push(Channel { id: label, sample_rate: samples_per_record as f64 / record_duration, data });
+        }
+        signal_offset += samples_per_record * sample_size;
+    }
+
+    Ok(result)
+}
+
+/// Converts the little endian bytes of one sample to its value.
+type SampleDecoder = fn(&[u8]) -> f64;
+
+/// Sample decoder for a WAV format code and bit depth, scaling integer samples to [-1, 1).
+fn wav_decoder(tag: u16, bits: usize) -> Result<SampleDecoder, Box<dyn Error>> {
+    Ok(match (tag, bits) {
+        (1, 8) => |b| (b[0] as f64 - 128.0) / 128.0,
+        (1, 16) => |b| i16::from_le_bytes([b[0], b[1]]) as f64 / 32_768.0,
+        (1, 24) => |b| (i32::from_le_bytes([0, b[0], b[1], b[2]]) >> 8) as f64 / 8_388_608.0,
+        (1, 32) => |b| i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64 / 2_147_483_648.0,
+        (3, 32) => |b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+        (3, 64) => |b| f64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]),
+        _ => return Err(format!("Unsupported WAV format {} with {} bits per sample.", tag, bits).into()),
+    })
+}
+
+/// Format of a WAV file and the location of its samples.
+struct WavHeader {
+    tag: u16,
+    channels: usize,
+    sample_rate: f64,
+    bits: usize,
+    /// Offset of the first sample in bytes.
+    data_start: u64,
+    /// Size of the data chunk in bytes, clamped to the file length.
+    data_size: u64,
+}
+
+/// Walks the chunks of a WAV file until both its fmt and data chunks are found.
+fn read_wav_header(file: &mut File) -> Result<WavHeader, Box<dyn Error>> {
+    let file_len = file.metadata()?.len();
+    let mut riff = [0u8; 12];
+    if file.read_exact(&mut riff).is_err() || &riff[0..4] != b"RIFF" || &riff[8..12] != b"WAVE" {
+        return Err("File is not a RIFF/WAVE file.".into());
+    }
+
+    let mut format = None;
+    let mut data = None;
+    let mut offset = 12u64;
+    while offset + 8 <= file_len {
+        let mut header = [0u8; 8];
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(&mut header)?;
+        let size = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as u64;
+        let body = offset + 8;
+        if &header[0..4] == b"fmt " && size >= 16 {
+            let mut fmt = vec![0u8; size.min(40) as usize];
+            file.read_exact(&mut fmt)?;
+            let u16_at = |at: usize| u16::from_le_bytes([fmt[at], fmt[at + 1]]);
+            // WAVE_FORMAT_EXTENSIBLE stores the actual format code at the start of the sub-format GUID
+            let tag = match u16_at(0) {
+                0xfffe if size >= 26 => u16_at(24),
+                tag => tag,
+            };
+            let sample_rate = u32::from_le_bytes([fmt[4], fmt[5], fmt[6], fmt[7]]) as f64;
+            format = Some((tag, u16_at(2) as usize, sample_rate, u16_at(14) as usize));
+        } else if &header[0..4] == b"data" {
+            data = Some((body, size.min(file_len - body)));
+        }
+        if let (Some((tag, channels, sample_rate, bits)), Some((data_start, data_size))) = (format, data) {
+            if channels == 0 {
+                return Err("WAV file has no channels.".into());
+            }
+            return Ok(WavHeader { tag, channels, sample_rate, bits, data_start, data_size });
+        }
+        // Chunks are padded to an even number of bytes
+        offset = body + size + size % 2;
+    }
+    Err(if format.is_none() { "WAV file has no fmt chunk." } else { "WAV file has no data chunk." }.into())
+}
+
+/// Reads a WAV file and returns the sample rate in Hz and the samples of each channel.
+///     PCM (8/16/24/32 bit) and IEEE float (32/64 bit) data is supported, integer samples are scaled to [-1, 1).
+pub fn read_wav(file_path: &str) -> Result<(f64, Matrix), Box<dyn Error>> {
+    let stream = open_wav_stream(file_path, usize::MAX)?;
+    let (sample_rate, channel_count, frames) = (stream.sample_rate(), stream.channels(), stream.frames());
+    let mut channels = vec![Vec::with_capacity(frames); channel_count];
+    for chunk in stream {
+        for (channel, samples) in channels.iter_mut().zip(chunk?) {
+            channel.extend(samples);
+        }
+    }
+
+    log::debug!("{}: {} channels of {} frames at {} Hz", file_path, channel_count, frames, sample_rate);
+    Ok((sample_rate, channels))
+}
+
+/// Chunks of a WAV file read one after another, see [`open_wav_stream`].
+pub struct WavStream {
+    reader: BufReader<File>,
+    sample_rate: f64,
+    channels: usize,
+    frames: usize,
+    remaining: usize,
+    chunk_frames: usize,
+    sample_size: usize,
+    decode: SampleDecoder,
+}
+
+impl WavStream {
+    /// Sample rate in Hz.
+    pub fn sample_rate(&self) -> f64 {
+        self.sample_rate
+    }
+
+    /// Number of channels.
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    /// Total number of frames, samples per channel, in the file.
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+}
+
+impl Iterator for WavStream {
+    type Item = Result<Matrix, Box<dyn Error>>;
+
+    /// Decodes the next chunk, one vector per channel, `None` once the data chunk is exhausted.
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let frames = self.remaining.min(self.chunk_frames);
+        let mut raw = vec![0u8; frames * self.channels * self.sample_size];
+        if let Err(e) = self.reader.read_exact(&mut raw) {
+            self.remaining = 0;
+            return Some(Err(e.into()));
+        }
+        self.remaining -= frames;
+
+        let mut channels = vec![Vec::with_capacity(frames); self.channels];
+        for frame in raw.chunks_exact(self.sample_size * self.channels) {
+            for (channel, sample) in channels.iter_mut().zip(frame.chunks_exact(self.sample_size)) {
+                channel.push((self.decode)(sample));
+            }
+        }
+        Some(Ok(channels))
+    }
+}
+
+/// Opens a WAV file for reading in chunks of `chunk_frames` samples per channel, the last one possibly shorter.
+///     Only the header is read up front, so files far larger than memory can be processed chunk by chunk, for
+///     example through a [`BiquadCascade`](crate::BiquadCascade) or [`Decimator`](crate::Decimator) whose state
+///     carries over between chunks. Supports the same formats as [`read_wav`].
+pub fn open_wav_stream(file_path: &str, chunk_frames: usize) -> Result<WavStream, Box<dyn Error>> {
+    if chunk_frames == 0 {
+        return Err("chunk_frames must be positive.".into());
+    }
+    let mut file = File::open(file_path)?;
+    let WavHeader { tag, channels, sample_rate, bits, data_start, data_size } = read_wav_header(&mut file)?;
+    let decode = wav_decoder(tag, bits)?;
+    let sample_size = bits / 8;
+    let frames = (data_size / (sample_size * channels) as u64) as usize;
+    file.seek(SeekFrom::Start(data_start))?;
+
+    log::debug!(
+        "{}: streaming {} channels of {} frames at {} Hz (format {}, {} bits) in chunks of {}",
+        file_path, channels, frames, sample_rate, tag, bits, chunk_frames
+    );
+    Ok(WavStream {
+        reader: BufReader::new(file),
+        sample_rate,
+        channels,
+        frames,
+        remaining: frames,
+        chunk_frames,
+        sample_size,
+        decode,
+    })
+}
+
+/// Reads a WAV or FLAC file, recognised by its content rather than its extension, and returns the sample rate in
+///     Hz and the samples of each channel scaled to [-1, 1). MP3 and Ogg (Vorbis or Opus) files are decoded with
+///     the `lossy` feature through libsndfile, see [`lossy`](crate::lossy), and are otherwise rejected.
+pub fn read_audio(file_path: &str) -> Result<(f64, Matrix), Box<dyn Error>> {
+    let mut file = File::open(file_path)?;
+    let mut magic = [0u8; 12];
+    let read = file.read(&mut magic)?;
+    let mut magic = &magic[..read];
+    if magic.starts_with(b"ID3") && magic.len() >= 10 {
+        // Skip the ID3v2 tag to find out whether FLAC or MP3 data follows
+        let size = magic[6..10].iter().fold(0u64, |size, &b| (size << 7) | (b & 0x7f) as u64);
+        let mut after = [0u8; 4];
+        file.seek(SeekFrom::Start(10 + size))?;
+        magic = if file.read_exact(&mut after).is_ok() && &after == b"fLaC" { b"fLaC" } else { b"ID3" };
+    }
+
+    match magic {
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'A', b'V', b'E', ..] => read_wav(file_path),
+        [b'f', b'L', b'a', b'C', ..] => read_flac(file_path),
+        #[cfg(feature = "lossy")]
+        [b'O', b'g', b'g', b'S', ..] | [b'I', b'D', b'3', ..] | [0xff, 0xe0..=0xff, ..] => crate::lossy::read_lossy(file_path),
+        #[cfg(not(feature = "lossy"))]
+        [b'O', b'g', b'g', b'S', ..] => Err("Ogg files need the lossy feature, or convert them to WAV or FLAC.".into()),
+        #[cfg(not(feature = "lossy"))]
+        [b'I', b'D', b'3', ..] | [0xff, 0xe0..=0xff, ..] => Err("MP3 files need the lossy feature, or convert them to WAV or FLAC.".into()),
+        _ => Err("File is neither a WAV nor a FLAC file.".into()),
+    }
+}
@@ -0,0 +1,263 @@
+//! Element-wise kernels used to post-process spectra.
+//!
+//! On x86_64 the kernels use AVX when the CPU supports it, detected at runtime since the baseline target only
+//! guarantees SSE2, and finish the last few values with the scalar code. AVX has no `log10` or `atan2`, so the dB
+//! and phase kernels evaluate the fdlibm logarithm and Cephes arctangent approximations on four lanes at a time,
+//! which stay within two ulp of the scalar functions. Zero, negative, subnormal and non-finite inputs take the
+//! scalar path. Other targets use the scalar loops.
+
+/// Computes `sqrt(re² + im²)` for each pair of values.
+pub fn magnitude(real: &[f64], imag: &[f64]) -> Vec<f64> {
+    let len = real.len().min(imag.len());
+    let mut out = vec![0.0; len];
+
+    #[cfg(target_arch = "x86_64")]
+    if is_x86_feature_detected!("avx") {
+        // SAFETY: AVX support was checked above
+        unsafe { avx::magnitude(&real[..len], &imag[..len], &mut out) };
+        return out;
+    }
+
+    for ((o, re), im) in out.iter_mut().zip(real).zip(imag) {
+        *o = (re * re + im * im).sqrt();
+    }
+    out
+}
+
+/// Computes the phase angle `atan2(im, re)` in radians for each pair of values.
+pub fn phase(real: &[f64], imag: &[f64]) -> Vec<f64> {
+    #[cfg(target_arch = "x86_64")]
+    if is_x86_feature_detected!("avx") {
+        let len = real.len().min(imag.len());
+        let mut out = vec![0.0; len];
+        // SAFETY: AVX support was checked above
+        unsafe { avx::phase(&real[..len], &imag[..len], &mut out) };
+        return out;
+    }
+
+    real.iter().zip(imag).map(|(re, im)| im.atan2(*re)).collect()
+}
+
+/// Multiplies the data by the window in place.
+pub fn apply_window(data: &mut [f64], window: &[f64]) {
+    let len = data.len().min(window.len());
+
+    #[cfg(target_arch = "x86_64")]
+    if is_x86_feature_detected!("avx") {
+        // SAFETY: AVX support was checked above
+        unsafe { avx::multiply(&mut data[..len], &window[..len]) };
+        return;
+    }
+
+    for (x, w) in data.iter_mut().zip(window) {
+        *x *= w;
+    }
+}
+
+/// Converts values to decibels as `factor * log10(value / reference)`.
+///     Use a factor of 20 for amplitudes and 10 for powers, zero values map to negative infinity.
+pub fn to_db(values: &[f64], reference: f64, factor: f64) -> Vec<f64> {
+    let scale = 1.0 / reference;
+
+    #[cfg(target_arch = "x86_64")]
+    if is_x86_feature_detected!("avx") {
+        let mut out = vec![0.0; values.len()];
+        // SAFETY: AVX support was checked above
+        unsafe { avx::to_db(values, scale, factor, &mut out) };
+        return out;
+    }
+
+    values.iter().map(|v| factor * (v * scale).log10()).collect()
+}
+
+#[cfg(target_arch = "x86_64")]
+mod avx {
+    use std::arch::x86_64::*;
+
+    #[target_feature(enable = "avx")]
+    pub(super) unsafe fn magnitude(real: &[f64], imag: &[f64], out: &mut [f64]) {
+        let body = out.len() / 4 * 4;
+        for i in (0..body).step_by(4) {
+            let re = _mm256_loadu_pd(real.as_ptr().add(i));
+            let im = _mm256_loadu_pd(imag.as_ptr().add(i));
+            let sum = _mm256_add_pd(_mm256_mul_pd(re, re), _mm256_mul_pd(im, im));
+            _mm256_storeu_pd(out.as_mut_ptr().add(i), _mm256_sqrt_pd(sum));
+        }
+        for i in body..out.len() {
+            out[i] = (real[i] * real[i] + imag[i] * imag[i]).sqrt();
+        }
+    }
+
+    #[target_feature(enable = "avx")]
+    pub(super) unsafe fn multiply(data: &mut [f64], window: &[f64]) {
+        let body = data.len() / 4 * 4;
+        for i in (0..body).step_by(4) {
+            let x = _mm256_loadu_pd(data.as_ptr().add(i));
+            let w = _mm256_loadu_pd(window.as_ptr().add(i));
+            _mm256_storeu_pd(data.as_mut_ptr().add(i), _mm256_mul_pd(x, w));
+        }
+        for i in body..data.len() {
+            data[i] *= window[i];
+        }
+    }
+
+    #[target_feature(enable = "avx")]
+    pub(super) unsafe fn to_db(values: &[f64], scale: f64, factor: f64, out: &mut [f64]) {
+        let body = out.len() / 4 * 4;
+        for i in (0..body).step_by(4) {
+            let x = _mm256_mul_pd(_mm256_loadu_pd(values.as_ptr().add(i)), _mm256_set1_pd(scale));
+            // Lanes outside the positive normal range need the special cases of the scalar log10
+            let normal = _mm256_and_pd(
+                _mm256_cmp_pd::<_CMP_GE_OQ>(x, _mm256_set1_pd(f64::MIN_POSITIVE)),
+                _mm256_cmp_pd::<_CMP_LE_OQ>(x, _mm256_set1_pd(f64::MAX)),
+            );
+            if _mm256_movemask_pd(normal) != 0b1111 {
+                for j in i..i + 4 {
+                    out[j] = factor * (values[j] * scale).log10();
+                }
+                continue;
+            }
+            let db = _mm256_mul_pd(_mm256_mul_pd(ln(x), _mm256_set1_pd(std::f64::consts::LOG10_E)), _mm256_set1_pd(factor));
+            _mm256_storeu_pd(out.as_mut_ptr().add(i), db);
+        }
+        for i in body..out.len() {
+            out[i] = factor * (values[i] * scale).log10();
+        }
+    }
+
+    #[target_feature(enable = "avx")]
+    pub(super) unsafe fn phase(real: &[f64], imag: &[f64], out: &mut [f64]) {
+        let body = out.len() / 4 * 4;
+        for i in (0..body).step_by(4) {
+            let x = _mm256_loadu_pd(real.as_ptr().add(i));
+            let y = _mm256_loadu_pd(imag.as_ptr().add(i));
+            match atan2(y, x) {
+                Some(angle) => _mm256_storeu_pd(out.as_mut_ptr().add(i), angle),
+                None => {
+                    for j in i..i + 4 {
+                        out[j] = imag[j].atan2(real[j]);
+                    }
+                }
+            }
+        }
+        for i in body..out.len() {
+            out[i] = imag[i].atan2(real[i]);
+        }
+    }
+
+    /// Natural logarithm of positive normal values, the reduction and polynomial of the fdlibm `log`.
+    #[target_feature(enable = "avx")]
+    unsafe fn ln(x: __m256d) -> __m256d {
+        const LG: [f64; 7] = [
+            0.6666666666666735,
+            0.3999999999940942,
+            0.2857142874366239,
+            0.22222198432149784,
+            0.1818357216161805,
+            0.15313837699209373,
+            0.14798198605116586,
+        ];
+        const LN2_HI: f64 = 0.6931471803691238;
+        const LN2_LO: f64 = 1.9082149292705877e-10;
+
+        // The exponent from the high words of the four lanes. AVX has no 256-bit integer shifts, so it goes
+        //     through SSE2 on the packed 32-bit words
+        let bits = _mm256_castpd_ps(x);
+        let high = _mm256_shuffle_ps::<0b11_01_11_01>(bits, bits);
+        let high = _mm_movelh_ps(_mm256_castps256_ps128(high), _mm256_extractf128_ps::<1>(high));
+        let exponent = _mm_sub_epi32(_mm_srli_epi32::<20>(_mm_castps_si128(high)), _mm_set1_epi32(1023));
+        let mut e = _mm256_cvtepi32_pd(exponent);
+
+        // The mantissa in [1, 2), moved to [sqrt(2)/2, sqrt(2)) so that f = m - 1 stays small
+        let mantissa_mask = _mm256_castsi256_pd(_mm256_set1_epi64x(0x000f_ffff_ffff_ffff));
+        let mut m = _mm256_or_pd(_mm256_and_pd(x, mantissa_mask), _mm256_set1_pd(1.0));
+        let large = _mm256_cmp_pd::<_CMP_GT_OQ>(m, _mm256_set1_pd(std::f64::consts::SQRT_2));
+        m = _mm256_blendv_pd(m, _mm256_mul_pd(m, _mm256_set1_pd(0.5)), large);
+        e = _mm256_blendv_pd(e, _mm256_add_pd(e, _mm256_set1_pd(1.0)), large);
+
+        let f = _mm256_sub_pd(m, _mm256_set1_pd(1.0));
+        let s = _mm256_div_pd(f, _mm256_add_pd(_mm256_set1_pd(2.0), f));
+        let z = _mm256_mul_pd(s, s);
+        let w = _mm256_mul_pd(z, z);
+        let c = |k: usize| _mm256_set1_pd(LG[k]);
+        let t1 = _mm256_mul_pd(w, _mm256_add_pd(c(1), _mm256_mul_pd(w, _mm256_add_pd(c(3), _mm256_mul_pd(w, c(5))))));
+        let t2 = _mm256_mul_pd(
+            z,
+            _mm256_add_pd(
+                c(0),
+                _mm256_mul_pd(w, _mm256_add_pd(c(2), _mm256_mul_pd(w, _mm256_add_pd(c(4), _mm256_mul_pd(w, c(6)))))),
+            ),
+        );
+        let r = _mm256_add_pd(t2, t1);
+        let hfsq = _mm256_mul_pd(_mm256_set1_pd(0.5), _mm256_mul_pd(f, f));
+
+        // e * ln2_hi - ((hfsq - (s * (hfsq + r) + e * ln2_lo)) - f)
+        let low = _mm256_add_pd(_mm256_mul_pd(s, _mm256_add_pd(hfsq, r)), _mm256_mul_pd(e, _mm256_set1_pd(LN2_LO)));
+        let tail = _mm256_sub_pd(_mm256_sub_pd(hfsq, low), f);
+        _mm256_sub_pd(_mm256_mul_pd(e, _mm256_set1_pd(LN2_HI)), tail)
+    }
+
+    /// `atan2(y, x)` from the Cephes `atan` on `min(|x|, |y|) / max(|x|, |y|)`, extended to the full circle by
+    ///     symmetry. Returns `None` when a lane has both inputs zero or one of them non-finite, which the caller
+    ///     hands to the scalar function for its special cases.
+    #[target_feature(enable = "avx")]
+    unsafe fn atan2(y: __m256d, x: __m256d) -> Option<__m256d> {
+        const P: [f64; 5] = [
+            -0.8750608600031904,
+            -16.157537187333652,
+            -75.00855792314705,
+            -122.88666844901361,
+            -64.85021904942025,
+        ];
+        const Q: [f64; 5] = [
+            24.858464901423062,
+            165.02700983169885,
+            432.88106049129027,
+            485.3903996359137,
+            194.5506571482614,
+        ];
+        // The part of pi / 2 below the precision of FRAC_PI_2
+        const MOREBITS: f64 = 6.123233995736766e-17;
+
+        let sign = _mm256_set1_pd(-0.0);
+        let ax = _mm256_andnot_pd(sign, x);
+        let ay = _mm256_andnot_pd(sign, y);
+        let num = _mm256_min_pd(ax, ay);
+        let den = _mm256_max_pd(ax, ay);
+        let finite = _mm256_and_pd(
+            _mm256_cmp_pd::<_CMP_LE_OQ>(den, _mm256_set1_pd(f64::MAX)),
+            _mm256_cmp_pd::<_CMP_GT_OQ>(den, _mm256_setzero_pd()),
+        );
+        if _mm256_movemask_pd(finite) != 0b1111 {
+            return None;
+        }
+
+        // atan(t) for t in [0, 1], with t > 0.66 reduced to pi / 4 + atan((t - 1) / (t + 1))
+        let one = _mm256_set1_pd(1.0);
+        let t = _mm256_div_pd(num, den);
+        let reduced = _mm256_cmp_pd::<_CMP_GT_OQ>(t, _mm256_set1_pd(0.66));
+        let u = _mm256_blendv_pd(t, _mm256_div_pd(_mm256_sub_pd(t, one), _mm256_add_pd(t, one)), reduced);
+        let z = _mm256_mul_pd(u, u);
+        let mut p = _mm256_set1_pd(P[0]);
+        for &c in &P[1..] {
+            p = _mm256_add_pd(_mm256_mul_pd(p, z), _mm256_set1_pd(c));
+        }
+        let mut q = _mm256_add_pd(z, _mm256_set1_pd(Q[0]));
+        for &c in &Q[1..] {
+            q = _mm256_add_pd(_mm256_mul_pd(q, z), _mm256_set1_pd(c));
+        }
+        let mut angle = _mm256_add_pd(_mm256_mul_pd(u, _mm256_div_pd(_mm256_mul_pd(z, p), q)), u);
+        let offset = _mm256_and_pd(reduced, _mm256_set1_pd(std::f64::consts::FRAC_PI_4));
+        let extra = _mm256_and_pd(reduced, _mm256_set1_pd(0.5 * MOREBITS));
+        angle = _mm256_add_pd(offset, _mm256_add_pd(angle, extra));
+
+        // Back to the octant and quadrant of (x, y)
+        let steep = _mm256_cmp_pd::<_CMP_GT_OQ>(ay, ax);
+        let complement = _mm256_add_pd(_mm256_sub_pd(_mm256_set1_pd(std::f64::consts::FRAC_PI_2), angle), _mm256_set1_pd(MOREBITS));
+        angle = _mm256_blendv_pd(angle, complement, steep);
+        let left = _mm256_cmp_pd::<_CMP_LT_OQ>(x, _mm256_setzero_pd());
+        let supplement = _mm256_add_pd(_mm256_sub_pd(_mm256_set1_pd(std::f64::consts::PI), angle), _mm256_set1_pd(2.0 * MOREBITS));
+        angle = _mm256_blendv_pd(angle, supplement, left);
+        Some(_mm256_or_pd(angle, _mm256_and_pd(sign, y)))
+    }
+}
@@ -0,0 +1,223 @@
+//! Small dense complex linear algebra used by the array processing routines.
+
+use rustfft::num_complex::Complex;
+
+use crate::ComplexMatrix;
+
+/// Returns the `n` by `n` identity matrix.
+pub fn identity(n: usize) -> ComplexMatrix {
+    (0..n)
+        .map(|i| (0..n).map(|j| if i == j { Complex::new(1.0, 0.0) } else { Complex::new(0.0, 0.0) }).collect())
+        .collect()
+}
+
+/// Inverts a square complex matrix with Gauss-Jordan elimination and partial pivoting.
+///     Returns `None` when the matrix is singular to working precision.
+pub fn invert(matrix: &ComplexMatrix) -> Option<ComplexMatrix> {
+    let n = matrix.len();
+    let mut a = matrix.clone();
+    let mut inverse = identity(n);
+
+    let scale = matrix.iter().flatten().map(|c| c.norm()).fold(0.0, f64::max);
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&i, &j| a[i][col].norm().total_cmp(&a[j][col].norm()))?;
+        if a[pivot][col].norm() <= scale * 1e-14 {
+            return None;
+        }
+        a.swap(col, pivot);
+        inverse.swap(col, pivot);
+
+        let factor = a[col][col].inv();
+        a[col].iter_mut().for_each(|x| *x *= factor);
+        inverse[col].iter_mut().for_each(|x| *x *= factor);
+
+        for row in 0..n {
+            if row == col || a[row][col].norm() == 0.0 {
+                continue;
+            }
+            let factor = a[row][col];
+            for k in 0..n {
+                let (pivot_a, pivot_inverse) = (a[col][k], inverse[col][k]);
+                a[row][k] -= factor * pivot_a;
+                inverse[row][k] -= factor * pivot_inverse;
+            }
+        }
+    }
+
+    Some(inverse)
+}
+
+/// Computes the eigendecomposition of a Hermitian matrix with cyclic Jacobi rotations.
+///     Returns the real eigenvalues in descending order and the matching unit eigenvectors as matrix columns.
+pub fn hermitian_eigen(matrix: &ComplexMatrix) -> (Vec<f64>, ComplexMatrix) {
+    let n = matrix.len();
+    let mut a = matrix.clone();
+    let mut v = identity(n);
+
+    let total: f64 = a.iter().flatten().map(|c| c.norm_sqr()).sum();
+    for _ in 0..100 {
+        let diagonal: f64 = (0..n).map(|i| a[i][i].norm_sqr()).sum();
+        let off = a.iter().flatten().map(|c| c.norm_sqr()).sum::<f64>() - diagonal;
+        if off <= total * 1e-30 {
+            break;
+        }
+        for p in 0..n {
+            for q in p + 1..n {
+                let norm = a[p][q].norm();
+                if norm <= f64::MIN_POSITIVE {
+                    continue;
+                }
+                // Rotate the phase of index q so that a[p][q] becomes real, then apply a real Jacobi rotation
+                let phase = a[p][q] / norm;
+                for k in 0..n {
+                    a[k][q] *= phase.conj();
+                    v[k][q] *= phase.conj();
+                }
+                a[q].iter_mut().for_each(|x| *x *= phase);
+
+                let theta = (a[q][q].re - a[p][p].re) / (2.0 * norm);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+                for k in 0..n {
+                    let (kp, kq) = (a[k][p], a[k][q]);
+                    a[k][p] = kp * c - kq * s;
+                    a[k][q] = kp * s + kq * c;
+                    let (kp, kq) = (v[k][p], v[k][q]);
+                    v[k][p] = kp * c - kq * s;
+                    v[k][q] = kp * s + kq * c;
+                }
+                let (upper, lower) = a.split_at_mut(q);
+                for (pk, qk) in upper[p].iter_mut().zip(lower[0].iter_mut()) {
+                    (*pk, *qk) = (*pk * c - *qk * s, *pk * s + *qk * c);
+                }
+            }
+        }
+    }
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&i, &j| a[j][j].re.total_cmp(&a[i][i].re));
+    let values = order.iter().map(|&i| a[i][i].re).collect();
+    let vectors = v.iter().map(|row| order.iter().map(|&i| row[i]).collect()).collect();
+    (values, vectors)
+}
+
+/// Computes `a^H M b` for vectors `a`, `b` and a square matrix `M`.
+pub fn quadratic_form(a: &[Complex<f64>], matrix: &ComplexMatrix, b: &[Complex<f64>]) -> Complex<f64> {
+    a.iter()
+        .zip(matrix)
+        .map(|(ai, row)| ai.conj() * row.iter().zip(b).map(|(m, bj)| m * bj).sum::<Complex<f64>>())
+        .sum()
+}
+
+/// Multiplies two complex matrices.
+pub fn matmul(a: &ComplexMatrix, b: &ComplexMatrix) -> ComplexMatrix {
+    let columns = b.first().map_or(0, Vec::len);
+    a.iter()
+        .map(|row| (0..columns).map(|j| row.iter().zip(b).map(|(x, b_row)| x * b_row[j]).sum()).collect())
+        .collect()
+}
+
+/// Returns the conjugate transpose of a rectangular complex matrix.
+pub fn conjugate_transpose(a: &ComplexMatrix) -> ComplexMatrix {
+    let columns = a.first().map_or(0, Vec::len);
+    (0..columns).map(|j| a.iter().map(|row| row[j].conj()).collect()).collect()
+}
+
+/// Solves the least squares problem `min |A x - b|` through the normal equations.
+///     Returns `None` when `A` does not have full column rank.
+pub fn least_squares(a: &ComplexMatrix, b: &[Complex<f64>]) -> Option<Vec<Complex<f64>>> {
+    let adjoint = conjugate_transpose(a);
+    let gram = invert(&matmul(&adjoint, a))?;
+    let projected: Vec<Complex<f64>> = adjoint.iter().map(|row| row.iter().zip(b).map(|(x, y)| x * y).sum()).collect();
+    Some(gram.iter().map(|row| row.iter().zip(&projected).map(|(x, y)| x * y).sum()).collect())
+}
+
+/// Computes the eigenvalues of a general square complex matrix.
+///     The matrix is reduced to Hessenberg form with Householder reflections, then deflated from the bottom
+///     with Wilkinson shifted QR steps built from Givens rotations.
+pub fn eigenvalues(matrix: &ComplexMatrix) -> Vec<Complex<f64>> {
+    let n = matrix.len();
+    let mut a = matrix.clone();
+    let zero = Complex::new(0.0, 0.0);
+
+    for k in 0..n.saturating_sub(2) {
+        let norm = (k + 1..n).map(|i| a[i][k].norm_sqr()).sum::<f64>().sqrt();
+        if norm == 0.0 {
+            continue;
+        }
+        let head = a[k + 1][k];
+        let alpha = -if head.norm() > 0.0 { head / head.norm() } else { Complex::new(1.0, 0.0) } * norm;
+        let mut v: Vec<Complex<f64>> = (k + 1..n).map(|i| a[i][k]).collect();
+        v[0] -= alpha;
+        let v_norm = v.iter().map(|x| x.norm_sqr()).sum::<f64>().sqrt();
+        if v_norm == 0.0 {
+            continue;
+        }
+        v.iter_mut().for_each(|x| *x /= v_norm);
+
+        let mut s = vec![zero; n];
+        for (vi, row) in v.iter().zip(&a[k + 1..]) {
+            s.iter_mut().zip(row).for_each(|(sj, x)| *sj += vi.conj() * x);
+        }
+        for (vi, row) in v.iter().zip(a[k + 1..].iter_mut()) {
+            row.iter_mut().zip(&s).for_each(|(x, sj)| *x -= 2.0 * vi * sj);
+        }
+        for row in a.iter_mut() {
+            let s: Complex<f64> = v.iter().enumerate().map(|(j, vj)| row[k + 1 + j] * vj).sum();
+            v.iter().enumerate().for_each(|(j, vj)| row[k + 1 + j] -= 2.0 * s * vj.conj());
+        }
+    }
+
+    let mut values = Vec::with_capacity(n);
+    let mut hi = n;
+    let mut iterations = 0;
+    while hi > 0 {
+        let last = hi - 1;
+        if last == 0 {
+            values.push(a[0][0]);
+            break;
+        }
+        let scale = a[last][last].norm() + a[last - 1][last - 1].norm();
+        if a[last][last - 1].norm() <= f64::EPSILON * scale.max(f64::MIN_POSITIVE) || iterations >= 500 {
+            values.push(a[last][last]);
+            hi -= 1;
+            iterations = 0;
+            continue;
+        }
+        iterations += 1;
+
+        // Wilkinson shift: the eigenvalue of the trailing 2x2 block closest to the last diagonal entry
+        let (p, q, r, s) = (a[last - 1][last - 1], a[last - 1][last], a[last][last - 1], a[last][last]);
+        let half_trace = (p + s) / 2.0;
+        let root = ((p - s) * (p - s) / 4.0 + q * r).sqrt();
+        let mut shift = if (half_trace + root - s).norm() < (half_trace - root - s).norm() { half_trace + root } else { half_trace - root };
+        if iterations % 50 == 0 {
+            // Exceptional shift to break cycles
+            shift += a[last][last - 1].norm();
+        }
+
+        (0..hi).for_each(|i| a[i][i] -= shift);
+        let mut rotations = Vec::with_capacity(last);
+        for k in 0..last {
+            let (x, y) = (a[k][k], a[k + 1][k]);
+            let radius = (x.norm_sqr() + y.norm_sqr()).sqrt();
+            let (c, s) = if radius == 0.0 { (Complex::new(1.0, 0.0), zero) } else { (x / radius, y / radius) };
+            let (upper, lower) = a.split_at_mut(k + 1);
+            for (top, bottom) in upper[k][k..hi].iter_mut().zip(&mut lower[0][k..hi]) {
+                (*top, *bottom) = (c.conj() * *top + s.conj() * *bottom, -s * *top + c * *bottom);
+            }
+            rotations.push((c, s));
+        }
+        for (k, (c, s)) in rotations.into_iter().enumerate() {
+            for row in a.iter_mut().take(hi) {
+                let (left, right) = (row[k], row[k + 1]);
+                row[k] = left * c + right * s;
+                row[k + 1] = -left * s.conj() + right * c.conj();
+            }
+        }
+        (0..hi).for_each(|i| a[i][i] += shift);
+    }
+
+    values
+}
@@ -0,0 +1,130 @@
+//! MP3 and Ogg (Vorbis and Opus) decoding through libsndfile, built with the `lossy` feature.
+//!
+//! libsndfile is loaded when the first lossy file is read instead of being linked, so building needs no libsndfile
+//! development files and a machine without it gets an error for lossy files only. MP3 needs libsndfile 1.1 or later,
+//! older versions report the file as unrecognised. Samples are read as doubles, which libsndfile scales to [-1, 1).
+
+use libloading::Library;
+use std::error::Error;
+use std::ffi::{c_char, c_int, c_void, CStr, CString};
+use std::sync::OnceLock;
+
+use crate::{deinterleave, Matrix};
+
+const SFM_READ: c_int = 0x10;
+
+/// Frames decoded per call.
+const CHUNK_FRAMES: usize = 1 << 14;
+
+#[cfg(target_os = "windows")]
+const LIBRARY: &str = "sndfile.dll";
+#[cfg(target_os = "macos")]
+const LIBRARY: &str = "libsndfile.1.dylib";
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const LIBRARY: &str = "libsndfile.so.1";
+
+type SndFileHandle = *mut c_void;
+
+/// The `SF_INFO` struct of sndfile.h.
+#[repr(C)]
+#[derive(Default)]
+struct SfInfo {
+    frames: i64,
+    samplerate: c_int,
+    channels: c_int,
+    format: c_int,
+    sections: c_int,
+    seekable: c_int,
+}
+
+/// The libsndfile functions used, resolved once per process.
+struct SndFile {
+    open: unsafe extern "C" fn(*const c_char, c_int, *mut SfInfo) -> SndFileHandle,
+    readf_double: unsafe extern "C" fn(SndFileHandle, *mut f64, i64) -> i64,
+    close: unsafe extern "C" fn(SndFileHandle) -> c_int,
+    strerror: unsafe extern "C" fn(SndFileHandle) -> *const c_char,
+    // Keeps the function pointers above valid
+    _library: Library,
+}
+
+impl SndFile {
+    fn load() -> Result<SndFile, String> {
+        // SAFETY: libsndfile runs no initialisation code with requirements on the caller, and every symbol is
+        //     given the signature of its declaration in sndfile.h
+        unsafe {
+            let library = Library::new(LIBRARY).map_err(|e| format!("libsndfile is not available: {}", e))?;
+            macro_rules! symbol {
+                ($name:literal) => {
+                    *library.get($name).map_err(|e| format!("libsndfile is not available: {}", e))?
+                };
+            }
+            Ok(SndFile {
+                open: symbol!(b"sf_open\0"),
+                readf_double: symbol!(b"sf_readf_double\0"),
+                close: symbol!(b"sf_close\0"),
+                strerror: symbol!(b"sf_strerror\0"),
+                _library: library,
+            })
+        }
+    }
+
+    fn get() -> Result<&'static SndFile, Box<dyn Error>> {
+        static SNDFILE: OnceLock<Result<SndFile, String>> = OnceLock::new();
+        SNDFILE.get_or_init(SndFile::load).as_ref().map_err(|e| e.clone().into())
+    }
+
+    /// The error of the open file, or of the last failed `sf_open` when `handle` is null.
+    fn describe(&self, handle: SndFileHandle) -> String {
+        // SAFETY: sf_strerror returns a string owned by libsndfile that stays valid until the next call
+        unsafe { CStr::from_ptr((self.strerror)(handle)) }.to_string_lossy().into_owned()
+    }
+}
+
+/// An open sound file, closed when dropped.
+struct Decoder {
+    sndfile: &'static SndFile,
+    handle: SndFileHandle,
+}
+
+impl Drop for Decoder {
+    fn drop(&mut self) {
+        // SAFETY: the handle came from sf_open and is not used again
+        unsafe { (self.sndfile.close)(self.handle) };
+    }
+}
+
+/// Decodes a file libsndfile can read, in practice the MP3 and Ogg files [`read_audio`](crate::read_audio) hands
+///     over, and returns the sample rate in Hz and the samples of each channel.
+pub(crate) fn read_lossy(file_path: &str) -> Result<(f64, Matrix), Box<dyn Error>> {
+    let sndfile = SndFile::get()?;
+    let path = CString::new(file_path)?;
+    let mut info = SfInfo::default();
+    // SAFETY: `path` and `info` outlive the call
+    let handle = unsafe { (sndfile.open)(path.as_ptr(), SFM_READ, &mut info) };
+    if handle.is_null() {
+        return Err(format!("Cannot decode {}: {}", file_path, sndfile.describe(handle)).into());
+    }
+    let decoder = Decoder { sndfile, handle };
+    if info.channels < 1 || info.samplerate < 1 {
+        return Err(format!("{} has {} channels at {} Hz.", file_path, info.channels, info.samplerate).into());
+    }
+
+    // The frame count of compressed streams is an estimate, so read until the decoder runs dry
+    let channels = info.channels as usize;
+    let mut interleaved = Vec::with_capacity((info.frames.max(0) as usize).min(1 << 26) * channels);
+    let mut chunk = vec![0.0; CHUNK_FRAMES * channels];
+    loop {
+        // SAFETY: `chunk` holds CHUNK_FRAMES whole frames
+        let frames = unsafe { (sndfile.readf_double)(decoder.handle, chunk.as_mut_ptr(), CHUNK_FRAMES as i64) };
+        if frames < 0 {
+            return Err(format!("Cannot decode {}: {}", file_path, sndfile.describe(decoder.handle)).into());
+        }
+        if frames == 0 {
+            break;
+        }
+        interleaved.extend_from_slice(&chunk[..frames as usize * channels]);
+    }
+    log::debug!("{}: decoded {} frames of {} channels at {} Hz", file_path, interleaved.len() / channels, channels, info.samplerate);
+
+    Ok((info.samplerate as f64, deinterleave(&interleaved, channels)?))
+}
@@ -0,0 +1,201 @@
+//! Streaming sample rate conversion.
+
+use std::error::Error;
+use std::f64::consts::PI;
+
+use crate::window::Window;
+
+/// Designs a Blackman windowed-sinc low-pass filter with `taps` coefficients, cutting at `cutoff` cycles per
+///     sample and scaled to a DC gain of `gain`.
+fn lowpass_taps(taps: usize, cutoff: f64, gain: f64) -> Vec<f64> {
+    let center = (taps - 1) as f64 / 2.0;
+    // The periodic window one sample longer, without its zero first point, is symmetric
+    let window = Window::Blackman.coefficients(taps + 1).into_iter().skip(1);
+    let sinc: Vec<f64> = (0..taps)
+        .zip(window)
+        .map(|(n, w)| {
+            let x = n as f64 - center;
+            let sinc = if x == 0.0 { 2.0 * cutoff } else { (2.0 * PI * cutoff * x).sin() / (PI * x) };
+            sinc * w
+        })
+        .collect();
+    let sum: f64 = sinc.iter().sum();
+    sinc.into_iter().map(|h| h * gain / sum).collect()
+}
+
+/// Low-pass filters and keeps every `factor`-th sample, evaluating the filter only at the kept outputs.
+///     State persists between calls to [`Decimator::process`], so chunks of any length can be streamed and the
+///     output matches processing the whole stream at once.
+#[derive(Clone, Debug)]
+pub struct Decimator {
+    factor: usize,
+    taps: Vec<f64>,
+    /// The last `taps.len() - 1` input samples.
+    history: Vec<f64>,
+    /// Input samples to consume before the next output.
+    skip: usize,
+}
+
+impl Decimator {
+    /// Creates a decimator whose anti-alias filter has `taps_per_phase * factor` coefficients and cuts at the
+    ///     output Nyquist frequency.
+    pub fn new(factor: usize, taps_per_phase: usize) -> Result<Self, Box<dyn Error>> {
+        if factor == 0 || taps_per_phase == 0 {
+            return Err("Factor and taps per phase must be positive.".into());
+        }
+        let taps = lowpass_taps(factor * taps_per_phase, 0.5 / factor as f64, 1.0);
+        Ok(Decimator { factor, history: vec![0.0; taps.len() - 1], taps, skip: 0 })
+    }
+
+    pub fn factor(&self) -> usize {
+        self.factor
+    }
+
+    /// Group delay of the filter in output samples.
+    pub fn delay(&self) -> f64 {
+        (self.taps.len() - 1) as f64 / 2.0 / self.factor as f64
+    }
+
+    /// Decimates the next chunk of the stream, returning one sample per `factor` inputs.
+    pub fn process(&mut self, chunk: &[f64]) -> Vec<f64> {
+        let order = self.taps.len() - 1;
+        let mut buffer = std::mem::take(&mut self.history);
+        buffer.extend_from_slice(chunk);
+
+        let mut position = order + self.skip;
+        let mut output = Vec::with_capacity(chunk.len() / self.factor + 1);
+        while position < buffer.len() {
+            let window = &buffer[position - order..=position];
+            output.push(self.taps.iter().zip(window.iter().rev()).map(|(h, x)| h * x).sum());
+            position += self.factor;
+        }
+
+        self.skip = position - buffer.len();
+        self.history = buffer.split_off(buffer.len() - order);
+        output
+    }
+
+    /// Clears the filter state, as before the first chunk.
+    pub fn reset(&mut self) {
+        self.history.iter_mut().for_each(|x| *x = 0.0);
+        self.skip = 0;
+    }
+}
+
+/// Raises the sample rate by `factor` with a polyphase filter bank, computing each output from one short
+///     sub-filter instead of filtering the zero stuffed stream. State persists between calls to
+///     [`Interpolator::process`].
+#[derive(Clone, Debug)]
+pub struct Interpolator {
+    factor: usize,
+    /// Sub-filter `p` holds the taps `p, p + factor, p + 2 factor, ...` of the prototype low-pass filter.
+    phases: Vec<Vec<f64>>,
+    /// The most recent `taps_per_phase` input samples, newest first.
+    history: Vec<f64>,
+}
+
+impl Interpolator {
+    /// Creates an interpolator whose anti-imaging filter has `taps_per_phase * factor` coefficients and cuts at
+    ///     the input Nyquist frequency.
+    pub fn new(factor: usize, taps_per_phase: usize) -> Result<Self, Box<dyn Error>> {
+        if factor == 0 || taps_per_phase == 0 {
+            return Err("Factor and taps per phase must be positive.".into());
+        }
+        let taps = lowpass_taps(factor * taps_per_phase, 0.5 / factor as f64, factor as f64);
+        let phases = (0..factor).map(|p| taps.iter().skip(p).step_by(factor).copied().collect()).collect();
+        Ok(Interpolator { factor, phases, history: vec![0.0; taps_per_phase] })
+    }
+
+    pub fn factor(&self) -> usize {
+        self.factor
+    }
+
+    /// Group delay of the filter in output samples.
+    pub fn delay(&self) -> f64 {
+        (self.factor * self.history.len() - 1) as f64 / 2.0
+    }
+
+    /// Interpolates the next chunk of the stream, returning `factor` samples per input.
+    pub fn process(&mut self, chunk: &[f64]) -> Vec<f64> {
+        let mut output = Vec::with_capacity(chunk.len() * self.factor);
+        for &x in chunk {
+            self.history.rotate_right(1);
+            self.history[0] = x;
+            for phase in &self.phases {
+                output.push(phase.iter().zip(&self.history).map(|(h, x)| h * x).sum());
+            }
+        }
+        output
+    }
+
+    /// Clears the filter state, as before the first chunk.
+    pub fn reset(&mut self) {
+        self.history.iter_mut().for_each(|x| *x = 0.0);
+    }
+}
+
+/// Resamples a stream by an arbitrary, adjustable ratio with cubic Lagrange interpolation in Farrow form.
+///     Each output is a polynomial in the fractional position whose coefficients are fixed combinations of four
+///     neighbouring inputs, so the ratio can change between chunks at no cost, e.g. to track the clock drift between
+///     two loggers. There is no anti-alias filter, so low-pass the stream before reducing the rate by much.
+#[derive(Clone, Debug)]
+pub struct FarrowResampler {
+    ratio: f64,
+    /// The last three input samples.
+    history: Vec<f64>,
+    /// Position of the next output in `history` followed by the next chunk, in input samples.
+    position: f64,
+}
+
+impl FarrowResampler {
+    /// Creates a resampler producing `ratio` output samples per input sample.
+    pub fn new(ratio: f64) -> Result<Self, Box<dyn Error>> {
+        let mut resampler = FarrowResampler { ratio: 1.0, history: vec![0.0; 3], position: 3.0 };
+        resampler.set_ratio(ratio)?;
+        Ok(resampler)
+    }
+
+    pub fn ratio(&self) -> f64 {
+        self.ratio
+    }
+
+    /// Changes the ratio, taking effect from the next output sample.
+    pub fn set_ratio(&mut self, ratio: f64) -> Result<(), Box<dyn Error>> {
+        if !(ratio > 0.0 && ratio.is_finite()) {
+            return Err("Ratio must be positive and finite.".into());
+        }
+        self.ratio = ratio;
+        Ok(())
+    }
+
+    /// Resamples the next chunk of the stream. The first output is aligned with the first input of the stream.
+    ///     Outputs lag the inputs by two samples, since each needs the two inputs after its position.
+    pub fn process(&mut self, chunk: &[f64]) -> Vec<f64> {
+        let mut buffer = std::mem::take(&mut self.history);
+        buffer.extend_from_slice(chunk);
+
+        let step = 1.0 / self.ratio;
+        let mut output = Vec::with_capacity((chunk.len() as f64 * self.ratio) as usize + 1);
+        while (self.position as usize) + 2 < buffer.len() {
+            let n = self.position as usize;
+            let mu = self.position - n as f64;
+            let (xm1, x0, x1, x2) = (buffer[n - 1], buffer[n], buffer[n + 1], buffer[n + 2]);
+            let c1 = -xm1 / 3.0 - x0 / 2.0 + x1 - x2 / 6.0;
+            let c2 = (xm1 + x1) / 2.0 - x0;
+            let c3 = (x2 - xm1) / 6.0 + (x0 - x1) / 2.0;
+            output.push(((c3 * mu + c2) * mu + c1) * mu + x0);
+            self.position += step;
+        }
+
+        let consumed = buffer.len() - 3;
+        self.position -= consumed as f64;
+        self.history = buffer.split_off(consumed);
+        output
+    }
+
+    /// Clears the state, as before the first chunk.
+    pub fn reset(&mut self) {
+        self.history.iter_mut().for_each(|x| *x = 0.0);
+        self.position = 3.0;
+    }
+}
@@ -0,0 +1,267 @@
+//! Parametric (model based) spectral estimation for short records.
+
+use rustfft::num_complex::Complex;
+use std::error::Error;
+use std::f64::consts::PI;
+
+use crate::{linalg, ComplexMatrix};
+
+/// Exponentially damped sinusoid `amplitude * exp(-damping * t) * cos(2 pi frequency t + phase)`.
+#[derive(Clone, Debug)]
+pub struct DampedComponent {
+    /// Frequency in Hz.
+    pub frequency: f64,
+    /// Decay rate in 1/s, negative for a growing component.
+    pub damping: f64,
+    pub amplitude: f64,
+    /// Phase at the first sample in radians.
+    pub phase: f64,
+}
+
+/// Estimates `n_components` damped sinusoids in a real record with the matrix pencil method.
+///     Resolves components far closer than the FFT bin spacing of short records. The components are returned
+///     in order of decreasing amplitude.
+pub fn matrix_pencil(data: Vec<f64>, fs: f64, n_components: usize) -> Result<Vec<DampedComponent>, Box<dyn Error>> {
+    // Each real sinusoid is a conjugate pair of complex exponentials
+    let order = 2 * n_components;
+    let len = data.len();
+    if n_components == 0 || fs <= 0.0 {
+        return Err("The number of components and fs must be positive.".into());
+    }
+    if len < 3 * order {
+        return Err(format!("At least {} samples are needed to estimate {} components.", 3 * order, n_components).into());
+    }
+
+    // Pencil parameter between N/3 and N/2 minimizes the noise sensitivity
+    let pencil = len / 3;
+    let samples: Vec<Complex<f64>> = data.iter().map(|&x| Complex::new(x, 0.0)).collect();
+    let hankel: ComplexMatrix = (0..len - pencil).map(|i| samples[i..=i + pencil].to_vec()).collect();
+
+    // The dominant right singular vectors span the signal subspace
+    let (_, vectors) = linalg::hermitian_eigen(&linalg::matmul(&linalg::conjugate_transpose(&hankel), &hankel));
+    let signal: ComplexMatrix = vectors.iter().map(|row| row[..order].to_vec()).collect();
+    let (first, second) = (signal[..pencil].to_vec(), signal[1..].to_vec());
+    let first_adjoint = linalg::conjugate_transpose(&first);
+    let gram = linalg::invert(&linalg::matmul(&first_adjoint, &first)).ok_or("The signal subspace is rank deficient.")?;
+    let poles = linalg::eigenvalues(&linalg::matmul(&gram, &linalg::matmul(&first_adjoint, &second)));
+
+    // Amplitudes and phases from a least squares fit of the poles to the data
+    let vandermonde: ComplexMatrix = (0..len).map(|n| poles.iter().map(|z| z.powu(n as u32)).collect()).collect();
+    let residues = linalg::least_squares(&vandermonde, &samples).ok_or("The estimated poles are not distinct.")?;
+
+    let mut components: Vec<DampedComponent> = poles
+        .iter()
+        .zip(&residues)
+        .filter(|(z, _)| z.im > -1e-12)
+        .map(|(z, h)| {
+            let real = z.im.abs() < 1e-12;
+            DampedComponent {
+                frequency: z.arg() * fs / (2.0 * PI),
+                damping: -z.norm().ln() * fs,
+                amplitude: if real { h.norm() } else { 2.0 * h.norm() },
+                phase: h.arg(),
+            }
+        })
+        .collect();
+    components.sort_by(|a, b| b.amplitude.total_cmp(&a.amplitude));
+    components.truncate(n_components);
+
+    Ok(components)
+}
+
+/// Forward-backward averaged `m` by `m` correlation matrix of the sliding windows of the data.
+fn correlation_matrix(data: &[f64], m: usize) -> ComplexMatrix {
+    let windows = data.len() - m + 1;
+    let mut matrix = vec![vec![Complex::new(0.0, 0.0); m]; m];
+    for window in data.windows(m) {
+        for (i, row) in matrix.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                // Forward term plus the time reversed (backward) term
+                *value += window[i] * window[j] + window[m - 1 - i] * window[m - 1 - j];
+            }
+        }
+    }
+    matrix.iter_mut().flatten().for_each(|value| *value /= 2.0 * windows as f64);
+    matrix
+}
+
+/// Eigenvectors of the data correlation matrix in order of decreasing eigenvalue, as matrix columns.
+fn subspaces(data: &[f64], n_sinusoids: usize) -> Result<ComplexMatrix, Box<dyn Error>> {
+    let order = 2 * n_sinusoids;
+    if n_sinusoids == 0 {
+        return Err("The number of sinusoids must be positive.".into());
+    }
+    let m = (4 * n_sinusoids).max(32).min(data.len() / 2);
+    if m <= order {
+        return Err(format!("At least {} samples are needed to estimate {} sinusoids.", 2 * (order + 1), n_sinusoids).into());
+    }
+    Ok(linalg::hermitian_eigen(&correlation_matrix(data, m)).1)
+}
+
+/// MUSIC pseudospectrum with the frequencies of its strongest peaks.
+pub struct MusicSpectrum {
+    /// Estimated sinusoid frequencies in Hz, in ascending order.
+    pub frequencies: Vec<f64>,
+    pub freqs: Vec<f64>,
+    pub pseudospectrum: Vec<f64>,
+}
+
+/// Estimates the frequencies of `n_sinusoids` real sinusoids in noise with the MUSIC subspace method,
+///     evaluating the pseudospectrum on `n_freqs` frequencies between 0 and the Nyquist frequency.
+pub fn music(data: Vec<f64>, n_sinusoids: usize, fs: f64, n_freqs: usize) -> Result<MusicSpectrum, Box<dyn Error>> {
+    if fs <= 0.0 || n_freqs < 2 {
+        return Err("fs must be greater than zero and at least two frequencies are needed.".into());
+    }
+    let vectors = subspaces(&data, n_sinusoids)?;
+    let noise: ComplexMatrix = vectors.iter().map(|row| row[2 * n_sinusoids..].to_vec()).collect();
+
+    let freqs: Vec<f64> = (0..n_freqs).map(|k| k as f64 * fs / 2.0 / (n_freqs - 1) as f64).collect();
+    let pseudospectrum: Vec<f64> = freqs
+        .iter()
+        .map(|f| {
+            // Projection of the steering vector onto the noise subspace
+            let steering: Vec<Complex<f64>> = (0..noise.len()).map(|n| Complex::from_polar(1.0, 2.0 * PI * f * n as f64 / fs)).collect();
+            let projection: f64 = (0..noise[0].len())
+                .map(|k| steering.iter().zip(&noise).map(|(e, row)| e.conj() * row[k]).sum::<Complex<f64>>().norm_sqr())
+                .sum();
+            1.0 / projection.max(f64::MIN_POSITIVE)
+        })
+        .collect();
+
+    let mut peaks: Vec<usize> = (1..n_freqs - 1)
+        .filter(|&k| pseudospectrum[k] > pseudospectrum[k - 1] && pseudospectrum[k] >= pseudospectrum[k + 1])
+        .collect();
+    peaks.sort_by(|&a, &b| pseudospectrum[b].total_cmp(&pseudospectrum[a]));
+    let mut frequencies: Vec<f64> = peaks.into_iter().take(n_sinusoids).map(|k| freqs[k]).collect();
+    frequencies.sort_by(f64::total_cmp);
+
+    Ok(MusicSpectrum { frequencies, freqs, pseudospectrum })
+}
+
+/// Estimates the frequencies in Hz of `n_sinusoids` real sinusoids in noise with ESPRIT, from the rotational
+///     invariance of the signal subspace. Returns them in ascending order.
+pub fn esprit(data: Vec<f64>, n_sinusoids: usize, fs: f64) -> Result<Vec<f64>, Box<dyn Error>> {
+    if fs <= 0.0 {
+        return Err("fs must be greater than zero.".into());
+    }
+    let vectors = subspaces(&data, n_sinusoids)?;
+    let signal: ComplexMatrix = vectors.iter().map(|row| row[..2 * n_sinusoids].to_vec()).collect();
+    let (first, second) = (signal[..signal.len() - 1].to_vec(), signal[1..].to_vec());
+
+    let first_adjoint = linalg::conjugate_transpose(&first);
+    let gram = linalg::invert(&linalg::matmul(&first_adjoint, &first)).ok_or("The signal subspace is rank deficient.")?;
+    let rotation = linalg::matmul(&gram, &linalg::matmul(&first_adjoint, &second));
+
+    let mut frequencies: Vec<f64> = linalg::eigenvalues(&rotation)
+        .into_iter()
+        .map(|z| z.arg() * fs / (2.0 * PI))
+        .filter(|f| *f >= 0.0)
+        .collect();
+    frequencies.sort_by(f64::total_cmp);
+    // A pair straddling DC can leave more positive roots than sinusoids
+    frequencies.truncate(n_sinusoids);
+
+    Ok(frequencies)
+}
+
+/// Estimator of the autoregressive model coefficients.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArMethod {
+    /// Yule-Walker equations solved with the Levinson-Durbin recursion on the biased autocorrelation.
+    YuleWalker,
+    /// Burg's method, minimizing forward and backward prediction errors. Preferred for short records.
+    Burg,
+}
+
+/// Autoregressive PSD estimate along with the fitted model.
+pub struct ArPsd {
+    pub freqs: Vec<f64>,
+    /// One sided PSD in units of data²/Hz.
+    pub psd: Vec<f64>,
+    /// Prediction error filter `[1, a1, ..., ap]`, so `x[n] + a1 x[n-1] + ... + ap x[n-p]` is white.
+    pub coefficients: Vec<f64>,
+    pub noise_variance: f64,
+}
+
+/// Fits AR models of every order up to `max_order`, returning the prediction error filter and the
+///     driving noise variance of each order (index 0 is the zero order model).
+pub(crate) fn ar_models(data: &[f64], max_order: usize, method: ArMethod) -> Vec<(Vec<f64>, f64)> {
+    let len = data.len();
+    let mut models = Vec::with_capacity(max_order + 1);
+    let mut a = vec![1.0];
+    let mut error = data.iter().map(|x| x * x).sum::<f64>() / len as f64;
+    models.push((a.clone(), error));
+
+    match method {
+        ArMethod::YuleWalker => {
+            let r: Vec<f64> =
+                (0..=max_order).map(|lag| data[lag..].iter().zip(data).map(|(x, y)| x * y).sum::<f64>() / len as f64).collect();
+            for m in 1..=max_order {
+                let k = -(0..m).map(|i| a[i] * r[m - i]).sum::<f64>() / error;
+                a.push(0.0);
+                a = (0..=m).map(|i| a[i] + k * a[m - i]).collect();
+                error *= 1.0 - k * k;
+                models.push((a.clone(), error));
+            }
+        }
+        ArMethod::Burg => {
+            let (mut forward, mut backward) = (data.to_vec(), data.to_vec());
+            for m in 1..=max_order {
+                let numerator: f64 = (m..len).map(|n| forward[n] * backward[n - 1]).sum();
+                let denominator: f64 = (m..len).map(|n| forward[n].powi(2) + backward[n - 1].powi(2)).sum();
+                let k = if denominator > 0.0 { -2.0 * numerator / denominator } else { 0.0 };
+                a.push(0.0);
+                a = (0..=m).map(|i| a[i] + k * a[m - i]).collect();
+                // Walk backwards so backward[n - 1] still holds the previous order's error
+                for n in (m..len).rev() {
+                    let previous = forward[n];
+                    forward[n] += k * backward[n - 1];
+                    backward[n] = backward[n - 1] + k * previous;
+                }
+                error *= 1.0 - k * k;
+                models.push((a.clone(), error));
+            }
+        }
+    }
+    models
+}
+
+/// Estimates the PSD from an autoregressive model, an alternative to Welch's method for short records.
+///     Without an `order`, the order up to `len / 4` (at most 100) minimizing the Akaike information criterion
+///     is used. The PSD is evaluated on `n_freqs` frequencies between 0 and the Nyquist frequency.
+pub fn ar_psd(data: Vec<f64>, fs: f64, order: Option<usize>, method: ArMethod, n_freqs: usize) -> Result<ArPsd, Box<dyn Error>> {
+    let len = data.len();
+    if fs <= 0.0 || n_freqs < 2 {
+        return Err("fs must be greater than zero and at least two frequencies are needed.".into());
+    }
+    if len < 4 || order.is_some_and(|p| p == 0 || p >= len) {
+        return Err("Data must have at least four samples and the order must be between 1 and its length.".into());
+    }
+
+    let mean = data.iter().sum::<f64>() / len as f64;
+    let centred: Vec<f64> = data.iter().map(|x| x - mean).collect();
+    let models = ar_models(&centred, order.unwrap_or((len / 4).clamp(1, 100)), method);
+    let (coefficients, noise_variance) = match order {
+        Some(p) => models[p].clone(),
+        None => {
+            let aic = |p: usize| len as f64 * models[p].1.max(f64::MIN_POSITIVE).ln() + 2.0 * p as f64;
+            let best = (1..models.len()).min_by(|&p, &q| aic(p).total_cmp(&aic(q))).unwrap_or(1);
+            log::debug!("AIC selected AR order {}", best);
+            models[best].clone()
+        }
+    };
+
+    let freqs: Vec<f64> = (0..n_freqs).map(|k| k as f64 * fs / 2.0 / (n_freqs - 1) as f64).collect();
+    let psd = freqs
+        .iter()
+        .enumerate()
+        .map(|(k, f)| {
+            let response: Complex<f64> =
+                coefficients.iter().enumerate().map(|(i, a)| Complex::from_polar(*a, -2.0 * PI * f * i as f64 / fs)).sum();
+            let one_sided = if k == 0 || k == n_freqs - 1 { 1.0 } else { 2.0 };
+            one_sided * noise_variance / fs / response.norm_sqr().max(f64::MIN_POSITIVE)
+        })
+        .collect();
+
+    Ok(ArPsd { freqs, psd, coefficients, noise_variance })
+}
@@ -0,0 +1,179 @@
+//! Filter-bank analysis on perceptual frequency scales.
+
+use std::error::Error;
+
+use rustfft::num_complex::Complex;
+
+use crate::{compute_spectrogram, linalg, ComplexMatrix, Matrix};
+
+/// Frequency scale on which the bands of [`band_spectrum`] are spaced evenly.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrequencyScale {
+    /// Critical band rate in Bark, after Traunmüller (1990).
+    Bark,
+    /// Equivalent rectangular bandwidth rate in Cams, after Glasberg and Moore (1990).
+    Erb,
+    /// Mel scale of the HTK toolkit, `2595 log10(1 + f / 700)`.
+    Mel,
+}
+
+impl FrequencyScale {
+    /// Lowercase name of the scale, as accepted by the Python bindings.
+    pub fn name(self) -> &'static str {
+        match self {
+            FrequencyScale::Bark => "bark",
+            FrequencyScale::Erb => "erb",
+            FrequencyScale::Mel => "mel",
+        }
+    }
+
+    /// Position of `freq` Hz on the scale.
+    pub fn from_hz(self, freq: f64) -> f64 {
+        match self {
+            FrequencyScale::Bark => 26.81 * freq / (1960.0 + freq) - 0.53,
+            FrequencyScale::Erb => 21.4 * (1.0 + 0.004_37 * freq).log10(),
+            FrequencyScale::Mel => 2595.0 * (1.0 + freq / 700.0).log10(),
+        }
+    }
+
+    /// Frequency in Hz of a position on the scale, the inverse of [`FrequencyScale::from_hz`].
+    pub fn to_hz(self, value: f64) -> f64 {
+        match self {
+            FrequencyScale::Bark => 1960.0 * (value + 0.53) / (26.28 - value),
+            FrequencyScale::Erb => (10f64.powf(value / 21.4) - 1.0) / 0.004_37,
+            FrequencyScale::Mel => 700.0 * (10f64.powf(value / 2595.0) - 1.0),
+        }
+    }
+}
+
+/// Triangular filters evenly spaced on `scale` between `fmin` and `fmax` Hz, evaluated at `freqs`.
+///     Each filter rises from the centre of its lower neighbour to its own centre and falls to the centre of its
+///     upper neighbour, linearly in Hz. Returns the centre frequencies and one row of weights per band.
+pub fn band_filterbank(
+    scale: FrequencyScale,
+    n_bands: usize,
+    freqs: &[f64],
+    fmin: f64,
+    fmax: f64,
+) -> Result<(Vec<f64>, Matrix), Box<dyn Error>> {
+    if n_bands == 0 {
+        return Err("n_bands must be positive.".into());
+    }
+    if !(fmin >= 0.0 && fmax > fmin) {
+        return Err("The band limits must satisfy 0 <= fmin < fmax.".into());
+    }
+    let (low, high) = (scale.from_hz(fmin), scale.from_hz(fmax));
+    let edges: Vec<f64> =
+        (0..n_bands + 2).map(|i| scale.to_hz(low + (high - low) * i as f64 / (n_bands + 1) as f64)).collect();
+
+    let weights: Matrix = edges
+        .windows(3)
+        .map(|band| {
+            freqs
+                .iter()
+                .map(|&f| {
+                    let rising = (f - band[0]) / (band[1] - band[0]);
+                    let falling = (band[2] - f) / (band[2] - band[1]);
+                    rising.min(falling).max(0.0)
+                })
+                .collect()
+        })
+        .collect();
+    Ok((edges[1..=n_bands].to_vec(), weights))
+}
+
+/// Band powers over time from [`band_spectrum`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct BandSpectrogram {
+    /// Centre of each segment in seconds.
+    pub times: Vec<f64>,
+    /// Centre frequency of each band in Hz.
+    pub centers: Vec<f64>,
+    /// Power of each band in each segment in units of data², one row per segment.
+    pub power: Matrix,
+}
+
+/// Splits the spectrogram of `data` into `n_bands` triangular bands evenly spaced on a perceptual scale from 0 Hz
+///     to Nyquist, like a mel spectrogram on the Bark or ERB scale. The segment PSDs of [`compute_spectrogram`]
+///     are integrated over each band, so the bands of a segment add up to about its mean square. Bands too narrow
+///     to contain a frequency bin stay empty, use a longer `nperseg` to resolve them.
+pub fn band_spectrum(
+    data: Vec<f64>,
+    fs: f64,
+    scale: FrequencyScale,
+    n_bands: usize,
+    nperseg: usize,
+    hop: usize,
+) -> Result<BandSpectrogram, Box<dyn Error>> {
+    let spectrogram = compute_spectrogram(data, fs, nperseg, hop)?;
+    let (centers, weights) = band_filterbank(scale, n_bands, &spectrogram.freqs, 0.0, fs / 2.0)?;
+    let empty = weights.iter().filter(|row| row.iter().all(|&w| w == 0.0)).count();
+    if empty > 0 {
+        log::warn!("{} of {} {} bands contain no frequency bin, increase nperseg", empty, n_bands, scale.name());
+    }
+
+    let df = fs / nperseg as f64;
+    let power = spectrogram
+        .power
+        .iter()
+        .map(|psd| weights.iter().map(|row| row.iter().zip(psd).map(|(w, p)| w * p).sum::<f64>() * df).collect())
+        .collect();
+    Ok(BandSpectrogram { times: spectrogram.times, centers, power })
+}
+
+/// [`band_spectrum`] on the Bark scale, 24 bands cover the critical bands of hearing.
+pub fn bark_spectrum(data: Vec<f64>, fs: f64, n_bands: usize, nperseg: usize, hop: usize) -> Result<BandSpectrogram, Box<dyn Error>> {
+    band_spectrum(data, fs, FrequencyScale::Bark, n_bands, nperseg, hop)
+}
+
+/// [`band_spectrum`] on the ERB-rate scale, with about one band per ERB for 40 bands at 44.1 kHz.
+pub fn erb_spectrum(data: Vec<f64>, fs: f64, n_bands: usize, nperseg: usize, hop: usize) -> Result<BandSpectrogram, Box<dyn Error>> {
+    band_spectrum(data, fs, FrequencyScale::Erb, n_bands, nperseg, hop)
+}
+
+/// The mel filter bank of [`band_filterbank`] over the `n_fft / 2 + 1` bins of an [`stft`](crate::stft) from 0 Hz
+///     to Nyquist, one row of weights per mel band. A mel spectrogram is each STFT frame weighted by every row.
+pub fn mel_filterbank(fs: f64, n_fft: usize, n_mels: usize) -> Result<Matrix, Box<dyn Error>> {
+    if n_fft < 2 || fs <= 0.0 {
+        return Err("n_fft must be at least 2 and fs must be greater than zero.".into());
+    }
+    let freqs: Vec<f64> = (0..=n_fft / 2).map(|k| k as f64 * fs / n_fft as f64).collect();
+    Ok(band_filterbank(FrequencyScale::Mel, n_mels, &freqs, 0.0, fs / 2.0)?.1)
+}
+
+/// Estimates the linear frequency spectrogram, one row of `n_fft / 2 + 1` bins per frame, from a mel spectrogram
+///     made with [`mel_filterbank`], by the Moore-Penrose pseudo-inverse of the filter bank. Negative values the
+///     inverse produces between bands are clipped to zero. The mel bands average away detail within each band, so
+///     the result is smooth, it pairs with [`griffin_lim`](crate::griffin_lim) to turn a mel spectrogram into audio.
+pub fn mel_to_linear(mel_spec: &Matrix, fs: f64, n_fft: usize, n_mels: usize) -> Result<Matrix, Box<dyn Error>> {
+    if mel_spec.iter().any(|frame| frame.len() != n_mels) {
+        return Err(format!("Each frame must have n_mels = {} values.", n_mels).into());
+    }
+    let weights = mel_filterbank(fs, n_fft, n_mels)?;
+
+    // Pseudo-inverse W+ = W^T (W W^T)+, with the eigenvalues of the Gram matrix of bands holding no bin dropped
+    let gram: ComplexMatrix = weights
+        .iter()
+        .map(|a| weights.iter().map(|b| Complex::new(a.iter().zip(b).map(|(x, y)| x * y).sum(), 0.0)).collect())
+        .collect();
+    let (values, vectors) = linalg::hermitian_eigen(&gram);
+    let tolerance = values.first().copied().unwrap_or(0.0) * 1e-10;
+    let gram_inverse: Matrix = (0..n_mels)
+        .map(|i| {
+            (0..n_mels)
+                .map(|j| {
+                    values.iter().enumerate().filter(|(_, &v)| v > tolerance).map(|(m, v)| (vectors[i][m] * vectors[j][m].conj()).re / v).sum()
+                })
+                .collect()
+        })
+        .collect();
+    let bins = n_fft / 2 + 1;
+    let inverse: Matrix = (0..bins)
+        .map(|k| (0..n_mels).map(|j| (0..n_mels).map(|i| weights[i][k] * gram_inverse[i][j]).sum()).collect())
+        .collect();
+
+    Ok(mel_spec
+        .iter()
+        .map(|frame| inverse.iter().map(|row| row.iter().zip(frame).map(|(p, m)| p * m).sum::<f64>().max(0.0)).collect())
+        .collect())
+}
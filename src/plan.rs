@@ -0,0 +1,190 @@
+//! Process wide cache of FFT plans.
+//!
+//! Every transform in the crate plans through this cache, so the planning cost for a length is only paid the first
+//! time it is used. [`warm_plans`] pays it up front for latency sensitive loops.
+
+use rustfft::{Fft, FftDirection, FftPlanner};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+
+struct PlanCache {
+    planner: FftPlanner<f64>,
+    forward: HashSet<usize>,
+    inverse: HashSet<usize>,
+    hits: u64,
+    misses: u64,
+}
+
+/// Counters describing the contents and effectiveness of the plan cache.
+pub struct PlanCacheStats {
+    pub forward_plans: usize,
+    pub inverse_plans: usize,
+    /// Plan requests answered from the cache.
+    pub hits: u64,
+    /// Plan requests that had to run the planner.
+    pub misses: u64,
+}
+
+fn cache() -> MutexGuard<'static, PlanCache> {
+    static CACHE: OnceLock<Mutex<PlanCache>> = OnceLock::new();
+    CACHE
+        .get_or_init(|| {
+            Mutex::new(PlanCache {
+                planner: FftPlanner::new(),
+                forward: HashSet::new(),
+                inverse: HashSet::new(),
+                hits: 0,
+                misses: 0,
+            })
+        })
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Returns the cached plan for a transform of the given length and direction, planning it on first use.
+pub fn plan_fft(len: usize, direction: FftDirection) -> Arc<dyn Fft<f64>> {
+    let mut cache = cache();
+    let known = match direction {
+        FftDirection::Forward => !cache.forward.insert(len),
+        FftDirection::Inverse => !cache.inverse.insert(len),
+    };
+    if known {
+        cache.hits += 1;
+    } else {
+        cache.misses += 1;
+        log::debug!("Planning {} FFT of length {}", direction, len);
+    }
+    cache.planner.plan_fft(len, direction)
+}
+
+/// Plans forward and inverse transforms for each size ahead of time.
+pub fn warm_plans(sizes: &[usize]) {
+    for &size in sizes {
+        plan_fft(size, FftDirection::Forward);
+        plan_fft(size, FftDirection::Inverse);
+    }
+}
+
+/// Returns the current plan cache counters.
+pub fn plan_cache_stats() -> PlanCacheStats {
+    let cache = cache();
+    PlanCacheStats {
+        forward_plans: cache.forward.len(),
+        inverse_plans: cache.inverse.len(),
+        hits: cache.hits,
+        misses: cache.misses,
+    }
+}
+
+/// Drops all cached plans and resets the counters.
+pub fn clear_plan_cache() {
+    let mut cache = cache();
+    cache.planner = FftPlanner::new();
+    cache.forward.clear();
+    cache.inverse.clear();
+    cache.hits = 0;
+    cache.misses = 0;
+}
+
+/// Largest prime with a hardcoded rustfft butterfly.
+const MAX_BUTTERFLY_PRIME: usize = 31;
+/// rustfft falls back from Rader's to Bluestein's algorithm when `p - 1` has a prime factor above this.
+const MAX_RADER_PRIME_FACTOR: usize = 23;
+
+/// How rustfft computes a transform length, see [`plan_info`].
+pub struct PlanInfo {
+    /// Top level strategy: "trivial", "butterfly", "radix4", "radix3", "mixed_radix", "raders" or "bluesteins".
+    pub algorithm: &'static str,
+    /// Prime factors in ascending order, with multiplicity.
+    pub factors: Vec<usize>,
+    /// Whether a prime factor is handled by Rader's algorithm.
+    pub uses_raders: bool,
+    /// Whether a prime factor is handled by Bluestein's algorithm, which needs transforms of more than twice its size.
+    pub uses_bluesteins: bool,
+    /// Rough operation count of the transform.
+    pub estimated_flops: f64,
+    /// `estimated_flops` relative to `5 N log2(N)`, the cost of a power of two transform of the same length.
+    pub relative_cost: f64,
+}
+
+fn prime_factors(mut n: usize) -> Vec<usize> {
+    let mut factors = Vec::new();
+    let mut p = 2;
+    while p * p <= n {
+        while n.is_multiple_of(p) {
+            factors.push(p);
+            n /= p;
+        }
+        p += 1;
+    }
+    if n > 1 {
+        factors.push(n);
+    }
+    factors
+}
+
+/// Estimated operations per sample contributed by a prime factor of a mixed radix transform.
+fn prime_cost(p: usize, raders: &mut bool, bluesteins: &mut bool) -> f64 {
+    if p <= MAX_BUTTERFLY_PRIME {
+        return 5.0 * (p as f64).log2();
+    }
+    let inner = prime_factors(p - 1);
+    if inner.iter().any(|&q| q > MAX_RADER_PRIME_FACTOR) {
+        // Bluestein's: a forward and an inverse transform of a power of two above 2p - 1, plus the chirp products
+        *bluesteins = true;
+        let m = (2 * p - 1).next_power_of_two() as f64;
+        (2.0 * 5.0 * m * m.log2() + 12.0 * m) / p as f64
+    } else {
+        // Rader's: a forward and an inverse transform of length p - 1
+        *raders = true;
+        2.0 * inner.into_iter().map(|q| prime_cost(q, raders, bluesteins)).sum::<f64>() + 6.0
+    }
+}
+
+/// Describes the strategy rustfft's planner picks for a transform of length `len` and estimates its cost, to spot
+///     pathological lengths with large prime factors. The SIMD planners handle primes the same way but may pick
+///     different radices, so time a length with [`crate::benchmark`] when the details matter.
+pub fn plan_info(len: usize) -> PlanInfo {
+    let factors = prime_factors(len);
+    let (mut uses_raders, mut uses_bluesteins) = (false, false);
+    let per_sample: f64 = factors.iter().map(|&p| prime_cost(p, &mut uses_raders, &mut uses_bluesteins)).sum();
+    let estimated_flops = if len < 2 { 0.0 } else { per_sample * len as f64 };
+    let baseline = 5.0 * len as f64 * (len as f64).log2();
+
+    let algorithm = match len {
+        0 | 1 => "trivial",
+        2..=9 | 11 | 13 | 16 | 17 | 19 | 23 | 27 | 29 | 31 | 32 => "butterfly",
+        _ if factors.len() == 1 && uses_bluesteins => "bluesteins",
+        _ if factors.len() == 1 => "raders",
+        _ if len.trailing_zeros() >= 5 && len.is_power_of_two() => "radix4",
+        _ if factors.iter().all(|&p| p == 3) && factors.len() >= 4 => "radix3",
+        _ => "mixed_radix",
+    };
+
+    PlanInfo {
+        algorithm,
+        factors,
+        uses_raders,
+        uses_bluesteins,
+        estimated_flops,
+        relative_cost: if baseline > 0.0 { estimated_flops / baseline } else { 1.0 },
+    }
+}
+
+/// Returns the smallest length of at least `len` whose only prime factors are 2, 3 and 5.
+///     Padding to such lengths keeps transforms on the fast mixed radix paths.
+pub fn fast_len(len: usize) -> usize {
+    let mut candidate = len.max(1);
+    loop {
+        let mut rest = candidate;
+        for p in [2, 3, 5] {
+            while rest.is_multiple_of(p) {
+                rest /= p;
+            }
+        }
+        if rest == 1 {
+            return candidate;
+        }
+        candidate += 1;
+    }
+}
@@ -0,0 +1,843 @@
+//! Rendering of data to PNG images.
+
+use image::codecs::png::PngEncoder;
+use image::ImageEncoder;
+use plotters::coord::ranged1d::{AsRangedCoord, ValueFormatter};
+use plotters::coord::Shift;
+use plotters::chart::MeshStyle;
+use plotters::prelude::*;
+use plotters_bitmap::BitMapBackend;
+use std::error::Error;
+
+use crate::{channel_len, kernels, Colormap, DbReference, MaskKind, MaskSegment, Matrix, Signal, Spectrogram, Spectrum, SpectrumComparison, SpectrumKind, Window};
+
+/// How the tick labels of an axis are written.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TickFormat {
+    /// Decimals as chosen by the plotting library.
+    #[default]
+    Auto,
+    /// Mantissa and power of ten, e.g. `1.5e-4`.
+    Scientific,
+    /// Scientific with the exponent a multiple of three, e.g. `150e-6`.
+    Engineering,
+    /// Engineering with the SI prefix in place of the exponent, e.g. `150µ`.
+    SiPrefix,
+}
+
+const SI_PREFIXES: [&str; 17] = ["y", "z", "a", "f", "p", "n", "µ", "m", "", "k", "M", "G", "T", "P", "E", "Z", "Y"];
+
+/// Formats a number with at most `decimals` digits after the point, dropping trailing zeros.
+fn trimmed(value: f64, decimals: usize) -> String {
+    let text = format!("{:.*}", decimals, value);
+    let text = if text.contains('.') { text.trim_end_matches('0').trim_end_matches('.') } else { &text };
+    if text == "-0" { "0".to_string() } else { text.to_string() }
+}
+
+impl TickFormat {
+    /// Lowercase name of the format, as accepted by the Python bindings.
+    pub fn name(self) -> &'static str {
+        match self {
+            TickFormat::Auto => "auto",
+            TickFormat::Scientific => "scientific",
+            TickFormat::Engineering => "engineering",
+            TickFormat::SiPrefix => "si",
+        }
+    }
+
+    /// The tick label of `value`, with at most three decimals in the mantissa.
+    pub fn format(self, value: f64) -> String {
+        if self == TickFormat::Auto || value == 0.0 || !value.is_finite() {
+            return if value == 0.0 { "0".to_string() } else { value.to_string() };
+        }
+        let step = if self == TickFormat::Scientific { 1 } else { 3 };
+        let mut exponent = (value.abs().log10().floor() as i32).div_euclid(step) * step;
+        let mut mantissa = value / 10f64.powi(exponent);
+        // Rounding can carry the mantissa into the next exponent, 999.9999 reading 1000
+        if (mantissa.abs() * 1000.0).round() / 1000.0 >= 10f64.powi(step) {
+            exponent += step;
+            mantissa /= 10f64.powi(step);
+        }
+        match self {
+            TickFormat::SiPrefix if (-24..=24).contains(&exponent) => {
+                format!("{}{}", trimmed(mantissa, 3), SI_PREFIXES[(exponent / 3 + 8) as usize])
+            }
+            _ if exponent == 0 => trimmed(mantissa, 3),
+            _ => format!("{}e{}", trimmed(mantissa, 3), exponent),
+        }
+    }
+}
+
+/// How an axis without limits is fitted to the data. Values that are NaN or infinite are always left out.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Autoscale {
+    /// From the smallest to the largest value.
+    #[default]
+    MinMax,
+    /// From the `p`th to the `100 - p`th percentile of the values with a 5% margin, so a few outliers such as a DC
+    ///     spike or a glitch run off the axis instead of flattening everything else. `p` is from 0 to below 50.
+    Percentile(f64),
+}
+
+/// The `q` quantile of sorted values, interpolating linearly between neighbours.
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let position = q * (sorted.len() - 1) as f64;
+    let (below, above) = (position.floor() as usize, position.ceil() as usize);
+    sorted[below] + (sorted[above] - sorted[below]) * (position - below as f64)
+}
+
+/// Range and ticks of one plot axis.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AxisOptions {
+    /// `(min, max)` of the axis, by default fitted to the data as set by `autoscale`. Data outside is cut off.
+    pub limits: Option<(f64, f64)>,
+    pub autoscale: Autoscale,
+    /// Most tick labels to draw, by default about ten.
+    pub ticks: Option<usize>,
+    pub format: TickFormat,
+}
+
+impl AxisOptions {
+    /// The axis range, the limits if set and otherwise the range of the finite `values` chosen by `autoscale`,
+    ///     widened by `margin` of its span on either side. A range of a single value is widened around it.
+    fn range(&self, name: &str, values: impl Iterator<Item = f64>, margin: f64) -> Result<std::ops::Range<f64>, Box<dyn Error>> {
+        match self.limits {
+            Some((low, high)) if low.is_finite() && high.is_finite() && low < high => return Ok(low..high),
+            Some(_) => return Err(format!("The {} limits must be finite with min below max.", name).into()),
+            None => {}
+        }
+        let mut values: Vec<f64> = values.filter(|v| v.is_finite()).collect();
+        if values.is_empty() {
+            return Err(format!("There are no finite {} values to plot.", name).into());
+        }
+        let (min, max, margin) = match self.autoscale {
+            Autoscale::MinMax => {
+                let (min, max) = values.iter().fold((f64::MAX, f64::MIN), |(lo, hi), &v| (lo.min(v), hi.max(v)));
+                (min, max, margin)
+            }
+            Autoscale::Percentile(p) if (0.0..50.0).contains(&p) => {
+                values.sort_by(f64::total_cmp);
+                (quantile(&values, p / 100.0), quantile(&values, 1.0 - p / 100.0), margin.max(0.05))
+            }
+            Autoscale::Percentile(_) => return Err(format!("The {} autoscale percentile must be from 0 to below 50.", name).into()),
+        };
+        let pad = if max > min { (max - min) * margin } else { min.abs().max(1.0) * 0.05 };
+        Ok(min - pad..max + pad)
+    }
+}
+
+/// Axis options of [`generate_plot_with_options`] and the plots built on it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PlotOptions {
+    pub x: AxisOptions,
+    pub y: AxisOptions,
+}
+
+/// Applies the tick counts and formats of the options to a mesh, the formatters calling [`TickFormat::format`].
+fn configure_ticks<'b, X, Y, DB>(
+    mesh: &mut MeshStyle<'_, 'b, X, Y, DB>,
+    options: &PlotOptions,
+    x_format: &'b dyn Fn(&f64) -> String,
+    y_format: &'b dyn Fn(&f64) -> String,
+) where
+    X: Ranged<ValueType = f64>,
+    Y: Ranged<ValueType = f64>,
+    DB: DrawingBackend,
+{
+    if let Some(ticks) = options.x.ticks {
+        mesh.x_labels(ticks);
+    }
+    if let Some(ticks) = options.y.ticks {
+        mesh.y_labels(ticks);
+    }
+    if options.x.format != TickFormat::Auto {
+        mesh.x_label_formatter(x_format);
+    }
+    if options.y.format != TickFormat::Auto {
+        mesh.y_label_formatter(y_format);
+    }
+}
+
+/// Splits points into the runs between those with a NaN or infinite coordinate, each run drawn as its own line.
+fn finite_runs(points: &[(f64, f64)]) -> impl Iterator<Item = &[(f64, f64)]> {
+    points.split(|(x, y)| !(x.is_finite() && y.is_finite())).filter(|run| !run.is_empty())
+}
+
+/// Generates a plot, leaving gaps at NaN or infinite values
+pub fn generate_plot(
+    data: Vec<(f64, f64)>,
+    x_label: &str,
+    y_label: &str,
+    title: &str,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    generate_plot_with_options(data, x_label, y_label, title, &PlotOptions::default())
+}
+
+/// Same as [`generate_plot`], with the axis limits, tick count and tick format set by `options`.
+pub fn generate_plot_with_options(
+    data: Vec<(f64, f64)>,
+    x_label: &str,
+    y_label: &str,
+    title: &str,
+    options: &PlotOptions,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let width = 1024;
+    let height = 768;
+
+    let mut buffer: Vec<u8> = vec![0; (width * height * 3) as usize];
+    {
+        // This block ensures `root_area` goes out of scope before we return `buffer`
+        let root_area =
+            BitMapBackend::with_buffer(&mut buffer, (width, height)).into_drawing_area();
+        root_area.fill(&WHITE)?;
+
+        let x_range = options.x.range("x", data.iter().map(|(x, _)| *x), 0.0)?;
+        let y_range = options.y.range("y", data.iter().map(|(_, y)| *y), 0.0)?;
+        let mut chart = ChartBuilder::on(&root_area)
+            .caption(title, ("sans-serif", 30))
+            .margin(10)
+            .x_label_area_size(40)
+            .y_label_area_size(40)
+            .build_cartesian_2d(x_range, y_range)?;
+
+        let x_format = |v: &f64| options.x.format.format(*v);
+        let y_format = |v: &f64| options.y.format.format(*v);
+        let mut mesh = chart.configure_mesh();
+        mesh.x_desc(x_label).y_desc(y_label);
+        configure_ticks(&mut mesh, options, &x_format, &y_format);
+        mesh.draw()?;
+
+        for run in finite_runs(&data) {
+            chart.draw_series(LineSeries::new(run.iter().copied(), &RED))?;
+        }
+
+        root_area.present()?;
+    }
+
+    // Encode the buffer into PNG format so it will be a known format by the image display
+    let mut png_buffer = Vec::new();
+    let encoder = PngEncoder::new(&mut png_buffer);
+    encoder.write_image(
+        &buffer,
+        width,
+        height,
+        image::ExtendedColorType::Rgb8,
+    )?;
+
+    Ok(png_buffer)
+}
+
+/// Plots a signal over time, labelling the values from its metadata, e.g. "Acceleration [m/s²]".
+///     The title defaults to the channel name.
+pub fn generate_signal_plot(signal: &Signal, title: Option<&str>, options: &PlotOptions) -> Result<Vec<u8>, Box<dyn Error>> {
+    let data = signal.data.iter().enumerate().map(|(n, &x)| (n as f64 / signal.fs, x)).collect();
+    let title = title.or(signal.metadata.name.as_deref()).unwrap_or("Signal");
+    generate_plot_with_options(data, "Time [s]", &signal.label(), title, options)
+}
+
+/// Overlays the channels over time in distinct colors with a legend naming each one, "Channel 0" and on unless
+///     `names` are given. The axes are set up by `options` as in [`generate_plot_with_options`].
+pub fn generate_channels_plot(
+    channels: &Matrix,
+    fs: f64,
+    names: Option<&[String]>,
+    title: &str,
+    options: &PlotOptions,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let len = channel_len(channels)?;
+    if len == 0 || fs <= 0.0 {
+        return Err("Channels must not be empty and fs must be greater than zero.".into());
+    }
+    if names.is_some_and(|names| names.len() != channels.len()) {
+        return Err("There must be one name per channel.".into());
+    }
+
+    let width = 1024;
+    let height = 768;
+    let mut buffer: Vec<u8> = vec![0; (width * height * 3) as usize];
+    {
+        let root_area = BitMapBackend::with_buffer(&mut buffer, (width, height)).into_drawing_area();
+        root_area.fill(&WHITE)?;
+
+        let x_range = options.x.range("x", [0.0, (len - 1) as f64 / fs].into_iter(), 0.0)?;
+        let y_range = options.y.range("y", channels.iter().flatten().copied(), 0.05)?;
+        let mut chart = ChartBuilder::on(&root_area)
+            .caption(title, ("sans-serif", 30))
+            .margin(10)
+            .x_label_area_size(40)
+            .y_label_area_size(60)
+            .build_cartesian_2d(x_range, y_range)?;
+        let x_format = |v: &f64| options.x.format.format(*v);
+        let y_format = |v: &f64| options.y.format.format(*v);
+        let mut mesh = chart.configure_mesh();
+        mesh.x_desc("Time [s]").y_desc("Amplitude");
+        configure_ticks(&mut mesh, options, &x_format, &y_format);
+        mesh.draw()?;
+
+        for (i, channel) in channels.iter().enumerate() {
+            let color = Palette99::pick(i).to_rgba();
+            let name = names.map_or_else(|| format!("Channel {}", i), |names| names[i].clone());
+            let points: Vec<(f64, f64)> = channel.iter().enumerate().map(|(n, &x)| (n as f64 / fs, x)).collect();
+            for (k, run) in finite_runs(&points).enumerate() {
+                let series = chart.draw_series(LineSeries::new(run.iter().copied(), color))?;
+                if k == 0 {
+                    series.label(name.clone()).legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color));
+                }
+            }
+        }
+        chart.configure_series_labels().background_style(WHITE.mix(0.8)).border_style(BLACK).draw()?;
+
+        root_area.present()?;
+    }
+
+    let mut png_buffer = Vec::new();
+    let encoder = PngEncoder::new(&mut png_buffer);
+    encoder.write_image(&buffer, width, height, image::ExtendedColorType::Rgb8)?;
+
+    Ok(png_buffer)
+}
+
+/// Plots a spectrum over frequency, labelling the values from its kind and unit, e.g. "PSD [(m/s²)²/Hz]".
+///     With a `reference` the values are drawn as levels in dB against it, e.g. "PSD [dB SPL/Hz]", amplitude
+///     spectra as `20 log10` and power spectra and PSDs as `10 log10`. The title defaults to the channel name.
+pub fn generate_spectrum_plot(
+    spectrum: &Spectrum,
+    title: Option<&str>,
+    reference: Option<DbReference>,
+    options: &PlotOptions,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let title = title.or(spectrum.metadata.name.as_deref()).unwrap_or("Spectrum");
+    let Some(reference) = reference else {
+        let data = spectrum.freqs.iter().copied().zip(spectrum.values.iter().copied()).collect();
+        return generate_plot_with_options(data, "Frequency [Hz]", &spectrum.label(), title, options);
+    };
+    let (levels, label) = match spectrum.kind {
+        SpectrumKind::Amplitude => (
+            kernels::to_db(&spectrum.values, reference.amplitude(), 20.0),
+            format!("{} [{}]", spectrum.metadata.quantity.as_deref().unwrap_or("Level"), reference.label()),
+        ),
+        SpectrumKind::Power => (kernels::to_db(&spectrum.values, reference.power(), 10.0), format!("Power [{}]", reference.label())),
+        SpectrumKind::Psd => (kernels::to_db(&spectrum.values, reference.power(), 10.0), format!("PSD [{}/Hz]", reference.label())),
+    };
+    // Empty bins would be -inf and break the axis range
+    let data = spectrum.freqs.iter().copied().zip(levels).filter(|(_, level)| level.is_finite()).collect();
+    generate_plot_with_options(data, "Frequency [Hz]", &label, title, options)
+}
+
+/// Options of [`generate_spectrogram_plot`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpectrogramPlotOptions {
+    pub colormap: Colormap,
+    /// Power in dB drawn with the bottom color, quieter cells are clamped to it. Defaults to 80 dB below `db_max`.
+    pub db_min: Option<f64>,
+    /// Power in dB drawn with the top color, louder cells are clamped to it. Defaults to the strongest cell.
+    pub db_max: Option<f64>,
+    /// Draws the frequency axis on a log scale starting at the first bin above 0 Hz.
+    pub log_frequency: bool,
+    /// Adds a bar to the right of the plot relating the colors to dB.
+    pub colorbar: bool,
+}
+
+impl Default for SpectrogramPlotOptions {
+    fn default() -> Self {
+        SpectrogramPlotOptions { colormap: Colormap::Viridis, db_min: None, db_max: None, log_frequency: false, colorbar: false }
+    }
+}
+
+/// Draws the cells of a spectrogram on a frequency axis of any scale spanning `y_bounds`. Cells extend half a
+///     step either side of their centre time and from their frequency up to the next, cut off at the axis ranges.
+fn draw_spectrogram_cells<Y>(
+    area: &DrawingArea<BitMapBackend<'_>, Shift>,
+    spectrogram: &Spectrogram,
+    x_range: std::ops::Range<f64>,
+    y_range: Y,
+    y_bounds: std::ops::Range<f64>,
+    color: &dyn Fn(f64) -> RGBColor,
+    axes: &PlotOptions,
+) -> Result<(), Box<dyn Error>>
+where
+    Y: AsRangedCoord<Value = f64>,
+    Y::CoordDescType: ValueFormatter<f64>,
+{
+    let (times, freqs) = (&spectrogram.times, &spectrogram.freqs);
+    let (dt, df) = cell_steps(spectrogram);
+
+    let mut chart = ChartBuilder::on(area)
+        .margin(10)
+        .x_label_area_size(40)
+        .y_label_area_size(60)
+        .build_cartesian_2d(x_range.clone(), y_range)?;
+    let x_format = |v: &f64| axes.x.format.format(*v);
+    let y_format = |v: &f64| axes.y.format.format(*v);
+    let mut mesh = chart.configure_mesh();
+    mesh.disable_mesh().x_desc("Time [s]").y_desc("Frequency [Hz]");
+    configure_ticks(&mut mesh, axes, &x_format, &y_format);
+    mesh.draw()?;
+
+    let visible = |low: f64, high: f64, range: &std::ops::Range<f64>| high > range.start && low < range.end;
+    chart.draw_series(
+        times
+            .iter()
+            .zip(&spectrogram.power)
+            .filter(|(t, _)| visible(**t - dt / 2.0, **t + dt / 2.0, &x_range))
+            .flat_map(|(t, row)| {
+                let (x_range, y_bounds) = (&x_range, &y_bounds);
+                freqs.iter().zip(row).filter(move |(f, _)| visible(**f, **f + df, y_bounds)).map(move |(f, p)| {
+                    let value = 10.0 * p.max(1e-300).log10();
+                    let (left, right) = ((t - dt / 2.0).max(x_range.start), (t + dt / 2.0).min(x_range.end));
+                    let (bottom, top) = (f.max(y_bounds.start), (f + df).min(y_bounds.end));
+                    Rectangle::new([(left, bottom), (right, top)], color(value).filled())
+                })
+            }),
+    )?;
+    Ok(())
+}
+
+/// The time and frequency steps between spectrogram cells, a single segment spanning from 0 s.
+fn cell_steps(spectrogram: &Spectrogram) -> (f64, f64) {
+    let (times, freqs) = (&spectrogram.times, &spectrogram.freqs);
+    let dt = if times.len() > 1 { times[1] - times[0] } else { 2.0 * times[0] };
+    let df = if freqs.len() > 1 { freqs[1] - freqs[0] } else { 1.0 };
+    (dt, df)
+}
+
+/// Draws a vertical bar of the colormap labelled in dB from `db_min` to `db_max`.
+fn draw_colorbar(area: &DrawingArea<BitMapBackend<'_>, Shift>, colormap: Colormap, db_min: f64, db_max: f64) -> Result<(), Box<dyn Error>> {
+    let mut chart = ChartBuilder::on(area)
+        .margin(10)
+        .x_label_area_size(40)
+        .right_y_label_area_size(60)
+        .build_cartesian_2d(0.0..1.0, db_min..db_max)?;
+    // Default label fonts scale with the area and would be tiny on the narrow bar
+    chart
+        .configure_mesh()
+        .disable_mesh()
+        .disable_x_axis()
+        .y_desc("Power [dB]")
+        .y_label_style(("sans-serif", 12))
+        .axis_desc_style(("sans-serif", 12))
+        .draw()?;
+
+    let steps = 256;
+    let step = (db_max - db_min) / steps as f64;
+    chart.draw_series((0..steps).map(|i| {
+        let low = db_min + i as f64 * step;
+        Rectangle::new([(0.0, low), (1.0, low + step)], colormap.color((i as f64 + 0.5) / steps as f64).filled())
+    }))?;
+    Ok(())
+}
+
+/// Generates a spectrogram heatmap with the power in dB mapped onto a colormap. The color scale spans the
+///     80 dB below the strongest cell unless limited by the options, cells outside are clamped to the end colors.
+///     The time and frequency axes span all cells unless limited by `axes`, their autoscale is not used.
+pub fn generate_spectrogram_plot(
+    spectrogram: &Spectrogram,
+    title: &str,
+    options: &SpectrogramPlotOptions,
+    axes: &PlotOptions,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let (times, freqs) = (&spectrogram.times, &spectrogram.freqs);
+    if times.is_empty() || freqs.is_empty() || spectrogram.power.len() != times.len() {
+        return Err("Spectrogram must have at least one segment and one row of power per segment.".into());
+    }
+    let strongest = spectrogram.power.iter().flatten().fold(f64::MIN, |max, p| max.max(10.0 * p.max(1e-300).log10()));
+    let db_max = options.db_max.unwrap_or(strongest);
+    let db_min = options.db_min.unwrap_or(db_max - 80.0);
+    if db_min.is_nan() || db_max.is_nan() || db_min >= db_max {
+        return Err("db_min must be below db_max.".into());
+    }
+    let (dt, df) = cell_steps(spectrogram);
+    let top = freqs[freqs.len() - 1] + df;
+    let span = |axis: &AxisOptions, name: &str, low: f64, high: f64| {
+        AxisOptions { autoscale: Autoscale::MinMax, ..axis.clone() }.range(name, [low, high].into_iter(), 0.0)
+    };
+    let x_range = span(&axes.x, "time", times[0] - dt / 2.0, times[times.len() - 1] + dt / 2.0)?;
+
+    let width = 1024;
+    let height = 768;
+    let mut buffer: Vec<u8> = vec![0; (width * height * 3) as usize];
+    {
+        let root_area = BitMapBackend::with_buffer(&mut buffer, (width, height)).into_drawing_area();
+        root_area.fill(&WHITE)?;
+        let root_area = root_area.titled(title, ("sans-serif", 30))?;
+        let (plot_area, bar_area) = if options.colorbar {
+            let (plot_area, bar_area) = root_area.split_horizontally(width - 120);
+            (plot_area, Some(bar_area))
+        } else {
+            (root_area.clone(), None)
+        };
+
+        let color = |value: f64| options.colormap.color((value - db_min) / (db_max - db_min));
+        if options.log_frequency {
+            let floor = freqs.iter().copied().find(|&f| f > 0.0).ok_or("A log frequency axis needs a bin above 0 Hz.")?;
+            let y_range = span(&axes.y, "frequency", floor, top)?;
+            if y_range.start <= 0.0 {
+                return Err("A log frequency axis needs frequency limits above 0 Hz.".into());
+            }
+            draw_spectrogram_cells(&plot_area, spectrogram, x_range, y_range.clone().log_scale(), y_range, &color, axes)?;
+        } else {
+            let y_range = span(&axes.y, "frequency", freqs[0], top)?;
+            draw_spectrogram_cells(&plot_area, spectrogram, x_range, y_range.clone(), y_range, &color, axes)?;
+        }
+        if let Some(bar_area) = bar_area {
+            draw_colorbar(&bar_area, options.colormap, db_min, db_max)?;
+        }
+
+        root_area.present()?;
+    }
+
+    let mut png_buffer = Vec::new();
+    let encoder = PngEncoder::new(&mut png_buffer);
+    encoder.write_image(&buffer, width, height, image::ExtendedColorType::Rgb8)?;
+
+    Ok(png_buffer)
+}
+
+/// Draws a PSD in dB against `reference` over frequency, with an optional confidence band shaded behind it.
+///     The axes are set up by `options` as in [`generate_plot_with_options`], the y axis fitting the band too.
+///     Bins that are empty, NaN or infinite leave gaps in the line and band.
+pub fn generate_psd_plot(
+    freqs: &[f64],
+    psd: &[f64],
+    band: Option<(&[f64], &[f64])>,
+    title: &str,
+    reference: DbReference,
+    options: &PlotOptions,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    if freqs.is_empty() || freqs.len() != psd.len() {
+        return Err("freqs and psd must be non-empty and have the same length.".into());
+    }
+    if band.is_some_and(|(lower, upper)| lower.len() != psd.len() || upper.len() != psd.len()) {
+        return Err("The confidence bounds must have one value per bin.".into());
+    }
+
+    let db = |values: &[f64]| kernels::to_db(values, reference.power(), 10.0);
+    let line = db(psd);
+    let band = band.map(|(lower, upper)| (db(lower), db(upper)));
+    let levels = line.iter().chain(band.iter().flat_map(|(lower, upper)| lower.iter().chain(upper)));
+    let x_range = options.x.range("x", freqs.iter().copied(), 0.0)?;
+    let y_range = options.y.range("y", levels.copied(), 0.05)?;
+
+    let width = 1024;
+    let height = 768;
+    let mut buffer: Vec<u8> = vec![0; (width * height * 3) as usize];
+    {
+        let root_area = BitMapBackend::with_buffer(&mut buffer, (width, height)).into_drawing_area();
+        root_area.fill(&WHITE)?;
+
+        let mut chart = ChartBuilder::on(&root_area)
+            .caption(title, ("sans-serif", 30))
+            .margin(10)
+            .x_label_area_size(40)
+            .y_label_area_size(60)
+            .build_cartesian_2d(x_range, y_range)?;
+
+        let x_format = |v: &f64| options.x.format.format(*v);
+        let y_format = |v: &f64| options.y.format.format(*v);
+        let mut mesh = chart.configure_mesh();
+        mesh.x_desc("Frequency [Hz]").y_desc(format!("PSD [{}/Hz]", reference.label()));
+        configure_ticks(&mut mesh, options, &x_format, &y_format);
+        mesh.draw()?;
+
+        if let Some((lower, upper)) = &band {
+            let bins: Vec<(f64, f64, f64)> = (0..freqs.len()).map(|k| (freqs[k], lower[k], upper[k])).collect();
+            for run in bins.split(|(f, l, u)| !(f.is_finite() && l.is_finite() && u.is_finite())).filter(|run| !run.is_empty()) {
+                // Upper bound left to right, then the lower bound back, closes the band outline
+                let outline: Vec<(f64, f64)> =
+                    run.iter().map(|&(f, _, u)| (f, u)).chain(run.iter().rev().map(|&(f, l, _)| (f, l))).collect();
+                chart.draw_series(std::iter::once(Polygon::new(outline, RED.mix(0.2).filled())))?;
+            }
+        }
+        let points: Vec<(f64, f64)> = freqs.iter().copied().zip(line).collect();
+        for run in finite_runs(&points) {
+            chart.draw_series(LineSeries::new(run.iter().copied(), &RED))?;
+        }
+
+        root_area.present()?;
+    }
+
+    let mut png_buffer = Vec::new();
+    let encoder = PngEncoder::new(&mut png_buffer);
+    encoder.write_image(&buffer, width, height, image::ExtendedColorType::Rgb8)?;
+
+    Ok(png_buffer)
+}
+
+/// Overlays the magnitude responses of all windows of `nperseg` points over the first 32 bins,
+///     drawing `highlight` in red on top of the faded others.
+pub fn generate_window_plot(highlight: Window, nperseg: usize) -> Result<Vec<u8>, Box<dyn Error>> {
+    if nperseg < 2 {
+        return Err("nperseg must be at least 2.".into());
+    }
+
+    let width = 1024;
+    let height = 768;
+    let max_bin = (nperseg / 2).min(32) as f64;
+    let mut buffer: Vec<u8> = vec![0; (width * height * 3) as usize];
+    {
+        let root_area = BitMapBackend::with_buffer(&mut buffer, (width, height)).into_drawing_area();
+        root_area.fill(&WHITE)?;
+
+        let mut chart = ChartBuilder::on(&root_area)
+            .caption(format!("Window responses, {} points", nperseg), ("sans-serif", 30))
+            .margin(10)
+            .x_label_area_size(40)
+            .y_label_area_size(60)
+            .build_cartesian_2d(0.0..max_bin, -160.0..5.0)?;
+
+        chart
+            .configure_mesh()
+            .x_desc("Frequency offset [bins]")
+            .y_desc("Magnitude [dB]")
+            .draw()?;
+
+        // Draw the highlighted window last so it stays visible where the curves overlap
+        let mut windows: Vec<Window> = Window::ALL.into_iter().filter(|w| *w != highlight).collect();
+        windows.push(highlight);
+        for (i, window) in windows.into_iter().enumerate() {
+            let (bins, db) = window.frequency_response(nperseg);
+            let points = bins.into_iter().zip(db).take_while(|(bin, _)| *bin <= max_bin).map(|(bin, d)| (bin, d.max(-160.0)));
+            let style = if window == highlight {
+                RED.stroke_width(1)
+            } else {
+                Palette99::pick(i).mix(0.4).stroke_width(1)
+            };
+            chart
+                .draw_series(LineSeries::new(points, style))?
+                .label(format!("{:?}", window))
+                .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], style));
+        }
+
+        chart.configure_series_labels().background_style(WHITE.mix(0.8)).border_style(BLACK).draw()?;
+        root_area.present()?;
+    }
+
+    let mut png_buffer = Vec::new();
+    let encoder = PngEncoder::new(&mut png_buffer);
+    encoder.write_image(&buffer, width, height, image::ExtendedColorType::Rgb8)?;
+
+    Ok(png_buffer)
+}
+
+/// Overlays the measured and reference spectra of a comparison in dB above a panel with their difference. The x
+///     options apply to both panels and the y options to the spectra, the difference axis always fits the data
+///     and 0 dB. Empty, NaN or infinite bins leave gaps in the curves.
+pub fn generate_comparison_plot(comparison: &SpectrumComparison, title: &str, options: &PlotOptions) -> Result<Vec<u8>, Box<dyn Error>> {
+    let freqs = &comparison.freqs;
+    if freqs.is_empty() {
+        return Err("Comparison must have at least one bin.".into());
+    }
+
+    let width = 1024;
+    let height = 768;
+    let mut buffer: Vec<u8> = vec![0; (width * height * 3) as usize];
+    {
+        let root_area = BitMapBackend::with_buffer(&mut buffer, (width, height)).into_drawing_area();
+        root_area.fill(&WHITE)?;
+        let root_area = root_area.titled(title, ("sans-serif", 30))?;
+        let (upper, lower) = root_area.split_vertically(440);
+
+        let db = |values: &[f64]| freqs.iter().copied().zip(values.iter().map(|v| 20.0 * v.abs().log10())).collect::<Vec<_>>();
+        let (measured, reference) = (db(&comparison.measured), db(&comparison.reference));
+        let x_range = options.x.range("x", freqs.iter().copied(), 0.0)?;
+        let x_format = |v: &f64| options.x.format.format(*v);
+        let y_format = |v: &f64| options.y.format.format(*v);
+
+        let mut chart = ChartBuilder::on(&upper)
+            .margin(10)
+            .x_label_area_size(30)
+            .y_label_area_size(60)
+            .build_cartesian_2d(x_range.clone(), options.y.range("y", measured.iter().chain(&reference).map(|(_, y)| *y), 0.05)?)?;
+        let mut mesh = chart.configure_mesh();
+        mesh.y_desc("Amplitude [dB]");
+        configure_ticks(&mut mesh, options, &x_format, &y_format);
+        mesh.draw()?;
+        for (points, name, color) in [(&reference, "Reference", BLUE), (&measured, "Measured", RED)] {
+            for (k, run) in finite_runs(points).enumerate() {
+                let series = chart.draw_series(LineSeries::new(run.iter().copied(), &color))?;
+                if k == 0 {
+                    series.label(name).legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color));
+                }
+            }
+        }
+        chart.configure_series_labels().background_style(WHITE.mix(0.8)).border_style(BLACK).draw()?;
+
+        let difference_axis = AxisOptions::default();
+        let difference_range = difference_axis.range("difference", comparison.difference_db.iter().copied().chain([0.0]), 0.05)?;
+        let mut chart = ChartBuilder::on(&lower)
+            .margin(10)
+            .x_label_area_size(40)
+            .y_label_area_size(60)
+            .build_cartesian_2d(x_range, difference_range)?;
+        let mut mesh = chart.configure_mesh();
+        mesh.x_desc("Frequency [Hz]").y_desc("Difference [dB]");
+        configure_ticks(&mut mesh, &PlotOptions { x: options.x.clone(), y: difference_axis }, &x_format, &y_format);
+        mesh.draw()?;
+        let difference: Vec<(f64, f64)> = freqs.iter().copied().zip(comparison.difference_db.iter().copied()).collect();
+        for run in finite_runs(&difference) {
+            chart.draw_series(LineSeries::new(run.iter().copied(), &BLACK))?;
+        }
+
+        root_area.present()?;
+    }
+
+    let mut png_buffer = Vec::new();
+    let encoder = PngEncoder::new(&mut png_buffer);
+    encoder.write_image(&buffer, width, height, image::ExtendedColorType::Rgb8)?;
+
+    Ok(png_buffer)
+}
+
+/// Draws a spectrum in dB with the limit lines of a mask, upper limits in red and lower limits in green. The axes
+///     are set up by `options` as in [`generate_plot_with_options`], fitting the mask lines too. NaN or infinite
+///     levels leave gaps in the spectrum.
+pub fn generate_mask_plot(
+    freqs: &[f64],
+    magnitude_db: &[f64],
+    segments: &[MaskSegment],
+    title: &str,
+    options: &PlotOptions,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    if freqs.is_empty() || freqs.len() != magnitude_db.len() {
+        return Err("freqs and magnitude_db must be non-empty and have the same length.".into());
+    }
+
+    // Each segment is drawn as a polyline, since sloped limits are straight over log frequency only
+    let lines: Vec<(MaskKind, Vec<(f64, f64)>)> = segments
+        .iter()
+        .map(|segment| {
+            let points = (0..=64)
+                .map(|i| {
+                    let f = segment.start_freq + (segment.end_freq - segment.start_freq) * i as f64 / 64.0;
+                    (f, segment.limit(f))
+                })
+                .collect();
+            (segment.kind, points)
+        })
+        .collect();
+    let points: Vec<(f64, f64)> = freqs.iter().copied().zip(magnitude_db.iter().copied()).collect();
+    let all = || points.iter().copied().chain(lines.iter().flat_map(|(_, line)| line.iter().copied()));
+    let x_range = options.x.range("x", all().map(|(x, _)| x), 0.0)?;
+    let y_range = options.y.range("y", all().map(|(_, y)| y), 0.05)?;
+
+    let width = 1024;
+    let height = 768;
+    let mut buffer: Vec<u8> = vec![0; (width * height * 3) as usize];
+    {
+        let root_area = BitMapBackend::with_buffer(&mut buffer, (width, height)).into_drawing_area();
+        root_area.fill(&WHITE)?;
+
+        let mut chart = ChartBuilder::on(&root_area)
+            .caption(title, ("sans-serif", 30))
+            .margin(10)
+            .x_label_area_size(40)
+            .y_label_area_size(60)
+            .build_cartesian_2d(x_range, y_range)?;
+
+        let x_format = |v: &f64| options.x.format.format(*v);
+        let y_format = |v: &f64| options.y.format.format(*v);
+        let mut mesh = chart.configure_mesh();
+        mesh.x_desc("Frequency [Hz]").y_desc("Magnitude [dB]");
+        configure_ticks(&mut mesh, options, &x_format, &y_format);
+        mesh.draw()?;
+
+        for run in finite_runs(&points) {
+            chart.draw_series(LineSeries::new(run.iter().copied(), &BLUE))?;
+        }
+        for (kind, line) in &lines {
+            let color = if *kind == MaskKind::Upper { RED } else { GREEN };
+            for run in finite_runs(line) {
+                chart.draw_series(LineSeries::new(run.iter().copied(), color.stroke_width(2)))?;
+            }
+        }
+
+        root_area.present()?;
+    }
+
+    let mut png_buffer = Vec::new();
+    let encoder = PngEncoder::new(&mut png_buffer);
+    encoder.write_image(&buffer, width, height, image::ExtendedColorType::Rgb8)?;
+
+    Ok(png_buffer)
+}
+
+/// Draws a Bode plot of a frequency response such as that of [`freqz`](crate::freqz), the magnitude in dB above
+///     the phase in degrees, unwrapped so it runs on across ±180°. The x options apply to both panels and the
+///     y options to the magnitude, the phase axis always fits the data.
+pub fn generate_bode_plot(
+    freqs: &[f64],
+    magnitude_db: &[f64],
+    phase: &[f64],
+    title: &str,
+    options: &PlotOptions,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    if freqs.is_empty() || freqs.len() != magnitude_db.len() || freqs.len() != phase.len() {
+        return Err("freqs, magnitude_db and phase must be non-empty and have the same length.".into());
+    }
+
+    let mut offset = 0.0;
+    let mut previous: Option<f64> = None;
+    let degrees: Vec<f64> = phase
+        .iter()
+        .map(|&p| {
+            if let Some(last) = previous.filter(|_| p.is_finite()) {
+                offset -= std::f64::consts::TAU * ((p - last) / std::f64::consts::TAU).round();
+            }
+            if p.is_finite() {
+                previous = Some(p);
+            }
+            (p + offset).to_degrees()
+        })
+        .collect();
+    let magnitude: Vec<(f64, f64)> = freqs.iter().copied().zip(magnitude_db.iter().copied()).collect();
+    let phase: Vec<(f64, f64)> = freqs.iter().copied().zip(degrees).collect();
+
+    let width = 1024;
+    let height = 768;
+    let mut buffer: Vec<u8> = vec![0; (width * height * 3) as usize];
+    {
+        let root_area = BitMapBackend::with_buffer(&mut buffer, (width, height)).into_drawing_area();
+        root_area.fill(&WHITE)?;
+        let root_area = root_area.titled(title, ("sans-serif", 30))?;
+        let (upper, lower) = root_area.split_vertically(360);
+
+        let x_range = options.x.range("x", freqs.iter().copied(), 0.0)?;
+        let x_format = |v: &f64| options.x.format.format(*v);
+        let y_format = |v: &f64| options.y.format.format(*v);
+
+        let mut chart = ChartBuilder::on(&upper)
+            .margin(10)
+            .x_label_area_size(30)
+            .y_label_area_size(60)
+            .build_cartesian_2d(x_range.clone(), options.y.range("magnitude", magnitude.iter().map(|(_, y)| *y), 0.05)?)?;
+        let mut mesh = chart.configure_mesh();
+        mesh.y_desc("Magnitude [dB]");
+        configure_ticks(&mut mesh, options, &x_format, &y_format);
+        mesh.draw()?;
+        for run in finite_runs(&magnitude) {
+            chart.draw_series(LineSeries::new(run.iter().copied(), &BLUE))?;
+        }
+
+        let phase_axis = AxisOptions::default();
+        let mut chart = ChartBuilder::on(&lower)
+            .margin(10)
+            .x_label_area_size(40)
+            .y_label_area_size(60)
+            .build_cartesian_2d(x_range, phase_axis.range("phase", phase.iter().map(|(_, y)| *y), 0.05)?)?;
+        let mut mesh = chart.configure_mesh();
+        mesh.x_desc("Frequency [Hz]").y_desc("Phase [°]");
+        configure_ticks(&mut mesh, &PlotOptions { x: options.x.clone(), y: phase_axis }, &x_format, &y_format);
+        mesh.draw()?;
+        for run in finite_runs(&phase) {
+            chart.draw_series(LineSeries::new(run.iter().copied(), &RED))?;
+        }
+
+        root_area.present()?;
+    }
+
+    let mut png_buffer = Vec::new();
+    let encoder = PngEncoder::new(&mut png_buffer);
+    encoder.write_image(&buffer, width, height, image::ExtendedColorType::Rgb8)?;
+
+    Ok(png_buffer)
+}
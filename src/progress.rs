@@ -0,0 +1,45 @@
+//! Progress reporting and cancellation for long running operations.
+
+use std::error::Error;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// Error returned when a progress callback cancels an operation.
+#[derive(Debug)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Operation cancelled.")
+    }
+}
+
+impl Error for Cancelled {}
+
+/// Forwards the fraction of completed work (0.0 to 1.0) to a callback whenever another percent is done, and at
+///     least every 100 ms so callers can react to cancellation promptly. The callback returns `false` to cancel.
+pub struct Progress<'a> {
+    callback: &'a mut dyn FnMut(f64) -> bool,
+    reported: f64,
+    reported_at: Instant,
+}
+
+impl<'a> Progress<'a> {
+    pub fn new(callback: &'a mut dyn FnMut(f64) -> bool) -> Self {
+        Progress { callback, reported: f64::NEG_INFINITY, reported_at: Instant::now() }
+    }
+
+    /// Reports the fraction of completed work, failing with [`Cancelled`] when the callback asks to stop.
+    pub fn update(&mut self, fraction: f64) -> Result<(), Cancelled> {
+        if fraction < 1.0 && fraction - self.reported < 0.01 && self.reported_at.elapsed() < Duration::from_millis(100) {
+            return Ok(());
+        }
+        self.reported = fraction;
+        self.reported_at = Instant::now();
+        if (self.callback)(fraction) {
+            Ok(())
+        } else {
+            Err(Cancelled)
+        }
+    }
+}
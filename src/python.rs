@@ -0,0 +1,3017 @@
+// The `#[pyfunction]` wrappers generated by pyo3 0.22 trip this lint on every `PyResult` return.
+#![allow(clippy::useless_conversion)]
+
+use pyo3::prelude::*;
+use pyo3::buffer::PyBuffer;
+use pyo3::types::{PyBytes, PyDict};
+use rustfft::num_complex::Complex;
+use std::ffi::CStr;
+
+use crate::*;
+
+/// One dimensional float data accepted from Python.
+///     Objects exposing a float64 buffer (numpy arrays, `array.array('d')`) are copied directly, pandas and polars
+///     objects are converted with `to_numpy()` first and anything else falls back to a sequence conversion.
+struct Samples(Vec<f64>);
+
+impl<'py> FromPyObject<'py> for Samples {
+    fn extract_bound(obj: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let obj = if obj.hasattr("to_numpy")? { obj.call_method0("to_numpy")? } else { obj.clone() };
+        if let Ok(buffer) = PyBuffer::<f64>::get_bound(&obj) {
+            if buffer.dimensions() == 1 {
+                return Ok(Samples(buffer.to_vec(obj.py())?));
+            }
+        }
+        Ok(Samples(obj.extract()?))
+    }
+}
+
+/// Complex data accepted from Python.
+///     complex128 and complex64 buffers (e.g. numpy arrays) are read as interleaved pairs without deinterleaving,
+///     anything else is converted as a sequence of Python complex numbers.
+struct ComplexSamples(Vec<Complex<f64>>);
+
+impl<'py> FromPyObject<'py> for ComplexSamples {
+    fn extract_bound(obj: &Bound<'py, PyAny>) -> PyResult<Self> {
+        match read_complex_buffer(obj) {
+            Some(samples) => Ok(ComplexSamples(samples)),
+            None => Ok(ComplexSamples(obj.extract()?)),
+        }
+    }
+}
+
+/// Copies a C contiguous complex128 ("Zd") or complex64 ("Zf") buffer, returning `None` for anything else.
+fn read_complex_buffer(obj: &Bound<'_, PyAny>) -> Option<Vec<Complex<f64>>> {
+    let mut view = std::mem::MaybeUninit::<pyo3::ffi::Py_buffer>::uninit();
+    let flags = pyo3::ffi::PyBUF_FORMAT | pyo3::ffi::PyBUF_C_CONTIGUOUS;
+    // SAFETY: a successful PyObject_GetBuffer initialises the view, which is released below
+    if unsafe { pyo3::ffi::PyObject_GetBuffer(obj.as_ptr(), view.as_mut_ptr(), flags) } != 0 {
+        // Not a buffer (or not contiguous), clear the error and let the caller fall back
+        drop(PyErr::take(obj.py()));
+        return None;
+    }
+    let mut view = unsafe { view.assume_init() };
+
+    // SAFETY: the view stays valid until PyBuffer_Release and `len` is its size in bytes
+    let format = if view.format.is_null() { &b"B"[..] } else { unsafe { CStr::from_ptr(view.format) }.to_bytes() };
+    let bytes = unsafe { std::slice::from_raw_parts(view.buf as *const u8, view.len as usize) };
+    let (big_endian, code) = match format {
+        [b'>' | b'!', code @ ..] => (true, code),
+        [b'<', code @ ..] => (false, code),
+        [b'@' | b'=', code @ ..] => (cfg!(target_endian = "big"), code),
+        code => (cfg!(target_endian = "big"), code),
+    };
+    let samples = match code {
+        b"Zd" => Some(
+            bytes
+                .chunks_exact(16)
+                .map(|c| {
+                    let part = |b: &[u8]| {
+                        let raw = b.try_into().unwrap_or([0; 8]);
+                        if big_endian { f64::from_be_bytes(raw) } else { f64::from_le_bytes(raw) }
+                    };
+                    Complex::new(part(&c[..8]), part(&c[8..]))
+                })
+                .collect(),
+        ),
+        b"Zf" => Some(
+            bytes
+                .chunks_exact(8)
+                .map(|c| {
+                    let part = |b: &[u8]| {
+                        let raw = b.try_into().unwrap_or([0; 4]);
+                        (if big_endian { f32::from_be_bytes(raw) } else { f32::from_le_bytes(raw) }) as f64
+                    };
+                    Complex::new(part(&c[..4]), part(&c[4..]))
+                })
+                .collect(),
+        ),
+        _ => None,
+    };
+
+    unsafe { pyo3::ffi::PyBuffer_Release(&mut view) };
+    samples
+}
+
+/// Precision of interleaved complex results handed back to Python.
+enum ComplexDtype {
+    Complex64,
+    Complex128,
+}
+
+impl<'py> FromPyObject<'py> for ComplexDtype {
+    fn extract_bound(obj: &Bound<'py, PyAny>) -> PyResult<Self> {
+        match obj.extract::<String>()?.as_str() {
+            "complex64" => Ok(ComplexDtype::Complex64),
+            "complex128" => Ok(ComplexDtype::Complex128),
+            other => Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Unknown dtype '{}', expected 'complex64' or 'complex128'.",
+                other
+            ))),
+        }
+    }
+}
+
+/// Packs complex values into native endian interleaved bytes, readable with `numpy.frombuffer(data, dtype)`.
+fn into_interleaved_bytes(py: Python<'_>, data: &[Complex<f64>], dtype: ComplexDtype) -> PyObject {
+    let bytes: Vec<u8> = match dtype {
+        ComplexDtype::Complex128 => data.iter().flat_map(|c| [c.re.to_ne_bytes(), c.im.to_ne_bytes()]).flatten().collect(),
+        ComplexDtype::Complex64 => data
+            .iter()
+            .flat_map(|c| [(c.re as f32).to_ne_bytes(), (c.im as f32).to_ne_bytes()])
+            .flatten()
+            .collect(),
+    };
+    PyBytes::new_bound(py, &bytes).into_py(py)
+}
+
+/// Multi-channel data accepted from Python: a list of channels, or interleaved samples split by a `channels` count.
+#[derive(FromPyObject)]
+enum PyChannels {
+    Channels(Matrix),
+    Interleaved(Samples),
+}
+
+impl PyChannels {
+    /// The data as one vector per channel. `channels` is required for interleaved data and checked otherwise.
+    fn into_matrix(self, channels: Option<usize>) -> PyResult<Matrix> {
+        let matrix = match (self, channels) {
+            (PyChannels::Channels(matrix), _) => matrix,
+            (PyChannels::Interleaved(samples), Some(channels)) => deinterleave(&samples.0, channels)
+                .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?,
+            (PyChannels::Interleaved(samples), None) => vec![samples.0],
+        };
+        match channels {
+            Some(channels) if channels != matrix.len() => Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Expected {} channels, got {}.",
+                channels,
+                matrix.len()
+            ))),
+            _ => Ok(matrix),
+        }
+    }
+}
+
+/// How column results are handed back to Python.
+enum ColumnOutput {
+    Tuple,
+    /// A `{name: column}` dict, which `pandas.DataFrame` accepts directly.
+    Dict,
+    Polars,
+}
+
+impl<'py> FromPyObject<'py> for ColumnOutput {
+    fn extract_bound(obj: &Bound<'py, PyAny>) -> PyResult<Self> {
+        match obj.extract::<String>()?.as_str() {
+            "tuple" => Ok(ColumnOutput::Tuple),
+            "dict" => Ok(ColumnOutput::Dict),
+            "polars" => Ok(ColumnOutput::Polars),
+            other => Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Unknown output '{}', expected 'tuple', 'dict' or 'polars'.",
+                other
+            ))),
+        }
+    }
+}
+
+/// Returns the columns in the requested output format.
+fn into_columns(py: Python<'_>, columns: Vec<(&str, Vec<f64>)>, output: ColumnOutput) -> PyResult<PyObject> {
+    if let ColumnOutput::Tuple = output {
+        let values: Vec<PyObject> = columns.into_iter().map(|(_, column)| column.into_py(py)).collect();
+        return Ok(pyo3::types::PyTuple::new_bound(py, values).into_py(py));
+    }
+
+    if let ColumnOutput::Polars = output {
+        let columns = columns.into_iter().map(|(name, column)| (name.to_string(), column)).collect();
+        let table = ArrowTable::new(columns).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        let table = Py::new(py, PyArrowTable { inner: table })?;
+        return Ok(py.import_bound("polars")?.getattr("DataFrame")?.call1((table,))?.into_py(py));
+    }
+
+    let dict = PyDict::new_bound(py);
+    for (name, column) in columns {
+        dict.set_item(name, column)?;
+    }
+    Ok(dict.into_py(py))
+}
+
+/// Columns exported through the Arrow PyCapsule interface, which polars, pyarrow and other Arrow consumers import
+///     without copying.
+#[pyclass(name = "ArrowTable")]
+struct PyArrowTable {
+    inner: ArrowTable,
+}
+
+#[pymethods]
+impl PyArrowTable {
+    #[pyo3(signature = (requested_schema=None))]
+    fn __arrow_c_stream__<'py>(
+        &self,
+        py: Python<'py>,
+        requested_schema: Option<PyObject>,
+    ) -> PyResult<Bound<'py, pyo3::types::PyCapsule>> {
+        // The columns are float64 already, so a requested schema cannot be honoured any better than the native one
+        let _ = requested_schema;
+        pyo3::types::PyCapsule::new_bound(py, self.inner.to_stream(), Some(c"arrow_array_stream".into()))
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.num_rows()
+    }
+}
+
+/// Runs `operation` with a progress callback that checks for pending signals, so Ctrl-C raises `KeyboardInterrupt`
+///     while it runs, and forwards the percentage completed to the optional Python `progress` callable. The callable
+///     cancels the operation by returning `False`, which raises `InterruptedError`, and any exception it raises is
+///     re-raised. Errors of the operation itself are returned in the inner result.
+fn with_progress<T>(
+    py: Python<'_>,
+    progress: Option<PyObject>,
+    operation: impl FnOnce(&mut dyn FnMut(f64) -> bool) -> Result<T, Box<dyn std::error::Error>>,
+) -> PyResult<Result<T, Box<dyn std::error::Error>>> {
+    let mut raised = None;
+    let result = operation(&mut |fraction| {
+        let outcome = py.check_signals().and_then(|_| match &progress {
+            Some(callback) => callback.call1(py, (fraction * 100.0,)),
+            None => Ok(py.None()),
+        });
+        match outcome {
+            Ok(value) => !matches!(value.extract::<bool>(py), Ok(false)),
+            Err(e) => {
+                raised = Some(e);
+                false
+            }
+        }
+    });
+
+    if let Some(e) = raised {
+        return Err(e);
+    }
+    match result {
+        Err(e) if e.is::<Cancelled>() => Err(pyo3::exceptions::PyInterruptedError::new_err(e.to_string())),
+        result => Ok(result),
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (file_path, output=ColumnOutput::Tuple, progress=None))]
+fn read_csv_py(py: Python<'_>, file_path: String, output: ColumnOutput, progress: Option<PyObject>) -> PyResult<PyObject> {
+    let (time, measured_data) = with_progress(py, progress, |p| read_csv_with_progress(&file_path, p))?
+        .map_err(|e| pyo3::exceptions::PyIOError::new_err(e.to_string()))?;
+    into_columns(py, vec![("time", time), ("measured_data", measured_data)], output)
+}
+
+#[pyfunction]
+fn read_dataframe_py(frame: &Bound<'_, PyAny>, time_column: &str, data_column: &str) -> PyResult<(Vec<f64>, Vec<f64>)> {
+    let time: Samples = frame.get_item(time_column)?.extract()?;
+    let measured_data: Samples = frame.get_item(data_column)?.extract()?;
+    if time.0.len() != measured_data.0.len() {
+        return Err(pyo3::exceptions::PyValueError::new_err("Time and data columns must have the same length."));
+    }
+    Ok((time.0, measured_data.0))
+}
+
+#[pyfunction]
+#[pyo3(signature = (file_path, trace_range=None))]
+fn read_segy_py(file_path: String, trace_range: Option<(usize, usize)>) -> PyResult<(f64, Matrix)> {
+    read_segy(&file_path, trace_range).map_err(|e| pyo3::exceptions::PyIOError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+fn read_mseed_py(file_path: String) -> PyResult<Vec<(String, f64, Vec<f64>)>> {
+    read_mseed(&file_path)
+        .map(|channels| channels.into_iter().map(|c| (c.id, c.sample_rate, c.data)).collect())
+        .map_err(|e| pyo3::exceptions::PyIOError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (file_path, channels=None))]
+fn read_edf_py(file_path: String, channels: Option<Vec<String>>) -> PyResult<Vec<(String, f64, Vec<f64>)>> {
+    read_edf(&file_path, channels)
+        .map(|channels| channels.into_iter().map(|c| (c.id, c.sample_rate, c.data)).collect())
+        .map_err(|e| pyo3::exceptions::PyIOError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+fn read_wav_py(file_path: String) -> PyResult<(f64, Matrix)> {
+    read_wav(&file_path).map_err(|e| pyo3::exceptions::PyIOError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+fn read_flac_py(file_path: String) -> PyResult<(f64, Matrix)> {
+    read_flac(&file_path).map_err(|e| pyo3::exceptions::PyIOError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+fn read_audio_py(file_path: String) -> PyResult<(f64, Matrix)> {
+    read_audio(&file_path).map_err(|e| pyo3::exceptions::PyIOError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+fn deinterleave_py(data: Samples, channels: usize) -> PyResult<Matrix> {
+    deinterleave(&data.0, channels).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+fn interleave_py(channels: Matrix) -> PyResult<Vec<f64>> {
+    interleave(&channels).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, indices, channels=None))]
+fn select_channels_py(data: PyChannels, indices: Vec<usize>, channels: Option<usize>) -> PyResult<Matrix> {
+    select_channels(&data.into_matrix(channels)?, &indices).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, mode="mean", channels=None))]
+fn to_mono_py(data: PyChannels, mode: &str, channels: Option<usize>) -> PyResult<Vec<f64>> {
+    let mode = match mode.to_lowercase().as_str() {
+        "mean" => MonoMode::Mean,
+        "sum" => MonoMode::Sum,
+        "side" => MonoMode::Side,
+        _ => {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Unknown mode '{}', expected mean, sum or side.",
+                mode
+            )))
+        }
+    };
+    to_mono(&data.into_matrix(channels)?, mode).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+/// Python iterator over the chunks of a [`WavStream`].
+#[pyclass(name = "WavStream")]
+struct PyWavStream {
+    inner: WavStream,
+}
+
+#[pymethods]
+impl PyWavStream {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<Matrix>> {
+        let inner = &mut self.inner;
+        py.allow_threads(|| inner.next().transpose().map_err(|e| e.to_string()))
+            .map_err(pyo3::exceptions::PyIOError::new_err)
+    }
+
+    #[getter]
+    fn sample_rate(&self) -> f64 {
+        self.inner.sample_rate()
+    }
+
+    #[getter]
+    fn channels(&self) -> usize {
+        self.inner.channels()
+    }
+
+    #[getter]
+    fn frames(&self) -> usize {
+        self.inner.frames()
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (file_path, chunk_frames=65536))]
+fn open_wav_stream_py(file_path: String, chunk_frames: usize) -> PyResult<PyWavStream> {
+    open_wav_stream(&file_path, chunk_frames)
+        .map(|inner| PyWavStream { inner })
+        .map_err(|e| pyo3::exceptions::PyIOError::new_err(e.to_string()))
+}
+
+/// Python handle of a live [`AudioCapture`] stream.
+#[cfg(all(feature = "audio", target_os = "linux"))]
+#[pyclass(name = "AudioCapture")]
+struct PyAudioCapture {
+    inner: AudioCapture,
+}
+
+#[cfg(all(feature = "audio", target_os = "linux"))]
+#[pymethods]
+impl PyAudioCapture {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python<'_>) -> PyResult<Matrix> {
+        let frames = self.inner.chunk_frames();
+        self.read(py, frames)
+    }
+
+    fn read(&mut self, py: Python<'_>, frames: usize) -> PyResult<Matrix> {
+        let inner = &mut self.inner;
+        py.allow_threads(|| inner.read(frames).map_err(|e| e.to_string()))
+            .map_err(pyo3::exceptions::PyIOError::new_err)
+    }
+
+    #[getter]
+    fn sample_rate(&self) -> f64 {
+        self.inner.sample_rate()
+    }
+
+    #[getter]
+    fn channels(&self) -> usize {
+        self.inner.channels()
+    }
+}
+
+#[cfg(all(feature = "audio", target_os = "linux"))]
+#[pyfunction]
+#[pyo3(signature = (fs=48000.0, channels=1, chunk_frames=4096, device="default"))]
+fn open_audio_capture_py(fs: f64, channels: usize, chunk_frames: usize, device: &str) -> PyResult<PyAudioCapture> {
+    AudioCapture::open(device, fs, channels, chunk_frames)
+        .map(|inner| PyAudioCapture { inner })
+        .map_err(|e| pyo3::exceptions::PyIOError::new_err(e.to_string()))
+}
+
+#[cfg(all(feature = "audio", target_os = "linux"))]
+#[pyfunction]
+#[pyo3(signature = (data, fs, channels=None, device="default", progress=None))]
+fn play_py(
+    py: Python<'_>,
+    data: PyChannels,
+    fs: f64,
+    channels: Option<usize>,
+    device: &str,
+    progress: Option<PyObject>,
+) -> PyResult<()> {
+    let data = data.into_matrix(channels)?;
+    with_progress(py, progress, |p| play_with_progress(&data, fs, device, p))?
+        .map_err(|e| pyo3::exceptions::PyIOError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, output=ColumnOutput::Tuple, precision="double"))]
+fn compute_fft_py(py: Python<'_>, data: Samples, output: ColumnOutput, precision: &str) -> PyResult<PyObject> {
+    let precision = match precision.to_lowercase().as_str() {
+        "double" => Precision::Double,
+        "extended" => Precision::Extended,
+        _ => {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Unknown precision '{}', expected double or extended.",
+                precision
+            )))
+        }
+    };
+    let (real, imag) = py
+        .allow_threads(|| compute_fft_with_precision(data.0, precision).map_err(|e| e.to_string()))
+        .map_err(pyo3::exceptions::PyValueError::new_err)?;
+    into_columns(py, vec![("real", real), ("imag", imag)], output)
+}
+
+#[pyfunction]
+#[pyo3(signature = (real, imag, output=ColumnOutput::Tuple))]
+fn compute_fft_complex_py(py: Python<'_>, real: Samples, imag: Samples, output: ColumnOutput) -> PyResult<PyObject> {
+    let (real, imag) = compute_fft_complex(real.0, imag.0).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+    into_columns(py, vec![("real", real), ("imag", imag)], output)
+}
+
+#[pyfunction]
+#[pyo3(signature = (real, imag, output=ColumnOutput::Tuple))]
+fn compute_ifft_complex_py(py: Python<'_>, real: Samples, imag: Samples, output: ColumnOutput) -> PyResult<PyObject> {
+    let (real, imag) = compute_ifft_complex(real.0, imag.0).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+    into_columns(py, vec![("real", real), ("imag", imag)], output)
+}
+
+#[pyfunction]
+#[pyo3(signature = (real, imag=None, word_bits=16, frac_bits=15, scale=true))]
+fn compute_fft_fixed_py(
+    real: Vec<i32>,
+    imag: Option<Vec<i32>>,
+    word_bits: u32,
+    frac_bits: u32,
+    scale: bool,
+) -> PyResult<(Vec<i32>, Vec<i32>)> {
+    let word = match word_bits {
+        16 => FixedWord::I16,
+        32 => FixedWord::I32,
+        _ => {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Unsupported word size {}, expected 16 or 32.",
+                word_bits
+            )))
+        }
+    };
+    let imag = imag.unwrap_or_else(|| vec![0; real.len()]);
+    fixed_point_fft(real, imag, word, frac_bits, scale).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, inverse=false, dtype=ComplexDtype::Complex128))]
+fn compute_fft_interleaved_py(
+    py: Python<'_>,
+    data: ComplexSamples,
+    inverse: bool,
+    dtype: ComplexDtype,
+) -> PyResult<PyObject> {
+    let result = compute_fft_complex_interleaved(data.0, inverse)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+    Ok(into_interleaved_bytes(py, &result, dtype))
+}
+
+#[pyfunction]
+#[pyo3(signature = (real, imag, dtype=ComplexDtype::Complex128))]
+fn to_interleaved_py(py: Python<'_>, real: Samples, imag: Samples, dtype: ComplexDtype) -> PyResult<PyObject> {
+    if real.0.len() != imag.0.len() {
+        return Err(pyo3::exceptions::PyValueError::new_err("Real and imaginary parts must have the same length."));
+    }
+    let data: Vec<Complex<f64>> = real.0.into_iter().zip(imag.0).map(|(re, im)| Complex::new(re, im)).collect();
+    Ok(into_interleaved_bytes(py, &data, dtype))
+}
+
+#[pyfunction]
+fn from_interleaved_py(data: ComplexSamples) -> (Vec<f64>, Vec<f64>) {
+    data.0.into_iter().map(|c| (c.re, c.im)).unzip()
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, axis=1, progress=None))]
+fn compute_fft_axis_py(py: Python<'_>, data: Matrix, axis: usize, progress: Option<PyObject>) -> PyResult<(Matrix, Matrix)> {
+    with_progress(py, progress, |p| compute_fft_axis_with_progress(data, axis, p))?
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+fn fft_shift_py(real: Samples, imag: Samples) -> PyResult<(Vec<f64>, Vec<f64>)> {
+    fft_shift(real.0, imag.0).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+fn compute_magnitude_py(real: Samples, imag: Samples) -> PyResult<Vec<f64>> {
+    compute_magnitude(real.0, imag.0).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+fn compute_phase_py(real: Samples, imag: Samples) -> PyResult<Vec<f64>> {
+    compute_phase(real.0, imag.0).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+/// A dB reference given as a value or by name, e.g. `"dBFS"`.
+#[derive(FromPyObject)]
+enum PyDbReference {
+    Value(f64),
+    Name(String),
+}
+
+fn parse_db_reference(name: &str) -> PyResult<DbReference> {
+    match name.to_lowercase().replace(' ', "").as_str() {
+        "db" => Ok(DbReference::Amplitude(1.0)),
+        "dbfs" | "fs" => Ok(DbReference::FullScale),
+        "dbv" | "v" => Ok(DbReference::Volt),
+        "dbu" | "u" => Ok(DbReference::Dbu),
+        "dbspl" | "spl" => Ok(DbReference::SoundPressure),
+        _ => Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "Unknown dB reference '{}', expected dB, dBFS, dBV, dBu or dB SPL.",
+            name
+        ))),
+    }
+}
+
+impl PyDbReference {
+    /// The reference, with a value taken as the amplitude that reads 0 dB.
+    fn amplitude(self) -> PyResult<DbReference> {
+        match self {
+            PyDbReference::Value(value) => Ok(DbReference::Amplitude(value)),
+            PyDbReference::Name(name) => parse_db_reference(&name),
+        }
+    }
+
+    /// The reference, with a value taken as the power that reads 0 dB.
+    fn power(self) -> PyResult<DbReference> {
+        match self {
+            PyDbReference::Value(value) => Ok(DbReference::Amplitude(value.sqrt())),
+            PyDbReference::Name(name) => parse_db_reference(&name),
+        }
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (values, reference=PyDbReference::Value(1.0)))]
+fn amplitude_to_db_py(values: Samples, reference: PyDbReference) -> PyResult<Vec<f64>> {
+    amplitude_to_db(values.0, reference.amplitude()?.amplitude())
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (values, reference=PyDbReference::Value(1.0)))]
+fn power_to_db_py(values: Samples, reference: PyDbReference) -> PyResult<Vec<f64>> {
+    // A power given as a value is used as is rather than squared back from its root
+    let reference = match reference {
+        PyDbReference::Value(value) => value,
+        PyDbReference::Name(name) => parse_db_reference(&name)?.power(),
+    };
+    power_to_db(values.0, reference).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+fn generate_frequencies_py(len: usize, sampling_interval: f64) -> PyResult<Vec<f64>> {
+    generate_frequencies(len, sampling_interval)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+fn fft_shift_frequencies_py(data: Samples) -> PyResult<Vec<f64>> {
+    fft_shift_frequencies(data.0).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (magnitude, method="median", window=31, percentile=None))]
+fn estimate_noise_floor_py(
+    py: Python<'_>,
+    magnitude: Samples,
+    method: &str,
+    window: usize,
+    percentile: Option<f64>,
+) -> PyResult<Vec<f64>> {
+    let method = match method.to_lowercase().as_str() {
+        "median" => NoiseFloorMethod::Median,
+        "percentile" => NoiseFloorMethod::Percentile(percentile.unwrap_or(25.0)),
+        _ => {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Unknown noise floor method '{}', expected median or percentile.",
+                method
+            )))
+        }
+    };
+    py.allow_threads(|| estimate_noise_floor(magnitude.0, method, window).map_err(|e| e.to_string()))
+        .map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+#[pyfunction]
+#[pyo3(signature = (real, imag, floor=None))]
+fn whiten_py(real: Samples, imag: Samples, floor: Option<Samples>) -> PyResult<(Vec<f64>, Vec<f64>)> {
+    whiten(real.0, imag.0, floor.map(|f| f.0)).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+fn crop_spectrum_py(real: Samples, imag: Samples, freqs: Samples, f_lo: f64, f_hi: f64) -> PyResult<(Vec<f64>, Vec<f64>, Vec<f64>)> {
+    crop_spectrum(real.0, imag.0, freqs.0, f_lo, f_hi)
+        .map(|band| (band.real, band.imag, band.freqs))
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (real, imag, freqs, f_lo, f_hi, taper=None))]
+fn band_pass_fd_py(
+    real: Samples,
+    imag: Samples,
+    freqs: Samples,
+    f_lo: f64,
+    f_hi: f64,
+    taper: Option<f64>,
+) -> PyResult<(Vec<f64>, Vec<f64>)> {
+    let taper = taper.unwrap_or((f_hi - f_lo).abs() / 10.0);
+    band_pass_fd(real.0, imag.0, freqs.0, f_lo, f_hi, taper).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+fn band_snr_py(magnitude: Samples, freqs: Samples, signal_band: (f64, f64), noise_band: (f64, f64)) -> PyResult<f64> {
+    band_snr(magnitude.0, freqs.0, signal_band, noise_band)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, fs, nperseg, output=ColumnOutput::Tuple, progress=None))]
+fn compute_psd_welch_py(
+    py: Python<'_>,
+    data: Samples,
+    fs: f64,
+    nperseg: usize,
+    output: ColumnOutput,
+    progress: Option<PyObject>,
+) -> PyResult<PyObject> {
+    let (freqs, psd) = with_progress(py, progress, |p| compute_psd_welch_with_progress(data.0, fs, nperseg, p))?
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+    into_columns(py, vec![("frequency", freqs), ("psd", psd)], output)
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, fs, order=None, method="burg", n_freqs=512))]
+fn ar_psd_py(
+    py: Python<'_>,
+    data: Samples,
+    fs: f64,
+    order: Option<usize>,
+    method: &str,
+    n_freqs: usize,
+) -> PyResult<PyObject> {
+    let method = match method.to_lowercase().as_str() {
+        "burg" => ArMethod::Burg,
+        "yule-walker" | "yule_walker" | "yw" => ArMethod::YuleWalker,
+        _ => {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Unknown AR method '{}', expected burg or yule-walker.",
+                method
+            )))
+        }
+    };
+    let ar = ar_psd(data.0, fs, order, method, n_freqs)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+    let dict = PyDict::new_bound(py);
+    dict.set_item("freqs", ar.freqs)?;
+    dict.set_item("psd", ar.psd)?;
+    dict.set_item("coefficients", ar.coefficients)?;
+    dict.set_item("noise_variance", ar.noise_variance)?;
+    Ok(dict.into_py(py))
+}
+
+#[pyfunction]
+fn dpss_py(n: usize, nw: f64, k: usize) -> PyResult<Matrix> {
+    dpss(n, nw, k).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, fs, nw=4.0, k=None))]
+fn multitaper_psd_py(py: Python<'_>, data: Samples, fs: f64, nw: f64, k: Option<usize>) -> PyResult<(Vec<f64>, Vec<f64>)> {
+    py.allow_threads(|| multitaper_psd(data.0, fs, nw, k).map_err(|e| e.to_string()))
+        .map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+#[pyfunction]
+#[pyo3(signature = (psd, n_averages, confidence=0.95))]
+fn psd_confidence_py(psd: Samples, n_averages: f64, confidence: f64) -> PyResult<(Vec<f64>, Vec<f64>)> {
+    psd_confidence(&psd.0, n_averages, confidence).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, fs, n_boot=1000, nperseg=256, confidence=0.95, seed=None))]
+fn bootstrap_psd_py(
+    py: Python<'_>,
+    data: Samples,
+    fs: f64,
+    n_boot: usize,
+    nperseg: usize,
+    confidence: f64,
+    seed: Option<u64>,
+) -> PyResult<PyObject> {
+    let result = py
+        .allow_threads(|| bootstrap_psd(data.0, fs, n_boot, nperseg, confidence, seed).map_err(|e| e.to_string()))
+        .map_err(pyo3::exceptions::PyValueError::new_err)?;
+
+    let dict = PyDict::new_bound(py);
+    dict.set_item("freqs", result.freqs)?;
+    dict.set_item("psd", result.psd)?;
+    dict.set_item("lower", result.lower)?;
+    dict.set_item("upper", result.upper)?;
+    Ok(dict.into_py(py))
+}
+
+#[pyfunction]
+fn stationarity_test_py(py: Python<'_>, data: Samples, nperseg: usize) -> PyResult<PyObject> {
+    let result = stationarity_test(&data.0, nperseg).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+
+    let dict = PyDict::new_bound(py);
+    dict.set_item("stationary", result.stationary)?;
+    dict.set_item("p_value", result.p_value)?;
+    dict.set_item("runs", result.runs)?;
+    dict.set_item("expected_runs", result.expected_runs)?;
+    dict.set_item("z_score", result.z_score)?;
+    dict.set_item("segment_mean_square", result.segment_mean_square)?;
+    Ok(dict.into_py(py))
+}
+
+#[pyfunction]
+fn spectral_kurtosis_py(data: Samples, fs: f64, nperseg: usize) -> PyResult<(Vec<f64>, Vec<f64>)> {
+    spectral_kurtosis(data.0, fs, nperseg).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, fs, max_level=6))]
+fn kurtogram_py(py: Python<'_>, data: Samples, fs: f64, max_level: usize) -> PyResult<(Matrix, f64, f64, f64)> {
+    py.allow_threads(|| kurtogram(data.0, fs, max_level).map_err(|e| e.to_string()))
+        .map(|k| (k.kurtosis, k.best_center, k.best_bandwidth, k.best_kurtosis))
+        .map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, fs, bands=None, nperseg=None))]
+fn band_power_py(
+    data: Samples,
+    fs: f64,
+    bands: Option<Vec<(String, f64, f64)>>,
+    nperseg: Option<usize>,
+) -> PyResult<Vec<(String, f64, f64)>> {
+    band_power(data.0, fs, bands, nperseg)
+        .map(|powers| powers.into_iter().map(|b| (b.name, b.absolute, b.relative)).collect())
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, fs, nperseg, hop=None, progress=None))]
+fn compute_spectrogram_py(
+    py: Python<'_>,
+    data: Samples,
+    fs: f64,
+    nperseg: usize,
+    hop: Option<usize>,
+    progress: Option<PyObject>,
+) -> PyResult<(Vec<f64>, Vec<f64>, Matrix)> {
+    let hop = hop.unwrap_or(nperseg / 2);
+    with_progress(py, progress, |p| compute_spectrogram_with_progress(data.0, fs, nperseg, hop, p))?
+        .map(|s| (s.times, s.freqs, s.power))
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, fs, n_bands=24, nperseg=1024, hop=None))]
+fn bark_spectrum_py(
+    py: Python<'_>,
+    data: Samples,
+    fs: f64,
+    n_bands: usize,
+    nperseg: usize,
+    hop: Option<usize>,
+) -> PyResult<(Vec<f64>, Vec<f64>, Matrix)> {
+    let hop = hop.unwrap_or(nperseg / 2);
+    py.allow_threads(|| bark_spectrum(data.0, fs, n_bands, nperseg, hop).map_err(|e| e.to_string()))
+        .map(|s| (s.times, s.centers, s.power))
+        .map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, fs, n_bands=40, nperseg=1024, hop=None))]
+fn erb_spectrum_py(
+    py: Python<'_>,
+    data: Samples,
+    fs: f64,
+    n_bands: usize,
+    nperseg: usize,
+    hop: Option<usize>,
+) -> PyResult<(Vec<f64>, Vec<f64>, Matrix)> {
+    let hop = hop.unwrap_or(nperseg / 2);
+    py.allow_threads(|| erb_spectrum(data.0, fs, n_bands, nperseg, hop).map_err(|e| e.to_string()))
+        .map(|s| (s.times, s.centers, s.power))
+        .map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+#[pyfunction]
+#[pyo3(signature = (channels, fs, nperseg=256, progress=None))]
+fn cross_spectral_matrix_py(
+    py: Python<'_>,
+    channels: Matrix,
+    fs: f64,
+    nperseg: usize,
+    progress: Option<PyObject>,
+) -> PyResult<(Vec<f64>, Vec<ComplexMatrix>)> {
+    with_progress(py, progress, |p| cross_spectral_matrix_with_progress(channels, fs, nperseg, p))?
+        .map(|csd| (csd.freqs, csd.matrices))
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (channels, fs, nperseg=256))]
+fn frequency_domain_decomposition_py(
+    py: Python<'_>,
+    channels: Matrix,
+    fs: f64,
+    nperseg: usize,
+) -> PyResult<(Vec<f64>, Matrix, ComplexMatrix)> {
+    py.allow_threads(|| frequency_domain_decomposition(channels, fs, nperseg).map_err(|e| e.to_string()))
+        .map(|fdd| (fdd.freqs, fdd.singular_values, fdd.mode_shapes))
+        .map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+#[pyfunction]
+#[pyo3(signature = (channels, fs, geometry, steering_angles, method="das", nperseg=256, speed_of_sound=343.0, band=None, diagonal_loading=1e-3))]
+#[allow(clippy::too_many_arguments)]
+fn delay_and_sum_py(
+    py: Python<'_>,
+    channels: Matrix,
+    fs: f64,
+    geometry: Vec<(f64, f64)>,
+    steering_angles: Vec<f64>,
+    method: &str,
+    nperseg: usize,
+    speed_of_sound: f64,
+    band: Option<(f64, f64)>,
+    diagonal_loading: f64,
+) -> PyResult<Vec<f64>> {
+    let method = match method.to_lowercase().as_str() {
+        "das" | "delay_and_sum" => Beamformer::DelayAndSum,
+        "mvdr" | "capon" => Beamformer::Mvdr,
+        _ => {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Unknown beamformer '{}', expected das or mvdr.",
+                method
+            )))
+        }
+    };
+    let options = BeamformOptions { nperseg, speed: speed_of_sound, band, diagonal_loading };
+    py.allow_threads(|| {
+        spatial_spectrum(channels, fs, geometry, steering_angles, method, &options).map_err(|e| e.to_string())
+    })
+    .map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+#[pyfunction]
+#[pyo3(signature = (a, b, fs, max_delay=None))]
+fn gcc_phat_py(a: Samples, b: Samples, fs: f64, max_delay: Option<f64>) -> PyResult<(f64, Vec<f64>, Vec<f64>)> {
+    gcc_phat(a.0, b.0, fs, max_delay)
+        .map(|estimate| (estimate.delay, estimate.lags, estimate.correlation))
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+fn resample_py(data: Samples, num: usize) -> PyResult<Vec<f64>> {
+    resample(data.0, num).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (a, b, max_lag=None, fs_a=None, fs_b=None))]
+fn align_signals_py(
+    a: Samples,
+    b: Samples,
+    max_lag: Option<usize>,
+    fs_a: Option<f64>,
+    fs_b: Option<f64>,
+) -> PyResult<(i64, Vec<f64>, Vec<f64>)> {
+    let rates = match (fs_a, fs_b) {
+        (Some(fs_a), Some(fs_b)) => Some((fs_a, fs_b)),
+        (None, None) => None,
+        _ => return Err(pyo3::exceptions::PyValueError::new_err("fs_a and fs_b must be given together.")),
+    };
+    align_signals(a.0, b.0, max_lag, rates)
+        .map(|aligned| (aligned.lag, aligned.a, aligned.b))
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+fn fractional_delay_py(data: Samples, fs: f64, delay_seconds: f64) -> PyResult<Vec<f64>> {
+    fractional_delay(data.0, fs, delay_seconds).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (x, y, new_x, method="linear"))]
+fn interpolate_py(x: Samples, y: Samples, new_x: Samples, method: &str) -> PyResult<Vec<f64>> {
+    let method = match method.to_lowercase().as_str() {
+        "linear" => Interpolation::Linear,
+        "cubic" | "spline" => Interpolation::Cubic,
+        "sinc" => Interpolation::Sinc,
+        _ => {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Unknown interpolation method '{}', expected linear, cubic or sinc.",
+                method
+            )))
+        }
+    };
+    interpolate(x.0, y.0, new_x.0, method).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, threshold=3.0, method="hampel", half_window=5))]
+fn despike_py(data: Samples, threshold: f64, method: &str, half_window: usize) -> PyResult<(Vec<f64>, Vec<usize>)> {
+    let method = match method.to_lowercase().as_str() {
+        "median" => DespikeMethod::Median,
+        "hampel" => DespikeMethod::Hampel,
+        _ => {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Unknown despike method '{}', expected median or hampel.",
+                method
+            )))
+        }
+    };
+    despike(data.0, threshold, method, half_window)
+        .map(|despiked| (despiked.data, despiked.spikes))
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, threshold=None))]
+fn detect_clipping_py(data: Samples, threshold: Option<f64>) -> Vec<(usize, usize)> {
+    detect_clipping(&data.0, threshold)
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, fs, frame_ms=20.0))]
+fn detect_voice_activity_py(py: Python<'_>, data: Samples, fs: f64, frame_ms: f64) -> PyResult<PyObject> {
+    let activity = detect_voice_activity(&data.0, fs, frame_ms).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+    let dict = PyDict::new_bound(py);
+    dict.set_item("frame_len", activity.frame_len)?;
+    dict.set_item("flags", activity.flags)?;
+    dict.set_item("energy_db", activity.energy_db)?;
+    dict.set_item("flatness", activity.flatness)?;
+    dict.set_item("segments", activity.segments)?;
+    Ok(dict.into_py(py))
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, fs, threshold_db=60.0))]
+fn trim_silence_py(data: Samples, fs: f64, threshold_db: f64) -> PyResult<(Vec<f64>, (usize, usize))> {
+    let (start, end) = trim_silence(&data.0, fs, threshold_db).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+    Ok((data.0[start..end].to_vec(), (start, end)))
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, fs, threshold_db=60.0, min_silence_ms=300.0))]
+fn split_on_silence_py(data: Samples, fs: f64, threshold_db: f64, min_silence_ms: f64) -> PyResult<Vec<(usize, usize)>> {
+    split_on_silence(&data.0, fs, threshold_db, min_silence_ms).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, threshold=None))]
+fn declip_py(data: Samples, threshold: Option<f64>) -> PyResult<Vec<f64>> {
+    declip(data.0, threshold).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, mode="peak", target=None))]
+fn normalize_py(data: Samples, mode: &str, target: Option<f64>) -> PyResult<Vec<f64>> {
+    let mode = match mode.to_lowercase().as_str() {
+        "peak" => Normalization::Peak,
+        "rms" => Normalization::Rms,
+        "zscore" | "z-score" => Normalization::ZScore,
+        _ => {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Unknown normalization '{}', expected peak, rms or zscore.",
+                mode
+            )))
+        }
+    };
+    normalize(data.0, mode, target).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+fn apply_gain_py(data: Samples, gain_db: f64) -> Vec<f64> {
+    apply_gain(data.0, gain_db)
+}
+
+/// A constant sensitivity or a `(freqs, values)` curve.
+#[derive(FromPyObject)]
+enum PySensitivity {
+    Constant(f64),
+    Curve(Samples, Samples),
+}
+
+#[pyfunction]
+fn apply_calibration_py(signal: PyRef<'_, PySignal>, sensitivity: PySensitivity, units: &str) -> PyResult<PySignal> {
+    let sensitivity = match sensitivity {
+        PySensitivity::Constant(value) => Sensitivity::Constant(value),
+        PySensitivity::Curve(freqs, values) => Sensitivity::Curve { freqs: freqs.0, values: values.0 },
+    };
+    apply_calibration(signal.inner.clone(), &sensitivity, units)
+        .map(|inner| PySignal { inner })
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, process_noise, measurement_noise, smooth=true))]
+fn kalman_trend_py(
+    data: Samples,
+    process_noise: f64,
+    measurement_noise: f64,
+    smooth: bool,
+) -> PyResult<(Vec<f64>, Vec<f64>, Vec<f64>)> {
+    kalman_trend(data.0, process_noise, measurement_noise, smooth)
+        .map(|trend| (trend.level, trend.slope, trend.variance))
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+fn analytic_signal_py(data: Samples) -> PyResult<(Vec<f64>, Vec<f64>)> {
+    analytic_signal(data.0).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, max_imfs=None))]
+fn emd_py(py: Python<'_>, data: Samples, max_imfs: Option<usize>) -> PyResult<Matrix> {
+    py.allow_threads(|| emd(data.0, max_imfs).map_err(|e| e.to_string()))
+        .map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+#[pyfunction]
+fn instantaneous_attributes_py(imfs: Matrix, fs: f64) -> PyResult<(Matrix, Matrix)> {
+    instantaneous_attributes(imfs, fs)
+        .map(|attributes| (attributes.amplitude, attributes.frequency))
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (imfs, fs, freq_bins=256))]
+fn hilbert_huang_spectrum_py(imfs: Matrix, fs: f64, freq_bins: usize) -> PyResult<(Vec<f64>, Vec<f64>, Matrix)> {
+    hilbert_huang_spectrum(imfs, fs, freq_bins)
+        .map(|s| (s.times, s.freqs, s.power))
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+fn matrix_pencil_py(py: Python<'_>, data: Samples, fs: f64, n_components: usize) -> PyResult<Vec<(f64, f64, f64, f64)>> {
+    py.allow_threads(|| matrix_pencil(data.0, fs, n_components).map_err(|e| e.to_string()))
+        .map(|components| components.into_iter().map(|c| (c.frequency, c.damping, c.amplitude, c.phase)).collect())
+        .map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, n_sinusoids, fs, n_freqs=1024))]
+fn music_py(
+    py: Python<'_>,
+    data: Samples,
+    n_sinusoids: usize,
+    fs: f64,
+    n_freqs: usize,
+) -> PyResult<(Vec<f64>, Vec<f64>, Vec<f64>)> {
+    py.allow_threads(|| music(data.0, n_sinusoids, fs, n_freqs).map_err(|e| e.to_string()))
+        .map(|m| (m.frequencies, m.freqs, m.pseudospectrum))
+        .map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+#[pyfunction]
+fn esprit_py(py: Python<'_>, data: Samples, n_sinusoids: usize, fs: f64) -> PyResult<Vec<f64>> {
+    py.allow_threads(|| esprit(data.0, n_sinusoids, fs).map_err(|e| e.to_string()))
+        .map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+#[pyfunction]
+#[pyo3(signature = (n, std=1.0, seed=None))]
+fn white_noise_py(n: usize, std: f64, seed: Option<u64>) -> PyResult<Vec<f64>> {
+    white_noise(n, std, seed).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (n, color="pink", std=1.0, seed=None))]
+fn colored_noise_py(n: usize, color: &str, std: f64, seed: Option<u64>) -> PyResult<Vec<f64>> {
+    let color = match color.to_lowercase().as_str() {
+        "white" => NoiseColor::White,
+        "pink" => NoiseColor::Pink,
+        "brown" | "brownian" | "red" => NoiseColor::Brown,
+        _ => {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Unknown noise color '{}', expected white, pink or brown.",
+                color
+            )))
+        }
+    };
+    colored_noise(n, color, std, seed).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+fn generate_ess_py(f1: f64, f2: f64, duration: f64, fs: f64) -> PyResult<Vec<f64>> {
+    generate_ess(f1, f2, duration, fs).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+fn extract_impulse_response_py(py: Python<'_>, recorded: Samples, sweep: Samples, fs: f64) -> PyResult<(Vec<f64>, Vec<f64>)> {
+    if fs <= 0.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err("fs must be greater than zero."));
+    }
+    let ir = py
+        .allow_threads(|| extract_impulse_response(recorded.0, sweep.0).map_err(|e| e.to_string()))
+        .map_err(pyo3::exceptions::PyValueError::new_err)?;
+    Ok(((0..ir.len()).map(|n| n as f64 / fs).collect(), ir))
+}
+
+#[pyfunction]
+fn generate_mls_py(order: u32) -> PyResult<Vec<f64>> {
+    generate_mls(order).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+fn mls_impulse_response_py(py: Python<'_>, recorded: Samples, order: u32) -> PyResult<Vec<f64>> {
+    py.allow_threads(|| mls_impulse_response(recorded.0, order).map_err(|e| e.to_string()))
+        .map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+#[pyfunction]
+fn ir_to_fr_py(ir: Samples, fs: f64, smoothing_octaves: f64) -> PyResult<(Vec<f64>, Vec<f64>, Vec<f64>)> {
+    ir_to_fr(ir.0, fs, smoothing_octaves)
+        .map(|fr| (fr.freqs, fr.magnitude_db, fr.phase))
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (ir, fs, method="t30"))]
+fn rt60_py(ir: Samples, fs: f64, method: &str) -> PyResult<f64> {
+    let method = match method.to_lowercase().as_str() {
+        "edt" => DecayMethod::Edt,
+        "t20" => DecayMethod::T20,
+        "t30" => DecayMethod::T30,
+        _ => {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Unknown decay method '{}', expected edt, t20 or t30.",
+                method
+            )))
+        }
+    };
+    rt60(ir.0, fs, method).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+fn clarity_py(py: Python<'_>, ir: Samples, fs: f64) -> PyResult<PyObject> {
+    let metrics = clarity(ir.0, fs).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+    let dict = PyDict::new_bound(py);
+    dict.set_item("c50", metrics.c50)?;
+    dict.set_item("c80", metrics.c80)?;
+    dict.set_item("d50", metrics.d50)?;
+    Ok(dict.into_py(py))
+}
+
+#[pyfunction]
+fn fft_bandpass_py(data: Samples, fs: f64, low: f64, high: f64) -> PyResult<Vec<f64>> {
+    fft_bandpass(data.0, fs, low, high).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, fs, order=1))]
+fn differentiate_fd_py(data: Samples, fs: f64, order: u32) -> PyResult<Vec<f64>> {
+    differentiate_fd(data.0, fs, order).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, fs, order=1, highpass_cutoff=None))]
+fn integrate_fd_py(data: Samples, fs: f64, order: u32, highpass_cutoff: Option<f64>) -> PyResult<Vec<f64>> {
+    integrate_fd(data.0, fs, order, highpass_cutoff).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+fn minimum_phase_py(magnitude: Samples) -> PyResult<(Vec<f64>, Vec<f64>)> {
+    minimum_phase(magnitude.0).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+fn linear_phase_fir_py(magnitude: Samples, taps: usize) -> PyResult<Vec<f64>> {
+    linear_phase_fir(magnitude.0, taps).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (freqs, measured_db, target_db, fs, n, kind="fir"))]
+fn design_eq_py(
+    py: Python<'_>,
+    freqs: Samples,
+    measured_db: Samples,
+    target_db: Samples,
+    fs: f64,
+    n: usize,
+    kind: &str,
+) -> PyResult<PyObject> {
+    let design = match kind.to_lowercase().as_str() {
+        "fir" => EqDesign::Fir(n),
+        "biquad" | "biquads" => EqDesign::Biquads(n),
+        _ => {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Unknown equalizer kind '{}', expected fir or biquad.",
+                kind
+            )))
+        }
+    };
+    let filter = design_eq(freqs.0, measured_db.0, target_db.0, fs, design)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+    Ok(match filter {
+        EqFilter::Fir(taps) => taps.into_py(py),
+        EqFilter::Biquads(sections) => sections.iter().map(|s| s.to_sos().to_vec()).collect::<Vec<_>>().into_py(py),
+    })
+}
+
+/// Python handle of a [`BiquadCascade`] keeping its state between chunks.
+#[pyclass(name = "BiquadCascade")]
+struct PyBiquadCascade {
+    cascade: BiquadCascade,
+}
+
+#[pymethods]
+impl PyBiquadCascade {
+    #[new]
+    fn new(sos: Matrix) -> PyResult<Self> {
+        BiquadCascade::from_sos(&sos)
+            .map(|cascade| PyBiquadCascade { cascade })
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
+    fn process(&mut self, chunk: Samples) -> Vec<f64> {
+        self.cascade.process(&chunk.0)
+    }
+
+    #[pyo3(signature = (fs, n_points=512))]
+    fn frequency_response(&self, fs: f64, n_points: usize) -> PyResult<(Vec<f64>, Vec<f64>, Vec<f64>)> {
+        self.cascade
+            .frequency_response(fs, n_points)
+            .map(|fr| (fr.freqs, fr.magnitude_db, fr.phase))
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
+    #[getter]
+    fn sos(&self) -> Matrix {
+        self.cascade.sections().iter().map(|s| s.to_sos().to_vec()).collect()
+    }
+
+    fn reset(&mut self) {
+        self.cascade.reset()
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (b, a, fs, n_points=512))]
+fn freqz_py(b: Samples, a: Samples, fs: f64, n_points: usize) -> PyResult<(Vec<f64>, Vec<f64>, Vec<f64>)> {
+    freqz(b.0, a.0, fs, n_points)
+        .map(|fr| (fr.freqs, fr.magnitude_db, fr.phase))
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+/// Python handle of a streaming [`Decimator`].
+#[pyclass(name = "Decimator")]
+struct PyDecimator {
+    inner: Decimator,
+}
+
+#[pymethods]
+impl PyDecimator {
+    #[new]
+    #[pyo3(signature = (factor, taps_per_phase=16))]
+    fn new(factor: usize, taps_per_phase: usize) -> PyResult<Self> {
+        Decimator::new(factor, taps_per_phase)
+            .map(|inner| PyDecimator { inner })
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
+    fn process(&mut self, chunk: Samples) -> Vec<f64> {
+        self.inner.process(&chunk.0)
+    }
+
+    #[getter]
+    fn factor(&self) -> usize {
+        self.inner.factor()
+    }
+
+    #[getter]
+    fn delay(&self) -> f64 {
+        self.inner.delay()
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset()
+    }
+}
+
+/// Python handle of a streaming [`Interpolator`].
+#[pyclass(name = "Interpolator")]
+struct PyInterpolator {
+    inner: Interpolator,
+}
+
+#[pymethods]
+impl PyInterpolator {
+    #[new]
+    #[pyo3(signature = (factor, taps_per_phase=16))]
+    fn new(factor: usize, taps_per_phase: usize) -> PyResult<Self> {
+        Interpolator::new(factor, taps_per_phase)
+            .map(|inner| PyInterpolator { inner })
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
+    fn process(&mut self, chunk: Samples) -> Vec<f64> {
+        self.inner.process(&chunk.0)
+    }
+
+    #[getter]
+    fn factor(&self) -> usize {
+        self.inner.factor()
+    }
+
+    #[getter]
+    fn delay(&self) -> f64 {
+        self.inner.delay()
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset()
+    }
+}
+
+/// Python handle of a streaming [`FarrowResampler`].
+#[pyclass(name = "FarrowResampler")]
+struct PyFarrowResampler {
+    inner: FarrowResampler,
+}
+
+#[pymethods]
+impl PyFarrowResampler {
+    #[new]
+    fn new(ratio: f64) -> PyResult<Self> {
+        FarrowResampler::new(ratio)
+            .map(|inner| PyFarrowResampler { inner })
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
+    fn process(&mut self, chunk: Samples) -> Vec<f64> {
+        self.inner.process(&chunk.0)
+    }
+
+    #[getter]
+    fn ratio(&self) -> f64 {
+        self.inner.ratio()
+    }
+
+    #[setter]
+    fn set_ratio(&mut self, ratio: f64) -> PyResult<()> {
+        self.inner.set_ratio(ratio).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset()
+    }
+}
+
+/// Python handle of a streaming [`DcBlocker`].
+#[pyclass(name = "DcBlocker")]
+struct PyDcBlocker {
+    inner: DcBlocker,
+}
+
+#[pymethods]
+impl PyDcBlocker {
+    #[new]
+    #[pyo3(signature = (fs, cutoff=1.0))]
+    fn new(fs: f64, cutoff: f64) -> PyResult<Self> {
+        DcBlocker::new(fs, cutoff)
+            .map(|inner| PyDcBlocker { inner })
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
+    fn process(&mut self, chunk: Samples) -> Vec<f64> {
+        self.inner.process(&chunk.0)
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset()
+    }
+}
+
+/// Python handle of a streaming [`Agc`].
+#[pyclass(name = "Agc")]
+struct PyAgc {
+    inner: Agc,
+}
+
+#[pymethods]
+impl PyAgc {
+    #[new]
+    #[pyo3(signature = (fs, target=1.0, attack=0.01, release=0.5, max_gain=100.0))]
+    fn new(fs: f64, target: f64, attack: f64, release: f64, max_gain: f64) -> PyResult<Self> {
+        Agc::new(fs, target, attack, release, max_gain)
+            .map(|inner| PyAgc { inner })
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
+    fn process(&mut self, chunk: Samples) -> Vec<f64> {
+        self.inner.process(&chunk.0)
+    }
+
+    #[getter]
+    fn gain(&self) -> f64 {
+        self.inner.gain()
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset()
+    }
+}
+
+/// Reads the metadata fields of a pickled [`Signal`] or [`Spectrum`], absent in states pickled without them.
+fn metadata_from_state(state: &Bound<'_, PyDict>) -> PyResult<Metadata> {
+    let text = |key: &str| -> PyResult<Option<String>> {
+        state.get_item(key)?.map_or(Ok(None), |value| value.extract())
+    };
+    Ok(Metadata {
+        quantity: text("quantity")?,
+        unit: text("unit")?,
+        name: text("name")?,
+        calibration: state.get_item("calibration")?.map_or(Ok(None), |value| value.extract())?,
+    })
+}
+
+fn metadata_to_state(state: &Bound<'_, PyDict>, metadata: &Metadata) -> PyResult<()> {
+    state.set_item("quantity", &metadata.quantity)?;
+    state.set_item("unit", &metadata.unit)?;
+    state.set_item("name", &metadata.name)?;
+    state.set_item("calibration", metadata.calibration)
+}
+
+/// Python handle of a [`Signal`], picklable through its state dict.
+#[pyclass(name = "Signal", module = "fft_rust_in_python")]
+struct PySignal {
+    inner: Signal,
+}
+
+#[pymethods]
+impl PySignal {
+    #[new]
+    #[pyo3(signature = (data, fs, quantity=None, unit=None, name=None, calibration=None))]
+    fn new(
+        data: Samples,
+        fs: f64,
+        quantity: Option<String>,
+        unit: Option<String>,
+        name: Option<String>,
+        calibration: Option<f64>,
+    ) -> PyResult<Self> {
+        let mut inner = Signal::new(data.0, fs).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        inner.metadata = Metadata { quantity, unit, name, calibration };
+        Ok(PySignal { inner })
+    }
+
+    #[getter]
+    fn data(&self) -> Vec<f64> {
+        self.inner.data.clone()
+    }
+
+    #[getter]
+    fn fs(&self) -> f64 {
+        self.inner.fs
+    }
+
+    #[getter]
+    fn duration(&self) -> f64 {
+        self.inner.duration()
+    }
+
+    #[getter]
+    fn quantity(&self) -> Option<String> {
+        self.inner.metadata.quantity.clone()
+    }
+
+    #[setter]
+    fn set_quantity(&mut self, quantity: Option<String>) {
+        self.inner.metadata.quantity = quantity;
+    }
+
+    #[getter]
+    fn unit(&self) -> Option<String> {
+        self.inner.metadata.unit.clone()
+    }
+
+    #[setter]
+    fn set_unit(&mut self, unit: Option<String>) {
+        self.inner.metadata.unit = unit;
+    }
+
+    #[getter]
+    fn name(&self) -> Option<String> {
+        self.inner.metadata.name.clone()
+    }
+
+    #[setter]
+    fn set_name(&mut self, name: Option<String>) {
+        self.inner.metadata.name = name;
+    }
+
+    #[getter]
+    fn calibration(&self) -> Option<f64> {
+        self.inner.metadata.calibration
+    }
+
+    #[setter]
+    fn set_calibration(&mut self, calibration: Option<f64>) {
+        self.inner.metadata.calibration = calibration;
+    }
+
+    #[getter]
+    fn label(&self) -> String {
+        self.inner.label()
+    }
+
+    #[pyo3(signature = (title=None, xlim=None, ylim=None, x_ticks=None, y_ticks=None, x_format="auto", y_format="auto", y_percentile=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn plot<'py>(
+        &self,
+        py: Python<'py>,
+        title: Option<&str>,
+        xlim: Option<(f64, f64)>,
+        ylim: Option<(f64, f64)>,
+        x_ticks: Option<usize>,
+        y_ticks: Option<usize>,
+        x_format: &str,
+        y_format: &str,
+        y_percentile: Option<f64>,
+    ) -> PyResult<Bound<'py, PyBytes>> {
+        let options = plot_options(xlim, ylim, x_ticks, y_ticks, x_format, y_format, y_percentile)?;
+        generate_signal_plot(&self.inner, title, &options)
+            .map(|png| PyBytes::new_bound(py, &png))
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.data.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Signal({} samples, fs={}, {})", self.inner.data.len(), self.inner.fs, self.inner.label())
+    }
+
+    fn __eq__(&self, other: PyRef<'_, Self>) -> bool {
+        self.inner == other.inner
+    }
+
+    fn __getnewargs__(&self) -> (Vec<f64>, f64) {
+        (Vec::new(), self.inner.fs)
+    }
+
+    fn __getstate__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let state = PyDict::new_bound(py);
+        state.set_item("data", &self.inner.data)?;
+        state.set_item("fs", self.inner.fs)?;
+        metadata_to_state(&state, &self.inner.metadata)?;
+        Ok(state)
+    }
+
+    fn __setstate__(&mut self, state: &Bound<'_, PyDict>) -> PyResult<()> {
+        let item = |key: &str| {
+            state.get_item(key)?.ok_or_else(|| pyo3::exceptions::PyKeyError::new_err(key.to_string()))
+        };
+        self.inner = Signal::new(item("data")?.extract()?, item("fs")?.extract()?)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        self.inner.metadata = metadata_from_state(state)?;
+        Ok(())
+    }
+
+    fn to_json(&self) -> String {
+        self.inner.to_json()
+    }
+
+    #[staticmethod]
+    fn from_json(text: &str) -> PyResult<Self> {
+        Signal::from_json(text)
+            .map(|inner| PySignal { inner })
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
+    fn to_npz(&self, path: &str) -> PyResult<()> {
+        self.inner.to_npz(path).map_err(|e| pyo3::exceptions::PyIOError::new_err(e.to_string()))
+    }
+}
+
+fn parse_spectrum_kind(kind: &str) -> PyResult<SpectrumKind> {
+    match kind.to_lowercase().as_str() {
+        "amplitude" => Ok(SpectrumKind::Amplitude),
+        "power" => Ok(SpectrumKind::Power),
+        "psd" => Ok(SpectrumKind::Psd),
+        _ => Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "Unknown spectrum kind '{}', expected amplitude, power or psd.",
+            kind
+        ))),
+    }
+}
+
+/// Python handle of a [`Spectrum`], picklable through its state dict.
+#[pyclass(name = "Spectrum", module = "fft_rust_in_python")]
+struct PySpectrum {
+    inner: Spectrum,
+}
+
+#[pymethods]
+impl PySpectrum {
+    #[new]
+    #[pyo3(signature = (freqs, values, kind="amplitude", quantity=None, unit=None, name=None, calibration=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        freqs: Samples,
+        values: Samples,
+        kind: &str,
+        quantity: Option<String>,
+        unit: Option<String>,
+        name: Option<String>,
+        calibration: Option<f64>,
+    ) -> PyResult<Self> {
+        let mut inner =
+            Spectrum::new(freqs.0, values.0).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        inner.kind = parse_spectrum_kind(kind)?;
+        inner.metadata = Metadata { quantity, unit, name, calibration };
+        Ok(PySpectrum { inner })
+    }
+
+    #[getter]
+    fn freqs(&self) -> Vec<f64> {
+        self.inner.freqs.clone()
+    }
+
+    #[getter]
+    fn values(&self) -> Vec<f64> {
+        self.inner.values.clone()
+    }
+
+    #[getter]
+    fn kind(&self) -> &'static str {
+        self.inner.kind.name()
+    }
+
+    #[getter]
+    fn quantity(&self) -> Option<String> {
+        self.inner.metadata.quantity.clone()
+    }
+
+    #[setter]
+    fn set_quantity(&mut self, quantity: Option<String>) {
+        self.inner.metadata.quantity = quantity;
+    }
+
+    #[getter]
+    fn unit(&self) -> Option<String> {
+        self.inner.metadata.unit.clone()
+    }
+
+    #[setter]
+    fn set_unit(&mut self, unit: Option<String>) {
+        self.inner.metadata.unit = unit;
+    }
+
+    #[getter]
+    fn name(&self) -> Option<String> {
+        self.inner.metadata.name.clone()
+    }
+
+    #[setter]
+    fn set_name(&mut self, name: Option<String>) {
+        self.inner.metadata.name = name;
+    }
+
+    #[getter]
+    fn calibration(&self) -> Option<f64> {
+        self.inner.metadata.calibration
+    }
+
+    #[setter]
+    fn set_calibration(&mut self, calibration: Option<f64>) {
+        self.inner.metadata.calibration = calibration;
+    }
+
+    #[getter]
+    fn label(&self) -> String {
+        self.inner.label()
+    }
+
+    #[pyo3(signature = (title=None, reference=None, xlim=None, ylim=None, x_ticks=None, y_ticks=None, x_format="auto", y_format="auto", y_percentile=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn plot<'py>(
+        &self,
+        py: Python<'py>,
+        title: Option<&str>,
+        reference: Option<PyDbReference>,
+        xlim: Option<(f64, f64)>,
+        ylim: Option<(f64, f64)>,
+        x_ticks: Option<usize>,
+        y_ticks: Option<usize>,
+        x_format: &str,
+        y_format: &str,
+        y_percentile: Option<f64>,
+    ) -> PyResult<Bound<'py, PyBytes>> {
+        let options = plot_options(xlim, ylim, x_ticks, y_ticks, x_format, y_format, y_percentile)?;
+        let reference = match reference {
+            Some(reference) if self.inner.kind == SpectrumKind::Amplitude => Some(reference.amplitude()?),
+            Some(reference) => Some(reference.power()?),
+            None => None,
+        };
+        generate_spectrum_plot(&self.inner, title, reference, &options)
+            .map(|png| PyBytes::new_bound(py, &png))
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.values.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Spectrum({} bins, {})", self.inner.values.len(), self.inner.label())
+    }
+
+    fn __eq__(&self, other: PyRef<'_, Self>) -> bool {
+        self.inner == other.inner
+    }
+
+    fn __getnewargs__(&self) -> (Vec<f64>, Vec<f64>) {
+        (Vec::new(), Vec::new())
+    }
+
+    fn __getstate__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let state = PyDict::new_bound(py);
+        state.set_item("freqs", &self.inner.freqs)?;
+        state.set_item("values", &self.inner.values)?;
+        state.set_item("kind", self.inner.kind.name())?;
+        metadata_to_state(&state, &self.inner.metadata)?;
+        Ok(state)
+    }
+
+    fn __setstate__(&mut self, state: &Bound<'_, PyDict>) -> PyResult<()> {
+        let item = |key: &str| {
+            state.get_item(key)?.ok_or_else(|| pyo3::exceptions::PyKeyError::new_err(key.to_string()))
+        };
+        self.inner = Spectrum::new(item("freqs")?.extract()?, item("values")?.extract()?)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        if let Some(kind) = state.get_item("kind")? {
+            self.inner.kind = parse_spectrum_kind(&kind.extract::<String>()?)?;
+        }
+        self.inner.metadata = metadata_from_state(state)?;
+        Ok(())
+    }
+
+    fn to_json(&self) -> String {
+        self.inner.to_json()
+    }
+
+    #[staticmethod]
+    fn from_json(text: &str) -> PyResult<Self> {
+        Spectrum::from_json(text)
+            .map(|inner| PySpectrum { inner })
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
+    fn to_npz(&self, path: &str) -> PyResult<()> {
+        self.inner.to_npz(path).map_err(|e| pyo3::exceptions::PyIOError::new_err(e.to_string()))
+    }
+}
+
+fn parse_vibration_weighting(name: &str) -> PyResult<VibrationWeighting> {
+    match name.to_lowercase().as_str() {
+        "wk" => Ok(VibrationWeighting::Wk),
+        "wd" => Ok(VibrationWeighting::Wd),
+        "wc" => Ok(VibrationWeighting::Wc),
+        "we" => Ok(VibrationWeighting::We),
+        "wf" => Ok(VibrationWeighting::Wf),
+        _ => Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "Unknown vibration weighting '{}', expected Wk, Wd, Wc, We or Wf.",
+            name
+        ))),
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (freqs, weighting="Wk"))]
+fn vibration_weighting_py(freqs: Samples, weighting: &str) -> PyResult<Vec<f64>> {
+    let weighting = parse_vibration_weighting(weighting)?;
+    Ok(freqs.0.into_iter().map(|f| weighting.response(f).norm()).collect())
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, fs, weighting="Wk"))]
+fn frequency_weight_py(data: Samples, fs: f64, weighting: &str) -> PyResult<Vec<f64>> {
+    frequency_weight(data.0, fs, parse_vibration_weighting(weighting)?)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, fs, weighting="Wk"))]
+fn vibration_metrics_py(py: Python<'_>, data: Samples, fs: f64, weighting: &str) -> PyResult<PyObject> {
+    let metrics = vibration_metrics(data.0, fs, parse_vibration_weighting(weighting)?)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+    let dict = PyDict::new_bound(py);
+    dict.set_item("rms", metrics.rms)?;
+    dict.set_item("vdv", metrics.vdv)?;
+    dict.set_item("mtvv", metrics.mtvv)?;
+    dict.set_item("crest_factor", metrics.crest_factor)?;
+    Ok(dict.into_py(py))
+}
+
+#[pyfunction]
+fn k_weighting_py(fs: f64) -> PyResult<Matrix> {
+    k_weighting(fs)
+        .map(|cascade| cascade.sections().iter().map(|s| s.to_sos().to_vec()).collect())
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (channels, fs, weights=None))]
+fn loudness_py(py: Python<'_>, channels: Matrix, fs: f64, weights: Option<Vec<f64>>) -> PyResult<PyObject> {
+    let result = py
+        .allow_threads(|| loudness(&channels, fs, weights.as_deref()).map_err(|e| e.to_string()))
+        .map_err(pyo3::exceptions::PyValueError::new_err)?;
+    let dict = PyDict::new_bound(py);
+    dict.set_item("integrated", result.integrated)?;
+    dict.set_item("momentary", result.momentary)?;
+    dict.set_item("short_term", result.short_term)?;
+    dict.set_item("true_peak", result.true_peak)?;
+    Ok(dict.into_py(py))
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, fs, taus=None))]
+fn allan_deviation_py(py: Python<'_>, data: Samples, fs: f64, taus: Option<Vec<f64>>) -> PyResult<(Vec<f64>, Vec<f64>)> {
+    py.allow_threads(|| allan_deviation(data.0, fs, taus).map_err(|e| e.to_string()))
+        .map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+/// Python handle of an [`AdaptiveFilter`] keeping its state between blocks.
+#[pyclass(name = "AdaptiveFilter")]
+struct PyAdaptiveFilter {
+    filter: AdaptiveFilter,
+}
+
+#[pymethods]
+impl PyAdaptiveFilter {
+    #[new]
+    #[pyo3(signature = (taps, step_size=0.1, algorithm="nlms"))]
+    fn new(taps: usize, step_size: f64, algorithm: &str) -> PyResult<Self> {
+        let algorithm = match algorithm.to_lowercase().as_str() {
+            "lms" => AdaptiveAlgorithm::Lms,
+            "nlms" => AdaptiveAlgorithm::Nlms,
+            _ => {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "Unknown adaptive algorithm '{}', expected lms or nlms.",
+                    algorithm
+                )))
+            }
+        };
+        AdaptiveFilter::new(taps, step_size, algorithm)
+            .map(|filter| PyAdaptiveFilter { filter })
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
+    fn process(&mut self, primary: Samples, reference: Samples) -> PyResult<(Vec<f64>, Vec<f64>)> {
+        self.filter.process(&primary.0, &reference.0).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
+    #[getter]
+    fn weights(&self) -> Vec<f64> {
+        self.filter.weights().to_vec()
+    }
+
+    fn reset(&mut self) {
+        self.filter.reset()
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, fs, shift_hz, dtype=ComplexDtype::Complex128))]
+fn frequency_shift_py(py: Python<'_>, data: Samples, fs: f64, shift_hz: f64, dtype: ComplexDtype) -> PyResult<PyObject> {
+    let shifted = frequency_shift(data.0, fs, shift_hz).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+    Ok(into_interleaved_bytes(py, &shifted, dtype))
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, fs, carrier, decimation=1, bandwidth=None))]
+fn mix_to_baseband_py(
+    data: Samples,
+    fs: f64,
+    carrier: f64,
+    decimation: usize,
+    bandwidth: Option<f64>,
+) -> PyResult<(Vec<f64>, Vec<f64>)> {
+    mix_to_baseband(data.0, fs, carrier, decimation, bandwidth)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, fs, carrier, bandwidth=None))]
+fn demodulate_am_py(data: Samples, fs: f64, carrier: f64, bandwidth: Option<f64>) -> PyResult<Vec<f64>> {
+    demodulate_am(data.0, fs, carrier, bandwidth).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, fs, carrier, bandwidth=None))]
+fn demodulate_fm_py(data: Samples, fs: f64, carrier: f64, bandwidth: Option<f64>) -> PyResult<Vec<f64>> {
+    demodulate_fm(data.0, fs, carrier, bandwidth).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+/// Python handle of a max-hold [`SpectrumAccumulator`].
+#[pyclass(name = "PeakHoldSpectrum")]
+struct PyPeakHoldSpectrum {
+    accumulator: SpectrumAccumulator,
+}
+
+#[pymethods]
+impl PyPeakHoldSpectrum {
+    #[new]
+    fn new() -> PyResult<Self> {
+        SpectrumAccumulator::new(Accumulation::PeakHold)
+            .map(|accumulator| PyPeakHoldSpectrum { accumulator })
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
+    fn update(&mut self, spectrum: Samples) -> PyResult<()> {
+        self.accumulator.update(&spectrum.0).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
+    #[getter]
+    fn values(&self) -> Vec<f64> {
+        self.accumulator.values().to_vec()
+    }
+
+    #[getter]
+    fn count(&self) -> usize {
+        self.accumulator.count()
+    }
+
+    fn reset(&mut self) {
+        self.accumulator.reset()
+    }
+}
+
+/// Python handle of a linear or exponential averaging [`SpectrumAccumulator`].
+#[pyclass(name = "AverageSpectrum")]
+struct PyAverageSpectrum {
+    accumulator: SpectrumAccumulator,
+}
+
+#[pymethods]
+impl PyAverageSpectrum {
+    #[new]
+    #[pyo3(signature = (mode="linear", alpha=0.1))]
+    fn new(mode: &str, alpha: f64) -> PyResult<Self> {
+        let mode = match mode.to_lowercase().as_str() {
+            "linear" => Accumulation::Linear,
+            "exponential" => Accumulation::Exponential(alpha),
+            _ => {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "Unknown averaging mode '{}', expected linear or exponential.",
+                    mode
+                )))
+            }
+        };
+        SpectrumAccumulator::new(mode)
+            .map(|accumulator| PyAverageSpectrum { accumulator })
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
+    fn update(&mut self, spectrum: Samples) -> PyResult<()> {
+        self.accumulator.update(&spectrum.0).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
+    #[getter]
+    fn values(&self) -> Vec<f64> {
+        self.accumulator.values().to_vec()
+    }
+
+    #[getter]
+    fn count(&self) -> usize {
+        self.accumulator.count()
+    }
+
+    fn reset(&mut self) {
+        self.accumulator.reset()
+    }
+}
+
+/// Python iterator over the frames of a [`Frames`].
+#[pyclass(name = "Frames")]
+struct PyFrames {
+    frames: Frames,
+}
+
+#[pymethods]
+impl PyFrames {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self) -> Option<Vec<f64>> {
+        self.frames.next()
+    }
+
+    fn __len__(&self) -> usize {
+        self.frames.len()
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, nperseg, hop=None))]
+fn frames_py(data: Samples, nperseg: usize, hop: Option<usize>) -> PyResult<PyFrames> {
+    frames(data.0, nperseg, hop.unwrap_or((nperseg / 2).max(1)))
+        .map(|frames| PyFrames { frames })
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, threshold, hysteresis, pre, post, slope="rising"))]
+fn find_triggers_py(
+    data: Samples,
+    threshold: f64,
+    hysteresis: f64,
+    pre: usize,
+    post: usize,
+    slope: &str,
+) -> PyResult<(Vec<usize>, Matrix)> {
+    let slope = match slope.to_lowercase().as_str() {
+        "rising" => TriggerSlope::Rising,
+        "falling" => TriggerSlope::Falling,
+        _ => {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Unknown trigger slope '{}', expected rising or falling.",
+                slope
+            )))
+        }
+    };
+    find_triggers(&data.0, threshold, hysteresis, pre, post, slope)
+        .map(|t| (t.indices, t.segments))
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+fn synchronous_average_py(segments: Matrix) -> PyResult<Vec<f64>> {
+    synchronous_average(&segments).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (signal, tacho_pulses, fs, samples_per_rev=None))]
+fn tacho_synchronous_average_py(
+    py: Python<'_>,
+    signal: Samples,
+    tacho_pulses: Samples,
+    fs: f64,
+    samples_per_rev: Option<usize>,
+) -> PyResult<(Vec<f64>, usize)> {
+    py.allow_threads(|| {
+        tacho_synchronous_average(signal.0, &tacho_pulses.0, fs, samples_per_rev).map_err(|e| e.to_string())
+    })
+    .map(|t| (t.average, t.revolutions))
+    .map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+#[pyfunction]
+#[pyo3(signature = (time, data, method="linear"))]
+fn fill_gaps_py(py: Python<'_>, time: Samples, data: Samples, method: &str) -> PyResult<PyObject> {
+    let method = match method.to_lowercase().as_str() {
+        "nan" => GapFill::Nan,
+        "zero" => GapFill::Zero,
+        "linear" => GapFill::Linear,
+        "ar" => GapFill::Ar,
+        _ => {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Unknown gap fill method '{}', expected nan, zero, linear or ar.",
+                method
+            )))
+        }
+    };
+    let filled = fill_gaps(&time.0, &data.0, method).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+
+    let dict = PyDict::new_bound(py);
+    dict.set_item("time", filled.time)?;
+    dict.set_item("data", filled.data)?;
+    let gaps = filled
+        .gaps
+        .iter()
+        .map(|gap| {
+            let entry = PyDict::new_bound(py);
+            entry.set_item("start_time", gap.start_time)?;
+            entry.set_item("end_time", gap.end_time)?;
+            entry.set_item("index", gap.index)?;
+            entry.set_item("missing", gap.missing)?;
+            Ok(entry)
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+    dict.set_item("gaps", gaps)?;
+    Ok(dict.into_py(py))
+}
+
+fn parse_window(name: &str) -> PyResult<Window> {
+    match name.to_lowercase().as_str() {
+        "rectangular" | "boxcar" => Ok(Window::Rectangular),
+        "hann" => Ok(Window::Hann),
+        "hamming" => Ok(Window::Hamming),
+        "blackman" => Ok(Window::Blackman),
+        "blackmanharris" => Ok(Window::BlackmanHarris),
+        "flattop" => Ok(Window::FlatTop),
+        _ => Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "Unknown window '{}', expected rectangular, hann, hamming, blackman, blackmanharris or flattop.",
+            name
+        ))),
+    }
+}
+
+#[pyfunction]
+fn check_cola_py(window: &str, nperseg: usize, hop: usize) -> PyResult<(bool, f64, Vec<usize>)> {
+    check_cola(parse_window(window)?, nperseg, hop)
+        .map(|c| (c.is_cola, c.deviation, c.valid_hops))
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, nperseg, hop=None, window="hann"))]
+fn stft_py(py: Python<'_>, data: Samples, nperseg: usize, hop: Option<usize>, window: &str) -> PyResult<ComplexMatrix> {
+    let window = parse_window(window)?;
+    let hop = hop.unwrap_or(nperseg / 4);
+    py.allow_threads(|| stft(&data.0, nperseg, hop, window).map_err(|e| e.to_string()))
+        .map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+#[pyfunction]
+#[pyo3(signature = (frames, nperseg, hop=None, window="hann", length=None))]
+fn istft_py(
+    py: Python<'_>,
+    frames: ComplexMatrix,
+    nperseg: usize,
+    hop: Option<usize>,
+    window: &str,
+    length: Option<usize>,
+) -> PyResult<Vec<f64>> {
+    let window = parse_window(window)?;
+    let hop = hop.unwrap_or(nperseg / 4);
+    py.allow_threads(|| istft(&frames, nperseg, hop, window, length).map_err(|e| e.to_string()))
+        .map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+#[pyfunction]
+#[pyo3(signature = (magnitude, n_iter=32, nperseg=1024, hop=None, seed=None))]
+fn griffin_lim_py(
+    py: Python<'_>,
+    magnitude: Matrix,
+    n_iter: usize,
+    nperseg: usize,
+    hop: Option<usize>,
+    seed: Option<u64>,
+) -> PyResult<Vec<f64>> {
+    let hop = hop.unwrap_or(nperseg / 4);
+    py.allow_threads(|| griffin_lim(&magnitude, n_iter, nperseg, hop, seed).map_err(|e| e.to_string()))
+        .map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+#[pyfunction]
+#[pyo3(signature = (fs, n_fft, n_mels=128))]
+fn mel_filterbank_py(fs: f64, n_fft: usize, n_mels: usize) -> PyResult<Matrix> {
+    mel_filterbank(fs, n_fft, n_mels).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (mel_spec, fs, n_fft, n_mels=128))]
+fn mel_to_linear_py(py: Python<'_>, mel_spec: Matrix, fs: f64, n_fft: usize, n_mels: usize) -> PyResult<Matrix> {
+    py.allow_threads(|| mel_to_linear(&mel_spec, fs, n_fft, n_mels).map_err(|e| e.to_string()))
+        .map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+#[pyfunction]
+fn time_stretch_py(py: Python<'_>, data: Samples, fs: f64, rate: f64) -> PyResult<Vec<f64>> {
+    py.allow_threads(|| time_stretch(&data.0, fs, rate).map_err(|e| e.to_string()))
+        .map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+#[pyfunction]
+fn pitch_shift_py(py: Python<'_>, data: Samples, fs: f64, semitones: f64) -> PyResult<Vec<f64>> {
+    py.allow_threads(|| pitch_shift(&data.0, fs, semitones).map_err(|e| e.to_string()))
+        .map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+#[pyfunction]
+#[pyo3(signature = (window, nperseg=1024))]
+fn window_report_py(py: Python<'_>, window: &str, nperseg: usize) -> PyResult<PyObject> {
+    let window = parse_window(window)?;
+    let report = window_report(window, nperseg).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+    let plot = generate_window_plot(window, nperseg).map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))?;
+
+    let dict = PyDict::new_bound(py);
+    dict.set_item("enbw", report.enbw)?;
+    dict.set_item("coherent_gain", report.coherent_gain)?;
+    dict.set_item("scalloping_loss_db", report.scalloping_loss_db)?;
+    dict.set_item("sidelobe_level_db", report.sidelobe_level_db)?;
+    dict.set_item("plot", PyBytes::new_bound(py, &plot))?;
+    Ok(dict.into_py(py))
+}
+
+#[pyfunction]
+fn suggest_fft_params_py(py: Python<'_>, n_samples: usize, fs: f64, desired_resolution_hz: f64) -> PyResult<PyObject> {
+    let advice = suggest_fft_params(n_samples, fs, desired_resolution_hz)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+
+    let dict = PyDict::new_bound(py);
+    dict.set_item("window", advice.window.name())?;
+    dict.set_item("nperseg", advice.nperseg)?;
+    dict.set_item("nfft", advice.nfft)?;
+    dict.set_item("resolution_hz", advice.resolution_hz)?;
+    dict.set_item("bin_spacing_hz", advice.bin_spacing_hz)?;
+    dict.set_item("segments", advice.segments)?;
+    dict.set_item("relative_std", advice.relative_std)?;
+    dict.set_item("achievable", advice.achievable)?;
+    Ok(dict.into_py(py))
+}
+
+fn parse_detrend(name: &str) -> PyResult<Detrend> {
+    match name.to_lowercase().as_str() {
+        "none" => Ok(Detrend::None),
+        "mean" | "constant" => Ok(Detrend::Mean),
+        "linear" => Ok(Detrend::Linear),
+        _ => Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "Unknown detrend '{}', expected none, mean or linear.",
+            name
+        ))),
+    }
+}
+
+/// Converts an [`AnalysisResult`] to the dict returned by the analysis functions.
+fn analysis_to_dict(py: Python<'_>, result: &AnalysisResult) -> PyResult<PyObject> {
+    let stats = PyDict::new_bound(py);
+    stats.set_item("mean", result.stats.mean)?;
+    stats.set_item("std", result.stats.std)?;
+    stats.set_item("rms", result.stats.rms)?;
+    stats.set_item("min", result.stats.min)?;
+    stats.set_item("max", result.stats.max)?;
+    stats.set_item("crest_factor", result.stats.crest_factor)?;
+    stats.set_item("dominant_frequency", result.stats.dominant_frequency)?;
+
+    let options = PyDict::new_bound(py);
+    options.set_item("window", result.options.window.name())?;
+    options.set_item("nperseg", result.options.nperseg)?;
+    options.set_item("detrend", result.options.detrend.name())?;
+
+    let plots = PyDict::new_bound(py);
+    for (name, png) in &result.plots {
+        plots.set_item(name, PyBytes::new_bound(py, png))?;
+    }
+
+    let dict = PyDict::new_bound(py);
+    dict.set_item("path", &result.path)?;
+    dict.set_item("fs", result.fs)?;
+    dict.set_item("n_samples", result.n_samples)?;
+    dict.set_item("options", options)?;
+    dict.set_item("freqs", &result.freqs)?;
+    dict.set_item("amplitude", &result.amplitude)?;
+    dict.set_item("psd_freqs", &result.psd_freqs)?;
+    dict.set_item("psd", &result.psd)?;
+    dict.set_item("stats", stats)?;
+    dict.set_item("plots", plots)?;
+    Ok(dict.into_py(py))
+}
+
+#[pyfunction]
+#[pyo3(signature = (path, fs=None, window="hann", nperseg=1024, detrend="linear", plots=false))]
+fn analyze_file_py(
+    py: Python<'_>,
+    path: String,
+    fs: Option<f64>,
+    window: &str,
+    nperseg: usize,
+    detrend: &str,
+    plots: bool,
+) -> PyResult<PyObject> {
+    let options = AnalysisOptions { window: parse_window(window)?, nperseg, detrend: parse_detrend(detrend)?, plots };
+    let result = py
+        .allow_threads(|| analyze_file(&path, fs, &options).map_err(|e| e.to_string()))
+        .map_err(pyo3::exceptions::PyValueError::new_err)?;
+    analysis_to_dict(py, &result)
+}
+
+/// Rebuilds an [`AnalysisResult`] from the dict made by [`analysis_to_dict`].
+fn analysis_from_dict(dict: &Bound<'_, PyDict>) -> PyResult<AnalysisResult> {
+    fn item<'py>(dict: &Bound<'py, PyDict>, key: &str) -> PyResult<Bound<'py, PyAny>> {
+        dict.get_item(key)?
+            .ok_or_else(|| pyo3::exceptions::PyKeyError::new_err(format!("Analysis result has no '{}' entry.", key)))
+    }
+    let options = item(dict, "options")?.downcast_into::<PyDict>()?;
+    let stats = item(dict, "stats")?.downcast_into::<PyDict>()?;
+    let plots = match dict.get_item("plots")? {
+        Some(plots) => plots.downcast_into::<PyDict>()?.iter().map(|(k, v)| Ok((k.extract()?, v.extract()?))).collect::<PyResult<_>>()?,
+        None => Vec::new(),
+    };
+    Ok(AnalysisResult {
+        path: item(dict, "path")?.extract()?,
+        fs: item(dict, "fs")?.extract()?,
+        n_samples: item(dict, "n_samples")?.extract()?,
+        options: AnalysisOptions {
+            window: parse_window(&item(&options, "window")?.extract::<String>()?)?,
+            nperseg: item(&options, "nperseg")?.extract()?,
+            detrend: parse_detrend(&item(&options, "detrend")?.extract::<String>()?)?,
+            plots: !plots.is_empty(),
+        },
+        freqs: item(dict, "freqs")?.extract::<Samples>()?.0,
+        amplitude: item(dict, "amplitude")?.extract::<Samples>()?.0,
+        psd_freqs: item(dict, "psd_freqs")?.extract::<Samples>()?.0,
+        psd: item(dict, "psd")?.extract::<Samples>()?.0,
+        stats: SignalStats {
+            mean: item(&stats, "mean")?.extract()?,
+            std: item(&stats, "std")?.extract()?,
+            rms: item(&stats, "rms")?.extract()?,
+            min: item(&stats, "min")?.extract()?,
+            max: item(&stats, "max")?.extract()?,
+            crest_factor: item(&stats, "crest_factor")?.extract()?,
+            dominant_frequency: item(&stats, "dominant_frequency")?.extract()?,
+        },
+        plots,
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (results, path, format="html"))]
+fn generate_report_py(py: Python<'_>, results: Vec<Bound<'_, PyDict>>, path: String, format: &str) -> PyResult<()> {
+    let format = match format.to_lowercase().as_str() {
+        "html" => ReportFormat::Html,
+        "pdf" => ReportFormat::Pdf,
+        _ => {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Unknown report format '{}', expected html or pdf.",
+                format
+            )))
+        }
+    };
+    let results = results.iter().map(analysis_from_dict).collect::<PyResult<Vec<_>>>()?;
+    py.allow_threads(|| generate_report(&results, &path, format).map_err(|e| e.to_string()))
+        .map_err(pyo3::exceptions::PyRuntimeError::new_err)
+}
+
+#[pyfunction]
+#[pyo3(signature = (pattern, output_dir, fs=None, window="hann", nperseg=1024, detrend="linear", plots=false, progress=None))]
+#[allow(clippy::too_many_arguments)]
+fn analyze_directory_py(
+    py: Python<'_>,
+    pattern: String,
+    output_dir: String,
+    fs: Option<f64>,
+    window: &str,
+    nperseg: usize,
+    detrend: &str,
+    plots: bool,
+    progress: Option<PyObject>,
+) -> PyResult<Vec<PyObject>> {
+    let options = AnalysisOptions { window: parse_window(window)?, nperseg, detrend: parse_detrend(detrend)?, plots };
+    let entries = with_progress(py, progress, |p| analyze_directory_with_progress(&pattern, fs, &options, &output_dir, p))?
+        .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))?;
+
+    entries
+        .into_iter()
+        .map(|entry| {
+            let dict = PyDict::new_bound(py);
+            dict.set_item("path", entry.path)?;
+            dict.set_item("fs", entry.fs)?;
+            dict.set_item("n_samples", entry.n_samples)?;
+            if let Some(stats) = entry.stats {
+                let stats_dict = PyDict::new_bound(py);
+                stats_dict.set_item("mean", stats.mean)?;
+                stats_dict.set_item("std", stats.std)?;
+                stats_dict.set_item("rms", stats.rms)?;
+                stats_dict.set_item("min", stats.min)?;
+                stats_dict.set_item("max", stats.max)?;
+                stats_dict.set_item("crest_factor", stats.crest_factor)?;
+                stats_dict.set_item("dominant_frequency", stats.dominant_frequency)?;
+                dict.set_item("stats", stats_dict)?;
+            } else {
+                dict.set_item("stats", py.None())?;
+            }
+            dict.set_item("error", entry.error)?;
+            Ok(dict.into_py(py))
+        })
+        .collect()
+}
+
+#[pyfunction]
+#[pyo3(signature = (path=None))]
+fn set_cache_dir_py(path: Option<&str>) -> PyResult<()> {
+    set_cache_dir(path).map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+fn get_cache_dir_py() -> Option<String> {
+    cache_dir().map(|dir| dir.to_string_lossy().into_owned())
+}
+
+#[pyfunction]
+fn clear_cache_py() -> PyResult<usize> {
+    clear_cache().map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (a, b, align=true, plot=false))]
+fn compare_spectra_py(py: Python<'_>, a: PyRef<'_, PySpectrum>, b: PyRef<'_, PySpectrum>, align: bool, plot: bool) -> PyResult<PyObject> {
+    let comparison =
+        compare_spectra(&a.inner, &b.inner, align).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+
+    let dict = PyDict::new_bound(py);
+    dict.set_item("freqs", &comparison.freqs)?;
+    dict.set_item("ratio", &comparison.ratio)?;
+    dict.set_item("difference_db", &comparison.difference_db)?;
+    dict.set_item("mean_deviation_db", comparison.mean_deviation_db)?;
+    dict.set_item("rms_deviation_db", comparison.rms_deviation_db)?;
+    dict.set_item("max_deviation_db", comparison.max_deviation_db)?;
+    dict.set_item("max_deviation_freq", comparison.max_deviation_freq)?;
+    if plot {
+        let png = generate_comparison_plot(&comparison, "Spectrum comparison", &PlotOptions::default())
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))?;
+        dict.set_item("plot", PyBytes::new_bound(py, &png))?;
+    }
+    Ok(dict.into_py(py))
+}
+
+/// A limit line given from Python as `(start_freq, end_freq, start_db, end_db)` with an optional fifth element
+///     "upper" (the default) or "lower".
+struct PyMaskSegment(MaskSegment);
+
+impl<'py> FromPyObject<'py> for PyMaskSegment {
+    fn extract_bound(obj: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let (start_freq, end_freq, start_db, end_db, kind) = match obj.extract::<(f64, f64, f64, f64)>() {
+            Ok((a, b, c, d)) => (a, b, c, d, "upper".to_string()),
+            Err(_) => obj.extract::<(f64, f64, f64, f64, String)>()?,
+        };
+        let kind = match kind.to_lowercase().as_str() {
+            "upper" => MaskKind::Upper,
+            "lower" => MaskKind::Lower,
+            _ => {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "Unknown mask kind '{}', expected upper or lower.",
+                    kind
+                )))
+            }
+        };
+        Ok(PyMaskSegment(MaskSegment { start_freq, end_freq, start_db, end_db, kind }))
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (freqs, magnitude_db, mask_segments, plot=false))]
+fn check_mask_py(
+    py: Python<'_>,
+    freqs: Samples,
+    magnitude_db: Samples,
+    mask_segments: Vec<PyMaskSegment>,
+    plot: bool,
+) -> PyResult<PyObject> {
+    let segments: Vec<MaskSegment> = mask_segments.into_iter().map(|s| s.0).collect();
+    let result =
+        check_mask(&freqs.0, &magnitude_db.0, &segments).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+
+    let dict = PyDict::new_bound(py);
+    dict.set_item("passed", result.passed)?;
+    let entries = result
+        .segments
+        .iter()
+        .map(|segment| {
+            let entry = PyDict::new_bound(py);
+            entry.set_item("passed", segment.passed)?;
+            entry.set_item("margin_db", segment.margin_db)?;
+            entry.set_item("worst_freq", segment.worst_freq)?;
+            entry.set_item("n_bins", segment.n_bins)?;
+            Ok(entry)
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+    dict.set_item("segments", entries)?;
+    if plot {
+        let title = if result.passed { "Mask check: PASS" } else { "Mask check: FAIL" };
+        let png = generate_mask_plot(&freqs.0, &magnitude_db.0, &segments, title, &PlotOptions::default())
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))?;
+        dict.set_item("plot", PyBytes::new_bound(py, &png))?;
+    }
+    Ok(dict.into_py(py))
+}
+
+#[pyfunction]
+#[pyo3(signature = (spectra, mode="linear"))]
+fn average_spectra_py(spectra: Vec<PyRef<'_, PySpectrum>>, mode: &str) -> PyResult<PySpectrum> {
+    let mode = match mode.to_lowercase().as_str() {
+        "linear" => SpectrumAverage::Linear,
+        "power" => SpectrumAverage::Power,
+        "db" => SpectrumAverage::Db,
+        "peak" | "peak_hold" => SpectrumAverage::PeakHold,
+        _ => {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Unknown averaging mode '{}', expected linear, power, db or peak_hold.",
+                mode
+            )))
+        }
+    };
+    let spectra: Vec<Spectrum> = spectra.iter().map(|s| s.inner.clone()).collect();
+    average_spectra(&spectra, mode)
+        .map(|inner| PySpectrum { inner })
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (sizes, repeats=10))]
+fn benchmark_py(sizes: Vec<usize>, repeats: usize) -> PyResult<Vec<(usize, f64, f64, f64)>> {
+    benchmark(sizes, repeats)
+        .map(|results| results.into_iter().map(|r| (r.size, r.mean_seconds, r.best_seconds, r.mflops)).collect())
+        .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+fn warm_plans_py(sizes: Vec<usize>) {
+    warm_plans(&sizes)
+}
+
+#[pyfunction]
+fn plan_cache_stats_py(py: Python<'_>) -> PyResult<PyObject> {
+    let stats = plan_cache_stats();
+    let dict = PyDict::new_bound(py);
+    dict.set_item("forward_plans", stats.forward_plans)?;
+    dict.set_item("inverse_plans", stats.inverse_plans)?;
+    dict.set_item("hits", stats.hits)?;
+    dict.set_item("misses", stats.misses)?;
+    Ok(dict.into_py(py))
+}
+
+#[pyfunction]
+fn plan_info_py(py: Python<'_>, n: usize) -> PyResult<PyObject> {
+    let info = plan_info(n);
+    let dict = PyDict::new_bound(py);
+    dict.set_item("algorithm", info.algorithm)?;
+    dict.set_item("factors", info.factors)?;
+    dict.set_item("uses_raders", info.uses_raders)?;
+    dict.set_item("uses_bluesteins", info.uses_bluesteins)?;
+    dict.set_item("estimated_flops", info.estimated_flops)?;
+    dict.set_item("relative_cost", info.relative_cost)?;
+    Ok(dict.into_py(py))
+}
+
+#[pyfunction]
+fn fast_len_py(n: usize) -> usize {
+    fast_len(n)
+}
+
+#[pyfunction]
+fn clear_plan_cache_py() {
+    clear_plan_cache()
+}
+
+fn parse_tick_format(name: &str) -> PyResult<TickFormat> {
+    match name.to_lowercase().as_str() {
+        "auto" => Ok(TickFormat::Auto),
+        "scientific" | "sci" => Ok(TickFormat::Scientific),
+        "engineering" | "eng" => Ok(TickFormat::Engineering),
+        "si" => Ok(TickFormat::SiPrefix),
+        _ => Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "Unknown tick format '{}', expected auto, scientific, engineering or si.",
+            name
+        ))),
+    }
+}
+
+/// Collects the axis keyword arguments shared by the line plots.
+#[allow(clippy::too_many_arguments)]
+fn plot_options(
+    xlim: Option<(f64, f64)>,
+    ylim: Option<(f64, f64)>,
+    x_ticks: Option<usize>,
+    y_ticks: Option<usize>,
+    x_format: &str,
+    y_format: &str,
+    y_percentile: Option<f64>,
+) -> PyResult<PlotOptions> {
+    Ok(PlotOptions {
+        x: AxisOptions { limits: xlim, ticks: x_ticks, format: parse_tick_format(x_format)?, ..Default::default() },
+        y: AxisOptions {
+            limits: ylim,
+            autoscale: y_percentile.map_or(Autoscale::MinMax, Autoscale::Percentile),
+            ticks: y_ticks,
+            format: parse_tick_format(y_format)?,
+        },
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (x, y, x_label, y_label, title, xlim=None, ylim=None, x_ticks=None, y_ticks=None, x_format="auto", y_format="auto", y_percentile=None))]
+#[allow(clippy::too_many_arguments)]
+fn generate_plot_py(
+    x: Samples,
+    y: Samples,
+    x_label: String,
+    y_label: String,
+    title: String,
+    xlim: Option<(f64, f64)>,
+    ylim: Option<(f64, f64)>,
+    x_ticks: Option<usize>,
+    y_ticks: Option<usize>,
+    x_format: &str,
+    y_format: &str,
+    y_percentile: Option<f64>,
+) -> PyResult<Vec<u8>> {
+    let options = plot_options(xlim, ylim, x_ticks, y_ticks, x_format, y_format, y_percentile)?;
+    let data: Vec<(f64, f64)> = x.0.into_iter().zip(y.0).collect();
+    generate_plot_with_options(data, &x_label, &y_label, &title, &options)
+        .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, fs, names=None, title="Channels", channels=None, xlim=None, ylim=None, x_ticks=None, y_ticks=None, x_format="auto", y_format="auto", y_percentile=None))]
+#[allow(clippy::too_many_arguments)]
+fn generate_channels_plot_py(
+    data: PyChannels,
+    fs: f64,
+    names: Option<Vec<String>>,
+    title: &str,
+    channels: Option<usize>,
+    xlim: Option<(f64, f64)>,
+    ylim: Option<(f64, f64)>,
+    x_ticks: Option<usize>,
+    y_ticks: Option<usize>,
+    x_format: &str,
+    y_format: &str,
+    y_percentile: Option<f64>,
+) -> PyResult<Vec<u8>> {
+    let options = plot_options(xlim, ylim, x_ticks, y_ticks, x_format, y_format, y_percentile)?;
+    generate_channels_plot(&data.into_matrix(channels)?, fs, names.as_deref(), title, &options)
+        .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))
+}
+
+fn parse_colormap(name: &str) -> PyResult<Colormap> {
+    Colormap::from_name(name).ok_or_else(|| {
+        pyo3::exceptions::PyValueError::new_err(format!(
+            "Unknown colormap '{}', expected viridis, magma, plasma, jet, gray or diverging.",
+            name
+        ))
+    })
+}
+
+#[pyfunction]
+fn colormaps_py() -> Vec<&'static str> {
+    Colormap::ALL.iter().map(|colormap| colormap.name()).collect()
+}
+
+#[pyfunction]
+#[pyo3(signature = (times, freqs, power, title, colormap="viridis", db_min=None, db_max=None, log_frequency=false, colorbar=false, xlim=None, ylim=None, x_ticks=None, y_ticks=None, x_format="auto", y_format="auto"))]
+#[allow(clippy::too_many_arguments)]
+fn generate_spectrogram_plot_py(
+    times: Vec<f64>,
+    freqs: Vec<f64>,
+    power: Matrix,
+    title: String,
+    colormap: &str,
+    db_min: Option<f64>,
+    db_max: Option<f64>,
+    log_frequency: bool,
+    colorbar: bool,
+    xlim: Option<(f64, f64)>,
+    ylim: Option<(f64, f64)>,
+    x_ticks: Option<usize>,
+    y_ticks: Option<usize>,
+    x_format: &str,
+    y_format: &str,
+) -> PyResult<Vec<u8>> {
+    let colormap = parse_colormap(colormap)?;
+    let options = SpectrogramPlotOptions { colormap, db_min, db_max, log_frequency, colorbar };
+    let axes = plot_options(xlim, ylim, x_ticks, y_ticks, x_format, y_format, None)?;
+    generate_spectrogram_plot(&Spectrogram { times, freqs, power }, &title, &options, &axes)
+        .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (freqs, psd, lower=None, upper=None, title="Power spectral density", reference=PyDbReference::Value(1.0), xlim=None, ylim=None, x_ticks=None, y_ticks=None, x_format="auto", y_format="auto", y_percentile=None))]
+#[allow(clippy::too_many_arguments)]
+fn generate_psd_plot_py(
+    freqs: Samples,
+    psd: Samples,
+    lower: Option<Samples>,
+    upper: Option<Samples>,
+    title: &str,
+    reference: PyDbReference,
+    xlim: Option<(f64, f64)>,
+    ylim: Option<(f64, f64)>,
+    x_ticks: Option<usize>,
+    y_ticks: Option<usize>,
+    x_format: &str,
+    y_format: &str,
+    y_percentile: Option<f64>,
+) -> PyResult<Vec<u8>> {
+    let options = plot_options(xlim, ylim, x_ticks, y_ticks, x_format, y_format, y_percentile)?;
+    let band = match (&lower, &upper) {
+        (Some(lower), Some(upper)) => Some((lower.0.as_slice(), upper.0.as_slice())),
+        (None, None) => None,
+        _ => return Err(pyo3::exceptions::PyValueError::new_err("Pass both lower and upper, or neither.")),
+    };
+    generate_psd_plot(&freqs.0, &psd.0, band, title, reference.power()?, &options)
+        .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (freqs, magnitude_db, phase, title="Frequency response", xlim=None, ylim=None, x_ticks=None, y_ticks=None, x_format="auto", y_format="auto", y_percentile=None))]
+#[allow(clippy::too_many_arguments)]
+fn generate_bode_plot_py(
+    freqs: Samples,
+    magnitude_db: Samples,
+    phase: Samples,
+    title: &str,
+    xlim: Option<(f64, f64)>,
+    ylim: Option<(f64, f64)>,
+    x_ticks: Option<usize>,
+    y_ticks: Option<usize>,
+    x_format: &str,
+    y_format: &str,
+    y_percentile: Option<f64>,
+) -> PyResult<Vec<u8>> {
+    let options = plot_options(xlim, ylim, x_ticks, y_ticks, x_format, y_format, y_percentile)?;
+    generate_bode_plot(&freqs.0, &magnitude_db.0, &phase.0, title, &options)
+        .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))
+}
+
+/// Forwards `log` records to Python's `logging` module, using the Rust module path as the logger name
+///     (e.g. `fft_rust_in_python.io`). Records must be emitted from the thread holding the GIL, logging from worker
+///     threads while the main thread runs Rust code under the GIL would deadlock.
+struct PythonLogger;
+
+impl log::Log for PythonLogger {
+    fn enabled(&self, metadata: &log::Metadata<'_>) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &log::Record<'_>) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let level = match record.level() {
+            log::Level::Error => 40,
+            log::Level::Warn => 30,
+            log::Level::Info => 20,
+            log::Level::Debug => 10,
+            log::Level::Trace => 5,
+        };
+        Python::with_gil(|py| {
+            let name = record.target().replace("::", ".");
+            let message = record.args().to_string();
+            let logged = py
+                .import_bound("logging")
+                .and_then(|logging| logging.call_method1("getLogger", (name,)))
+                .and_then(|logger| logger.call_method1("log", (level, message)));
+            if let Err(e) = logged {
+                e.print(py);
+            }
+        });
+    }
+
+    fn flush(&self) {}
+}
+
+#[pyfunction]
+fn set_num_threads_py(n: usize) -> PyResult<()> {
+    set_num_threads(n).map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+fn get_num_threads_py() -> usize {
+    get_num_threads()
+}
+
+#[cfg(feature = "gpu")]
+#[pyfunction]
+fn gpu_device_py() -> Option<String> {
+    gpu_device()
+}
+
+#[pyfunction]
+fn set_log_level_py(level: &str) -> PyResult<()> {
+    let filter = match level.to_lowercase().as_str() {
+        "off" => log::LevelFilter::Off,
+        "error" => log::LevelFilter::Error,
+        "warn" | "warning" => log::LevelFilter::Warn,
+        "info" => log::LevelFilter::Info,
+        "debug" => log::LevelFilter::Debug,
+        "trace" => log::LevelFilter::Trace,
+        _ => {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Unknown log level '{}', expected off, error, warn, info, debug or trace.",
+                level
+            )))
+        }
+    };
+    log::set_max_level(filter);
+    Ok(())
+}
+
+/// A Python module implemented in Rust.
+#[pymodule]
+fn fft_rust_in_python(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    // Another extension in the process may already have installed a logger
+    if log::set_logger(&PythonLogger).is_ok() {
+        log::set_max_level(log::LevelFilter::Warn);
+    }
+
+    m.add_function(wrap_pyfunction!(read_csv_py, m)?)?;
+    m.add_function(wrap_pyfunction!(read_dataframe_py, m)?)?;
+    m.add_function(wrap_pyfunction!(read_segy_py, m)?)?;
+    m.add_function(wrap_pyfunction!(read_mseed_py, m)?)?;
+    m.add_function(wrap_pyfunction!(read_edf_py, m)?)?;
+    m.add_function(wrap_pyfunction!(read_wav_py, m)?)?;
+    m.add_function(wrap_pyfunction!(open_wav_stream_py, m)?)?;
+    #[cfg(all(feature = "audio", target_os = "linux"))]
+    m.add_function(wrap_pyfunction!(open_audio_capture_py, m)?)?;
+    #[cfg(all(feature = "audio", target_os = "linux"))]
+    m.add_function(wrap_pyfunction!(play_py, m)?)?;
+    m.add_function(wrap_pyfunction!(read_flac_py, m)?)?;
+    m.add_function(wrap_pyfunction!(read_audio_py, m)?)?;
+    m.add_function(wrap_pyfunction!(deinterleave_py, m)?)?;
+    m.add_function(wrap_pyfunction!(interleave_py, m)?)?;
+    m.add_function(wrap_pyfunction!(select_channels_py, m)?)?;
+    m.add_function(wrap_pyfunction!(to_mono_py, m)?)?;
+    m.add_function(wrap_pyfunction!(compute_fft_py, m)?)?;
+    m.add_function(wrap_pyfunction!(compute_fft_complex_py, m)?)?;
+    m.add_function(wrap_pyfunction!(compute_ifft_complex_py, m)?)?;
+    m.add_function(wrap_pyfunction!(compute_fft_fixed_py, m)?)?;
+    m.add_function(wrap_pyfunction!(compute_fft_interleaved_py, m)?)?;
+    m.add_function(wrap_pyfunction!(to_interleaved_py, m)?)?;
+    m.add_function(wrap_pyfunction!(from_interleaved_py, m)?)?;
+    m.add_function(wrap_pyfunction!(compute_fft_axis_py, m)?)?;
+    m.add_function(wrap_pyfunction!(fft_shift_py, m)?)?;
+    m.add_function(wrap_pyfunction!(compute_magnitude_py, m)?)?;
+    m.add_function(wrap_pyfunction!(compute_phase_py, m)?)?;
+    m.add_function(wrap_pyfunction!(amplitude_to_db_py, m)?)?;
+    m.add_function(wrap_pyfunction!(power_to_db_py, m)?)?;
+    m.add_function(wrap_pyfunction!(estimate_noise_floor_py, m)?)?;
+    m.add_function(wrap_pyfunction!(whiten_py, m)?)?;
+    m.add_function(wrap_pyfunction!(crop_spectrum_py, m)?)?;
+    m.add_function(wrap_pyfunction!(band_pass_fd_py, m)?)?;
+    m.add_function(wrap_pyfunction!(band_snr_py, m)?)?;
+    m.add_function(wrap_pyfunction!(generate_frequencies_py, m)?)?;
+    m.add_function(wrap_pyfunction!(fft_shift_frequencies_py, m)?)?;
+    m.add_function(wrap_pyfunction!(compute_psd_welch_py, m)?)?;
+    m.add_function(wrap_pyfunction!(ar_psd_py, m)?)?;
+    m.add_function(wrap_pyfunction!(dpss_py, m)?)?;
+    m.add_function(wrap_pyfunction!(multitaper_psd_py, m)?)?;
+    m.add_function(wrap_pyfunction!(psd_confidence_py, m)?)?;
+    m.add_function(wrap_pyfunction!(bootstrap_psd_py, m)?)?;
+    m.add_function(wrap_pyfunction!(stationarity_test_py, m)?)?;
+    m.add_function(wrap_pyfunction!(spectral_kurtosis_py, m)?)?;
+    m.add_function(wrap_pyfunction!(kurtogram_py, m)?)?;
+    m.add_function(wrap_pyfunction!(band_power_py, m)?)?;
+    m.add_function(wrap_pyfunction!(matrix_pencil_py, m)?)?;
+    m.add_function(wrap_pyfunction!(music_py, m)?)?;
+    m.add_function(wrap_pyfunction!(esprit_py, m)?)?;
+    m.add_function(wrap_pyfunction!(frames_py, m)?)?;
+    m.add_function(wrap_pyfunction!(find_triggers_py, m)?)?;
+    m.add_function(wrap_pyfunction!(synchronous_average_py, m)?)?;
+    m.add_function(wrap_pyfunction!(tacho_synchronous_average_py, m)?)?;
+    m.add_function(wrap_pyfunction!(fill_gaps_py, m)?)?;
+    m.add_function(wrap_pyfunction!(check_cola_py, m)?)?;
+    m.add_function(wrap_pyfunction!(stft_py, m)?)?;
+    m.add_function(wrap_pyfunction!(istft_py, m)?)?;
+    m.add_function(wrap_pyfunction!(griffin_lim_py, m)?)?;
+    m.add_function(wrap_pyfunction!(mel_filterbank_py, m)?)?;
+    m.add_function(wrap_pyfunction!(mel_to_linear_py, m)?)?;
+    m.add_function(wrap_pyfunction!(time_stretch_py, m)?)?;
+    m.add_function(wrap_pyfunction!(pitch_shift_py, m)?)?;
+    m.add_function(wrap_pyfunction!(window_report_py, m)?)?;
+    m.add_function(wrap_pyfunction!(suggest_fft_params_py, m)?)?;
+    m.add_function(wrap_pyfunction!(analyze_file_py, m)?)?;
+    m.add_function(wrap_pyfunction!(generate_report_py, m)?)?;
+    m.add_function(wrap_pyfunction!(analyze_directory_py, m)?)?;
+    m.add_function(wrap_pyfunction!(set_cache_dir_py, m)?)?;
+    m.add_function(wrap_pyfunction!(get_cache_dir_py, m)?)?;
+    m.add_function(wrap_pyfunction!(clear_cache_py, m)?)?;
+    m.add_function(wrap_pyfunction!(compare_spectra_py, m)?)?;
+    m.add_function(wrap_pyfunction!(check_mask_py, m)?)?;
+    m.add_function(wrap_pyfunction!(average_spectra_py, m)?)?;
+    m.add_class::<PyFrames>()?;
+    m.add_function(wrap_pyfunction!(compute_spectrogram_py, m)?)?;
+    m.add_function(wrap_pyfunction!(bark_spectrum_py, m)?)?;
+    m.add_function(wrap_pyfunction!(erb_spectrum_py, m)?)?;
+    m.add_function(wrap_pyfunction!(cross_spectral_matrix_py, m)?)?;
+    m.add_function(wrap_pyfunction!(frequency_domain_decomposition_py, m)?)?;
+    m.add_function(wrap_pyfunction!(delay_and_sum_py, m)?)?;
+    m.add_function(wrap_pyfunction!(gcc_phat_py, m)?)?;
+    m.add_function(wrap_pyfunction!(resample_py, m)?)?;
+    m.add_function(wrap_pyfunction!(align_signals_py, m)?)?;
+    m.add_function(wrap_pyfunction!(fractional_delay_py, m)?)?;
+    m.add_function(wrap_pyfunction!(interpolate_py, m)?)?;
+    m.add_function(wrap_pyfunction!(despike_py, m)?)?;
+    m.add_function(wrap_pyfunction!(detect_clipping_py, m)?)?;
+    m.add_function(wrap_pyfunction!(detect_voice_activity_py, m)?)?;
+    m.add_function(wrap_pyfunction!(trim_silence_py, m)?)?;
+    m.add_function(wrap_pyfunction!(split_on_silence_py, m)?)?;
+    m.add_function(wrap_pyfunction!(declip_py, m)?)?;
+    m.add_function(wrap_pyfunction!(normalize_py, m)?)?;
+    m.add_function(wrap_pyfunction!(apply_gain_py, m)?)?;
+    m.add_function(wrap_pyfunction!(apply_calibration_py, m)?)?;
+    m.add_function(wrap_pyfunction!(kalman_trend_py, m)?)?;
+    m.add_function(wrap_pyfunction!(analytic_signal_py, m)?)?;
+    m.add_function(wrap_pyfunction!(emd_py, m)?)?;
+    m.add_function(wrap_pyfunction!(instantaneous_attributes_py, m)?)?;
+    m.add_function(wrap_pyfunction!(hilbert_huang_spectrum_py, m)?)?;
+    m.add_function(wrap_pyfunction!(white_noise_py, m)?)?;
+    m.add_function(wrap_pyfunction!(colored_noise_py, m)?)?;
+    m.add_function(wrap_pyfunction!(generate_ess_py, m)?)?;
+    m.add_function(wrap_pyfunction!(extract_impulse_response_py, m)?)?;
+    m.add_function(wrap_pyfunction!(generate_mls_py, m)?)?;
+    m.add_function(wrap_pyfunction!(mls_impulse_response_py, m)?)?;
+    m.add_function(wrap_pyfunction!(ir_to_fr_py, m)?)?;
+    m.add_function(wrap_pyfunction!(rt60_py, m)?)?;
+    m.add_function(wrap_pyfunction!(clarity_py, m)?)?;
+    m.add_function(wrap_pyfunction!(fft_bandpass_py, m)?)?;
+    m.add_function(wrap_pyfunction!(differentiate_fd_py, m)?)?;
+    m.add_function(wrap_pyfunction!(integrate_fd_py, m)?)?;
+    m.add_function(wrap_pyfunction!(minimum_phase_py, m)?)?;
+    m.add_function(wrap_pyfunction!(linear_phase_fir_py, m)?)?;
+    m.add_function(wrap_pyfunction!(design_eq_py, m)?)?;
+    m.add_function(wrap_pyfunction!(freqz_py, m)?)?;
+    m.add_class::<PyBiquadCascade>()?;
+    m.add_class::<PyWavStream>()?;
+    m.add_class::<PyArrowTable>()?;
+    #[cfg(all(feature = "audio", target_os = "linux"))]
+    m.add_class::<PyAudioCapture>()?;
+    m.add_class::<PyDecimator>()?;
+    m.add_class::<PyInterpolator>()?;
+    m.add_class::<PyFarrowResampler>()?;
+    m.add_class::<PyDcBlocker>()?;
+    m.add_class::<PyAgc>()?;
+    m.add_class::<PyAdaptiveFilter>()?;
+    m.add_class::<PyPeakHoldSpectrum>()?;
+    m.add_class::<PyAverageSpectrum>()?;
+    m.add_class::<PySignal>()?;
+    m.add_class::<PySpectrum>()?;
+    m.add_function(wrap_pyfunction!(vibration_weighting_py, m)?)?;
+    m.add_function(wrap_pyfunction!(frequency_weight_py, m)?)?;
+    m.add_function(wrap_pyfunction!(vibration_metrics_py, m)?)?;
+    m.add_function(wrap_pyfunction!(k_weighting_py, m)?)?;
+    m.add_function(wrap_pyfunction!(loudness_py, m)?)?;
+    m.add_function(wrap_pyfunction!(allan_deviation_py, m)?)?;
+    m.add_function(wrap_pyfunction!(frequency_shift_py, m)?)?;
+    m.add_function(wrap_pyfunction!(mix_to_baseband_py, m)?)?;
+    m.add_function(wrap_pyfunction!(demodulate_am_py, m)?)?;
+    m.add_function(wrap_pyfunction!(demodulate_fm_py, m)?)?;
+    m.add_function(wrap_pyfunction!(benchmark_py, m)?)?;
+    m.add_function(wrap_pyfunction!(warm_plans_py, m)?)?;
+    m.add_function(wrap_pyfunction!(plan_cache_stats_py, m)?)?;
+    m.add_function(wrap_pyfunction!(plan_info_py, m)?)?;
+    m.add_function(wrap_pyfunction!(fast_len_py, m)?)?;
+    m.add_function(wrap_pyfunction!(clear_plan_cache_py, m)?)?;
+    m.add_function(wrap_pyfunction!(set_num_threads_py, m)?)?;
+    m.add_function(wrap_pyfunction!(get_num_threads_py, m)?)?;
+    #[cfg(feature = "gpu")]
+    m.add_function(wrap_pyfunction!(gpu_device_py, m)?)?;
+    m.add_function(wrap_pyfunction!(generate_plot_py, m)?)?;
+    m.add_function(wrap_pyfunction!(generate_channels_plot_py, m)?)?;
+    m.add_function(wrap_pyfunction!(colormaps_py, m)?)?;
+    m.add_function(wrap_pyfunction!(generate_spectrogram_plot_py, m)?)?;
+    m.add_function(wrap_pyfunction!(generate_psd_plot_py, m)?)?;
+    m.add_function(wrap_pyfunction!(generate_bode_plot_py, m)?)?;
+    m.add_function(wrap_pyfunction!(set_log_level_py, m)?)?;
+
+    Ok(())
+}
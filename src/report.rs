@@ -0,0 +1,249 @@
+//! Self-contained HTML and PDF reports of analysis results.
+
+use std::error::Error;
+use std::fmt::Write as _;
+
+use crate::{generate_plot, AnalysisResult, NamedPlot};
+
+/// File format written by [`generate_report`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// A single HTML page with the plots embedded as data URIs.
+    Html,
+    /// A PDF document with one page per result.
+    Pdf,
+}
+
+/// Plots of a result, rendering the spectrum and PSD when the analysis did not keep any.
+fn report_plots(result: &AnalysisResult) -> Result<Vec<NamedPlot>, Box<dyn Error>> {
+    if !result.plots.is_empty() {
+        return Ok(result.plots.clone());
+    }
+    let db = |x: &[f64], values: &[f64], scale: f64| {
+        x.iter().zip(values).map(|(&f, v)| (f, scale * v.max(1e-300).log10())).collect::<Vec<_>>()
+    };
+    Ok(vec![
+        (
+            "spectrum".to_string(),
+            generate_plot(db(&result.freqs, &result.amplitude, 20.0), "Frequency [Hz]", "Amplitude [dB]", &result.path)?,
+        ),
+        ("psd".to_string(), generate_plot(db(&result.psd_freqs, &result.psd, 10.0), "Frequency [Hz]", "PSD [dB/Hz]", &result.path)?),
+    ])
+}
+
+/// Parameters and metrics of a result as label and value pairs, in report order.
+fn report_rows(result: &AnalysisResult) -> Vec<(&'static str, String)> {
+    let stats = &result.stats;
+    vec![
+        ("Sampling rate [Hz]", format!("{}", result.fs)),
+        ("Samples", result.n_samples.to_string()),
+        ("Duration [s]", format!("{:.6}", result.n_samples as f64 / result.fs)),
+        ("Window", result.options.window.name().to_string()),
+        ("Welch segment length", result.options.nperseg.to_string()),
+        ("Detrend", result.options.detrend.name().to_string()),
+        ("Mean", format!("{:.6e}", stats.mean)),
+        ("Standard deviation", format!("{:.6e}", stats.std)),
+        ("RMS", format!("{:.6e}", stats.rms)),
+        ("Minimum", format!("{:.6e}", stats.min)),
+        ("Maximum", format!("{:.6e}", stats.max)),
+        ("Crest factor", format!("{:.4}", stats.crest_factor)),
+        ("Dominant frequency [Hz]", format!("{:.4}", stats.dominant_frequency)),
+    ]
+}
+
+/// Writes the plots, parameters and metrics of each result to a single shareable file at `path`.
+pub fn generate_report(results: &[AnalysisResult], path: &str, format: ReportFormat) -> Result<(), Box<dyn Error>> {
+    if results.is_empty() {
+        return Err("At least one result is needed for a report.".into());
+    }
+    let bytes = match format {
+        ReportFormat::Html => html_report(results)?.into_bytes(),
+        ReportFormat::Pdf => pdf_report(results)?,
+    };
+    std::fs::write(path, bytes)?;
+    log::debug!("Wrote a {:?} report of {} results to {}", format, results.len(), path);
+    Ok(())
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Standard base64 with padding.
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let triple = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(triple >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+fn html_report(results: &[AnalysisResult]) -> Result<String, Box<dyn Error>> {
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Analysis report</title>\n<style>\n\
+         body { font-family: sans-serif; margin: 2em; }\n\
+         table { border-collapse: collapse; margin-bottom: 1em; }\n\
+         td { border: 1px solid #ccc; padding: 0.2em 0.6em; }\n\
+         img { width: 48%; margin: 0.5%; }\n\
+         </style>\n</head>\n<body>\n<h1>Analysis report</h1>\n",
+    );
+    for result in results {
+        writeln!(html, "<section>\n<h2>{}</h2>\n<table>", escape_html(&result.path))?;
+        for (label, value) in report_rows(result) {
+            writeln!(html, "<tr><td>{}</td><td>{}</td></tr>", label, escape_html(&value))?;
+        }
+        html.push_str("</table>\n");
+        for (name, png) in report_plots(result)? {
+            writeln!(html, "<img alt=\"{}\" src=\"data:image/png;base64,{}\">", escape_html(&name), base64(&png))?;
+        }
+        html.push_str("</section>\n");
+    }
+    html.push_str("</body>\n</html>\n");
+    Ok(html)
+}
+
+/// A PNG image reduced to what a PDF image XObject needs.
+struct PdfImage {
+    width: u32,
+    height: u32,
+    /// Concatenated IDAT chunks, a zlib stream PDF decodes with the PNG predictors.
+    data: Vec<u8>,
+}
+
+/// Extracts the compressed pixel data of an 8 bit RGB, non-interlaced PNG as written by the plot functions.
+fn pdf_image(png: &[u8]) -> Result<PdfImage, Box<dyn Error>> {
+    let mut offset = 8;
+    let mut header = None;
+    let mut data = Vec::new();
+    while offset + 8 <= png.len() {
+        let length = u32::from_be_bytes(png[offset..offset + 4].try_into()?) as usize;
+        let kind = &png[offset + 4..offset + 8];
+        let body = png.get(offset + 8..offset + 8 + length).ok_or("Truncated PNG chunk.")?;
+        match kind {
+            b"IHDR" => header = Some(body.to_vec()),
+            b"IDAT" => data.extend_from_slice(body),
+            _ => {}
+        }
+        offset += length + 12;
+    }
+    let header = header.filter(|h| h.len() == 13).ok_or("PNG has no valid header.")?;
+    if header[8] != 8 || header[9] != 2 || header[12] != 0 {
+        return Err("Only 8 bit RGB, non-interlaced PNG images can be embedded in a PDF.".into());
+    }
+    Ok(PdfImage {
+        width: u32::from_be_bytes(header[0..4].try_into()?),
+        height: u32::from_be_bytes(header[4..8].try_into()?),
+        data,
+    })
+}
+
+/// Escapes text for a PDF string literal, replacing characters outside printable ASCII.
+fn escape_pdf(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '(' | ')' | '\\' => format!("\\{}", c),
+            ' '..='~' => c.to_string(),
+            _ => "?".to_string(),
+        })
+        .collect()
+}
+
+fn pdf_report(results: &[AnalysisResult]) -> Result<Vec<u8>, Box<dyn Error>> {
+    // A4 portrait in points, plots in two columns below the text
+    const PAGE: (f64, f64) = (595.0, 842.0);
+    const MARGIN: f64 = 40.0;
+    let plot_width = (PAGE.0 - 2.0 * MARGIN - 15.0) / 2.0;
+
+    // Objects 1 to 3 are the catalog, page tree and font, pages follow in blocks
+    let mut objects: Vec<Vec<u8>> = vec![Vec::new(), Vec::new(), b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_vec()];
+    let mut page_ids = Vec::new();
+    for result in results {
+        let images = report_plots(result)?
+            .iter()
+            .map(|(_, png)| pdf_image(png))
+            .collect::<Result<Vec<_>, _>>()?;
+        let first_image = objects.len() + 3;
+
+        let mut content = String::new();
+        let mut y = PAGE.1 - MARGIN - 14.0;
+        writeln!(content, "BT /F1 14 Tf {} {} Td ({}) Tj ET", MARGIN, y, escape_pdf(&result.path))?;
+        y -= 8.0;
+        for (label, value) in report_rows(result) {
+            y -= 13.0;
+            writeln!(content, "BT /F1 9 Tf {} {} Td ({}) Tj ET", MARGIN, y, escape_pdf(label))?;
+            writeln!(content, "BT /F1 9 Tf {} {} Td ({}) Tj ET", MARGIN + 160.0, y, escape_pdf(&value))?;
+        }
+        y -= 15.0;
+        for (i, image) in images.iter().enumerate() {
+            let height = plot_width * image.height as f64 / image.width as f64;
+            let x = MARGIN + (i % 2) as f64 * (plot_width + 15.0);
+            if i % 2 == 0 {
+                y -= height;
+            }
+            writeln!(content, "q {} 0 0 {} {} {} cm /Im{} Do Q", plot_width, height, x, y, i)?;
+            if i % 2 == 1 {
+                y -= 10.0;
+            }
+        }
+
+        let resources: String = (0..images.len()).map(|i| format!("/Im{} {} 0 R ", i, first_image + i)).collect();
+        page_ids.push(objects.len() + 1);
+        objects.push(
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Contents {} 0 R /Resources << /Font << /F1 3 0 R >> /XObject << {}>> >> >>",
+                PAGE.0,
+                PAGE.1,
+                objects.len() + 2,
+                resources
+            )
+            .into_bytes(),
+        );
+        objects.push(pdf_stream(String::new(), content.as_bytes()));
+        for image in images {
+            let dictionary = format!(
+                "/Type /XObject /Subtype /Image /Width {w} /Height {h} /ColorSpace /DeviceRGB /BitsPerComponent 8 \
+                 /Filter /FlateDecode /DecodeParms << /Predictor 15 /Colors 3 /BitsPerComponent 8 /Columns {w} >> ",
+                w = image.width,
+                h = image.height
+            );
+            objects.push(pdf_stream(dictionary, &image.data));
+        }
+    }
+    objects[0] = b"<< /Type /Catalog /Pages 2 0 R >>".to_vec();
+    let kids: String = page_ids.iter().map(|id| format!("{} 0 R ", id)).collect();
+    objects[1] = format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids, page_ids.len()).into_bytes();
+
+    let mut pdf = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend_from_slice(format!("{} 0 obj\n", i + 1).as_bytes());
+        pdf.extend_from_slice(object);
+        pdf.extend_from_slice(b"\nendobj\n");
+    }
+    let xref = pdf.len();
+    let mut trailer = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+    for offset in offsets {
+        writeln!(trailer, "{:010} 00000 n ", offset)?;
+    }
+    write!(trailer, "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n", objects.len() + 1, xref)?;
+    pdf.extend_from_slice(trailer.as_bytes());
+    Ok(pdf)
+}
+
+/// A stream object with the given extra dictionary entries.
+fn pdf_stream(dictionary: String, data: &[u8]) -> Vec<u8> {
+    let mut object = format!("<< {}/Length {} >>\nstream\n", dictionary, data.len()).into_bytes();
+    object.extend_from_slice(data);
+    object.extend_from_slice(b"\nendstream");
+    object
+}
@@ -0,0 +1,787 @@
+//! Magnitude and power spectrum estimation.
+
+use rayon::prelude::*;
+use rustfft::{Fft, FftDirection, num_complex::Complex};
+use std::error::Error;
+use std::sync::Arc;
+
+use crate::{cache, kernels, linalg, plan_fft, threads, ComplexMatrix, Matrix, Progress};
+
+/// Computes the magnitude of complex data (real and imaginary parts).
+pub fn compute_magnitude(real: Vec<f64>, imag: Vec<f64>) -> Result<Vec<f64>, Box<dyn Error>> {
+    if real.len() != imag.len() {
+        return Err("Real and imaginary parts must have the same length.".into());
+    }
+    Ok(kernels::magnitude(&real, &imag))
+}
+
+/// Computes the phase angle in radians of complex data (real and imaginary parts).
+pub fn compute_phase(real: Vec<f64>, imag: Vec<f64>) -> Result<Vec<f64>, Box<dyn Error>> {
+    if real.len() != imag.len() {
+        return Err("Real and imaginary parts must have the same length.".into());
+    }
+    Ok(kernels::phase(&real, &imag))
+}
+
+/// Converts amplitudes (e.g. FFT magnitudes) to dB relative to `reference`, as `20 * log10(value / reference)`.
+pub fn amplitude_to_db(values: Vec<f64>, reference: f64) -> Result<Vec<f64>, Box<dyn Error>> {
+    if reference <= 0.0 {
+        return Err("Reference must be greater than zero.".into());
+    }
+    Ok(kernels::to_db(&values, reference, 20.0))
+}
+
+/// Converts powers (e.g. PSD values) to dB relative to `reference`, as `10 * log10(value / reference)`.
+pub fn power_to_db(values: Vec<f64>, reference: f64) -> Result<Vec<f64>, Box<dyn Error>> {
+    if reference <= 0.0 {
+        return Err("Reference must be greater than zero.".into());
+    }
+    Ok(kernels::to_db(&values, reference, 10.0))
+}
+
+/// Level reference of dB values, defined by the amplitude that reads 0 dB. Powers are referred to its square.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DbReference {
+    /// An arbitrary amplitude, labelled plain dB when it is 1.
+    Amplitude(f64),
+    /// Digital full scale for samples normalized to ±1, so a full scale sine peaks at 0 dBFS.
+    FullScale,
+    /// 1 V.
+    Volt,
+    /// 0.7746 V, the voltage that dissipates 1 mW in 600 Ω.
+    Dbu,
+    /// 20 µPa, the threshold of hearing, for sound pressure in Pa.
+    SoundPressure,
+}
+
+impl Default for DbReference {
+    fn default() -> Self {
+        DbReference::Amplitude(1.0)
+    }
+}
+
+impl DbReference {
+    /// The amplitude that reads 0 dB.
+    pub fn amplitude(self) -> f64 {
+        match self {
+            DbReference::Amplitude(value) => value,
+            DbReference::FullScale | DbReference::Volt => 1.0,
+            DbReference::Dbu => 0.6f64.sqrt(),
+            DbReference::SoundPressure => 20e-6,
+        }
+    }
+
+    /// The power that reads 0 dB.
+    pub fn power(self) -> f64 {
+        self.amplitude() * self.amplitude()
+    }
+
+    /// Unit of levels against this reference, e.g. `"dBFS"` or `"dB SPL"`.
+    pub fn label(self) -> String {
+        match self {
+            DbReference::Amplitude(1.0) => "dB".to_string(),
+            DbReference::Amplitude(value) => format!("dB re {}", value),
+            DbReference::FullScale => "dBFS".to_string(),
+            DbReference::Volt => "dBV".to_string(),
+            DbReference::Dbu => "dBu".to_string(),
+            DbReference::SoundPressure => "dB SPL".to_string(),
+        }
+    }
+}
+
+/// Generates a periodic Hann window of the given length.
+pub(crate) fn hann_window(len: usize) -> Vec<f64> {
+    (0..len)
+        .map(|i| 0.5 - 0.5 * (2.0 * std::f64::consts::PI * i as f64 / len as f64).cos())
+        .collect()
+}
+
+/// Computes one sided power spectral densities of mean detrended, Hann windowed segments of a fixed length.
+struct SegmentPsd {
+    window: Vec<f64>,
+    scale: f64,
+    fft: Arc<dyn Fft<f64>>,
+    windowed: Vec<f64>,
+    buffer: Vec<Complex<f64>>,
+}
+
+impl SegmentPsd {
+    fn new(nperseg: usize, fs: f64) -> Self {
+        let window = hann_window(nperseg);
+        let scale = 1.0 / (fs * window.iter().map(|w| w * w).sum::<f64>());
+        let fft = plan_fft(nperseg, FftDirection::Forward);
+        let buffer = vec![Complex::new(0.0, 0.0); nperseg];
+        SegmentPsd { window, scale, fft, windowed: vec![0.0; nperseg], buffer }
+    }
+
+    /// Mean detrends and windows the segment into `self.windowed`.
+    fn prepare(&mut self, segment: &[f64]) {
+        let mean = segment.iter().sum::<f64>() / self.window.len() as f64;
+        for (w, x) in self.windowed.iter_mut().zip(segment) {
+            *w = x - mean;
+        }
+        kernels::apply_window(&mut self.windowed, &self.window);
+    }
+
+    /// Scales the squared magnitudes of the FFT bins of a prepared segment to the one sided PSD.
+    fn fold(&self, power: impl Iterator<Item = f64>) -> Vec<f64> {
+        // Fold the negative frequencies into the positive ones, DC and Nyquist appear only once
+        let nperseg = self.window.len();
+        power
+            .take(nperseg / 2 + 1)
+            .enumerate()
+            .map(|(k, p)| {
+                let one_sided = if k == 0 || 2 * k == nperseg { 1.0 } else { 2.0 };
+                p * self.scale * one_sided
+            })
+            .collect()
+    }
+
+    /// Returns the PSD of the segment in units of data²/Hz for bins `0..=nperseg / 2`.
+    fn process(&mut self, segment: &[f64]) -> Vec<f64> {
+        self.prepare(segment);
+        for (b, x) in self.buffer.iter_mut().zip(&self.windowed) {
+            *b = Complex::new(*x, 0.0);
+        }
+        self.fft.process(&mut self.buffer);
+        self.fold(self.buffer.iter().map(|c| c.norm_sqr()))
+    }
+}
+
+/// The PSDs of all segments from one batch of FFTs on the GPU, or `None` when the batch is too small for it or no
+///     GPU is available.
+#[cfg(feature = "gpu")]
+fn offload_segments(segment_psd: &mut SegmentPsd, data: &[f64], starts: &[usize]) -> Option<Matrix> {
+    // Checked before building the windowed copies of every segment that the GPU needs
+    let nperseg = segment_psd.window.len();
+    if !nperseg.is_power_of_two() || starts.len() * nperseg < crate::gpu::GPU_MIN_SAMPLES {
+        return None;
+    }
+    let lanes: Matrix = starts
+        .iter()
+        .map(|&start| {
+            segment_psd.prepare(&data[start..start + nperseg]);
+            segment_psd.windowed.clone()
+        })
+        .collect();
+    let (real, imag) = crate::gpu::fft_lanes(&lanes)?;
+    Some(real.iter().zip(&imag).map(|(re, im)| segment_psd.fold(re.iter().zip(im).map(|(r, i)| r * r + i * i))).collect())
+}
+
+/// Estimates the one sided power spectral density of the data using Welch's method.
+///     Segments of `nperseg` samples are Hann windowed with 50% overlap, mean detrended and averaged.
+///     Returns the frequency bins and the PSD in units of data²/Hz. Results are reused from the [`cache`] when
+///     it is enabled.
+pub fn compute_psd_welch(data: Vec<f64>, fs: f64, nperseg: usize) -> Result<(Vec<f64>, Vec<f64>), Box<dyn Error>> {
+    compute_psd_welch_with_progress(data, fs, nperseg, &mut |_| true)
+}
+
+/// Same as [`compute_psd_welch`], reporting the fraction of segments averaged so far to `progress`.
+pub fn compute_psd_welch_with_progress(
+    data: Vec<f64>,
+    fs: f64,
+    nperseg: usize,
+    progress: &mut dyn FnMut(f64) -> bool,
+) -> Result<(Vec<f64>, Vec<f64>), Box<dyn Error>> {
+    if data.is_empty() || nperseg == 0 || fs <= 0.0 {
+        return Err("Data must not be empty, nperseg must be positive and fs must be greater than zero.".into());
+    }
+
+    let key = cache::psd_key(&data, fs, nperseg);
+    if let Some(cached) = key.as_ref().and_then(|key| cache::load(key, |d| Some((d.floats()?, d.floats()?)))) {
+        progress(1.0);
+        return Ok(cached);
+    }
+
+    let nperseg = nperseg.min(data.len());
+    let step = (nperseg / 2).max(1);
+    let segments = (data.len() - nperseg) / step + 1;
+    log::debug!("Welch PSD of {} samples with {} segments of {}", data.len(), segments, nperseg);
+    let mut progress = Progress::new(progress);
+    let mut segment_psd = SegmentPsd::new(nperseg, fs);
+    let mut psd = vec![0.0; nperseg / 2 + 1];
+
+    for (i, start) in (0..=data.len() - nperseg).step_by(step).enumerate() {
+        for (p, x) in psd.iter_mut().zip(segment_psd.process(&data[start..start + nperseg])) {
+            *p += x;
+        }
+        progress.update((i + 1) as f64 / segments as f64)?;
+    }
+    psd.iter_mut().for_each(|p| *p /= segments as f64);
+    let freqs: Vec<f64> = (0..psd.len()).map(|k| k as f64 * fs / nperseg as f64).collect();
+
+    if let Some(key) = key {
+        cache::store(&key, |encoder| {
+            encoder.floats(&freqs).floats(&psd);
+        });
+    }
+    Ok((freqs, psd))
+}
+
+/// A time-frequency power map, one PSD row per segment.
+pub struct Spectrogram {
+    /// Centre of each segment in seconds.
+    pub times: Vec<f64>,
+    pub freqs: Vec<f64>,
+    /// PSD of each segment in units of data²/Hz.
+    pub power: Matrix,
+}
+
+/// Computes a spectrogram from mean detrended, Hann windowed segments of `nperseg` samples starting every `hop` samples.
+///     With the `gpu` feature the segment FFTs run on the GPU as one batch under the same conditions as
+///     [`compute_fft_axis`](crate::compute_fft_axis).
+pub fn compute_spectrogram(data: Vec<f64>, fs: f64, nperseg: usize, hop: usize) -> Result<Spectrogram, Box<dyn Error>> {
+    compute_spectrogram_with_progress(data, fs, nperseg, hop, &mut |_| true)
+}
+
+/// Same as [`compute_spectrogram`], reporting the fraction of segments transformed so far to `progress`.
+pub fn compute_spectrogram_with_progress(
+    data: Vec<f64>,
+    fs: f64,
+    nperseg: usize,
+    hop: usize,
+    progress: &mut dyn FnMut(f64) -> bool,
+) -> Result<Spectrogram, Box<dyn Error>> {
+    if nperseg == 0 || hop == 0 || fs <= 0.0 {
+        return Err("nperseg and hop must be positive and fs must be greater than zero.".into());
+    }
+    if data.len() < nperseg {
+        return Err("Data must contain at least nperseg samples.".into());
+    }
+
+    let mut progress = Progress::new(progress);
+    let mut segment_psd = SegmentPsd::new(nperseg, fs);
+    let starts: Vec<usize> = (0..=data.len() - nperseg).step_by(hop).collect();
+    log::debug!("Spectrogram of {} samples with {} segments of {} every {}", data.len(), starts.len(), nperseg, hop);
+    let times = starts.iter().map(|&start| (start as f64 + nperseg as f64 / 2.0) / fs).collect();
+    #[cfg(feature = "gpu")]
+    let offloaded = offload_segments(&mut segment_psd, &data, &starts);
+    #[cfg(not(feature = "gpu"))]
+    let offloaded = None;
+
+    let power = match offloaded {
+        Some(power) => {
+            progress.update(1.0)?;
+            power
+        }
+        None => {
+            let mut power = Vec::with_capacity(starts.len());
+            for (i, &start) in starts.iter().enumerate() {
+                power.push(segment_psd.process(&data[start..start + nperseg]));
+                progress.update((i + 1) as f64 / starts.len() as f64)?;
+            }
+            power
+        }
+    };
+    let freqs = (0..nperseg / 2 + 1).map(|k| k as f64 * fs / nperseg as f64).collect();
+
+    Ok(Spectrogram { times, freqs, power })
+}
+
+/// The power contained in a named frequency band.
+pub struct BandPower {
+    pub name: String,
+    pub absolute: f64,
+    /// Fraction of the total power of the spectrum.
+    pub relative: f64,
+}
+
+/// Computes the absolute and relative power of the data in frequency bands using a Welch PSD.
+///     `bands` holds `(name, low, high)` ranges in Hz, the classic EEG bands (delta to gamma) are used when it is `None`.
+pub fn band_power(
+    data: Vec<f64>,
+    fs: f64,
+    bands: Option<Vec<(String, f64, f64)>>,
+    nperseg: Option<usize>,
+) -> Result<Vec<BandPower>, Box<dyn Error>> {
+    let bands = bands.unwrap_or_else(|| {
+        [("delta", 0.5, 4.0), ("theta", 4.0, 8.0), ("alpha", 8.0, 13.0), ("beta", 13.0, 30.0), ("gamma", 30.0, 45.0)]
+            .iter()
+            .map(|&(name, low, high)| (name.to_string(), low, high))
+            .collect()
+    });
+    if let Some((name, _, _)) = bands.iter().find(|(_, low, high)| low >= high || *low < 0.0) {
+        return Err(format!("Band '{}' must have 0 <= low < high.", name).into());
+    }
+
+    // Four seconds per segment gives 0.25 Hz resolution, enough to resolve the delta band
+    let nperseg = nperseg.unwrap_or((4.0 * fs) as usize);
+    let (freqs, psd) = compute_psd_welch(data, fs, nperseg)?;
+    let df = freqs.get(1).copied().unwrap_or(fs);
+    let total: f64 = psd.iter().sum::<f64>() * df;
+
+    Ok(bands
+        .into_iter()
+        .map(|(name, low, high)| {
+            let power = freqs
+                .iter()
+                .zip(&psd)
+                .filter(|(f, _)| **f >= low && **f < high)
+                .map(|(_, p)| p)
+                .sum::<f64>()
+                * df;
+            let relative = if total > 0.0 { power / total } else { 0.0 };
+            BandPower { name, absolute: power, relative }
+        })
+        .collect())
+}
+
+/// Averaged one sided cross-spectral density matrices of a set of channels, one matrix per frequency bin.
+pub struct CrossSpectralMatrix {
+    pub freqs: Vec<f64>,
+    /// Entry `[i][j]` of each matrix is the cross-spectral density of channel `i` with channel `j`.
+    pub matrices: Vec<ComplexMatrix>,
+}
+
+/// Estimates cross-spectral density matrices with Welch's method (Hann window, 50% overlap, mean detrended).
+///     The diagonal of each matrix holds the PSD of the corresponding channel.
+pub fn cross_spectral_matrix(channels: Matrix, fs: f64, nperseg: usize) -> Result<CrossSpectralMatrix, Box<dyn Error>> {
+    cross_spectral_matrix_with_progress(channels, fs, nperseg, &mut |_| true)
+}
+
+/// Same as [`cross_spectral_matrix`], reporting the fraction of segments averaged so far to `progress`.
+pub fn cross_spectral_matrix_with_progress(
+    channels: Matrix,
+    fs: f64,
+    nperseg: usize,
+    progress: &mut dyn FnMut(f64) -> bool,
+) -> Result<CrossSpectralMatrix, Box<dyn Error>> {
+    let len = channels.first().map_or(0, Vec::len);
+    if channels.is_empty() || len == 0 || channels.iter().any(|c| c.len() != len) {
+        return Err("Channels must be non-empty and all have the same length.".into());
+    }
+    if nperseg == 0 || fs <= 0.0 {
+        return Err("nperseg must be positive and fs must be greater than zero.".into());
+    }
+
+    let nperseg = nperseg.min(len);
+    let step = (nperseg / 2).max(1);
+    let bins = nperseg / 2 + 1;
+    let window = hann_window(nperseg);
+    let scale = 1.0 / (fs * window.iter().map(|w| w * w).sum::<f64>());
+    let fft = plan_fft(nperseg, FftDirection::Forward);
+    let count = channels.len();
+
+    let zero = Complex::new(0.0, 0.0);
+    let mut matrices = vec![vec![vec![zero; count]; count]; bins];
+    let total = (len - nperseg) / step + 1;
+    let mut progress = Progress::new(progress);
+    let mut segments = 0;
+    for start in (0..=len - nperseg).step_by(step) {
+        let spectra: Vec<Vec<Complex<f64>>> = channels
+            .iter()
+            .map(|channel| {
+                let segment = &channel[start..start + nperseg];
+                let mean = segment.iter().sum::<f64>() / nperseg as f64;
+                let mut buffer: Vec<Complex<f64>> =
+                    segment.iter().zip(&window).map(|(x, w)| Complex::new((x - mean) * w, 0.0)).collect();
+                fft.process(&mut buffer);
+                buffer
+            })
+            .collect();
+        for (k, matrix) in matrices.iter_mut().enumerate() {
+            for (i, row) in matrix.iter_mut().enumerate() {
+                for (j, value) in row.iter_mut().enumerate() {
+                    *value += spectra[i][k] * spectra[j][k].conj();
+                }
+            }
+        }
+        segments += 1;
+        progress.update(segments as f64 / total as f64)?;
+    }
+
+    for (k, matrix) in matrices.iter_mut().enumerate() {
+        let one_sided = if k == 0 || 2 * k == nperseg { 1.0 } else { 2.0 };
+        let factor = scale * one_sided / segments as f64;
+        matrix.iter_mut().flatten().for_each(|value| *value *= factor);
+    }
+    let freqs = (0..bins).map(|k| k as f64 * fs / nperseg as f64).collect();
+
+    Ok(CrossSpectralMatrix { freqs, matrices })
+}
+
+/// Result of a frequency domain decomposition.
+pub struct FrequencyDomainDecomposition {
+    pub freqs: Vec<f64>,
+    /// Singular values of the cross-spectral matrix at each frequency, in descending order.
+    pub singular_values: Matrix,
+    /// First singular vector (operational mode shape) at each frequency, unit norm with its largest entry real.
+    pub mode_shapes: ComplexMatrix,
+}
+
+/// Performs a frequency domain decomposition (FDD) for operational modal analysis.
+///     Peaks of the first singular value indicate modes and the matching singular vector estimates their shape.
+pub fn frequency_domain_decomposition(
+    channels: Matrix,
+    fs: f64,
+    nperseg: usize,
+) -> Result<FrequencyDomainDecomposition, Box<dyn Error>> {
+    let csd = cross_spectral_matrix(channels, fs, nperseg)?;
+    let (singular_values, mode_shapes) = csd
+        .matrices
+        .iter()
+        .map(|matrix| {
+            // The cross-spectral matrix is Hermitian positive semi-definite so its SVD is its eigendecomposition
+            let (values, vectors) = linalg::hermitian_eigen(matrix);
+            let mut shape: Vec<Complex<f64>> = vectors.iter().map(|row| row[0]).collect();
+            let largest = shape.iter().copied().max_by(|a, b| a.norm().total_cmp(&b.norm())).unwrap_or_default();
+            if largest.norm() > 0.0 {
+                let rotation = largest.conj() / largest.norm();
+                shape.iter_mut().for_each(|c| *c *= rotation);
+            }
+            (values.into_iter().map(|v| v.max(0.0)).collect(), shape)
+        })
+        .unzip();
+
+    Ok(FrequencyDomainDecomposition { freqs: csd.freqs, singular_values, mode_shapes })
+}
+
+/// Running statistic used by [`estimate_noise_floor`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NoiseFloorMethod {
+    Median,
+    /// Percentile between 0 and 100, low percentiles ignore peaks more aggressively than the median.
+    Percentile(f64),
+}
+
+/// Estimates the noise floor of a magnitude spectrum with a running median or percentile over `window` bins.
+///     Narrow peaks barely move these statistics, so the result follows the broadband floor.
+pub fn estimate_noise_floor(magnitude: Vec<f64>, method: NoiseFloorMethod, window: usize) -> Result<Vec<f64>, Box<dyn Error>> {
+    let percentile = match method {
+        NoiseFloorMethod::Median => 50.0,
+        NoiseFloorMethod::Percentile(p) if (0.0..=100.0).contains(&p) => p,
+        NoiseFloorMethod::Percentile(_) => return Err("Percentile must be between 0 and 100.".into()),
+    };
+    if window == 0 {
+        return Err("Window must be positive.".into());
+    }
+
+    let len = magnitude.len();
+    let half = window / 2;
+    Ok(threads::install(|| {
+        (0..len)
+            .into_par_iter()
+            .map(|i| {
+                let mut values = magnitude[i.saturating_sub(half)..(i + half + 1).min(len)].to_vec();
+                values.sort_unstable_by(f64::total_cmp);
+                // Linear interpolation between the closest ranks
+                let rank = percentile / 100.0 * (values.len() - 1) as f64;
+                let (lower, upper) = (rank.floor() as usize, rank.ceil() as usize);
+                values[lower] + (values[upper] - values[lower]) * (rank - lower as f64)
+            })
+            .collect()
+    }))
+}
+
+/// Whitens a complex spectrum by dividing it by a noise floor, or by its own magnitude (keeping only the phase)
+///     when no floor is given. Bins with a zero floor are set to zero.
+pub fn whiten(real: Vec<f64>, imag: Vec<f64>, floor: Option<Vec<f64>>) -> Result<(Vec<f64>, Vec<f64>), Box<dyn Error>> {
+    if real.len() != imag.len() || floor.as_ref().is_some_and(|f| f.len() != real.len()) {
+        return Err("Real part, imaginary part and floor must have the same length.".into());
+    }
+    let floor = floor.unwrap_or_else(|| kernels::magnitude(&real, &imag));
+
+    Ok(real
+        .iter()
+        .zip(&imag)
+        .zip(&floor)
+        .map(|((re, im), f)| if *f > 0.0 { (re / f, im / f) } else { (0.0, 0.0) })
+        .unzip())
+}
+
+/// A band of bins extracted from a spectrum by [`crop_spectrum`].
+pub struct SpectrumBand {
+    pub real: Vec<f64>,
+    pub imag: Vec<f64>,
+    pub freqs: Vec<f64>,
+}
+
+/// Extracts the bins whose absolute frequency lies between `low` and `high` Hz, inclusive.
+///     Negative frequency bins of a two sided spectrum are kept alongside their positive counterparts.
+pub fn crop_spectrum(real: Vec<f64>, imag: Vec<f64>, freqs: Vec<f64>, low: f64, high: f64) -> Result<SpectrumBand, Box<dyn Error>> {
+    if real.len() != imag.len() || real.len() != freqs.len() {
+        return Err("Real part, imaginary part and frequencies must have the same length.".into());
+    }
+    if low < 0.0 || low > high {
+        return Err("Band edges must satisfy 0 <= low <= high.".into());
+    }
+
+    let mut band = SpectrumBand { real: Vec::new(), imag: Vec::new(), freqs: Vec::new() };
+    for ((re, im), f) in real.into_iter().zip(imag).zip(freqs) {
+        if (low..=high).contains(&f.abs()) {
+            band.real.push(re);
+            band.imag.push(im);
+            band.freqs.push(f);
+        }
+    }
+    Ok(band)
+}
+
+/// Band-pass filters a complex spectrum, keeping bins whose absolute frequency lies between `low` and `high` Hz.
+///     Outside the band the gain falls to zero along a raised cosine over `taper` Hz, which avoids the ringing of
+///     a brick-wall filter. A zero taper zeroes every bin outside the band.
+pub fn band_pass_fd(
+    real: Vec<f64>,
+    imag: Vec<f64>,
+    freqs: Vec<f64>,
+    low: f64,
+    high: f64,
+    taper: f64,
+) -> Result<(Vec<f64>, Vec<f64>), Box<dyn Error>> {
+    if real.len() != imag.len() || real.len() != freqs.len() {
+        return Err("Real part, imaginary part and frequencies must have the same length.".into());
+    }
+    if low < 0.0 || low > high || taper < 0.0 {
+        return Err("Band edges must satisfy 0 <= low <= high and the taper must not be negative.".into());
+    }
+
+    let gain = |f: f64| {
+        // Distance outside the band in Hz, zero inside it
+        let distance = (low - f).max(f - high).max(0.0);
+        if distance == 0.0 {
+            1.0
+        } else if distance < taper {
+            0.5 + 0.5 * (std::f64::consts::PI * distance / taper).cos()
+        } else {
+            0.0
+        }
+    };
+    Ok(real
+        .iter()
+        .zip(&imag)
+        .zip(&freqs)
+        .map(|((re, im), f)| {
+            let g = gain(f.abs());
+            (re * g, im * g)
+        })
+        .unzip())
+}
+
+/// Computes the signal-to-noise ratio in dB of a magnitude spectrum from the power in two frequency bands.
+///     The noise power is measured in `noise_band` and scaled to the width of `signal_band`, so the bands may
+///     differ in width. Bands are inclusive `(low, high)` ranges in Hz.
+pub fn band_snr(magnitude: Vec<f64>, freqs: Vec<f64>, signal_band: (f64, f64), noise_band: (f64, f64)) -> Result<f64, Box<dyn Error>> {
+    if magnitude.len() != freqs.len() {
+        return Err("Magnitude and frequencies must have the same length.".into());
+    }
+
+    // Mean power per bin within a band, together with the number of bins
+    let band_power = |(low, high): (f64, f64)| {
+        let powers: Vec<f64> = freqs
+            .iter()
+            .zip(&magnitude)
+            .filter(|(f, _)| **f >= low && **f <= high)
+            .map(|(_, m)| m * m)
+            .collect();
+        (powers.iter().sum::<f64>() / powers.len().max(1) as f64, powers.len())
+    };
+    let (signal, signal_bins) = band_power(signal_band);
+    let (noise, noise_bins) = band_power(noise_band);
+    if signal_bins == 0 || noise_bins == 0 {
+        return Err("Both bands must contain at least one frequency bin.".into());
+    }
+    if noise == 0.0 {
+        return Err("The noise band holds no power.".into());
+    }
+
+    Ok(10.0 * (signal / noise).log10())
+}
+
+/// Computes `k` discrete prolate spheroidal (Slepian) sequences of length `n` with time half bandwidth `nw`.
+///     Each taper has unit energy. They are the eigenvectors of the symmetric tridiagonal matrix commuting with
+///     the concentration problem, found by Sturm sequence bisection followed by inverse iteration.
+pub fn dpss(n: usize, nw: f64, k: usize) -> Result<Matrix, Box<dyn Error>> {
+    if n < 2 || k == 0 || k > n || nw <= 0.0 || nw >= n as f64 / 2.0 {
+        return Err("Need n >= 2, 1 <= k <= n and 0 < nw < n / 2.".into());
+    }
+
+    let w = nw / n as f64;
+    let centre = (n - 1) as f64 / 2.0;
+    let diagonal: Vec<f64> = (0..n).map(|i| (centre - i as f64).powi(2) * (2.0 * std::f64::consts::PI * w).cos()).collect();
+    let off: Vec<f64> = (0..n).map(|i| i as f64 * (n - i) as f64 / 2.0).collect();
+
+    // Number of eigenvalues below x
+    let sturm_count = |x: f64| {
+        let mut count = 0;
+        let mut q = 1.0;
+        for i in 0..n {
+            q = diagonal[i] - x - if i > 0 { off[i] * off[i] / q } else { 0.0 };
+            if q == 0.0 {
+                q = -f64::EPSILON;
+            }
+            if q < 0.0 {
+                count += 1;
+            }
+        }
+        count
+    };
+    let radius = (0..n).map(|i| diagonal[i].abs() + off[i] + off.get(i + 1).copied().unwrap_or(0.0)).fold(0.0, f64::max);
+
+    let tapers = threads::install(|| {
+        (0..k)
+            .into_par_iter()
+            .map(|order| {
+                // The tapers belong to the largest eigenvalues
+                let target = n - 1 - order;
+                let (mut low, mut high) = (-radius, radius);
+                for _ in 0..200 {
+                    let mid = 0.5 * (low + high);
+                    if sturm_count(mid) > target {
+                        high = mid;
+                    } else {
+                        low = mid;
+                    }
+                    if high - low <= f64::EPSILON * radius {
+                        break;
+                    }
+                }
+                let eigenvalue = 0.5 * (low + high) + f64::EPSILON * radius;
+
+                // Inverse iteration with the Thomas algorithm on the shifted matrix
+                let mut vector = vec![1.0 / (n as f64).sqrt(); n];
+                for _ in 0..3 {
+                    let mut upper = vec![0.0; n];
+                    let mut rhs = vector.clone();
+                    let mut pivot = diagonal[0] - eigenvalue;
+                    for i in 1..n {
+                        let safe = if pivot.abs() < f64::MIN_POSITIVE { f64::MIN_POSITIVE } else { pivot };
+                        upper[i - 1] = off[i] / safe;
+                        rhs[i - 1] /= safe;
+                        pivot = diagonal[i] - eigenvalue - off[i] * upper[i - 1];
+                        rhs[i] -= off[i] * rhs[i - 1];
+                    }
+                    rhs[n - 1] /= if pivot.abs() < f64::MIN_POSITIVE { f64::MIN_POSITIVE } else { pivot };
+                    for i in (0..n - 1).rev() {
+                        rhs[i] -= upper[i] * rhs[i + 1];
+                    }
+                    let norm = rhs.iter().map(|x| x * x).sum::<f64>().sqrt();
+                    vector = rhs.into_iter().map(|x| x / norm).collect();
+                }
+
+                // Symmetric tapers sum to a positive value, antisymmetric ones start with a positive lobe
+                let sign = if order % 2 == 0 {
+                    vector.iter().sum::<f64>()
+                } else {
+                    vector.iter().enumerate().map(|(i, v)| (centre - i as f64) * v).sum::<f64>()
+                };
+                if sign < 0.0 {
+                    vector.iter_mut().for_each(|v| *v = -*v);
+                }
+                vector
+            })
+            .collect()
+    });
+
+    Ok(tapers)
+}
+
+/// Estimates the one sided PSD with Thomson's multitaper method, averaging the periodograms of the data
+///     weighted by `k` DPSS tapers (default `2 nw - 1`). Returns the frequencies and the PSD in data²/Hz.
+pub fn multitaper_psd(data: Vec<f64>, fs: f64, nw: f64, k: Option<usize>) -> Result<(Vec<f64>, Vec<f64>), Box<dyn Error>> {
+    if fs <= 0.0 {
+        return Err("Sampling frequency must be greater than zero.".into());
+    }
+    let len = data.len();
+    let k = k.unwrap_or(((2.0 * nw).floor() as usize).saturating_sub(1).max(1));
+    let tapers = dpss(len, nw, k)?;
+    let mean = data.iter().sum::<f64>() / len as f64;
+    let fft = plan_fft(len, FftDirection::Forward);
+
+    let bins = len / 2 + 1;
+    let spectra: Vec<Vec<f64>> = threads::install(|| {
+        tapers
+            .into_par_iter()
+            .map(|taper| {
+                let mut buffer: Vec<Complex<f64>> = data.iter().zip(&taper).map(|(x, v)| Complex::new((x - mean) * v, 0.0)).collect();
+                fft.process(&mut buffer);
+                buffer[..bins].iter().map(|c| c.norm_sqr()).collect()
+            })
+            .collect()
+    });
+
+    let psd = (0..bins)
+        .map(|b| {
+            let one_sided = if b == 0 || 2 * b == len { 1.0 } else { 2.0 };
+            one_sided * spectra.iter().map(|s| s[b]).sum::<f64>() / (k as f64 * fs)
+        })
+        .collect();
+    let freqs = (0..bins).map(|b| b as f64 * fs / len as f64).collect();
+
+    Ok((freqs, psd))
+}
+
+/// Computes the spectral kurtosis `<|X|^4> / <|X|^2>^2 - 2` of the STFT of the data with 75% overlap.
+///     Stationary Gaussian noise gives values near zero, impulsive content (e.g. bearing faults) large ones.
+pub fn spectral_kurtosis(data: Vec<f64>, fs: f64, nperseg: usize) -> Result<(Vec<f64>, Vec<f64>), Box<dyn Error>> {
+    let spectrogram = compute_spectrogram(data, fs, nperseg, (nperseg / 4).max(1))?;
+    let segments = spectrogram.power.len() as f64;
+    let kurtosis = (0..spectrogram.freqs.len())
+        .map(|b| {
+            let second = spectrogram.power.iter().map(|p| p[b]).sum::<f64>() / segments;
+            let fourth = spectrogram.power.iter().map(|p| p[b] * p[b]).sum::<f64>() / segments;
+            // The DC and Nyquist bins are real, so Gaussian noise has a normalized fourth moment of 3 there
+            let gaussian = if b == 0 || 2 * b == nperseg { 3.0 } else { 2.0 };
+            if second > 0.0 { fourth / (second * second) - gaussian } else { 0.0 }
+        })
+        .collect();
+    Ok((spectrogram.freqs, kurtosis))
+}
+
+/// Kurtosis of the complex envelope in every band of a dyadic filter bank.
+pub struct Kurtogram {
+    /// Row `l` holds the kurtosis of the `2^l` equal bands between 0 and the Nyquist frequency.
+    pub kurtosis: Matrix,
+    /// Centre frequency in Hz of the band with the largest kurtosis.
+    pub best_center: f64,
+    pub best_bandwidth: f64,
+    pub best_kurtosis: f64,
+}
+
+/// Computes a fast kurtogram for selecting the demodulation band in bearing diagnostics.
+///     Each level `0..=max_level` splits the spectrum into `2^level` bands with ideal FFT filters, and the
+///     kurtosis of each band's complex envelope is computed.
+pub fn kurtogram(data: Vec<f64>, fs: f64, max_level: usize) -> Result<Kurtogram, Box<dyn Error>> {
+    let len = data.len();
+    if len < 2 || fs <= 0.0 {
+        return Err("Data must have at least two samples and fs must be greater than zero.".into());
+    }
+    if max_level > 16 || (1usize << max_level) > len / 2 {
+        return Err("Too many levels for the length of the data.".into());
+    }
+
+    let mean = data.iter().sum::<f64>() / len as f64;
+    let mut spectrum: Vec<Complex<f64>> = data.iter().map(|x| Complex::new(x - mean, 0.0)).collect();
+    plan_fft(len, FftDirection::Forward).process(&mut spectrum);
+    let inverse = plan_fft(len, FftDirection::Inverse);
+
+    let kurtosis: Matrix = threads::install(|| {
+        (0..=max_level)
+            .map(|level| {
+                let bands = 1usize << level;
+                (0..bands)
+                    .into_par_iter()
+                    .map(|band| {
+                        // Keep the positive frequency bins of the band, giving the analytic band signal
+                        let (low, high) = (band * len / 2 / bands, ((band + 1) * len / 2 / bands).max(band * len / 2 / bands + 1));
+                        let mut buffer = vec![Complex::new(0.0, 0.0); len];
+                        buffer[low..high].copy_from_slice(&spectrum[low..high]);
+                        inverse.process(&mut buffer);
+                        let second = buffer.iter().map(|c| c.norm_sqr()).sum::<f64>() / len as f64;
+                        let fourth = buffer.iter().map(|c| c.norm_sqr().powi(2)).sum::<f64>() / len as f64;
+                        if second > 0.0 { fourth / (second * second) - 2.0 } else { 0.0 }
+                    })
+                    .collect()
+            })
+            .collect()
+    });
+
+    let (level, band, best_kurtosis) = kurtosis
+        .iter()
+        .enumerate()
+        .flat_map(|(level, row)| row.iter().enumerate().map(move |(band, k)| (level, band, *k)))
+        .max_by(|a, b| a.2.total_cmp(&b.2))
+        .unwrap_or((0, 0, 0.0));
+    let best_bandwidth = fs / 2.0 / (1usize << level) as f64;
+
+    Ok(Kurtogram { kurtosis, best_center: (band as f64 + 0.5) * best_bandwidth, best_bandwidth, best_kurtosis })
+}
@@ -0,0 +1,47 @@
+//! Frequency stability and sensor drift analysis.
+
+use rayon::prelude::*;
+use std::error::Error;
+
+use crate::threads;
+
+/// Computes the overlapping Allan deviation of rate or fractional frequency samples at the averaging times `taus`
+///     (seconds, rounded to whole samples). Without `taus`, octave spaced averaging times are used.
+///     Returns the averaging times actually used along with the deviation at each.
+pub fn allan_deviation(data: Vec<f64>, fs: f64, taus: Option<Vec<f64>>) -> Result<(Vec<f64>, Vec<f64>), Box<dyn Error>> {
+    let len = data.len();
+    if len < 3 || fs <= 0.0 {
+        return Err("Data must have at least three samples and fs must be greater than zero.".into());
+    }
+
+    let max_m = (len - 1) / 2;
+    let mut factors: Vec<usize> = match taus {
+        Some(taus) => taus.iter().map(|tau| (tau * fs).round() as usize).filter(|&m| m >= 1 && m <= max_m).collect(),
+        None => std::iter::successors(Some(1usize), |m| m.checked_mul(2)).take_while(|&m| m <= max_m).collect(),
+    };
+    factors.dedup();
+    if factors.is_empty() {
+        return Err("No averaging time fits between one sample and half the record length.".into());
+    }
+
+    // Integrate the rate into phase so each cluster average is a difference of two phase samples
+    let mut phase = Vec::with_capacity(len + 1);
+    phase.push(0.0);
+    for y in &data {
+        phase.push(phase.last().copied().unwrap_or(0.0) + y / fs);
+    }
+
+    let deviations = threads::install(|| {
+        factors
+            .par_iter()
+            .map(|&m| {
+                let tau = m as f64 / fs;
+                let terms = phase.len() - 2 * m;
+                let sum: f64 = (0..terms).map(|k| (phase[k + 2 * m] - 2.0 * phase[k + m] + phase[k]).powi(2)).sum();
+                (sum / (2.0 * tau * tau * terms as f64)).sqrt()
+            })
+            .collect()
+    });
+
+    Ok((factors.into_iter().map(|m| m as f64 / fs).collect(), deviations))
+}
@@ -0,0 +1,245 @@
+//! Statistical uncertainty of spectral estimates.
+
+use std::error::Error;
+
+use crate::{compute_spectrogram, Rng};
+
+/// Natural logarithm of the gamma function for positive arguments (Lanczos approximation, g = 7).
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        // Reflection keeps the series in its accurate range
+        return (std::f64::consts::PI / (std::f64::consts::PI * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let series = COEFFICIENTS[1..].iter().enumerate().fold(COEFFICIENTS[0], |sum, (i, c)| sum + c / (x + i as f64 + 1.0));
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + series.ln()
+}
+
+/// Regularized lower incomplete gamma function P(a, x), by its series below `a + 1` and continued fraction above.
+fn regularized_gamma(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    let prefactor = (a * x.ln() - x - ln_gamma(a)).exp();
+    if x < a + 1.0 {
+        let (mut term, mut sum, mut n) = (1.0 / a, 1.0 / a, a);
+        while term.abs() > sum.abs() * 1e-15 {
+            n += 1.0;
+            term *= x / n;
+            sum += term;
+        }
+        return sum * prefactor;
+    }
+    // Modified Lentz evaluation of the continued fraction of Q(a, x)
+    let tiny = 1e-300;
+    let mut b = x + 1.0 - a;
+    let mut c = 1.0 / tiny;
+    let mut d = 1.0 / b;
+    let mut fraction = d;
+    for i in 1..1000 {
+        let an = -(i as f64) * (i as f64 - a);
+        b += 2.0;
+        d = an * d + b;
+        d = if d.abs() < tiny { 1.0 / tiny } else { 1.0 / d };
+        c = b + an / c;
+        if c.abs() < tiny {
+            c = tiny;
+        }
+        let delta = d * c;
+        fraction *= delta;
+        if (delta - 1.0).abs() < 1e-15 {
+            break;
+        }
+    }
+    1.0 - prefactor * fraction
+}
+
+/// Value below which a chi-squared variable with `dof` degrees of freedom falls with probability `p`.
+pub(crate) fn chi_squared_quantile(p: f64, dof: f64) -> f64 {
+    let (mut low, mut high) = (0.0, dof + 40.0 * (2.0 * dof).sqrt() + 100.0);
+    for _ in 0..200 {
+        let mid = 0.5 * (low + high);
+        if regularized_gamma(dof / 2.0, mid / 2.0) < p {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    0.5 * (low + high)
+}
+
+/// Chi-squared confidence interval of a PSD estimated by averaging `n_averages` independent periodograms,
+///     e.g. Welch segments or multitaper tapers, with `2 n_averages` degrees of freedom.
+///     Overlapping Welch segments are not fully independent, pass a smaller, possibly fractional, equivalent count
+///     for a conservative band. Returns the lower and upper bounds of each bin.
+pub fn psd_confidence(psd: &[f64], n_averages: f64, confidence: f64) -> Result<(Vec<f64>, Vec<f64>), Box<dyn Error>> {
+    if !(n_averages > 0.0 && n_averages.is_finite()) {
+        return Err("n_averages must be positive and finite.".into());
+    }
+    if !(confidence > 0.0 && confidence < 1.0) {
+        return Err("confidence must be between 0 and 1.".into());
+    }
+    let dof = 2.0 * n_averages;
+    let alpha = 1.0 - confidence;
+    let lower_factor = dof / chi_squared_quantile(1.0 - alpha / 2.0, dof);
+    let upper_factor = dof / chi_squared_quantile(alpha / 2.0, dof);
+    Ok((psd.iter().map(|p| p * lower_factor).collect(), psd.iter().map(|p| p * upper_factor).collect()))
+}
+
+/// Welch PSD with percentile bands from [`bootstrap_psd`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct BootstrapPsd {
+    pub freqs: Vec<f64>,
+    /// The Welch estimate from all segments.
+    pub psd: Vec<f64>,
+    pub lower: Vec<f64>,
+    pub upper: Vec<f64>,
+}
+
+/// Value at `fraction` between 0 and 1 of sorted data, interpolating between neighbouring ranks.
+fn percentile(sorted: &[f64], fraction: f64) -> f64 {
+    let rank = fraction * (sorted.len() - 1) as f64;
+    let (below, above) = (rank.floor() as usize, rank.ceil() as usize);
+    sorted[below] + (rank - below as f64) * (sorted[above] - sorted[below])
+}
+
+/// Percentile bands of a Welch PSD from a moving block bootstrap over its segment periodograms.
+///     Each of the `n_boot` replicates averages blocks of consecutive segments drawn with replacement, so the band
+///     follows the actual spread of the data instead of assuming Gaussian noise like [`psd_confidence`]. Blocks of
+///     about the cube root of the segment count keep the correlation of overlapping segments. The same `seed`
+///     always gives the same bands.
+pub fn bootstrap_psd(
+    data: Vec<f64>,
+    fs: f64,
+    n_boot: usize,
+    nperseg: usize,
+    confidence: f64,
+    seed: Option<u64>,
+) -> Result<BootstrapPsd, Box<dyn Error>> {
+    if n_boot < 2 {
+        return Err("n_boot must be at least 2.".into());
+    }
+    if !(confidence > 0.0 && confidence < 1.0) {
+        return Err("confidence must be between 0 and 1.".into());
+    }
+    let nperseg = nperseg.min(data.len());
+    let spectrogram = compute_spectrogram(data, fs, nperseg, (nperseg / 2).max(1))?;
+    let segments = &spectrogram.power;
+    let count = segments.len();
+    let block = (count as f64).cbrt().ceil() as usize;
+    let bins = spectrogram.freqs.len();
+    log::debug!("Bootstrap PSD with {} replicates of {} segments in blocks of {}", n_boot, count, block);
+
+    let mut rng = seed.map_or_else(Rng::from_entropy, Rng::new);
+    // One row per bin so each can be sorted for its percentiles
+    let mut replicates = vec![Vec::with_capacity(n_boot); bins];
+    let mut sum = vec![0.0; bins];
+    for _ in 0..n_boot {
+        sum.iter_mut().for_each(|s| *s = 0.0);
+        let mut drawn = 0;
+        while drawn < count {
+            let start = (rng.next_u64() % (count - block + 1) as u64) as usize;
+            for segment in &segments[start..start + block.min(count - drawn)] {
+                sum.iter_mut().zip(segment).for_each(|(s, p)| *s += p);
+            }
+            drawn += block;
+        }
+        for (row, s) in replicates.iter_mut().zip(&sum) {
+            row.push(s / count as f64);
+        }
+    }
+
+    let alpha = 1.0 - confidence;
+    let (mut lower, mut upper) = (Vec::with_capacity(bins), Vec::with_capacity(bins));
+    for row in &mut replicates {
+        row.sort_by(f64::total_cmp);
+        lower.push(percentile(row, alpha / 2.0));
+        upper.push(percentile(row, 1.0 - alpha / 2.0));
+    }
+    let psd = (0..bins).map(|k| segments.iter().map(|s| s[k]).sum::<f64>() / count as f64).collect();
+
+    Ok(BootstrapPsd { freqs: spectrogram.freqs, psd, lower, upper })
+}
+
+/// Standard normal cumulative distribution, from the complementary error function approximation of Numerical
+///     Recipes with a relative error below 1.2e-7.
+fn normal_cdf(z: f64) -> f64 {
+    let x = z.abs() / std::f64::consts::SQRT_2;
+    let t = 1.0 / (1.0 + 0.5 * x);
+    let polynomial = [
+        -1.265_512_23,
+        1.000_023_68,
+        0.374_091_96,
+        0.096_784_18,
+        -0.186_288_06,
+        0.278_868_07,
+        -1.135_203_98,
+        1.488_515_87,
+        -0.822_152_23,
+        0.170_872_77,
+    ]
+    .iter()
+    .rev()
+    .fold(0.0, |sum, c| sum * t + c);
+    let erfc = t * (-x * x + polynomial).exp();
+    if z >= 0.0 { 1.0 - erfc / 2.0 } else { erfc / 2.0 }
+}
+
+/// Outcome of [`stationarity_test`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct StationarityResult {
+    /// Whether the run test does not reject stationarity at the 5% level.
+    pub stationary: bool,
+    /// Two sided probability of a run count at least this far from the expected one for a stationary signal.
+    pub p_value: f64,
+    pub runs: usize,
+    pub expected_runs: f64,
+    pub z_score: f64,
+    /// Mean square of each segment, the sequence the test runs on.
+    pub segment_mean_square: Vec<f64>,
+}
+
+/// Tests whether the power of a signal stays constant with the run test of Bendat and Piersol on the mean squares
+///     of consecutive segments of `nperseg` samples. Too few or too many runs above and below the median mean that
+///     the power drifts or alternates, so a single spectrum averages over changing content and a spectrogram
+///     ([`compute_spectrogram`]) describes the signal better. At least 20 segments give a reliable result.
+pub fn stationarity_test(data: &[f64], nperseg: usize) -> Result<StationarityResult, Box<dyn Error>> {
+    if nperseg == 0 || data.len() / nperseg < 4 {
+        return Err("The data must hold at least 4 segments of nperseg samples.".into());
+    }
+    let segment_mean_square: Vec<f64> =
+        data.chunks_exact(nperseg).map(|segment| segment.iter().map(|x| x * x).sum::<f64>() / nperseg as f64).collect();
+
+    let mut sorted = segment_mean_square.clone();
+    sorted.sort_by(f64::total_cmp);
+    let median = percentile(&sorted, 0.5);
+    // Segments equal to the median belong to neither side and are skipped
+    let signs: Vec<bool> = segment_mean_square.iter().filter(|&&v| v != median).map(|&v| v > median).collect();
+    let above = signs.iter().filter(|&&s| s).count() as f64;
+    let below = signs.len() as f64 - above;
+    let total = above + below;
+    let runs = if signs.is_empty() { 0 } else { 1 + signs.windows(2).filter(|w| w[0] != w[1]).count() };
+
+    // A constant signal has no segments off the median and is trivially stationary
+    let expected_runs = if total > 0.0 { 2.0 * above * below / total + 1.0 } else { 0.0 };
+    let variance = if total > 1.0 { 2.0 * above * below * (2.0 * above * below - total) / (total * total * (total - 1.0)) } else { 0.0 };
+    let z_score = if variance > 0.0 { (runs as f64 - expected_runs) / variance.sqrt() } else { 0.0 };
+    let p_value = (2.0 * (1.0 - normal_cdf(z_score.abs()))).min(1.0);
+    if p_value < 0.05 {
+        log::info!("Signal is not stationary (p = {:.3e}), consider a spectrogram instead of a single spectrum", p_value);
+    }
+
+    Ok(StationarityResult { stationary: p_value >= 0.05, p_value, runs, expected_runs, z_score, segment_mean_square })
+}
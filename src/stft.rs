@@ -0,0 +1,136 @@
+//! Short-time Fourier transform and its inverse.
+
+use rustfft::{num_complex::Complex, FftDirection};
+use std::error::Error;
+
+use crate::{plan_fft, ComplexMatrix, Matrix, Rng, Window};
+
+fn check_frames(nperseg: usize, hop: usize) -> Result<(), Box<dyn Error>> {
+    if nperseg < 2 || hop == 0 || hop > nperseg {
+        return Err("nperseg must be at least 2 and hop between 1 and nperseg.".into());
+    }
+    Ok(())
+}
+
+/// One sided spectra of windowed frames of `nperseg` samples starting every `hop` samples, one row of
+///     `nperseg / 2 + 1` bins per frame. Frames are centred on multiples of `hop`, the data being zero padded by
+///     half a frame at both ends, so [`istft`] recovers every sample.
+pub fn stft(data: &[f64], nperseg: usize, hop: usize, window: Window) -> Result<ComplexMatrix, Box<dyn Error>> {
+    check_frames(nperseg, hop)?;
+    if data.is_empty() {
+        return Err("Data must not be empty.".into());
+    }
+    let coefficients = window.coefficients(nperseg);
+    let fft = plan_fft(nperseg, FftDirection::Forward);
+    let half = (nperseg / 2) as isize;
+    let n_frames = data.len() / hop + 1;
+
+    Ok((0..n_frames)
+        .map(|t| {
+            let start = (t * hop) as isize - half;
+            let mut buffer: Vec<Complex<f64>> = coefficients
+                .iter()
+                .enumerate()
+                .map(|(n, w)| {
+                    let index = start + n as isize;
+                    let x = if index >= 0 { data.get(index as usize).copied().unwrap_or(0.0) } else { 0.0 };
+                    Complex::new(w * x, 0.0)
+                })
+                .collect();
+            fft.process(&mut buffer);
+            buffer.truncate(nperseg / 2 + 1);
+            buffer
+        })
+        .collect())
+}
+
+/// Inverts an [`stft`] with the same frame parameters by weighted overlap-add, dividing by the summed squared
+///     window so any window and hop with enough overlap reconstruct exactly. Modified spectra give the signal
+///     whose STFT is closest to them in the least squares sense (Griffin and Lim). The output is cut or zero
+///     padded to `length`, by default `(frames - 1) * hop` samples.
+pub fn istft(frames: &ComplexMatrix, nperseg: usize, hop: usize, window: Window, length: Option<usize>) -> Result<Vec<f64>, Box<dyn Error>> {
+    check_frames(nperseg, hop)?;
+    if frames.iter().any(|frame| frame.len() != nperseg / 2 + 1) {
+        return Err(format!("Each frame must have nperseg / 2 + 1 = {} bins.", nperseg / 2 + 1).into());
+    }
+    let coefficients = window.coefficients(nperseg);
+    let ifft = plan_fft(nperseg, FftDirection::Inverse);
+    let half = nperseg / 2;
+    let padded = frames.len().saturating_sub(1) * hop + nperseg;
+    let (mut output, mut weight) = (vec![0.0; padded], vec![0.0; padded]);
+
+    for (t, frame) in frames.iter().enumerate() {
+        // Rebuild the negative frequencies as the mirrored conjugates of the positive ones
+        let mut buffer = vec![Complex::new(0.0, 0.0); nperseg];
+        buffer[..frame.len()].copy_from_slice(frame);
+        for k in 1..nperseg.div_ceil(2) {
+            buffer[nperseg - k] = frame[k].conj();
+        }
+        ifft.process(&mut buffer);
+        for (n, (c, w)) in buffer.iter().zip(&coefficients).enumerate() {
+            output[t * hop + n] += c.re * w / nperseg as f64;
+            weight[t * hop + n] += w * w;
+        }
+    }
+
+    let length = length.unwrap_or(frames.len().saturating_sub(1) * hop);
+    Ok((half..half + length)
+        .map(|n| match (output.get(n), weight.get(n)) {
+            (Some(x), Some(&w)) if w > 1e-10 => x / w,
+            _ => 0.0,
+        })
+        .collect())
+}
+
+/// Weight of the previous estimate in the fast Griffin-Lim update of Perraudin et al. (2013).
+const MOMENTUM: f64 = 0.99;
+
+/// Reconstructs a signal from the magnitude of its Hann windowed [`stft`], one row of `nperseg / 2 + 1` bins per
+///     frame, by alternating between the signal whose STFT is closest to the current estimate and the spectra with
+///     the given magnitudes and the phases of that STFT. The fast variant with momentum converges in tens of
+///     iterations, `n_iter = 32` is usually enough. Phases start random, from `seed` if given. The result is
+///     `(frames - 1) * hop` samples long, and its sign or a time shift may differ from the original signal.
+pub fn griffin_lim(
+    magnitude: &Matrix,
+    n_iter: usize,
+    nperseg: usize,
+    hop: usize,
+    seed: Option<u64>,
+) -> Result<Vec<f64>, Box<dyn Error>> {
+    check_frames(nperseg, hop)?;
+    if magnitude.len() < 2 || magnitude.iter().any(|frame| frame.len() != nperseg / 2 + 1) {
+        return Err(format!("At least two frames of nperseg / 2 + 1 = {} bins are needed.", nperseg / 2 + 1).into());
+    }
+    let length = (magnitude.len() - 1) * hop;
+    let mut rng = seed.map_or_else(Rng::from_entropy, Rng::new);
+    let mut angles: ComplexMatrix = magnitude
+        .iter()
+        .map(|frame| frame.iter().map(|_| Complex::from_polar(1.0, 2.0 * std::f64::consts::PI * rng.uniform())).collect())
+        .collect();
+    let with_magnitude = |angles: &ComplexMatrix| -> ComplexMatrix {
+        magnitude.iter().zip(angles).map(|(m, a)| m.iter().zip(a).map(|(m, a)| a * m).collect()).collect()
+    };
+
+    let mut previous: Option<ComplexMatrix> = None;
+    for _ in 0..n_iter {
+        let signal = istft(&with_magnitude(&angles), nperseg, hop, Window::Hann, Some(length))?;
+        let rebuilt = stft(&signal, nperseg, hop, Window::Hann)?;
+        for (t, frame) in angles.iter_mut().enumerate() {
+            for (k, angle) in frame.iter_mut().enumerate() {
+                let estimate = rebuilt[t][k] - previous.as_ref().map_or(Complex::new(0.0, 0.0), |p| p[t][k] * (MOMENTUM / (1.0 + MOMENTUM)));
+                *angle = estimate / (estimate.norm() + 1e-16);
+            }
+        }
+        previous = Some(rebuilt);
+    }
+
+    let signal = istft(&with_magnitude(&angles), nperseg, hop, Window::Hann, Some(length))?;
+    if log::log_enabled!(log::Level::Debug) {
+        let rebuilt = stft(&signal, nperseg, hop, Window::Hann)?;
+        let (error, total) = magnitude.iter().zip(&rebuilt).flat_map(|(m, r)| m.iter().zip(r)).fold((0.0, 0.0), |(e, t), (m, r)| {
+            (e + (m - r.norm()).powi(2), t + m * m)
+        });
+        log::debug!("Griffin-Lim spectral convergence after {} iterations: {:.3e}", n_iter, (error / total.max(1e-300)).sqrt());
+    }
+    Ok(signal)
+}
@@ -0,0 +1,94 @@
+//! Lightweight sample by sample blocks for live streams.
+
+use std::error::Error;
+use std::f64::consts::PI;
+
+/// One-pole high-pass filter `y[n] = g (x[n] - x[n-1]) + r y[n-1]` removing DC offset and slow drift.
+///     Unlike batch detrending it needs no look-ahead, so it suits live data. The gain `g = (1 + r) / 2` makes the
+///     response unity at the Nyquist frequency.
+#[derive(Clone, Debug)]
+pub struct DcBlocker {
+    pole: f64,
+    previous_input: f64,
+    previous_output: f64,
+}
+
+impl DcBlocker {
+    /// Creates a blocker with its -3 dB point near `cutoff` Hz.
+    pub fn new(fs: f64, cutoff: f64) -> Result<Self, Box<dyn Error>> {
+        if fs <= 0.0 || cutoff <= 0.0 || cutoff >= fs / 2.0 {
+            return Err("Cutoff must be between 0 and fs / 2.".into());
+        }
+        Ok(DcBlocker { pole: (-2.0 * PI * cutoff / fs).exp(), previous_input: 0.0, previous_output: 0.0 })
+    }
+
+    /// Filters the next chunk of the stream.
+    pub fn process(&mut self, chunk: &[f64]) -> Vec<f64> {
+        let gain = (1.0 + self.pole) / 2.0;
+        chunk
+            .iter()
+            .map(|&x| {
+                let y = gain * (x - self.previous_input) + self.pole * self.previous_output;
+                self.previous_input = x;
+                self.previous_output = y;
+                y
+            })
+            .collect()
+    }
+
+    /// Clears the filter state, as before the first chunk.
+    pub fn reset(&mut self) {
+        self.previous_input = 0.0;
+        self.previous_output = 0.0;
+    }
+}
+
+/// Automatic gain control scaling a stream toward a target peak level.
+///     A peak envelope follower rises with the `attack` time constant and decays with the `release` one, and each
+///     sample is multiplied by `target / envelope`, limited to `max_gain` so silence is not amplified to full scale.
+///     The envelope starts at the target, so the gain starts at one.
+#[derive(Clone, Debug)]
+pub struct Agc {
+    target: f64,
+    max_gain: f64,
+    attack: f64,
+    release: f64,
+    envelope: f64,
+}
+
+impl Agc {
+    /// Creates the control loop, with the attack and release time constants in seconds.
+    pub fn new(fs: f64, target: f64, attack: f64, release: f64, max_gain: f64) -> Result<Self, Box<dyn Error>> {
+        if fs <= 0.0 || target <= 0.0 || max_gain <= 0.0 {
+            return Err("fs, target and maximum gain must be greater than zero.".into());
+        }
+        if attack < 0.0 || release < 0.0 {
+            return Err("Attack and release times must not be negative.".into());
+        }
+        let coefficient = |time: f64| if time == 0.0 { 0.0 } else { (-1.0 / (time * fs)).exp() };
+        Ok(Agc { target, max_gain, attack: coefficient(attack), release: coefficient(release), envelope: target })
+    }
+
+    /// The gain applied to the most recent sample.
+    pub fn gain(&self) -> f64 {
+        (self.target / self.envelope).min(self.max_gain)
+    }
+
+    /// Levels the next chunk of the stream.
+    pub fn process(&mut self, chunk: &[f64]) -> Vec<f64> {
+        chunk
+            .iter()
+            .map(|&x| {
+                let level = x.abs();
+                let coefficient = if level > self.envelope { self.attack } else { self.release };
+                self.envelope = coefficient * self.envelope + (1.0 - coefficient) * level;
+                x * self.gain()
+            })
+            .collect()
+    }
+
+    /// Returns the envelope to the target, as before the first chunk.
+    pub fn reset(&mut self) {
+        self.envelope = self.target;
+    }
+}
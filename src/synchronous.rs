@@ -0,0 +1,76 @@
+//! Time synchronous averaging of repeated events.
+//!
+//! Averaging many repetitions aligned on the same event keeps what is synchronous with it and suppresses
+//! everything else by the square root of the repetition count, e.g. the mesh of one gear against the rest of a
+//! gearbox.
+
+use std::error::Error;
+
+use crate::{interpolate, Interpolation, Matrix};
+
+/// Averages equally long segments sample by sample, e.g. the output of [`find_triggers`](crate::find_triggers).
+pub fn synchronous_average(segments: &Matrix) -> Result<Vec<f64>, Box<dyn Error>> {
+    let first = segments.first().ok_or("At least one segment is needed.")?;
+    if segments.iter().any(|s| s.len() != first.len()) {
+        return Err("All segments must have the same length.".into());
+    }
+    let mut average = vec![0.0; first.len()];
+    for segment in segments {
+        average.iter_mut().zip(segment).for_each(|(a, x)| *a += x);
+    }
+    average.iter_mut().for_each(|a| *a /= segments.len() as f64);
+    Ok(average)
+}
+
+/// Result of [`tacho_synchronous_average`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct TachoAverage {
+    /// One revolution of the averaged signal, sampled at equal angle increments starting at a pulse.
+    pub average: Vec<f64>,
+    pub revolutions: usize,
+}
+
+/// Averages the revolutions of a rotating machine delimited by tachometer pulses.
+///     Each revolution is resampled by cubic spline onto `samples_per_rev` equally spaced angles, so speed changes
+///     between revolutions do not smear the average. `tacho_pulses` holds the pulse times in seconds, one per
+///     revolution. `samples_per_rev` defaults to the median revolution length in samples.
+pub fn tacho_synchronous_average(
+    signal: Vec<f64>,
+    tacho_pulses: &[f64],
+    fs: f64,
+    samples_per_rev: Option<usize>,
+) -> Result<TachoAverage, Box<dyn Error>> {
+    if fs <= 0.0 || signal.len() < 2 {
+        return Err("fs must be positive and the signal must hold at least two samples.".into());
+    }
+    let pulses: Vec<f64> = tacho_pulses.iter().map(|t| t * fs).collect();
+    if pulses.len() < 2 || pulses.windows(2).any(|w| w[1] <= w[0]) {
+        return Err("At least two strictly increasing tacho pulses are needed.".into());
+    }
+    // Only whole revolutions inside the record are averaged
+    let last = (signal.len() - 1) as f64;
+    let pulses: Vec<f64> = pulses.into_iter().filter(|&p| (0.0..=last).contains(&p)).collect();
+    if pulses.len() < 2 {
+        return Err("Fewer than two tacho pulses fall within the signal.".into());
+    }
+
+    let samples_per_rev = match samples_per_rev {
+        Some(0) => return Err("samples_per_rev must be positive.".into()),
+        Some(n) => n,
+        None => {
+            let mut lengths: Vec<f64> = pulses.windows(2).map(|w| w[1] - w[0]).collect();
+            lengths.sort_by(f64::total_cmp);
+            (lengths[lengths.len() / 2].round() as usize).max(1)
+        }
+    };
+
+    let positions: Vec<f64> = pulses
+        .windows(2)
+        .flat_map(|w| (0..samples_per_rev).map(move |j| w[0] + (w[1] - w[0]) * j as f64 / samples_per_rev as f64))
+        .collect();
+    let index = (0..signal.len()).map(|n| n as f64).collect();
+    let resampled = interpolate(index, signal, positions, Interpolation::Cubic)?;
+    let revolutions: Matrix = resampled.chunks(samples_per_rev).map(<[f64]>::to_vec).collect();
+
+    Ok(TachoAverage { average: synchronous_average(&revolutions)?, revolutions: revolutions.len() })
+}
@@ -0,0 +1,69 @@
+//! Size of the thread pool used by the parallel routines.
+//!
+//! Parallel routines run on rayon's global pool, sized by `RAYON_NUM_THREADS` or the number of CPUs, until
+//! [`set_num_threads`] gives the crate a dedicated pool. Limiting it keeps multiprocess workers from oversubscribing
+//! the machine.
+
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::error::Error;
+use std::sync::{Arc, RwLock};
+
+use crate::{Cancelled, Progress};
+
+static POOL: RwLock<Option<Arc<ThreadPool>>> = RwLock::new(None);
+
+fn current_pool() -> Option<Arc<ThreadPool>> {
+    POOL.read().unwrap_or_else(|poisoned| poisoned.into_inner()).clone()
+}
+
+/// Runs the parallel routines of the crate on `threads` worker threads from now on.
+///     Zero goes back to rayon's global pool. Calls already running keep the pool they started on.
+pub fn set_num_threads(threads: usize) -> Result<(), Box<dyn Error>> {
+    let pool = if threads == 0 {
+        None
+    } else {
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .thread_name(|i| format!("fft-rust-worker-{}", i))
+            .build()?;
+        Some(Arc::new(pool))
+    };
+    log::debug!("Using {} worker threads", threads);
+    *POOL.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = pool;
+    Ok(())
+}
+
+/// Returns the number of worker threads the parallel routines run on.
+pub fn get_num_threads() -> usize {
+    current_pool().map_or_else(rayon::current_num_threads, |pool| pool.current_num_threads())
+}
+
+/// Runs `op` on the configured pool, so the rayon iterators inside it use that pool's threads.
+pub(crate) fn install<R: Send>(op: impl FnOnce() -> R + Send) -> R {
+    match current_pool() {
+        Some(pool) => pool.install(op),
+        None => op(),
+    }
+}
+
+/// Maps `items` in parallel on the configured pool, in at most a hundred batches with the fraction done reported to
+///     `progress` between them. The callback runs on the calling thread, so it may check for Python signals, and
+///     cancelling stops the work after the current batch.
+pub(crate) fn par_map_with_progress<T: Send, R: Send>(
+    items: Vec<T>,
+    progress: &mut dyn FnMut(f64) -> bool,
+    operation: impl Fn(T) -> R + Sync,
+) -> Result<Vec<R>, Cancelled> {
+    let total = items.len();
+    let batch = total.div_ceil(100).max(get_num_threads());
+    let mut progress = Progress::new(progress);
+    let mut results = Vec::with_capacity(total);
+    let mut items = items.into_iter();
+    while results.len() < total {
+        let chunk: Vec<T> = items.by_ref().take(batch).collect();
+        results.extend(install(|| chunk.into_par_iter().map(&operation).collect::<Vec<R>>()));
+        progress.update(results.len() as f64 / total as f64)?;
+    }
+    Ok(results)
+}
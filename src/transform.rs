@@ -0,0 +1,298 @@
+//! Forward transforms and frequency bin helpers.
+
+use rustfft::{FftDirection, num_complex::Complex};
+use std::error::Error;
+
+use crate::{extended, plan_fft, threads, Matrix};
+
+/// Computes the FFT of the provided data (measured_data) and returns separate vectors for real and imaginary parts.
+pub fn compute_fft(data: Vec<f64>) -> Result<(Vec<f64>, Vec<f64>), Box<dyn Error>> {
+    let fft = plan_fft(data.len(), FftDirection::Forward);
+    // Convert the input data to Complex numbers
+    let mut buffer: Vec<Complex<f64>> = data
+        .into_iter()
+        .map(|x| Complex::new(x, 0.0))
+        .collect();
+    // Perform the FFT
+    fft.process(&mut buffer);
+    // Separate the real and imaginary parts
+    let real: Vec<f64> = buffer.iter().map(|c| c.re).collect();
+    let imag: Vec<f64> = buffer.iter().map(|c| c.im).collect();
+
+    Ok((real, imag))
+}
+
+/// Arithmetic precision of [`compute_fft_with_precision`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Precision {
+    /// rustfft in `f64`.
+    Double,
+    /// Double-double arithmetic with about 106 bits of mantissa, rounded to `f64` at the end.
+    ///     Much slower, meant for measuring the round-off of the double precision result.
+    Extended,
+}
+
+/// Same as [`compute_fft`], computed in the requested arithmetic precision.
+pub fn compute_fft_with_precision(data: Vec<f64>, precision: Precision) -> Result<(Vec<f64>, Vec<f64>), Box<dyn Error>> {
+    match precision {
+        Precision::Double => compute_fft(data),
+        Precision::Extended => {
+            log::debug!("Extended precision FFT of {} samples", data.len());
+            Ok(extended::fft(&data).into_iter().map(|(re, im)| (re.to_f64(), im.to_f64())).unzip())
+        }
+    }
+}
+
+/// Computes the forward or inverse FFT of complex data.
+///     The inverse transform is scaled by `1 / N` so a forward and inverse pair returns the original data.
+pub fn compute_fft_complex_interleaved(
+    mut data: Vec<Complex<f64>>,
+    inverse: bool,
+) -> Result<Vec<Complex<f64>>, Box<dyn Error>> {
+    let len = data.len();
+    let direction = if inverse { FftDirection::Inverse } else { FftDirection::Forward };
+    plan_fft(len, direction).process(&mut data);
+    if inverse {
+        let scale = 1.0 / len as f64;
+        data.iter_mut().for_each(|c| *c *= scale);
+    }
+    Ok(data)
+}
+
+/// Computes the FFT of complex data given as separate real and imaginary parts, e.g. I/Q samples.
+pub fn compute_fft_complex(real: Vec<f64>, imag: Vec<f64>) -> Result<(Vec<f64>, Vec<f64>), Box<dyn Error>> {
+    transform_parts(real, imag, false)
+}
+
+/// Computes the inverse FFT of complex data given as separate real and imaginary parts, scaled by `1 / N`.
+pub fn compute_ifft_complex(real: Vec<f64>, imag: Vec<f64>) -> Result<(Vec<f64>, Vec<f64>), Box<dyn Error>> {
+    transform_parts(real, imag, true)
+}
+
+fn transform_parts(real: Vec<f64>, imag: Vec<f64>, inverse: bool) -> Result<(Vec<f64>, Vec<f64>), Box<dyn Error>> {
+    if real.len() != imag.len() {
+        return Err("Real and imaginary parts must have the same length.".into());
+    }
+    let data = real.into_iter().zip(imag).map(|(re, im)| Complex::new(re, im)).collect();
+    Ok(compute_fft_complex_interleaved(data, inverse)?.into_iter().map(|c| (c.re, c.im)).unzip())
+}
+
+/// Computes the analytic signal of real data with the FFT based Hilbert transform.
+///     The real part is the data itself and the imaginary part its Hilbert transform.
+pub fn analytic_signal(data: Vec<f64>) -> Result<(Vec<f64>, Vec<f64>), Box<dyn Error>> {
+    if data.is_empty() {
+        return Err("Data must not be empty.".into());
+    }
+    Ok(analytic(data).into_iter().map(|c| (c.re, c.im)).unzip())
+}
+
+/// Analytic signal as complex samples: negative frequencies removed and positive ones doubled.
+pub(crate) fn analytic(data: Vec<f64>) -> Vec<Complex<f64>> {
+    let len = data.len();
+    let mut buffer: Vec<Complex<f64>> = data.into_iter().map(|x| Complex::new(x, 0.0)).collect();
+    plan_fft(len, FftDirection::Forward).process(&mut buffer);
+    for (k, c) in buffer.iter_mut().enumerate() {
+        let factor = if k == 0 || 2 * k == len {
+            1.0
+        } else if 2 * k < len {
+            2.0
+        } else {
+            0.0
+        };
+        *c *= factor / len as f64;
+    }
+    plan_fft(len, FftDirection::Inverse).process(&mut buffer);
+    buffer
+}
+
+/// Transposes a rectangular matrix.
+pub(crate) fn transpose(data: &Matrix) -> Matrix {
+    let columns = data.first().map_or(0, Vec::len);
+    (0..columns).map(|j| data.iter().map(|row| row[j]).collect()).collect()
+}
+
+/// Computes the FFT of each row (`axis = 1`) or each column (`axis = 0`) of a rectangular matrix in parallel.
+///     Returns the real and imaginary parts as matrices with the same shape as the input. With the `gpu` feature
+///     large batches run on the GPU when one is available, see [`gpu`](crate::gpu).
+pub fn compute_fft_axis(data: Matrix, axis: usize) -> Result<(Matrix, Matrix), Box<dyn Error>> {
+    compute_fft_axis_with_progress(data, axis, &mut |_| true)
+}
+
+/// Same as [`compute_fft_axis`], reporting the fraction of rows or columns transformed so far to `progress`.
+pub fn compute_fft_axis_with_progress(
+    data: Matrix,
+    axis: usize,
+    progress: &mut dyn FnMut(f64) -> bool,
+) -> Result<(Matrix, Matrix), Box<dyn Error>> {
+    if axis > 1 {
+        return Err("Axis must be 0 (columns) or 1 (rows).".into());
+    }
+    let columns = data.first().map_or(0, Vec::len);
+    if data.iter().any(|row| row.len() != columns) {
+        return Err("All rows must have the same length.".into());
+    }
+
+    let lanes = if axis == 0 { transpose(&data) } else { data };
+    #[cfg(feature = "gpu")]
+    let offloaded = crate::gpu::fft_lanes(&lanes);
+    #[cfg(not(feature = "gpu"))]
+    let offloaded = None;
+
+    let (real, imag): (Matrix, Matrix) = match offloaded {
+        Some(result) => {
+            progress(1.0);
+            result
+        }
+        None => {
+            // Plan on the calling thread so the workers only execute the shared plan
+            let fft = plan_fft(lanes.first().map_or(0, Vec::len), FftDirection::Forward);
+            threads::par_map_with_progress(lanes, progress, |lane| -> (Vec<f64>, Vec<f64>) {
+                let mut buffer: Vec<Complex<f64>> = lane.into_iter().map(|x| Complex::new(x, 0.0)).collect();
+                fft.process(&mut buffer);
+                buffer.into_iter().map(|c| (c.re, c.im)).unzip()
+            })?
+            .into_iter()
+            .unzip()
+        }
+    };
+
+    if axis == 0 {
+        Ok((transpose(&real), transpose(&imag)))
+    } else {
+        Ok((real, imag))
+    }
+}
+
+/// Performs FFT shift on the real and imaginary parts.
+pub fn fft_shift(real: Vec<f64>, imag: Vec<f64>) -> Result<(Vec<f64>, Vec<f64>), Box<dyn Error>> {
+    let len = real.len();
+    let half = len / 2;
+
+    // Rearrange the real and imaginary parts
+    let mut shifted_real = real;
+    let mut shifted_imag = imag;
+
+    shifted_real.rotate_left(half);
+    shifted_imag.rotate_left(half);
+
+    Ok((shifted_real, shifted_imag))
+}
+
+/// Generates frequency bins for FFT data.
+pub fn generate_frequencies(len: usize, sampling_interval: f64) -> Result<Vec<f64>, Box<dyn Error>> {
+    if len == 0 || sampling_interval <= 0.0 {
+        return Err("Length must be positive and sampling interval must be greater than zero.".into());
+    }
+
+    let total_duration = len as f64 * sampling_interval;
+    let freq: Vec<f64> = (0..len)
+        .map(|k| {
+            if k < len / 2 {
+                k as f64 / total_duration
+            } else {
+                -(len as f64 - k as f64) / total_duration
+            }
+        })
+        .collect();
+
+    Ok(freq)
+}
+
+/// Shifts the zero-frequency component of the frequency bins to the center.
+pub fn fft_shift_frequencies(data: Vec<f64>) -> Result<Vec<f64>, Box<dyn Error>> {
+    let len = data.len();
+    let half = len / 2;
+
+    let mut shifted_data = data.clone();
+    shifted_data.rotate_left(half);
+
+    Ok(shifted_data)
+}
+
+/// Timing of repeated forward FFTs of one size.
+pub struct FftBenchmark {
+    pub size: usize,
+    pub mean_seconds: f64,
+    pub best_seconds: f64,
+    /// Throughput of the best run using the conventional `5 N log2(N)` flop count.
+    pub mflops: f64,
+}
+
+/// Measures forward FFT throughput for each size on the current machine, excluding planning time.
+///     Each size is also round-tripped through the inverse transform as a smoke test of the installation.
+pub fn benchmark(sizes: Vec<usize>, repeats: usize) -> Result<Vec<FftBenchmark>, Box<dyn Error>> {
+    if repeats == 0 || sizes.contains(&0) {
+        return Err("Sizes and repeats must be positive.".into());
+    }
+
+    let mut results = Vec::with_capacity(sizes.len());
+    for size in sizes {
+        let fft = plan_fft(size, FftDirection::Forward);
+        let input: Vec<Complex<f64>> = (0..size).map(|i| Complex::new((i as f64 * 0.37).sin(), 0.0)).collect();
+
+        let mut round_trip = input.clone();
+        fft.process(&mut round_trip);
+        plan_fft(size, FftDirection::Inverse).process(&mut round_trip);
+        let error = round_trip
+            .iter()
+            .zip(&input)
+            .map(|(y, x)| (y / size as f64 - x).norm())
+            .fold(0.0, f64::max);
+        if error > 1e-9 {
+            return Err(format!("FFT round trip of size {} has error {:e}, the installation looks broken.", size, error).into());
+        }
+
+        let mut times = Vec::with_capacity(repeats);
+        for _ in 0..repeats {
+            let mut buffer = input.clone();
+            let start = std::time::Instant::now();
+            fft.process(&mut buffer);
+            times.push(start.elapsed().as_secs_f64());
+        }
+        let mean_seconds = times.iter().sum::<f64>() / repeats as f64;
+        let best_seconds = times.iter().copied().fold(f64::INFINITY, f64::min);
+        let flops = 5.0 * size as f64 * (size as f64).log2();
+
+        log::debug!("FFT of size {}: best {:.3e} s, mean {:.3e} s", size, best_seconds, mean_seconds);
+        results.push(FftBenchmark { size, mean_seconds, best_seconds, mflops: flops / best_seconds.max(1e-12) / 1e6 });
+    }
+
+    Ok(results)
+}
+
+/// Resamples the data to `num` samples with the Fourier method, assuming the signal is periodic.
+pub fn resample(data: Vec<f64>, num: usize) -> Result<Vec<f64>, Box<dyn Error>> {
+    let len = data.len();
+    if len == 0 || num == 0 {
+        return Err("Data and the target length must not be empty.".into());
+    }
+    if num == len {
+        return Ok(data);
+    }
+
+    let mut spectrum: Vec<Complex<f64>> = data.into_iter().map(|x| Complex::new(x, 0.0)).collect();
+    plan_fft(len, FftDirection::Forward).process(&mut spectrum);
+
+    // Keep the bins both lengths can represent and split the Nyquist bin of the shorter one between both halves
+    let shared = len.min(num);
+    let mut resized = vec![Complex::new(0.0, 0.0); num];
+    for k in 0..shared.div_ceil(2) {
+        resized[k] = spectrum[k];
+        if k > 0 {
+            resized[num - k] = spectrum[len - k];
+        }
+    }
+    if shared.is_multiple_of(2) {
+        let nyquist = shared / 2;
+        if num > len {
+            resized[nyquist] = spectrum[nyquist] * 0.5;
+            resized[num - nyquist] = spectrum[nyquist] * 0.5;
+        } else {
+            resized[nyquist] = spectrum[nyquist] + spectrum[len - nyquist];
+        }
+    }
+
+    plan_fft(num, FftDirection::Inverse).process(&mut resized);
+    let scale = 1.0 / len as f64;
+    Ok(resized.into_iter().map(|c| c.re * scale).collect())
+}
@@ -0,0 +1,114 @@
+//! Human vibration exposure metrics following ISO 2631-1.
+
+use rustfft::num_complex::Complex;
+use std::error::Error;
+use std::f64::consts::PI;
+
+use crate::filter::apply_response;
+
+/// ISO 2631-1 frequency weighting curves.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VibrationWeighting {
+    /// Vertical (z axis) whole-body vibration for health, comfort and perception.
+    Wk,
+    /// Horizontal (x and y axes) whole-body vibration.
+    Wd,
+    /// Seat-back (x axis) vibration.
+    Wc,
+    /// Rotational vibration.
+    We,
+    /// Vertical motion sickness.
+    Wf,
+}
+
+/// Corner frequencies in Hz and quality factors of the ISO 2631-1 weighting filters.
+struct WeightingParameters {
+    f1: f64,
+    f2: f64,
+    f3: f64,
+    f4: f64,
+    q4: f64,
+    /// Upward step `(f5, q5, f6, q6)`, absent for the weightings without one.
+    step: Option<(f64, f64, f64, f64)>,
+}
+
+impl VibrationWeighting {
+    fn parameters(self) -> WeightingParameters {
+        let band = |f3, f4, q4, step| WeightingParameters { f1: 0.4, f2: 100.0, f3, f4, q4, step };
+        match self {
+            VibrationWeighting::Wk => band(12.5, 12.5, 0.63, Some((2.37, 0.91, 3.35, 0.91))),
+            VibrationWeighting::Wd => band(2.0, 2.0, 0.63, None),
+            VibrationWeighting::Wc => band(8.0, 8.0, 0.63, None),
+            VibrationWeighting::We => band(1.0, 1.0, 0.63, None),
+            VibrationWeighting::Wf => WeightingParameters {
+                f1: 0.08,
+                f2: 0.63,
+                f3: f64::INFINITY,
+                f4: 0.25,
+                q4: 0.86,
+                step: Some((0.0625, 0.80, 0.1, 0.80)),
+            },
+        }
+    }
+
+    /// Complex frequency response of the weighting at `freq` Hz: band limiting, acceleration-velocity
+    ///     transition and upward step filters in series.
+    pub fn response(self, freq: f64) -> Complex<f64> {
+        let p = self.parameters();
+        let s = Complex::new(0.0, 2.0 * PI * freq);
+        let w = |f: f64| 2.0 * PI * f;
+        let one = Complex::new(1.0, 0.0);
+        let q = 1.0 / 2f64.sqrt();
+
+        let high_pass = s * s / (s * s + s * w(p.f1) / q + w(p.f1).powi(2));
+        let low_pass = w(p.f2).powi(2) / (s * s + s * w(p.f2) / q + w(p.f2).powi(2));
+        let transition = (one + s / w(p.f3)) / (one + s / (p.q4 * w(p.f4)) + s * s / w(p.f4).powi(2));
+        let step = p.step.map_or(one, |(f5, q5, f6, q6)| {
+            (one + s / (q5 * w(f5)) + s * s / w(f5).powi(2)) / (one + s / (q6 * w(f6)) + s * s / w(f6).powi(2))
+                * (f5 / f6).powi(2)
+        });
+        high_pass * low_pass * transition * step
+    }
+}
+
+/// Applies an ISO 2631-1 frequency weighting to an acceleration signal in the frequency domain.
+pub fn frequency_weight(data: Vec<f64>, fs: f64, weighting: VibrationWeighting) -> Result<Vec<f64>, Box<dyn Error>> {
+    if data.is_empty() || fs <= 0.0 {
+        return Err("Data must not be empty and fs must be greater than zero.".into());
+    }
+    Ok(apply_response(data, fs, |freq| weighting.response(freq)))
+}
+
+/// Vibration exposure metrics of a frequency weighted acceleration signal.
+pub struct VibrationMetrics {
+    /// Frequency weighted RMS acceleration `a_w`.
+    pub rms: f64,
+    /// Vibration dose value, the fourth power integral `(integral a_w^4 dt)^(1/4)`.
+    pub vdv: f64,
+    /// Maximum transient vibration value, the largest one second running RMS.
+    pub mtvv: f64,
+    /// Ratio of the weighted peak to the weighted RMS.
+    pub crest_factor: f64,
+}
+
+/// Computes the ISO 2631-1 exposure metrics of an acceleration signal in m/s^2.
+pub fn vibration_metrics(data: Vec<f64>, fs: f64, weighting: VibrationWeighting) -> Result<VibrationMetrics, Box<dyn Error>> {
+    let weighted = frequency_weight(data, fs, weighting)?;
+    let len = weighted.len();
+    let rms = (weighted.iter().map(|a| a * a).sum::<f64>() / len as f64).sqrt();
+    let vdv = (weighted.iter().map(|a| a.powi(4)).sum::<f64>() / fs).powf(0.25);
+    let peak = weighted.iter().fold(0.0, |m: f64, a| m.max(a.abs()));
+
+    // Running RMS over one second windows from prefix sums of the squared signal
+    let window = (fs.round() as usize).clamp(1, len);
+    let mut prefix = Vec::with_capacity(len + 1);
+    prefix.push(0.0);
+    for a in &weighted {
+        prefix.push(prefix.last().copied().unwrap_or(0.0) + a * a);
+    }
+    let mtvv = (window..=len)
+        .map(|end| ((prefix[end] - prefix[end - window]).max(0.0) / window as f64).sqrt())
+        .fold(0.0, f64::max);
+
+    Ok(VibrationMetrics { rms, vdv, mtvv, crest_factor: if rms > 0.0 { peak / rms } else { 0.0 } })
+}
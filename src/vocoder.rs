@@ -0,0 +1,78 @@
+//! Time stretching and pitch shifting with a phase vocoder.
+
+use rustfft::num_complex::Complex;
+use std::error::Error;
+use std::f64::consts::PI;
+
+use crate::{istft, resample, stft, Window};
+
+/// Frame length of about 46 ms, 2048 samples at 44.1 kHz, and a quarter of it as hop.
+fn vocoder_frames(fs: f64) -> (usize, usize) {
+    let nperseg = ((0.046 * fs).round() as usize).next_power_of_two().max(64);
+    (nperseg, nperseg / 4)
+}
+
+/// Changes the duration of the data by `1 / rate` without changing its pitch, `rate = 2` playing twice as fast.
+///     The STFT frames are resampled in time, interpolating magnitudes between neighbouring frames and advancing
+///     the phase of each spectral peak by its measured instantaneous frequency, so partials stay continuous.
+///     Transients smear over about one 46 ms frame.
+pub fn time_stretch(data: &[f64], fs: f64, rate: f64) -> Result<Vec<f64>, Box<dyn Error>> {
+    if !(rate > 0.0 && rate.is_finite() && fs > 0.0) {
+        return Err("rate and fs must be positive.".into());
+    }
+    let (nperseg, hop) = vocoder_frames(fs);
+    let frames = stft(data, nperseg, hop, Window::Hann)?;
+    let bins = nperseg / 2 + 1;
+    // Phase a bin centred sinusoid advances over one hop
+    let expected: Vec<f64> = (0..bins).map(|k| 2.0 * PI * hop as f64 * k as f64 / nperseg as f64).collect();
+
+    let mut phase: Vec<f64> = frames[0].iter().map(|c| c.arg()).collect();
+    let mut stretched = Vec::new();
+    let mut position = 0.0;
+    while position < (frames.len() - 1) as f64 {
+        let t = position as usize;
+        let alpha = position - t as f64;
+        let (left, right) = (&frames[t], &frames[t + 1]);
+        let magnitude: Vec<f64> = (0..bins).map(|k| (1.0 - alpha) * left[k].norm() + alpha * right[k].norm()).collect();
+
+        // Identity phase locking (Laroche and Dolson): bins follow the phase of their nearest spectral peak with the
+        // offset they have in the analysis frame, keeping the lobe of each partial coherent
+        let peaks: Vec<usize> = (0..bins)
+            .filter(|&k| (k == 0 || magnitude[k] > magnitude[k - 1]) && (k + 1 == bins || magnitude[k] >= magnitude[k + 1]))
+            .collect();
+        let mut nearest = 0;
+        for k in 0..bins {
+            while nearest + 1 < peaks.len() && peaks[nearest + 1].abs_diff(k) < peaks[nearest].abs_diff(k) {
+                nearest += 1;
+            }
+            if let Some(&peak) = peaks.get(nearest).filter(|&&peak| peak != k) {
+                phase[k] = phase[peak] + left[k].arg() - left[peak].arg();
+            }
+        }
+        stretched.push((0..bins).map(|k| Complex::from_polar(magnitude[k], phase[k])).collect::<Vec<_>>());
+
+        for k in 0..bins {
+            let deviation = right[k].arg() - left[k].arg() - expected[k];
+            phase[k] += expected[k] + (deviation + PI).rem_euclid(2.0 * PI) - PI;
+        }
+        position += rate;
+    }
+    if stretched.is_empty() {
+        return Err("The data is too short to stretch, it must span more than one hop.".into());
+    }
+
+    let length = (data.len() as f64 / rate).round() as usize;
+    log::debug!("Stretched {} samples to {} over {} frames", data.len(), length, stretched.len());
+    istft(&stretched, nperseg, hop, Window::Hann, Some(length))
+}
+
+/// Shifts the pitch of the data by `semitones`, negative for lower, keeping its duration: the data is stretched
+///     by the frequency ratio with [`time_stretch`] and resampled back to its original length.
+pub fn pitch_shift(data: &[f64], fs: f64, semitones: f64) -> Result<Vec<f64>, Box<dyn Error>> {
+    if !semitones.is_finite() {
+        return Err("semitones must be finite.".into());
+    }
+    let ratio = 2f64.powf(semitones / 12.0);
+    let stretched = time_stretch(data, fs, 1.0 / ratio)?;
+    resample(stretched, data.len())
+}
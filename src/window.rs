@@ -0,0 +1,222 @@
+//! Window functions and their overlap-add properties.
+
+use rustfft::{FftDirection, num_complex::Complex};
+use std::error::Error;
+use std::f64::consts::PI;
+
+use crate::plan_fft;
+
+/// Window functions, all generated in their periodic (DFT-even) form for spectral analysis and STFT use.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Window {
+    Rectangular,
+    Hann,
+    Hamming,
+    Blackman,
+    /// Four term Blackman-Harris window with -92 dB sidelobes.
+    BlackmanHarris,
+    /// Flat top window for accurate amplitude readings.
+    FlatTop,
+}
+
+impl Window {
+    /// Every window, in order of increasing sidelobe suppression with the flat top last.
+    pub const ALL: [Window; 6] =
+        [Window::Rectangular, Window::Hann, Window::Hamming, Window::Blackman, Window::BlackmanHarris, Window::FlatTop];
+
+    /// Lowercase name of the window, as accepted by the Python bindings.
+    pub fn name(self) -> &'static str {
+        match self {
+            Window::Rectangular => "rectangular",
+            Window::Hann => "hann",
+            Window::Hamming => "hamming",
+            Window::Blackman => "blackman",
+            Window::BlackmanHarris => "blackmanharris",
+            Window::FlatTop => "flattop",
+        }
+    }
+
+    /// Cosine series coefficients `a_k` of `w[n] = sum (-1)^k a_k cos(2 pi k n / len)`.
+    fn cosine_terms(self) -> &'static [f64] {
+        match self {
+            Window::Rectangular => &[1.0],
+            Window::Hann => &[0.5, 0.5],
+            Window::Hamming => &[0.54, 0.46],
+            Window::Blackman => &[0.42, 0.5, 0.08],
+            Window::BlackmanHarris => &[0.35875, 0.48829, 0.14128, 0.01168],
+            Window::FlatTop => &[0.21557895, 0.41663158, 0.277263158, 0.083578947, 0.006947368],
+        }
+    }
+
+    /// Returns the `len` window coefficients.
+    pub fn coefficients(self, len: usize) -> Vec<f64> {
+        let terms = self.cosine_terms();
+        (0..len)
+            .map(|n| {
+                let x = 2.0 * PI * n as f64 / len as f64;
+                terms.iter().enumerate().map(|(k, &a)| if k % 2 == 0 { a } else { -a } * (k as f64 * x).cos()).sum()
+            })
+            .collect()
+    }
+
+    /// Magnitude response of the `len` point window, zero padded 64 times to resolve the sidelobes.
+    ///     Returns the frequency offsets in bins of the unpadded DFT from 0 to `len / 2` and the response in dB
+    ///     relative to its value at DC.
+    pub fn frequency_response(self, len: usize) -> (Vec<f64>, Vec<f64>) {
+        const OVERSAMPLING: usize = 64;
+        let padded = len * OVERSAMPLING;
+        let mut buffer = vec![Complex::new(0.0, 0.0); padded];
+        for (b, w) in buffer.iter_mut().zip(self.coefficients(len)) {
+            *b = Complex::new(w, 0.0);
+        }
+        plan_fft(padded, FftDirection::Forward).process(&mut buffer);
+
+        let peak = buffer[0].norm();
+        let bins = (0..=padded / 2).map(|k| k as f64 / OVERSAMPLING as f64).collect();
+        let db = buffer[..=padded / 2].iter().map(|c| 20.0 * (c.norm() / peak).max(1e-15).log10()).collect();
+        (bins, db)
+    }
+}
+
+/// Leakage figures of merit of a window, see [`window_report`].
+pub struct WindowReport {
+    /// Equivalent noise bandwidth in bins.
+    pub enbw: f64,
+    /// Mean of the window, the amplitude gain for a tone centred on a bin.
+    pub coherent_gain: f64,
+    /// Amplitude loss in dB for a tone halfway between two bins.
+    pub scalloping_loss_db: f64,
+    /// Level of the highest sidelobe in dB relative to the main lobe peak.
+    pub sidelobe_level_db: f64,
+}
+
+/// Computes the equivalent noise bandwidth, coherent gain, scalloping loss and highest sidelobe level
+///     of the `nperseg` point window.
+pub fn window_report(window: Window, nperseg: usize) -> Result<WindowReport, Box<dyn Error>> {
+    if nperseg < 2 {
+        return Err("nperseg must be at least 2.".into());
+    }
+
+    let coefficients = window.coefficients(nperseg);
+    let sum: f64 = coefficients.iter().sum();
+    let sum_squares: f64 = coefficients.iter().map(|w| w * w).sum();
+    let half_bin: Complex<f64> = coefficients
+        .iter()
+        .enumerate()
+        .map(|(n, w)| Complex::from_polar(*w, -PI * n as f64 / nperseg as f64))
+        .sum();
+
+    // The main lobe ends at the first local minimum of the response, the highest sidelobe is the maximum past it
+    let (_, db) = window.frequency_response(nperseg);
+    let main_lobe_end = (1..db.len() - 1).find(|&k| db[k] <= db[k - 1] && db[k] <= db[k + 1]).unwrap_or(db.len() - 1);
+    let sidelobe_level_db = db[main_lobe_end..].iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+    Ok(WindowReport {
+        enbw: nperseg as f64 * sum_squares / (sum * sum),
+        coherent_gain: sum / nperseg as f64,
+        scalloping_loss_db: -20.0 * (half_bin.norm() / sum).log10(),
+        sidelobe_level_db,
+    })
+}
+
+/// Result of [`check_cola`].
+pub struct ColaCheck {
+    /// Whether overlap-adding the window at the hop gives a constant sum.
+    pub is_cola: bool,
+    /// Largest deviation of the overlap-added sum from its mean, relative to the mean.
+    pub deviation: f64,
+    /// Every hop between 1 and `nperseg` satisfying the condition for this window.
+    pub valid_hops: Vec<usize>,
+}
+
+/// Relative deviation of the overlap-added window from a constant, over one hop period.
+fn cola_deviation(window: &[f64], hop: usize) -> f64 {
+    let sums: Vec<f64> = (0..hop).map(|n| window.iter().skip(n).step_by(hop).sum()).collect();
+    let mean = sums.iter().sum::<f64>() / hop as f64;
+    if mean == 0.0 {
+        return f64::INFINITY;
+    }
+    sums.iter().map(|s| (s - mean).abs()).fold(0.0, f64::max) / mean.abs()
+}
+
+/// Checks the constant overlap-add condition that STFT reconstruction relies on
+///     and lists the hops that satisfy it for this window and segment length.
+pub fn check_cola(window: Window, nperseg: usize, hop: usize) -> Result<ColaCheck, Box<dyn Error>> {
+    if nperseg == 0 || hop == 0 || hop > nperseg {
+        return Err("nperseg must be positive and hop must be between 1 and nperseg.".into());
+    }
+
+    const TOLERANCE: f64 = 1e-10;
+    let coefficients = window.coefficients(nperseg);
+    let deviation = cola_deviation(&coefficients, hop);
+    let valid_hops = (1..=nperseg).filter(|&h| cola_deviation(&coefficients, h) < TOLERANCE).collect();
+    log::debug!("COLA deviation of {:?} with {} samples every {}: {:e}", window, nperseg, hop, deviation);
+
+    Ok(ColaCheck { is_cola: deviation < TOLERANCE, deviation, valid_hops })
+}
+
+/// Recommended analysis parameters, see [`suggest_fft_params`].
+pub struct FftAdvice {
+    pub window: Window,
+    /// Segment length, a power of two.
+    pub nperseg: usize,
+    /// Transform length after zero padding. Padding interpolates the spectrum but does not improve resolution.
+    pub nfft: usize,
+    /// Effective resolution, the equivalent noise bandwidth of the window in Hz.
+    pub resolution_hz: f64,
+    pub bin_spacing_hz: f64,
+    /// Number of Welch segments with 50% overlap.
+    pub segments: usize,
+    /// Standard deviation of the Welch PSD estimate relative to its mean, accounting for the segment overlap.
+    pub relative_std: f64,
+    /// Whether `resolution_hz` reaches the desired resolution.
+    pub achievable: bool,
+}
+
+/// Variance of a Welch estimate relative to the squared mean for `segments` windows overlapping every `step` samples,
+///     following Welch (1967).
+fn welch_variance(window: &[f64], step: usize, segments: usize) -> f64 {
+    let energy: f64 = window.iter().map(|w| w * w).sum();
+    let correlation_sum: f64 = (1..segments)
+        .map(|j| {
+            let overlap = window.iter().zip(window.iter().skip(j * step)).map(|(a, b)| a * b).sum::<f64>() / energy;
+            (1.0 - j as f64 / segments as f64) * overlap * overlap
+        })
+        .sum();
+    (1.0 + 2.0 * correlation_sum) / segments as f64
+}
+
+/// Recommends a window, segment length and padding to resolve `desired_resolution_hz` in `n_samples` sampled at `fs`
+///     with Welch's method, and reports the resolution and variance that go with them.
+///     Hann is preferred. When even a single Hann segment cannot reach the resolution the rectangular window,
+///     which has the narrowest main lobe, is suggested instead.
+pub fn suggest_fft_params(n_samples: usize, fs: f64, desired_resolution_hz: f64) -> Result<FftAdvice, Box<dyn Error>> {
+    if n_samples < 2 || fs <= 0.0 || desired_resolution_hz <= 0.0 {
+        return Err("n_samples must be at least 2 and fs and the desired resolution must be greater than zero.".into());
+    }
+
+    let required = |window: Window| {
+        let enbw = window_report(window, 64).map(|r| r.enbw).unwrap_or(1.0);
+        (enbw * fs / desired_resolution_hz).ceil() as usize
+    };
+    let window = if required(Window::Hann) <= n_samples { Window::Hann } else { Window::Rectangular };
+    let nperseg = required(window).max(2).next_power_of_two().min(n_samples);
+    let nfft = nperseg.next_power_of_two();
+
+    let coefficients = window.coefficients(nperseg);
+    let enbw = window_report(window, nperseg)?.enbw;
+    let step = (nperseg / 2).max(1);
+    let segments = (n_samples - nperseg) / step + 1;
+    let resolution_hz = enbw * fs / nperseg as f64;
+
+    Ok(FftAdvice {
+        window,
+        nperseg,
+        nfft,
+        resolution_hz,
+        bin_spacing_hz: fs / nfft as f64,
+        segments,
+        relative_std: welch_variance(&coefficients, step, segments).sqrt(),
+        achievable: resolution_hz <= desired_resolution_hz * (1.0 + 1e-9),
+    })
+}